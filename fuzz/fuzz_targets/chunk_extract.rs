@@ -0,0 +1,39 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Writes a valid region and then damages one chunk's header entry, length field,
+//! compression byte and payload before extracting it.
+//!
+//! Starting from a valid file keeps the fuzzer past header checks and inside chunk
+//! extraction, where most corruption is found in practice.
+
+#![no_main]
+
+use anvil_nbt::anvil::access::{Region, RegionOptions};
+use anvil_nbt::anvil::encode::minimal_region;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (u32, u8, u32, u8, Vec<u8>)| {
+    let (location, sectors, length, compression, payload) = input;
+    let mut data = minimal_region(&[(0, 0)]).unwrap();
+    data[..3].copy_from_slice(&location.to_be_bytes()[1..]);
+    data[3] = sectors;
+
+    let start = 8192;
+    data[start..start + 4].copy_from_slice(&length.to_be_bytes());
+    data[start + 4] = compression;
+    data.truncate(start + 5);
+    data.extend_from_slice(&payload);
+
+    let Ok(mut region) = Region::from_reader(&data[..]) else {
+        return;
+    };
+    // A few bytes of zlib can claim gigabytes; keep bombs from exhausting memory
+    region.set_options(RegionOptions {
+        max_chunk_size: Some(16 << 20),
+    });
+    let _ = region.get_chunk_nbt(0, 0);
+    if let Ok(Some(mut reader)) = region.chunk_reader(0, 0) {
+        let _ = std::io::copy(&mut reader, &mut std::io::sink());
+    }
+});
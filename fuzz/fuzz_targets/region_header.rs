@@ -0,0 +1,24 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Opens arbitrary bytes as a region and inspects every header entry.
+
+#![no_main]
+
+use anvil_nbt::anvil::access::{Region, RegionOptions};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(mut region) = Region::from_reader(data) else {
+        return;
+    };
+    // A few bytes of zlib can claim gigabytes; keep bombs from exhausting memory
+    region.set_options(RegionOptions {
+        max_chunk_size: Some(16 << 20),
+    });
+    for z in 0..32 {
+        for x in 0..32 {
+            let _ = region.get_chunk_data(x, z);
+        }
+    }
+});
@@ -1,23 +1,100 @@
 // Copyright 2026 driedpampas@proton.me
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use crate::anvil::{ChunkLocation, CompressionType, RegionHeader, SECTOR_SIZE};
+use crate::anvil::codec::{CUSTOM_COMPRESSION_ID, CodecRegistry, CustomPayload};
+#[cfg(feature = "lz4")]
+use crate::anvil::lz4::Lz4Decoder;
+use crate::anvil::{CompressionType, HEADER_SIZE, RegionHeader, from_timestamp};
 use crate::nbt::NbtTag;
-use crate::nbt::parse::parse_named_tag;
+use crate::nbt::parse::{parse_named_fields, parse_named_tag};
+#[cfg(feature = "compression")]
 use flate2::read::{GzDecoder, ZlibDecoder};
 use memmap2::Mmap;
 use std::fs::File;
 use std::io::{Read, Result};
 use std::path::Path;
+use std::sync::Arc;
+use std::time::SystemTime;
 
-/// A memory-mapped Anvil region file.
+/// The bytes backing a [`Region`].
+///
+/// Chunks are handed out as slices of the source, so it must expose the whole file as
+/// one contiguous byte slice. This crate implements it for memory maps (used by
+/// [`Region::open`]) and for in-memory buffers (used by [`Region::from_reader`]);
+/// implement it for other storage, such as a shared buffer from a network stack or an
+/// archive held in memory, and pass it to [`Region::from_source`]. Sources that can only
+/// be read incrementally, like a `Read + Seek` stream, are buffered with
+/// [`Region::from_reader`]; this also works where memory mapping is unavailable, such as
+/// WebAssembly.
+pub trait RegionSource: Send + Sync {
+    /// Returns the contents of the region file.
+    fn as_bytes(&self) -> &[u8];
+}
+
+impl RegionSource for Mmap {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl RegionSource for Vec<u8> {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl RegionSource for Box<[u8]> {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl RegionSource for Arc<[u8]> {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl RegionSource for &'static [u8] {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+/// Limits applied when reading chunks from a [`Region`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegionOptions {
+    /// Maximum size of a decompressed chunk in bytes, or `None` for no limit (the
+    /// default).
+    ///
+    /// A few kilobytes of crafted zlib data can expand to gigabytes. With a limit set,
+    /// reads stop once the limit is exceeded and fail with
+    /// [`InvalidData`](std::io::ErrorKind::InvalidData). Real chunks rarely exceed a few
+    /// megabytes.
+    pub max_chunk_size: Option<usize>,
+}
+
+/// An Anvil region file, memory-mapped or held in memory.
 ///
 /// This struct provides efficient access to chunks within a `.mca` file.
+///
+/// A `Region` is `Send + Sync`, and clones share the mapping and the parsed header, so
+/// handing one to each worker thread costs two reference count increments. See
+/// [`snapshot`](Self::snapshot).
+#[derive(Clone)]
 pub struct Region {
-    mmap: Mmap,
-    header: RegionHeader,
+    data: Arc<dyn RegionSource>,
+    header: Arc<RegionHeader>,
+    codecs: Arc<CodecRegistry>,
+    options: RegionOptions,
 }
 
+// Worker threads rely on sharing regions; keep it that way
+const _: fn() = || {
+    fn assert_shareable<T: Send + Sync + Clone>() {}
+    assert_shareable::<Region>();
+};
+
 impl Region {
     /// Opens an Anvil region file and memory-maps it.
     ///
@@ -25,50 +102,110 @@ impl Region {
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
         let file = File::open(path)?;
         let mmap = unsafe { Mmap::map(&file)? };
+        Self::from_source(mmap)
+    }
+
+    /// Reads a whole region from any reader, such as an archive entry or a network stream.
+    ///
+    /// The data is buffered in memory, so no temporary file is needed.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Self::from_bytes(data)
+    }
+
+    /// Opens a region held in memory, taking ownership of the buffer.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        Self::from_source(data)
+    }
+
+    /// Opens a region from borrowed bytes, copying them into a buffer owned by the region.
+    pub fn from_slice(data: &[u8]) -> Result<Self> {
+        Self::from_bytes(data.to_vec())
+    }
 
-        if mmap.len() < SECTOR_SIZE * 2 {
+    /// Reads a region stored as an entry of a zip archive (e.g. a world backup).
+    ///
+    /// `name` is the entry's path inside the archive, such as `"world/region/r.0.0.mca"`.
+    #[cfg(feature = "zip")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "zip")))]
+    pub fn from_zip<R: Read + std::io::Seek>(
+        archive: &mut zip::ZipArchive<R>,
+        name: &str,
+    ) -> Result<Self> {
+        let entry = archive.by_name(name).map_err(|e| match e {
+            zip::result::ZipError::Io(e) => e,
+            zip::result::ZipError::FileNotFound => {
+                std::io::Error::new(std::io::ErrorKind::NotFound, e)
+            }
+            e => std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+        })?;
+        Self::from_reader(entry)
+    }
+
+    /// Opens a region backed by any [`RegionSource`].
+    pub fn from_source<S: RegionSource + 'static>(source: S) -> Result<Self> {
+        let data = source.as_bytes();
+        if data.len() < HEADER_SIZE {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
                 "MCA file too small for headers",
             ));
         }
 
-        let mut locations = [ChunkLocation {
-            offset: 0,
-            sector_count: 0,
-        }; 1024];
-        let mut timestamps = [0u32; 1024];
-
-        for (i, location) in locations.iter_mut().enumerate() {
-            let start = i * 4;
-            let offset = ((mmap[start] as u32) << 16)
-                | ((mmap[start + 1] as u32) << 8)
-                | (mmap[start + 2] as u32);
-            let sector_count = mmap[start + 3];
-            *location = ChunkLocation {
-                offset,
-                sector_count,
-            };
-        }
-
-        for (i, timestamp_slot) in timestamps.iter_mut().enumerate() {
-            let start = SECTOR_SIZE + i * 4;
-            let timestamp = ((mmap[start] as u32) << 24)
-                | ((mmap[start + 1] as u32) << 16)
-                | ((mmap[start + 2] as u32) << 8)
-                | (mmap[start + 3] as u32);
-            *timestamp_slot = timestamp;
-        }
+        let header_bytes: &[u8; HEADER_SIZE] = data[..HEADER_SIZE].try_into().unwrap();
+        let header = RegionHeader::parse(header_bytes);
 
         Ok(Region {
-            mmap,
-            header: RegionHeader {
-                locations,
-                timestamps,
-            },
+            data: Arc::new(source),
+            header: Arc::new(header),
+            codecs: Arc::default(),
+            options: RegionOptions::default(),
         })
     }
 
+    /// Returns a read handle on the file as it is now.
+    ///
+    /// The handle shares this region's bytes and stays readable after the file on disk
+    /// is replaced, since the writers in this crate ([`CowRegion`](crate::anvil::cow::CowRegion),
+    /// [`WorldTransaction`](crate::world::transaction::WorldTransaction)) write a new file
+    /// and rename it over the old one. A server can keep serving reads from snapshots
+    /// while a writer prepares the next version, then reopen the region once it lands.
+    /// Truncating or rewriting a mapped file in place is still undefined behavior.
+    pub fn snapshot(&self) -> Region {
+        self.clone()
+    }
+
+    /// Sets the codecs used to read chunks with custom compression (id 127).
+    ///
+    /// Without a registered codec, reading such a chunk fails with
+    /// [`Unsupported`](std::io::ErrorKind::Unsupported); its raw bytes stay available
+    /// through [`custom_chunk_payload`](Self::custom_chunk_payload).
+    pub fn set_codecs(&mut self, codecs: Arc<CodecRegistry>) {
+        self.codecs = codecs;
+    }
+
+    /// Returns the codecs used to read chunks with custom compression.
+    pub fn codecs(&self) -> &CodecRegistry {
+        &self.codecs
+    }
+
+    /// Sets the limits applied when reading chunks.
+    ///
+    /// The limits apply to every method that decompresses whole chunks, such as
+    /// [`get_chunk_data`](Self::get_chunk_data) and [`get_chunk_nbt`](Self::get_chunk_nbt).
+    /// A [`ChunkReader`] streams and is not limited, except for chunks decoded by a
+    /// custom codec; bound it with [`Read::take`] or
+    /// [`ParseOptions`](crate::nbt::parse::ParseOptions) instead.
+    pub fn set_options(&mut self, options: RegionOptions) {
+        self.options = options;
+    }
+
+    /// Returns the limits applied when reading chunks.
+    pub fn options(&self) -> &RegionOptions {
+        &self.options
+    }
+
     /// Retrieves the raw decompressed NBT data for a chunk at the given world coordinates.
     ///
     /// Coordinates are in chunk units (not blocks). For example, (0, 0) is the first chunk
@@ -79,47 +216,133 @@ impl Region {
     /// `Ok(None)` if the chunk is not present in this region file, or an `Err` if
     /// decompression fails or the file is corrupted.
     pub fn get_chunk_data(&self, x: i32, z: i32) -> Result<Option<Vec<u8>>> {
-        let rel_x = x.rem_euclid(32);
-        let rel_z = z.rem_euclid(32);
-        let index = (rel_z * 32 + rel_x) as usize;
+        match self.raw_payload(chunk_index(x, z))? {
+            Some((CUSTOM_COMPRESSION_ID, data)) => self.decode_custom(data).map(Some),
+            Some((id, data)) => self.decompress(compression_type(id)?, data).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Decompresses a chunk payload, enforcing [`RegionOptions::max_chunk_size`].
+    pub(crate) fn decompress(&self, compression: CompressionType, data: &[u8]) -> Result<Vec<u8>> {
+        decompress_limited(compression, data, self.options.max_chunk_size)
+    }
+
+    /// Decodes a payload with custom compression through the registered codecs.
+    fn decode_custom(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let decoded = self.codecs.decompress(&CustomPayload::parse(data)?)?;
+        check_chunk_size(decoded, self.options.max_chunk_size)
+    }
+
+    /// Returns the algorithm name and still-compressed bytes of a chunk with custom
+    /// compression (id 127).
+    ///
+    /// Returns `Ok(None)` if the chunk is not present or uses a standard compression type.
+    pub fn custom_chunk_payload(&self, x: i32, z: i32) -> Result<Option<CustomPayload<'_>>> {
+        match self.raw_payload(chunk_index(x, z))? {
+            Some((CUSTOM_COMPRESSION_ID, data)) => CustomPayload::parse(data).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns a reader over the decompressed NBT data of a chunk, without buffering the
+    /// whole chunk in memory.
+    ///
+    /// Pair it with [`parse_from_reader_with`](crate::nbt::parse::parse_from_reader_with)
+    /// and a [`max_allocation`](crate::nbt::parse::ParseOptions::max_allocation) to cap
+    /// the memory a chunk may take before it is read in full. Coordinates wrap as in
+    /// [`get_chunk_data`](Self::get_chunk_data); returns `Ok(None)` if the chunk is not
+    /// present.
+    pub fn chunk_reader(&self, x: i32, z: i32) -> Result<Option<ChunkReader<'_>>> {
+        match self.raw_payload(chunk_index(x, z))? {
+            Some((CUSTOM_COMPRESSION_ID, data)) => {
+                let decoded = self.decode_custom(data)?;
+                Ok(Some(ChunkReader::Decoded(std::io::Cursor::new(decoded))))
+            }
+            Some((id, data)) => Ok(Some(ChunkReader::new(compression_type(id)?, data))),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the compression type and still-compressed bytes of a chunk, or `Ok(None)`
+    /// if it is not present.
+    ///
+    /// The slice borrows the region's mapping, so nothing is copied or decompressed.
+    /// Together with
+    /// [`RegionWriter::write_raw_chunk`](crate::anvil::encode::RegionWriter::write_raw_chunk)
+    /// this copies, splits and merges regions losslessly at the cost of I/O alone.
+    /// Coordinates wrap as in [`get_chunk_data`](Self::get_chunk_data).
+    pub fn get_chunk_raw(&self, x: i32, z: i32) -> Result<Option<(CompressionType, &[u8])>> {
+        self.chunk_payload(chunk_index(x, z))
+    }
+
+    /// Returns the compression type and still-compressed bytes of the chunk at `index`
+    /// in the header table.
+    pub(crate) fn chunk_payload(&self, index: usize) -> Result<Option<(CompressionType, &[u8])>> {
+        match self.raw_payload(index)? {
+            Some((id, data)) => Ok(Some((compression_type(id)?, data))),
+            None => Ok(None),
+        }
+    }
 
+    /// Returns the raw compression id and payload of the chunk at `index`.
+    ///
+    /// Offsets and lengths come from the file and are checked against its size; a chunk
+    /// reaching past the end fails with [`InvalidData`](std::io::ErrorKind::InvalidData).
+    pub(crate) fn raw_payload(&self, index: usize) -> Result<Option<(u8, &[u8])>> {
         let location = self.header.locations[index];
-        if location.offset == 0 {
+        if location.is_empty() {
             return Ok(None);
         }
 
-        let start_byte = location.offset as usize * SECTOR_SIZE;
-        let length = ((self.mmap[start_byte] as u32) << 24)
-            | ((self.mmap[start_byte + 1] as u32) << 16)
-            | ((self.mmap[start_byte + 2] as u32) << 8)
-            | (self.mmap[start_byte + 3] as u32);
+        let (x, z) = (index % 32, index / 32);
+        let out_of_bounds = || {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Chunk ({}, {}) extends past the end of the region file",
+                    x, z
+                ),
+            )
+        };
+        let start_byte = location.start_byte();
+        let data = self.data.as_bytes();
+        let prefix = data
+            .get(start_byte..start_byte + 4)
+            .ok_or_else(out_of_bounds)?;
+        let length = u32::from_be_bytes(prefix.try_into().unwrap()) as usize;
 
         if length < 1 {
             return Ok(None);
         }
 
-        let compression_type_raw = self.mmap[start_byte + 4];
-        let compression_type = CompressionType::try_from(compression_type_raw)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-
-        let data = &self.mmap[start_byte + 5..start_byte + 4 + length as usize];
+        let chunk = data
+            .get(start_byte + 4..start_byte + 4 + length)
+            .ok_or_else(out_of_bounds)?;
+        Ok(Some((chunk[0], &chunk[1..])))
+    }
 
-        let mut decoded = Vec::new();
-        match compression_type {
-            CompressionType::Gzip => {
-                let mut decoder = GzDecoder::new(data);
-                decoder.read_to_end(&mut decoded)?;
-            }
-            CompressionType::Zlib => {
-                let mut decoder = ZlibDecoder::new(data);
-                decoder.read_to_end(&mut decoded)?;
-            }
-            CompressionType::None => {
-                decoded.extend_from_slice(data);
-            }
+    /// Returns when the chunk at `(x, z)` was last saved, from the header's timestamp
+    /// table.
+    ///
+    /// Returns `None` if the chunk is not present or its timestamp was never set.
+    /// Coordinates wrap as in [`get_chunk_data`](Self::get_chunk_data).
+    pub fn get_timestamp(&self, x: i32, z: i32) -> Option<SystemTime> {
+        let index = chunk_index(x, z);
+        if self.header.locations[index].is_empty() {
+            return None;
         }
+        from_timestamp(self.header.timestamps[index])
+    }
 
-        Ok(Some(decoded))
+    /// Returns the parsed region header.
+    pub(crate) fn header(&self) -> &RegionHeader {
+        &self.header
+    }
+
+    /// Returns the whole file, header included.
+    pub(crate) fn bytes(&self) -> &[u8] {
+        self.data.as_bytes()
     }
 
     /// Parses the NBT data for a chunk at the given world coordinates.
@@ -128,13 +351,330 @@ impl Region {
     /// and then parses the resulting bytes into an [`NbtTag`].
     pub fn get_chunk_nbt(&self, x: i32, z: i32) -> Result<Option<(String, NbtTag)>> {
         if let Some(data) = self.get_chunk_data(x, z)? {
-            let mut input = &data[..];
-            let result = parse_named_tag(&mut input).map_err(|_| {
-                std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse NBT")
-            })?;
-            Ok(Some(result))
+            parse_chunk(&data).map(Some)
         } else {
             Ok(None)
         }
     }
+
+    /// Parses several chunks, reading them in file order rather than request order.
+    ///
+    /// Requests are sorted by their sector offset before reading, so the region is read
+    /// front to back, which is much faster than random access on spinning disks and
+    /// network filesystems. Results are returned in the order of `coords`, with `None`
+    /// for chunks that are not present.
+    pub fn get_chunks(&self, coords: &[(i32, i32)]) -> Result<Vec<Option<(String, NbtTag)>>> {
+        let mut results = vec![None; coords.len()];
+        for i in self.offset_order(coords) {
+            let (x, z) = coords[i];
+            results[i] = self.get_chunk_nbt(x, z)?;
+        }
+        Ok(results)
+    }
+
+    /// Like [`get_chunks`](Self::get_chunks), but decompresses and parses on up to
+    /// `threads` threads. Each thread handles a contiguous run of the file.
+    pub fn get_chunks_parallel(
+        &self,
+        coords: &[(i32, i32)],
+        threads: usize,
+    ) -> Result<Vec<Option<(String, NbtTag)>>> {
+        let order = self.offset_order(coords);
+        if order.is_empty() {
+            return Ok(Vec::new());
+        }
+        let run = order.len().div_ceil(threads.max(1));
+        let parsed = std::thread::scope(|scope| {
+            let handles: Vec<_> = order
+                .chunks(run)
+                .map(|indices| {
+                    scope.spawn(move || {
+                        indices
+                            .iter()
+                            .map(|&i| Ok((i, self.get_chunk_nbt(coords[i].0, coords[i].1)?)))
+                            .collect::<Result<Vec<_>>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().expect("chunk parsing thread panicked"))
+                .collect::<Result<Vec<_>>>()
+        })?;
+
+        let mut results = vec![None; coords.len()];
+        for (i, chunk) in parsed.into_iter().flatten() {
+            results[i] = chunk;
+        }
+        Ok(results)
+    }
+
+    /// Iterates over the chunks last modified in `start..end`, in seconds since the Unix
+    /// epoch.
+    ///
+    /// Only the header's timestamp table is consulted to pick chunks, so chunks outside
+    /// the range are never decompressed. This makes incremental exports cheap: pass the
+    /// time of the previous backup as `start` and `u32::MAX` as `end`. Chunks are read in
+    /// file order and yielded as `(x, z, name, tag)` with region-relative coordinates,
+    /// ready for [`RegionWriter::write_all_chunks`](crate::anvil::encode::RegionWriter::write_all_chunks).
+    pub fn iter_chunks_in_time_range(&self, start: u32, end: u32) -> ChunksInTimeRange<'_> {
+        let indices = self.indices_by_offset(|i| (start..end).contains(&self.header.timestamps[i]));
+        ChunksInTimeRange {
+            region: self,
+            indices: indices.into_iter(),
+        }
+    }
+
+    /// Iterates over every chunk present in the region, yielding `(x, z, chunk)` with
+    /// region-relative coordinates (0-31).
+    ///
+    /// Chunks are yielded sorted by sector offset, not by coordinates, so a full scan
+    /// reads the file front to back; this matters on a cold cache, spinning disks and
+    /// network filesystems. Use [`present_chunks`](Self::present_chunks) for coordinate
+    /// order. A chunk that fails to decompress or parse yields its error without ending
+    /// the iteration, so one corrupted chunk does not hide the rest.
+    pub fn iter_chunks(&self) -> Chunks<'_> {
+        Chunks {
+            region: self,
+            indices: self.indices_by_offset(|_| true).into_iter(),
+        }
+    }
+
+    /// Like [`iter_chunks`](Self::iter_chunks), but decompresses and parses chunks on the
+    /// rayon thread pool.
+    ///
+    /// The order of the results is unspecified. Use it for full-region scans such as
+    /// collecting block entities or computing statistics.
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    pub fn par_iter_chunks(
+        &self,
+    ) -> impl rayon::iter::ParallelIterator<Item = (i32, i32, Result<(String, NbtTag)>)> + '_ {
+        use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+        self.indices_by_offset(|_| true)
+            .into_par_iter()
+            .filter_map(move |index| {
+                let (x, z) = ((index % 32) as i32, (index / 32) as i32);
+                self.get_chunk_nbt(x, z)
+                    .transpose()
+                    .map(|chunk| (x, z, chunk))
+            })
+    }
+
+    /// Returns the region-relative coordinates (0-31) of every chunk present in the
+    /// region, ordered by `z`, then `x`. Only the header is consulted.
+    pub fn present_chunks(&self) -> Vec<(i32, i32)> {
+        (0..1024)
+            .filter(|&i| !self.header.locations[i].is_empty())
+            .map(|i| ((i % 32) as i32, (i / 32) as i32))
+            .collect()
+    }
+
+    /// Returns the header indices of present chunks accepted by `filter`, sorted by file
+    /// offset.
+    fn indices_by_offset(&self, filter: impl Fn(usize) -> bool) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..1024)
+            .filter(|&i| !self.header.locations[i].is_empty() && filter(i))
+            .collect();
+        indices.sort_by_key(|&i| self.header.locations[i].offset);
+        indices
+    }
+
+    /// Returns the indices of `coords` sorted by the file offset of their chunks.
+    fn offset_order(&self, coords: &[(i32, i32)]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..coords.len()).collect();
+        order.sort_by_key(|&i| {
+            let (x, z) = coords[i];
+            self.header.locations[chunk_index(x, z)].offset
+        });
+        order
+    }
+
+    /// Decodes only selected fields of a chunk, skipping everything else.
+    ///
+    /// `paths` are dot-separated compound paths such as `"block_entities"` or
+    /// `"Level.TileEntities"`; see [`parse_named_fields`]. Returns `Ok(None)` if the chunk
+    /// is not present, otherwise one entry per path.
+    pub fn get_chunk_fields(
+        &self,
+        x: i32,
+        z: i32,
+        paths: &[&str],
+    ) -> Result<Option<Vec<Option<NbtTag>>>> {
+        let Some(data) = self.get_chunk_data(x, z)? else {
+            return Ok(None);
+        };
+        let mut input = &data[..];
+        parse_named_fields(&mut input, paths)
+            .map(Some)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// Returns the index into the 32x32 header table for chunk coordinates, wrapping
+/// coordinates outside the region with `rem_euclid(32)`.
+pub(crate) fn chunk_index(x: i32, z: i32) -> usize {
+    let rel_x = x.rem_euclid(32);
+    let rel_z = z.rem_euclid(32);
+    (rel_z * 32 + rel_x) as usize
+}
+
+/// Converts a standard compression id, failing for unknown ids.
+fn compression_type(id: u8) -> Result<CompressionType> {
+    CompressionType::try_from(id)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// Iterator over the chunks of a region modified in a time range, created by
+/// [`Region::iter_chunks_in_time_range`].
+pub struct ChunksInTimeRange<'a> {
+    region: &'a Region,
+    indices: std::vec::IntoIter<usize>,
+}
+
+impl Iterator for ChunksInTimeRange<'_> {
+    type Item = Result<(i32, i32, String, NbtTag)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let index = self.indices.next()?;
+            let (x, z) = ((index % 32) as i32, (index / 32) as i32);
+            match self.region.get_chunk_nbt(x, z) {
+                Ok(Some((name, tag))) => return Some(Ok((x, z, name, tag))),
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.indices.len()))
+    }
+}
+
+/// Iterator over all chunks of a region, created by [`Region::iter_chunks`].
+pub struct Chunks<'a> {
+    region: &'a Region,
+    indices: std::vec::IntoIter<usize>,
+}
+
+impl Iterator for Chunks<'_> {
+    type Item = (i32, i32, Result<(String, NbtTag)>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let index = self.indices.next()?;
+            let (x, z) = ((index % 32) as i32, (index / 32) as i32);
+            match self.region.get_chunk_nbt(x, z) {
+                Ok(Some(chunk)) => return Some((x, z, Ok(chunk))),
+                // A zero length prefix marks an empty slot despite its location
+                Ok(None) => continue,
+                Err(e) => return Some((x, z, Err(e))),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.indices.len()))
+    }
+}
+
+/// A streaming reader over a chunk's decompressed NBT data, borrowing the compressed
+/// bytes from its [`Region`].
+pub enum ChunkReader<'a> {
+    /// A gzip-compressed chunk.
+    #[cfg(feature = "compression")]
+    Gzip(GzDecoder<&'a [u8]>),
+    /// A zlib-compressed chunk.
+    #[cfg(feature = "compression")]
+    Zlib(ZlibDecoder<&'a [u8]>),
+    /// An uncompressed chunk.
+    None(&'a [u8]),
+    /// An LZ4-compressed chunk.
+    #[cfg(feature = "lz4")]
+    Lz4(Lz4Decoder<&'a [u8]>),
+    /// A chunk already decompressed by a custom [`ChunkCodec`](crate::anvil::codec::ChunkCodec).
+    Decoded(std::io::Cursor<Vec<u8>>),
+    /// A compressed chunk that cannot be decoded because the `compression` or `lz4`
+    /// feature is disabled. Every read fails with
+    /// [`Unsupported`](std::io::ErrorKind::Unsupported).
+    #[cfg(not(all(feature = "compression", feature = "lz4")))]
+    Unsupported(CompressionType),
+}
+
+impl<'a> ChunkReader<'a> {
+    /// Decompresses `data` according to `compression_type`.
+    pub fn new(compression_type: CompressionType, data: &'a [u8]) -> Self {
+        match compression_type {
+            #[cfg(feature = "compression")]
+            CompressionType::Gzip => ChunkReader::Gzip(GzDecoder::new(data)),
+            #[cfg(feature = "compression")]
+            CompressionType::Zlib => ChunkReader::Zlib(ZlibDecoder::new(data)),
+            CompressionType::None => ChunkReader::None(data),
+            #[cfg(feature = "lz4")]
+            CompressionType::Lz4 => ChunkReader::Lz4(Lz4Decoder::new(data)),
+            #[cfg(not(all(feature = "compression", feature = "lz4")))]
+            compression => ChunkReader::Unsupported(compression),
+        }
+    }
+}
+
+impl Read for ChunkReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        match self {
+            #[cfg(feature = "compression")]
+            ChunkReader::Gzip(decoder) => decoder.read(buf),
+            #[cfg(feature = "compression")]
+            ChunkReader::Zlib(decoder) => decoder.read(buf),
+            ChunkReader::None(data) => data.read(buf),
+            #[cfg(feature = "lz4")]
+            ChunkReader::Lz4(decoder) => decoder.read(buf),
+            ChunkReader::Decoded(data) => data.read(buf),
+            #[cfg(not(all(feature = "compression", feature = "lz4")))]
+            ChunkReader::Unsupported(compression) => {
+                Err(crate::anvil::unsupported_compression(*compression))
+            }
+        }
+    }
+}
+
+/// Decompresses a chunk payload according to its compression type.
+pub(crate) fn decompress(compression_type: CompressionType, data: &[u8]) -> Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    ChunkReader::new(compression_type, data).read_to_end(&mut decoded)?;
+    Ok(decoded)
+}
+
+/// Decompresses a chunk payload, failing once it exceeds `max_chunk_size` bytes.
+pub(crate) fn decompress_limited(
+    compression: CompressionType,
+    data: &[u8],
+    max_chunk_size: Option<usize>,
+) -> Result<Vec<u8>> {
+    let Some(limit) = max_chunk_size else {
+        return decompress(compression, data);
+    };
+    let mut decoded = Vec::new();
+    ChunkReader::new(compression, data)
+        .take(limit as u64 + 1)
+        .read_to_end(&mut decoded)?;
+    check_chunk_size(decoded, max_chunk_size)
+}
+
+fn check_chunk_size(decoded: Vec<u8>, max_chunk_size: Option<usize>) -> Result<Vec<u8>> {
+    match max_chunk_size {
+        Some(limit) if decoded.len() > limit => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Chunk decompresses to more than {} bytes", limit),
+        )),
+        _ => Ok(decoded),
+    }
+}
+
+/// Parses decompressed chunk bytes into a named tag.
+pub(crate) fn parse_chunk(data: &[u8]) -> Result<(String, NbtTag)> {
+    let mut input = data;
+    parse_named_tag(&mut input)
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse NBT"))
 }
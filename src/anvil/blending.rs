@@ -0,0 +1,231 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! The chunk fields that drive the 1.18 world-height upgrade.
+//!
+//! When a pre-1.18 chunk is loaded, the game extends it below Y=0 (`below_zero_retrogen`)
+//! and blends its terrain with newly generated neighbours (`blending_data`). Tools that
+//! convert or prepare old worlds need to set both fields the way the game's own upgrade
+//! does, or old chunks end up with hard seams and no deepslate layer.
+
+use crate::anvil::chunk::Chunk;
+use crate::nbt::NbtTag;
+use indexmap::IndexMap;
+
+/// Terrain blending information of an upgraded chunk (`blending_data`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlendingData {
+    /// Lowest section index that holds old terrain (`min_section`).
+    pub min_section: i32,
+    /// Section index just above the old terrain (`max_section`).
+    pub max_section: i32,
+    /// Any other fields, such as cached `heights`, kept as they were read.
+    pub extra: IndexMap<String, NbtTag>,
+}
+
+impl BlendingData {
+    /// Blending data for a chunk from a world with the old 0..256 build height, as
+    /// written by the game's upgrade.
+    pub fn legacy() -> Self {
+        BlendingData {
+            min_section: 0,
+            max_section: 16,
+            extra: IndexMap::new(),
+        }
+    }
+
+    /// Reads a `blending_data` compound.
+    pub fn from_nbt(tag: &NbtTag) -> Option<Self> {
+        let NbtTag::Compound(map) = tag else {
+            return None;
+        };
+        let mut extra = map.clone();
+        let min_section = match extra.shift_remove("min_section") {
+            Some(NbtTag::Int(v)) => v,
+            _ => 0,
+        };
+        let max_section = match extra.shift_remove("max_section") {
+            Some(NbtTag::Int(v)) => v,
+            _ => 16,
+        };
+        Some(BlendingData {
+            min_section,
+            max_section,
+            extra,
+        })
+    }
+
+    /// Encodes the data as a `blending_data` compound.
+    pub fn to_nbt(&self) -> NbtTag {
+        let mut map = IndexMap::new();
+        map.insert("min_section".to_string(), NbtTag::Int(self.min_section));
+        map.insert("max_section".to_string(), NbtTag::Int(self.max_section));
+        map.extend(self.extra.clone());
+        NbtTag::Compound(map)
+    }
+}
+
+/// Pending generation below Y=0 for an upgraded chunk (`below_zero_retrogen`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BelowZeroRetrogen {
+    /// Status the new lower part is generated up to, usually `"minecraft:full"` for
+    /// fully generated chunks (`target_status`).
+    pub target_status: String,
+    /// A 256-bit set of columns (`x + z * 16`) without bedrock at the old bottom, where
+    /// the new bedrock floor is not placed (`missing_bedrock`).
+    pub missing_bedrock: Vec<i64>,
+}
+
+impl BelowZeroRetrogen {
+    /// Retrogen up to `target_status`, with bedrock present in every column.
+    pub fn new(target_status: &str) -> Self {
+        BelowZeroRetrogen {
+            target_status: target_status.to_string(),
+            missing_bedrock: Vec::new(),
+        }
+    }
+
+    /// Reads a `below_zero_retrogen` compound.
+    pub fn from_nbt(tag: &NbtTag) -> Option<Self> {
+        let NbtTag::Compound(map) = tag else {
+            return None;
+        };
+        let Some(NbtTag::String(target_status)) = map.get("target_status") else {
+            return None;
+        };
+        let missing_bedrock = match map.get("missing_bedrock") {
+            Some(NbtTag::LongArray(bits)) => bits.clone(),
+            _ => Vec::new(),
+        };
+        Some(BelowZeroRetrogen {
+            target_status: target_status.clone(),
+            missing_bedrock,
+        })
+    }
+
+    /// Encodes the data as a `below_zero_retrogen` compound.
+    pub fn to_nbt(&self) -> NbtTag {
+        let mut map = IndexMap::new();
+        map.insert(
+            "target_status".to_string(),
+            NbtTag::String(self.target_status.clone()),
+        );
+        if self.missing_bedrock.iter().any(|&bits| bits != 0) {
+            map.insert(
+                "missing_bedrock".to_string(),
+                NbtTag::LongArray(self.missing_bedrock.clone()),
+            );
+        }
+        NbtTag::Compound(map)
+    }
+
+    /// Returns `true` if column `(x, z)` (0-15) has no bedrock at the old bottom.
+    pub fn is_bedrock_missing(&self, x: usize, z: usize) -> bool {
+        let bit = x + z * 16;
+        self.missing_bedrock
+            .get(bit / 64)
+            .is_some_and(|word| (word >> (bit % 64)) & 1 != 0)
+    }
+
+    /// Marks column `(x, z)` (0-15) as missing (or having) bedrock at the old bottom.
+    pub fn set_bedrock_missing(&mut self, x: usize, z: usize, missing: bool) {
+        let bit = x + z * 16;
+        if self.missing_bedrock.len() <= bit / 64 {
+            self.missing_bedrock.resize(bit / 64 + 1, 0);
+        }
+        let mask = 1i64 << (bit % 64);
+        if missing {
+            self.missing_bedrock[bit / 64] |= mask;
+        } else {
+            self.missing_bedrock[bit / 64] &= !mask;
+        }
+    }
+}
+
+impl Chunk {
+    /// Returns the chunk's `blending_data`, if present.
+    pub fn blending_data(&self) -> Option<BlendingData> {
+        self.root
+            .get_path("blending_data")
+            .and_then(BlendingData::from_nbt)
+    }
+
+    /// Sets or (with `None`) removes the chunk's `blending_data`.
+    pub fn set_blending_data(&mut self, data: Option<&BlendingData>) {
+        match data {
+            Some(data) => {
+                self.root.set_path("blending_data", data.to_nbt()).ok();
+            }
+            None => {
+                self.root.remove_path("blending_data").ok();
+            }
+        }
+    }
+
+    /// Returns the chunk's `below_zero_retrogen`, if present.
+    pub fn below_zero_retrogen(&self) -> Option<BelowZeroRetrogen> {
+        self.root
+            .get_path("below_zero_retrogen")
+            .and_then(BelowZeroRetrogen::from_nbt)
+    }
+
+    /// Sets or (with `None`) removes the chunk's `below_zero_retrogen`.
+    pub fn set_below_zero_retrogen(&mut self, retrogen: Option<&BelowZeroRetrogen>) {
+        match retrogen {
+            Some(retrogen) => {
+                self.root
+                    .set_path("below_zero_retrogen", retrogen.to_nbt())
+                    .ok();
+            }
+            None => {
+                self.root.remove_path("below_zero_retrogen").ok();
+            }
+        }
+    }
+
+    /// Prepares a chunk from a pre-1.18 world for the world-height upgrade the way the
+    /// game does: blending over the old 0..256 range and, for chunks that were fully
+    /// generated, retrogen below Y=0 up to `minecraft:full`.
+    ///
+    /// Chunks that already carry either field are left untouched.
+    pub fn prepare_height_upgrade(&mut self) {
+        if self.blending_data().is_some() || self.below_zero_retrogen().is_some() {
+            return;
+        }
+        self.set_blending_data(Some(&BlendingData::legacy()));
+        let status = self
+            .root
+            .get_path("Status")
+            .or_else(|| self.root.get_path("Level.Status"));
+        if let Some(NbtTag::String(status)) = status {
+            let full = matches!(status.as_str(), "full" | "minecraft:full");
+            if full {
+                self.set_below_zero_retrogen(Some(&BelowZeroRetrogen::new("minecraft:full")));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::snbt::parse_snbt;
+
+    #[test]
+    fn test_prepare_height_upgrade() {
+        let mut chunk = Chunk::new(String::new(), parse_snbt("{Status: full}").unwrap());
+        chunk.prepare_height_upgrade();
+        assert_eq!(chunk.blending_data(), Some(BlendingData::legacy()));
+
+        let mut retrogen = chunk.below_zero_retrogen().unwrap();
+        assert_eq!(retrogen.target_status, "minecraft:full");
+        retrogen.set_bedrock_missing(15, 15, true);
+        assert!(retrogen.is_bedrock_missing(15, 15));
+        assert!(!retrogen.is_bedrock_missing(0, 0));
+        chunk.set_below_zero_retrogen(Some(&retrogen));
+        assert_eq!(chunk.below_zero_retrogen(), Some(retrogen));
+
+        chunk.set_blending_data(None);
+        assert_eq!(chunk.blending_data(), None);
+    }
+}
@@ -0,0 +1,242 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Memoization of decoded chunk NBT for interactive tools.
+//!
+//! Viewers and editors tend to revisit the same chunks over and over. A [`ChunkCache`]
+//! keeps the most recently used decoded chunks in memory, keyed by region, chunk position,
+//! the chunk's header entries and its length, so a chunk rewritten on disk is not served
+//! stale. Timestamps only have one-second resolution and copied chunks keep theirs, so
+//! the key also changes whenever the chunk moves or its length changes; only a rewrite
+//! in place to exactly the same length within the same second goes unnoticed. Call
+//! [`ChunkCache::clear`] after such edits. One cache can be shared by any number of
+//! [`CachedRegion`]s.
+//!
+//! A cache is bounded by entry count ([`ChunkCache::new`]) or by the total decompressed
+//! size of the cached chunks ([`ChunkCache::with_byte_budget`]), which keeps memory use
+//! predictable when chunk sizes vary a lot.
+
+use crate::anvil::access::{Region, chunk_index, parse_chunk};
+use crate::nbt::NbtTag;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Result;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// A decoded chunk as stored in the cache: the root tag name and the root tag.
+pub type CachedChunk = Arc<(String, NbtTag)>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    region: Arc<Path>,
+    index: u16,
+    timestamp: u32,
+    offset: u32,
+    sector_count: u8,
+    /// The chunk's length prefix.
+    length: u32,
+}
+
+/// Hit and miss counters of a [`ChunkCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups answered from the cache.
+    pub hits: u64,
+    /// Lookups that had to decode the chunk.
+    pub misses: u64,
+}
+
+#[derive(Default)]
+struct LruState {
+    /// Cached chunks with their decompressed size and last use tick.
+    entries: HashMap<CacheKey, (CachedChunk, usize, u64)>,
+    /// Entries ordered by last use; the smallest tick is evicted first.
+    order: BTreeMap<u64, CacheKey>,
+    tick: u64,
+    /// Sum of the decompressed sizes of all entries.
+    bytes: usize,
+    stats: CacheStats,
+}
+
+/// A thread-safe, least-recently-used cache of decoded chunks bounded by entry count or
+/// by decompressed size.
+pub struct ChunkCache {
+    capacity: usize,
+    byte_budget: Option<usize>,
+    state: Mutex<LruState>,
+}
+
+impl ChunkCache {
+    /// Creates a cache holding at most `capacity` decoded chunks.
+    pub fn new(capacity: usize) -> Self {
+        ChunkCache {
+            capacity,
+            byte_budget: None,
+            state: Mutex::new(LruState::default()),
+        }
+    }
+
+    /// Creates a cache holding decoded chunks whose decompressed NBT data totals at most
+    /// `bytes`, however many chunks that is.
+    ///
+    /// A chunk larger than the whole budget is not cached.
+    pub fn with_byte_budget(bytes: usize) -> Self {
+        ChunkCache {
+            capacity: usize::MAX,
+            byte_budget: Some(bytes),
+            state: Mutex::new(LruState::default()),
+        }
+    }
+
+    /// Returns the maximum number of chunks kept.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the maximum total decompressed size of the cached chunks, if bounded.
+    pub fn byte_budget(&self) -> Option<usize> {
+        self.byte_budget
+    }
+
+    /// Returns the total decompressed size of the chunks currently cached.
+    pub fn bytes(&self) -> usize {
+        self.lock().bytes
+    }
+
+    /// Returns the number of chunks currently cached.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Returns `true` if no chunks are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the hit and miss counters accumulated so far.
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats
+    }
+
+    /// Drops all cached chunks.
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.entries.clear();
+        state.order.clear();
+        state.bytes = 0;
+    }
+
+    fn get(&self, key: &CacheKey) -> Option<CachedChunk> {
+        let mut state = self.lock();
+        state.tick += 1;
+        let tick = state.tick;
+        let Some((chunk, _, last_used)) = state.entries.get_mut(key) else {
+            state.stats.misses += 1;
+            return None;
+        };
+        let chunk = chunk.clone();
+        let previous = std::mem::replace(last_used, tick);
+        state.order.remove(&previous);
+        state.order.insert(tick, key.clone());
+        state.stats.hits += 1;
+        Some(chunk)
+    }
+
+    /// Caches `chunk`, whose decompressed NBT data is `size` bytes long.
+    fn insert(&self, key: CacheKey, chunk: CachedChunk, size: usize) {
+        if self.capacity == 0 || self.byte_budget.is_some_and(|budget| size > budget) {
+            return;
+        }
+        let mut state = self.lock();
+        state.tick += 1;
+        let tick = state.tick;
+        if let Some((_, previous_size, previous)) =
+            state.entries.insert(key.clone(), (chunk, size, tick))
+        {
+            state.order.remove(&previous);
+            state.bytes -= previous_size;
+        }
+        state.order.insert(tick, key);
+        state.bytes += size;
+        while state.entries.len() > self.capacity
+            || self.byte_budget.is_some_and(|budget| state.bytes > budget)
+        {
+            let Some((_, oldest)) = state.order.pop_first() else {
+                break;
+            };
+            if let Some((_, size, _)) = state.entries.remove(&oldest) {
+                state.bytes -= size;
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LruState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A [`Region`] whose decoded chunks are memoized in a shared [`ChunkCache`].
+pub struct CachedRegion {
+    region: Region,
+    key: Arc<Path>,
+    cache: Arc<ChunkCache>,
+}
+
+impl CachedRegion {
+    /// Opens a region file whose chunks are cached in `cache`, keyed by `path`.
+    pub fn open<P: AsRef<Path>>(path: P, cache: Arc<ChunkCache>) -> Result<Self> {
+        let region = Region::open(&path)?;
+        Ok(Self::new(region, path, cache))
+    }
+
+    /// Wraps an already opened region. `key` identifies the region in the cache and is
+    /// usually its path; regions sharing a cache must use distinct keys.
+    pub fn new<P: AsRef<Path>>(region: Region, key: P, cache: Arc<ChunkCache>) -> Self {
+        CachedRegion {
+            region,
+            key: Arc::from(key.as_ref()),
+            cache,
+        }
+    }
+
+    /// Returns the underlying region.
+    pub fn region(&self) -> &Region {
+        &self.region
+    }
+
+    /// Returns the cache shared by this region.
+    pub fn cache(&self) -> &Arc<ChunkCache> {
+        &self.cache
+    }
+
+    /// Parses the NBT data for a chunk, serving it from the cache when possible.
+    ///
+    /// Behaves like [`Region::get_chunk_nbt`], but returns a shared handle to the
+    /// decoded chunk. Absent chunks and errors are not cached.
+    pub fn get_chunk_nbt(&self, x: i32, z: i32) -> Result<Option<CachedChunk>> {
+        let index = chunk_index(x, z);
+        let Some((_, payload)) = self.region.raw_payload(index)? else {
+            return Ok(None);
+        };
+        let location = self.region.header().locations[index];
+        let key = CacheKey {
+            region: self.key.clone(),
+            index: index as u16,
+            timestamp: self.region.header().timestamps[index],
+            offset: location.offset,
+            sector_count: location.sector_count,
+            length: payload.len() as u32 + 1,
+        };
+        if let Some(chunk) = self.cache.get(&key) {
+            return Ok(Some(chunk));
+        }
+        match self.region.get_chunk_data(x, z)? {
+            Some(data) => {
+                let chunk = Arc::new(parse_chunk(&data)?);
+                self.cache.insert(key, chunk.clone(), data.len());
+                Ok(Some(chunk))
+            }
+            None => Ok(None),
+        }
+    }
+}
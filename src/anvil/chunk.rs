@@ -0,0 +1,382 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Typed access to the contents of a terrain chunk.
+
+use crate::anvil::access::Region;
+use crate::nbt::NbtTag;
+use indexmap::IndexMap;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{ErrorKind, Result};
+
+/// Number of blocks in a 16x16x16 chunk section.
+const SECTION_BLOCKS: usize = 4096;
+
+/// First data version (20w17a) whose block state arrays never span two longs.
+const NON_SPANNING_VERSION: i32 = 2527;
+
+/// A block state as stored in a section palette, e.g. `minecraft:chest[facing=north]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BlockState {
+    /// The namespaced block ID.
+    pub name: String,
+    /// Block state properties, sorted by key.
+    pub properties: BTreeMap<String, String>,
+}
+
+impl BlockState {
+    /// Reads a palette entry (`{Name: "...", Properties: {...}}`).
+    pub fn from_nbt(tag: &NbtTag) -> Option<Self> {
+        let NbtTag::Compound(map) = tag else {
+            return None;
+        };
+        let Some(NbtTag::String(name)) = map.get("Name") else {
+            return None;
+        };
+        let mut properties = BTreeMap::new();
+        if let Some(NbtTag::Compound(props)) = map.get("Properties") {
+            for (key, value) in props {
+                if let NbtTag::String(value) = value {
+                    properties.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        Some(BlockState {
+            name: name.clone(),
+            properties,
+        })
+    }
+
+    /// Parses the `name[key=value,...]` form produced by [`Display`](fmt::Display).
+    pub fn parse(s: &str) -> Option<Self> {
+        let (name, properties) = match s.split_once('[') {
+            Some((name, rest)) => (name, rest.strip_suffix(']')?),
+            None => (s, ""),
+        };
+        if name.is_empty() {
+            return None;
+        }
+        let mut map = BTreeMap::new();
+        for pair in properties.split(',').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=')?;
+            map.insert(key.to_string(), value.to_string());
+        }
+        Some(BlockState {
+            name: name.to_string(),
+            properties: map,
+        })
+    }
+
+    /// Builds a palette entry, omitting `Properties` when there are none.
+    pub fn to_nbt(&self) -> NbtTag {
+        let mut map = IndexMap::new();
+        map.insert("Name".to_string(), NbtTag::String(self.name.clone()));
+        if !self.properties.is_empty() {
+            let properties = self
+                .properties
+                .iter()
+                .map(|(key, value)| (key.clone(), NbtTag::String(value.clone())))
+                .collect();
+            map.insert("Properties".to_string(), NbtTag::Compound(properties));
+        }
+        NbtTag::Compound(map)
+    }
+}
+
+impl fmt::Display for BlockState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+        if !self.properties.is_empty() {
+            f.write_str("[")?;
+            for (i, (key, value)) in self.properties.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(",")?;
+                }
+                write!(f, "{}={}", key, value)?;
+            }
+            f.write_str("]")?;
+        }
+        Ok(())
+    }
+}
+
+/// A decoded terrain chunk.
+///
+/// Both the current layout and the pre-1.18 one, which nests everything in a `Level`
+/// compound, are understood.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    /// The name of the root tag (usually empty).
+    pub name: String,
+    /// The root compound of the chunk.
+    pub root: NbtTag,
+}
+
+impl Chunk {
+    /// Wraps a parsed chunk root.
+    pub fn new(name: String, root: NbtTag) -> Self {
+        Chunk { name, root }
+    }
+
+    /// Returns the chunk's `DataVersion`, if present.
+    pub fn data_version(&self) -> Option<i32> {
+        match field(&self.root, "DataVersion") {
+            Some(NbtTag::Int(version)) => Some(*version),
+            _ => None,
+        }
+    }
+
+    /// Returns the chunk's section list (`sections`, or `Level.Sections` in old chunks).
+    pub fn sections(&self) -> &[NbtTag] {
+        let sections = field(&self.root, "sections")
+            .or_else(|| field(&self.root, "Level").and_then(|l| field(l, "Sections")));
+        match sections {
+            Some(NbtTag::List(sections)) => sections,
+            _ => &[],
+        }
+    }
+
+    /// Removes all stored light (`SkyLight` and `BlockLight` of every section) and clears
+    /// `isLightOn`, so the game recomputes lighting when the chunk is loaded.
+    ///
+    /// Editors should call this after changing blocks, since stale light is otherwise
+    /// kept as-is. Returns the number of light arrays removed.
+    pub fn strip_lighting(&mut self) -> usize {
+        let mut removed = 0;
+        for sections in ["sections", "Level.Sections"] {
+            for light in ["SkyLight", "BlockLight"] {
+                let path = format!("{}[].{}", sections, light);
+                removed += self.root.remove_path(&path).map_or(0, |r| r.len());
+            }
+        }
+        for flag in ["isLightOn", "Level.isLightOn"] {
+            if let Some(light_on) = self.root.get_path_mut(flag) {
+                *light_on = NbtTag::Byte(0);
+            }
+        }
+        removed
+    }
+
+    /// Counts the blocks in the chunk per block state.
+    ///
+    /// Sections with a single-entry palette are counted without touching their data
+    /// array; only mixed sections are unpacked.
+    pub fn block_counts(&self) -> Result<BTreeMap<BlockState, u64>> {
+        self.block_counts_matching(|_| true)
+    }
+
+    /// Like [`block_counts`](Self::block_counts), but only counts states accepted by
+    /// `filter`. Sections whose palette has no accepted state are skipped entirely.
+    pub fn block_counts_matching<F>(&self, mut filter: F) -> Result<BTreeMap<BlockState, u64>>
+    where
+        F: FnMut(&BlockState) -> bool,
+    {
+        let spanning = self
+            .data_version()
+            .is_some_and(|v| v < NON_SPANNING_VERSION);
+        let mut counts = BTreeMap::new();
+        for section in self.sections() {
+            let (palette, data) = match field(section, "block_states") {
+                Some(states) => (field(states, "palette"), field(states, "data")),
+                None => (field(section, "Palette"), field(section, "BlockStates")),
+            };
+            let Some(NbtTag::List(palette)) = palette else {
+                continue;
+            };
+            let states: Vec<Option<BlockState>> = palette
+                .iter()
+                .map(|entry| BlockState::from_nbt(entry).filter(|s| filter(s)))
+                .collect();
+            if states.iter().all(Option::is_none) {
+                continue;
+            }
+
+            let per_index = match (palette.len(), data) {
+                (1, _) => vec![SECTION_BLOCKS as u64],
+                (_, Some(NbtTag::LongArray(data))) => count_indices(data, palette.len(), spanning)?,
+                _ => return Err(invalid_section("mixed palette without block data")),
+            };
+            for (state, count) in states.into_iter().zip(per_index) {
+                if let Some(state) = state
+                    && count > 0
+                {
+                    *counts.entry(state).or_insert(0) += count;
+                }
+            }
+        }
+        Ok(counts)
+    }
+}
+
+/// Unpacks a section's block state array and counts how often each palette index occurs.
+fn count_indices(data: &[i64], palette_len: usize, spanning: bool) -> Result<Vec<u64>> {
+    let bits = section_bits(palette_len);
+    let mut counts = vec![0u64; palette_len];
+    if data.len() < packed_len(SECTION_BLOCKS, bits, spanning) {
+        return Err(invalid_section("block data array too short"));
+    }
+    for i in 0..SECTION_BLOCKS {
+        match counts.get_mut(unpack(data, i, bits, spanning) as usize) {
+            Some(count) => *count += 1,
+            None => return Err(invalid_section("palette index out of range")),
+        }
+    }
+    Ok(counts)
+}
+
+/// Returns the number of bits needed to store indices into a palette of `len` entries.
+pub(crate) fn bits_for(len: usize) -> usize {
+    (usize::BITS - len.saturating_sub(1).leading_zeros()) as usize
+}
+
+/// Returns the bits per entry of a section's block state array, which the game never
+/// makes narrower than 4.
+pub(crate) fn section_bits(palette_len: usize) -> usize {
+    bits_for(palette_len).max(4)
+}
+
+/// Returns the number of longs a packed array of `count` entries of `bits` bits takes.
+///
+/// Entries of `spanning` arrays, as in chunks before 20w17a and in Litematica
+/// schematics, may be split across two longs. Otherwise each long holds `64 / bits`
+/// entries and its remaining high bits are padding.
+pub(crate) fn packed_len(count: usize, bits: usize, spanning: bool) -> usize {
+    if spanning {
+        (count * bits).div_ceil(64)
+    } else {
+        count.div_ceil(64 / bits)
+    }
+}
+
+/// Reads entry `i` of a packed array laid out as described by [`packed_len`].
+pub(crate) fn unpack(data: &[i64], i: usize, bits: usize, spanning: bool) -> u64 {
+    let mask = (1u64 << bits) - 1;
+    if spanning {
+        let bit = i * bits;
+        let (word, offset) = (bit / 64, bit % 64);
+        let mut value = (data[word] as u64) >> offset;
+        if offset + bits > 64 {
+            value |= (data[word + 1] as u64) << (64 - offset);
+        }
+        value & mask
+    } else {
+        let per_long = 64 / bits;
+        ((data[i / per_long] as u64) >> ((i % per_long) * bits)) & mask
+    }
+}
+
+/// Packs `values` of at most `bits` bits each; the inverse of [`unpack`].
+pub(crate) fn pack(values: &[u64], bits: usize, spanning: bool) -> Vec<i64> {
+    let mut data = vec![0u64; packed_len(values.len(), bits, spanning)];
+    for (i, &value) in values.iter().enumerate() {
+        if spanning {
+            let bit = i * bits;
+            let (word, offset) = (bit / 64, bit % 64);
+            data[word] |= value << offset;
+            if offset + bits > 64 {
+                data[word + 1] |= value >> (64 - offset);
+            }
+        } else {
+            let per_long = 64 / bits;
+            data[i / per_long] |= value << ((i % per_long) * bits);
+        }
+    }
+    data.into_iter().map(|l| l as i64).collect()
+}
+
+/// Looks up `key` if `tag` is a compound.
+fn field<'a>(tag: &'a NbtTag, key: &str) -> Option<&'a NbtTag> {
+    match tag {
+        NbtTag::Compound(map) => map.get(key),
+        _ => None,
+    }
+}
+
+fn invalid_section(message: &str) -> std::io::Error {
+    std::io::Error::new(
+        ErrorKind::InvalidData,
+        format!("Invalid chunk section: {}", message),
+    )
+}
+
+impl Region {
+    /// Reads the chunk at the given coordinates as a typed [`Chunk`].
+    pub fn get_chunk(&self, x: i32, z: i32) -> Result<Option<Chunk>> {
+        Ok(self
+            .get_chunk_nbt(x, z)?
+            .map(|(name, root)| Chunk::new(name, root)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::snbt::parse_snbt;
+
+    #[test]
+    fn test_strip_lighting() {
+        let root = parse_snbt(
+            "{isLightOn: 1b, sections: [{Y: 0b, SkyLight: [B; 1b], BlockLight: [B; 2b]}, {Y: 1b}]}",
+        )
+        .unwrap();
+        let mut chunk = Chunk::new(String::new(), root);
+        assert_eq!(chunk.strip_lighting(), 2);
+        assert_eq!(
+            chunk.root,
+            parse_snbt("{isLightOn: 0b, sections: [{Y: 0b}, {Y: 1b}]}").unwrap()
+        );
+
+        let legacy = parse_snbt("{Level: {isLightOn: 1b, Sections: [{SkyLight: [B;]}]}}").unwrap();
+        let mut chunk = Chunk::new(String::new(), legacy);
+        assert_eq!(chunk.strip_lighting(), 1);
+        assert_eq!(
+            chunk.root.get_path("Level.isLightOn"),
+            Some(&NbtTag::Byte(0))
+        );
+    }
+
+    #[test]
+    fn test_count_indices_packing() {
+        // 5 bits per entry: 12 entries per long without spanning, 4096 * 5 / 64 longs with
+        let palette_len = 17;
+        let mut packed = vec![0i64; 4096usize.div_ceil(12)];
+        for i in 0..4096 {
+            packed[i / 12] |= ((i % palette_len) as i64) << ((i % 12) * 5);
+        }
+        let counts = count_indices(&packed, palette_len, false).unwrap();
+        assert_eq!(counts.iter().sum::<u64>(), 4096);
+        assert_eq!(counts[0], 241);
+        assert_eq!(counts[16], 240);
+
+        let mut spanning = vec![0u64; 4096 * 5 / 64];
+        for i in 0..4096 {
+            let (bit, value) = (i * 5, (i % palette_len) as u64);
+            spanning[bit / 64] |= value << (bit % 64);
+            if bit % 64 + 5 > 64 {
+                spanning[bit / 64 + 1] |= value >> (64 - bit % 64);
+            }
+        }
+        let spanning: Vec<i64> = spanning.into_iter().map(|v| v as i64).collect();
+        assert_eq!(count_indices(&spanning, palette_len, true).unwrap(), counts);
+
+        assert!(count_indices(&packed[..10], palette_len, false).is_err());
+    }
+
+    #[test]
+    fn test_pack_round_trip() {
+        let values: Vec<u64> = (0..100).map(|i| i % 21).collect();
+        for spanning in [false, true] {
+            let packed = pack(&values, 5, spanning);
+            assert_eq!(packed.len(), packed_len(values.len(), 5, spanning));
+            let unpacked: Vec<u64> = (0..values.len())
+                .map(|i| unpack(&packed, i, 5, spanning))
+                .collect();
+            assert_eq!(unpacked, values);
+        }
+        assert_eq!(packed_len(4096, 5, false), 342);
+        assert_eq!(packed_len(4096, 5, true), 320);
+        assert_eq!((bits_for(1), bits_for(2), bits_for(17)), (0, 1, 5));
+        assert_eq!(section_bits(2), 4);
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Chunks stored with a custom compression algorithm.
+//!
+//! Compression id 127 marks a chunk compressed by an algorithm outside the format, such
+//! as one added by a server mod. The payload starts with the algorithm's namespaced name,
+//! prefixed by its length as an unsigned 16-bit integer, followed by the compressed data.
+//!
+//! This crate does not know any custom algorithm. [`CustomPayload`] exposes the name and
+//! the raw data, and a [`CodecRegistry`] attached to a
+//! [`Region`](crate::anvil::access::Region) lets reads decode the algorithms you
+//! register:
+//!
+//! ```
+//! use anvil_nbt::anvil::codec::CodecRegistry;
+//!
+//! let mut codecs = CodecRegistry::new();
+//! codecs.register("example:identity", |data: &[u8]| Ok(data.to_vec()));
+//! assert!(codecs.get("example:identity").is_some());
+//! ```
+
+use std::collections::HashMap;
+use std::io::Result;
+use std::sync::Arc;
+
+/// The compression id of chunks using a custom algorithm.
+pub const CUSTOM_COMPRESSION_ID: u8 = 127;
+
+/// The payload of a chunk with compression id 127, split into algorithm and data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CustomPayload<'a> {
+    /// The namespaced algorithm name, such as `"example:lz4"`.
+    pub algorithm: &'a str,
+    /// The compressed chunk data following the name.
+    pub data: &'a [u8],
+}
+
+impl<'a> CustomPayload<'a> {
+    /// Splits a chunk payload (everything after the compression id) into the algorithm
+    /// name and the compressed data.
+    pub fn parse(payload: &'a [u8]) -> Result<Self> {
+        let invalid = |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, msg);
+        let Some((len, rest)) = payload.split_first_chunk::<2>() else {
+            return Err(invalid("Custom compression header is truncated"));
+        };
+        let len = u16::from_be_bytes(*len) as usize;
+        if rest.len() < len {
+            return Err(invalid("Custom compression header is truncated"));
+        }
+        let (name, data) = rest.split_at(len);
+        let algorithm = std::str::from_utf8(name)
+            .map_err(|_| invalid("Custom compression name is not valid UTF-8"))?;
+        Ok(CustomPayload { algorithm, data })
+    }
+}
+
+/// Decompresses chunks for one custom algorithm.
+///
+/// Closures of the right signature implement this trait.
+pub trait ChunkCodec: Send + Sync {
+    /// Returns the decompressed NBT data of a chunk.
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>>;
+}
+
+impl<F> ChunkCodec for F
+where
+    F: Fn(&[u8]) -> Result<Vec<u8>> + Send + Sync,
+{
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self(data)
+    }
+}
+
+/// Custom compression algorithms by name.
+///
+/// Clones share the registered codecs.
+#[derive(Clone, Default)]
+pub struct CodecRegistry {
+    codecs: HashMap<String, Arc<dyn ChunkCodec>>,
+}
+
+impl CodecRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the codec for `algorithm`, replacing any previous one.
+    pub fn register(&mut self, algorithm: impl Into<String>, codec: impl ChunkCodec + 'static) {
+        self.codecs.insert(algorithm.into(), Arc::new(codec));
+    }
+
+    /// Returns the codec registered for `algorithm`.
+    pub fn get(&self, algorithm: &str) -> Option<&dyn ChunkCodec> {
+        self.codecs.get(algorithm).map(|codec| codec.as_ref())
+    }
+
+    /// Decompresses a custom payload with its registered codec.
+    ///
+    /// Fails with [`Unsupported`](std::io::ErrorKind::Unsupported) if no codec is
+    /// registered for the payload's algorithm.
+    pub fn decompress(&self, payload: &CustomPayload<'_>) -> Result<Vec<u8>> {
+        let codec = self.get(payload.algorithm).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!(
+                    "No codec registered for custom compression {}",
+                    payload.algorithm
+                ),
+            )
+        })?;
+        codec.decompress(payload.data)
+    }
+}
+
+impl std::fmt::Debug for CodecRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.codecs.keys()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_payload_parse() {
+        let payload = [0, 4, b'a', b':', b'b', b'c', 1, 2, 3];
+        let parsed = CustomPayload::parse(&payload).unwrap();
+        assert_eq!(parsed.algorithm, "a:bc");
+        assert_eq!(parsed.data, &[1, 2, 3]);
+
+        assert!(CustomPayload::parse(&[0]).is_err());
+        assert!(CustomPayload::parse(&[0, 5, b'a']).is_err());
+    }
+}
@@ -0,0 +1,467 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Copy-on-write editing of region files.
+//!
+//! A [`CowRegion`] never touches the original `.mca` file while edits are being made.
+//! Every modification is appended to a sidecar journal (`<file>.mca.cow`) and kept in
+//! memory as an overlay over the original chunks. [`CowRegion::commit`] materializes the
+//! merged result into a new file and atomically renames it over the original;
+//! [`CowRegion::discard`] throws all edits away. If the process dies before either, the
+//! journal is replayed the next time the region is opened.
+//!
+//! How often the journal and the committed file are synced to disk is controlled by
+//! [`SyncPolicy`]; [`CowRegion::flush`] syncs the journal on demand.
+//!
+//! Tools that do not want to resume a crashed session can settle it up front with
+//! [`Region::recover`], which either applies or drops whatever the journal holds.
+
+use crate::anvil::access::{Region, chunk_index, decompress, parse_chunk};
+use crate::anvil::encode::{RegionWriter, compress_chunk};
+use crate::anvil::{CompressionPreset, CompressionType, SyncPolicy, to_timestamp};
+use crate::cancel::CancellationToken;
+use crate::nbt::NbtTag;
+use crate::nbt::encode::KeyCache;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Error, ErrorKind, Read, Result, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Magic bytes at the start of every journal file.
+const JOURNAL_MAGIC: &[u8; 8] = b"ANVILCOW";
+
+const RECORD_SET: u8 = 1;
+const RECORD_REMOVE: u8 = 2;
+const RECORD_REVERT: u8 = 3;
+/// Every record ends with a CRC-32 of its other bytes.
+const CRC_LEN: usize = 4;
+
+/// What [`Region::recover`] does with edits left in a journal by an interrupted session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalRecovery {
+    /// Apply every complete journaled edit to the region file and delete the journal.
+    Replay,
+    /// Delete the journal, leaving the region file as it was before the session.
+    Discard,
+}
+
+/// A pending modification of one chunk.
+type Overlay = Option<(CompressionType, Vec<u8>)>;
+
+/// A region opened for copy-on-write editing.
+///
+/// Reads see the original chunks with all pending edits applied on top.
+pub struct CowRegion {
+    path: PathBuf,
+    journal_path: PathBuf,
+    base: Region,
+    journal: File,
+    /// Pending edits by header index; `None` marks a removed chunk.
+    overlay: HashMap<usize, Overlay>,
+    sync_policy: SyncPolicy,
+    /// Encoded keys shared by all chunks written through this region.
+    keys: KeyCache,
+    compression: CompressionPreset,
+    cancellation: Option<CancellationToken>,
+}
+
+impl CowRegion {
+    /// Opens a region for copy-on-write editing.
+    ///
+    /// If a journal from an earlier session exists next to the file, its complete
+    /// records are replayed so that uncommitted edits survive a crash. Replay stops at
+    /// the first record that is cut short, fails its checksum or is not a known record,
+    /// as left by a crash mid-write; that record and everything after it are dropped.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let journal_path = journal_path_for(&path);
+        let base = Region::open(&path)?;
+
+        let mut overlay = HashMap::new();
+        let mut journal = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&journal_path)?;
+        let mut contents = Vec::new();
+        journal.read_to_end(&mut contents)?;
+
+        let valid_len = if contents.is_empty() {
+            journal.write_all(JOURNAL_MAGIC)?;
+            JOURNAL_MAGIC.len()
+        } else if contents.starts_with(JOURNAL_MAGIC) {
+            replay(&contents[JOURNAL_MAGIC.len()..], &mut overlay) + JOURNAL_MAGIC.len()
+        } else {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Region journal has an unknown format",
+            ));
+        };
+        journal.set_len(valid_len as u64)?;
+
+        Ok(CowRegion {
+            path,
+            journal_path,
+            base,
+            journal,
+            overlay,
+            sync_policy: SyncPolicy::default(),
+            keys: KeyCache::new(),
+            compression: CompressionPreset::default(),
+            cancellation: None,
+        })
+    }
+
+    /// Returns the current [`SyncPolicy`].
+    pub fn sync_policy(&self) -> SyncPolicy {
+        self.sync_policy
+    }
+
+    /// Sets when the journal and committed file are synced to disk.
+    pub fn set_sync_policy(&mut self, policy: SyncPolicy) {
+        self.sync_policy = policy;
+    }
+
+    /// Sets how chunks passed to [`set_chunk`](Self::set_chunk) are compressed
+    /// ([`CompressionPreset::Balanced`] by default).
+    pub fn set_compression(&mut self, preset: CompressionPreset) {
+        self.compression = preset;
+    }
+
+    /// Makes [`commit`](Self::commit) check `token` before writing each chunk and fail
+    /// with [`Interrupted`](ErrorKind::Interrupted) once it is cancelled. A cancelled
+    /// commit leaves the original file and the journal untouched.
+    pub fn set_cancellation(&mut self, token: Option<CancellationToken>) {
+        self.cancellation = token;
+    }
+
+    /// Forces all journaled edits to stable storage, regardless of the sync policy.
+    ///
+    /// After this returns, the edits made so far survive a crash or power loss and will be
+    /// replayed by the next [`open`](Self::open).
+    pub fn flush(&mut self) -> Result<()> {
+        self.journal.flush()?;
+        self.journal.sync_data()
+    }
+
+    /// Returns the path of the sidecar journal holding uncommitted edits.
+    pub fn journal_path(&self) -> &Path {
+        &self.journal_path
+    }
+
+    /// Returns `true` if there are edits that have not been committed.
+    pub fn is_modified(&self) -> bool {
+        !self.overlay.is_empty()
+    }
+
+    /// Returns the coordinates (0-31) of all chunks with pending edits.
+    pub fn modified_chunks(&self) -> Vec<(i32, i32)> {
+        let mut coords: Vec<_> = self
+            .overlay
+            .keys()
+            .map(|&index| ((index % 32) as i32, (index / 32) as i32))
+            .collect();
+        coords.sort_unstable_by_key(|&(x, z)| (z, x));
+        coords
+    }
+
+    /// Retrieves the decompressed NBT data for a chunk, with pending edits applied.
+    ///
+    /// See [`Region::get_chunk_data`] for the coordinate conventions.
+    pub fn get_chunk_data(&self, x: i32, z: i32) -> Result<Option<Vec<u8>>> {
+        match self.overlay.get(&chunk_index(x, z)) {
+            Some(Some((compression, data))) => decompress(*compression, data).map(Some),
+            Some(None) => Ok(None),
+            None => self.base.get_chunk_data(x, z),
+        }
+    }
+
+    /// Parses the NBT data for a chunk, with pending edits applied.
+    pub fn get_chunk_nbt(&self, x: i32, z: i32) -> Result<Option<(String, NbtTag)>> {
+        match self.get_chunk_data(x, z)? {
+            Some(data) => parse_chunk(&data).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Replaces (or inserts) a chunk. The change is journaled but the original file
+    /// is left untouched until [`commit`](Self::commit).
+    pub fn set_chunk(&mut self, x: i32, z: i32, name: &str, tag: &NbtTag) -> Result<()> {
+        let index = chunk_index(x, z);
+        let compressed = compress_chunk(name, tag, &mut self.keys, self.compression)?;
+
+        let mut record = Vec::with_capacity(compressed.len() + 8 + CRC_LEN);
+        record.push(RECORD_SET);
+        record.extend_from_slice(&(index as u16).to_be_bytes());
+        let compression = self.compression.chunk_compression();
+        record.push(compression as u8);
+        record.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+        record.extend_from_slice(&compressed);
+        self.append(&mut record)?;
+
+        self.overlay.insert(index, Some((compression, compressed)));
+        Ok(())
+    }
+
+    /// Removes a chunk so that it will be absent after [`commit`](Self::commit).
+    pub fn remove_chunk(&mut self, x: i32, z: i32) -> Result<()> {
+        let index = chunk_index(x, z);
+        self.append_index_record(RECORD_REMOVE, index)?;
+        self.overlay.insert(index, None);
+        Ok(())
+    }
+
+    /// Undoes all pending edits to a chunk, restoring the original file's version.
+    pub fn revert_chunk(&mut self, x: i32, z: i32) -> Result<()> {
+        let index = chunk_index(x, z);
+        if self.overlay.contains_key(&index) {
+            self.append_index_record(RECORD_REVERT, index)?;
+            self.overlay.remove(&index);
+        }
+        Ok(())
+    }
+
+    /// Writes the merged region to a temporary file (`<file>.mca.cow.tmp`), renames it
+    /// over the original and deletes the journal. If writing or renaming fails, the
+    /// temporary file is removed and the journal is kept.
+    ///
+    /// Unmodified chunks are copied as raw compressed bytes along with their timestamps;
+    /// edited chunks are stamped with the current time.
+    pub fn commit(self) -> Result<()> {
+        let tmp_path = tmp_path_for(&self.path);
+        let sync = self.sync_policy != SyncPolicy::Never;
+        if let Err(e) = self.write_merged(&tmp_path, sync) {
+            fs::remove_file(&tmp_path).ok();
+            return Err(e);
+        }
+
+        let CowRegion {
+            path,
+            journal_path,
+            base,
+            journal,
+            ..
+        } = self;
+        drop(base);
+        drop(journal);
+        if let Err(e) = fs::rename(&tmp_path, &path) {
+            fs::remove_file(&tmp_path).ok();
+            return Err(e);
+        }
+        if sync {
+            sync_parent_dir(&path)?;
+        }
+        fs::remove_file(journal_path)
+    }
+
+    /// Writes the original chunks with the pending edits applied to `tmp_path`.
+    fn write_merged(&self, tmp_path: &Path, sync: bool) -> Result<()> {
+        let now = to_timestamp(SystemTime::now());
+        let mut timestamps = [0u32; 1024];
+        let mut payloads = Vec::new();
+        for (index, timestamp) in timestamps.iter_mut().enumerate() {
+            match self.overlay.get(&index) {
+                Some(Some((compression, data))) => {
+                    payloads.push((index, *compression, &data[..]));
+                    *timestamp = now;
+                }
+                Some(None) => {}
+                None => {
+                    if let Some((compression, data)) = self.base.chunk_payload(index)? {
+                        payloads.push((index, compression, data));
+                        *timestamp = self.base.header().timestamps[index];
+                    }
+                }
+            }
+        }
+
+        let file = File::create(tmp_path)?;
+        let mut writer = RegionWriter::new(BufWriter::new(file));
+        writer.set_cancellation(self.cancellation.clone());
+        writer.write_payloads(&payloads, &timestamps)?;
+        let file = writer
+            .into_inner()
+            .into_inner()
+            .map_err(|e| e.into_error())?;
+        if sync {
+            file.sync_all()?;
+        }
+        Ok(())
+    }
+
+    /// Discards all pending edits and deletes the journal. The original file is unchanged.
+    pub fn discard(self) -> Result<()> {
+        drop(self.journal);
+        fs::remove_file(&self.journal_path)
+    }
+
+    fn append_index_record(&mut self, kind: u8, index: usize) -> Result<()> {
+        let mut record = Vec::with_capacity(3 + CRC_LEN);
+        record.push(kind);
+        record.extend_from_slice(&(index as u16).to_be_bytes());
+        self.append(&mut record)
+    }
+
+    /// Seals `record` with its checksum and appends it to the journal.
+    fn append(&mut self, record: &mut Vec<u8>) -> Result<()> {
+        let crc = crc32(record);
+        record.extend_from_slice(&crc.to_be_bytes());
+        self.journal.write_all(record)?;
+        if self.sync_policy == SyncPolicy::Always {
+            self.flush()
+        } else {
+            self.journal.flush()
+        }
+    }
+}
+
+impl Region {
+    /// Settles the journal left next to a region file by a [`CowRegion`] session that
+    /// never committed or discarded, for example because the tool crashed.
+    ///
+    /// With [`JournalRecovery::Replay`] the intact records are committed as by
+    /// [`CowRegion::commit`] and a torn tail is dropped, as described for
+    /// [`CowRegion::open`]; with
+    /// [`JournalRecovery::Discard`] the journal is deleted unread. In both cases the
+    /// `<file>.mca.cow.tmp` left by an interrupted commit is removed; other temporary
+    /// files, such as those staged by a
+    /// [`WorldTransaction`](crate::world::transaction::WorldTransaction), are not touched. Replaying is safe even if
+    /// the crash happened after the committed file was renamed into place, since
+    /// journaled edits overwrite whole chunks.
+    ///
+    /// Returns `true` if a journal was found.
+    pub fn recover<P: AsRef<Path>>(path: P, action: JournalRecovery) -> Result<bool> {
+        let path = path.as_ref();
+        match fs::remove_file(tmp_path_for(path)) {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+
+        let journal_path = journal_path_for(path);
+        if !journal_path.exists() {
+            return Ok(false);
+        }
+        match action {
+            JournalRecovery::Replay => CowRegion::open(path)?.commit()?,
+            JournalRecovery::Discard => fs::remove_file(journal_path)?,
+        }
+        Ok(true)
+    }
+}
+
+/// Syncs the directory containing `path` so that a rename into it is durable.
+pub(crate) fn sync_parent_dir(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        let dir = if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        };
+        File::open(dir)?.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Returns `<file>.cow` for a region path.
+fn journal_path_for(path: &Path) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".cow");
+    PathBuf::from(name)
+}
+
+/// Returns `<file>.cow.tmp`, where a commit writes the merged region before renaming it
+/// over the original.
+fn tmp_path_for(path: &Path) -> PathBuf {
+    let mut name = journal_path_for(path).into_os_string();
+    name.push(".tmp");
+    PathBuf::from(name)
+}
+
+/// Applies journal records to the overlay, returning the number of bytes that formed
+/// intact records.
+///
+/// Records are only ever appended, so the first one that is incomplete, fails its
+/// checksum or cannot be decoded marks where a crash tore the journal. Nothing from it
+/// onwards is applied.
+fn replay(mut data: &[u8], overlay: &mut HashMap<usize, Overlay>) -> usize {
+    let total = data.len();
+    loop {
+        let consumed = total - data.len();
+        let Some((record, rest)) = split_record(data) else {
+            return consumed;
+        };
+        let (body, crc) = record.split_at(record.len() - CRC_LEN);
+        if crc32(body).to_be_bytes() != crc {
+            return consumed;
+        }
+        let index = u16::from_be_bytes([body[1], body[2]]) as usize;
+        if index >= 1024 {
+            return consumed;
+        }
+        match body[0] {
+            RECORD_SET => match CompressionType::try_from(body[3]) {
+                Ok(compression) => {
+                    overlay.insert(index, Some((compression, body[8..].to_vec())));
+                }
+                Err(_) => return consumed,
+            },
+            RECORD_REMOVE => {
+                overlay.insert(index, None);
+            }
+            RECORD_REVERT => {
+                overlay.remove(&index);
+            }
+            _ => unreachable!(),
+        }
+        data = rest;
+    }
+}
+
+/// Splits the next whole record, checksum included, off the front of `data`. Returns
+/// `None` if `data` ends before the record does or starts with an unknown record type.
+fn split_record(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    let len = match *data.first()? {
+        RECORD_SET => {
+            let len = data.get(4..8)?;
+            8 + u32::from_be_bytes(len.try_into().unwrap()) as usize
+        }
+        RECORD_REMOVE | RECORD_REVERT => 3,
+        _ => return None,
+    };
+    if data.len() < len + CRC_LEN {
+        return None;
+    }
+    Some(data.split_at(len + CRC_LEN))
+}
+
+/// The CRC-32 used by zlib and gzip.
+fn crc32(data: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ 0xedb8_8320
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+    !data.iter().fold(!0u32, |crc, &b| {
+        TABLE[((crc ^ b as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
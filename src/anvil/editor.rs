@@ -0,0 +1,425 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! In-place editing of region files.
+//!
+//! A [`RegionEditor`] changes single chunks of an existing `.mca` file without
+//! rewriting the rest: a replaced chunk is written to free sectors, then its 4-byte
+//! location and timestamp entries are updated. Sectors released by replaced or removed
+//! chunks are tracked in a free list and reused by later writes, so a long editing
+//! session does not grow the file without bound. A sector is only released once no
+//! header entry points at it, and the header sectors never are, so damaged entries that
+//! overlap the header or each other cannot make a write clobber live data.
+//!
+//! New data is always written before the header entry pointing at it, and a chunk's old
+//! sectors are only released afterwards, so an interrupted edit leaves either the old or
+//! the new version of the chunk. Use [`CowRegion`](crate::anvil::cow::CowRegion) when a
+//! group of edits must land together or not at all.
+//!
+//! [`copy_chunk`] and [`copy_chunks`] move chunks from a [`Region`] into an editor without
+//! decompressing them, for merging worlds.
+
+use crate::anvil::access::{Region, RegionOptions, chunk_index, decompress_limited, parse_chunk};
+use crate::anvil::encode::{SectorBuffer, raw_sectors};
+use crate::anvil::{
+    ChunkLocation, CompressionPreset, CompressionType, HEADER_SIZE, RegionHeader, SECTOR_SIZE,
+    SyncPolicy, sector_count, to_timestamp,
+};
+use crate::cancel::{self, CancellationToken};
+use crate::nbt::NbtTag;
+use std::fs::{File, OpenOptions};
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::SystemTime;
+
+/// A region file opened for in-place editing.
+pub struct RegionEditor {
+    file: File,
+    header: RegionHeader,
+    /// Unused runs of sectors as `(start, len)`, sorted by start and never adjacent.
+    free: Vec<(u32, u32)>,
+    /// Number of header entries pointing at each sector of the file. The two header
+    /// sectors start at 1 and are never released.
+    refs: Vec<u32>,
+    buffer: SectorBuffer,
+    sync_policy: SyncPolicy,
+    options: RegionOptions,
+    cancellation: Option<CancellationToken>,
+}
+
+impl RegionEditor {
+    /// Opens an existing region file for editing.
+    ///
+    /// Sectors not claimed by any chunk in the header, such as those left behind by
+    /// earlier in-place writes, are reused.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+        let mut header_bytes = [0u8; HEADER_SIZE];
+        file.read_exact(&mut header_bytes)
+            .map_err(|e| match e.kind() {
+                ErrorKind::UnexpectedEof => {
+                    Error::new(ErrorKind::InvalidData, "MCA file too small for headers")
+                }
+                _ => e,
+            })?;
+        let header = RegionHeader::parse(&header_bytes);
+        let sectors = file.metadata()?.len().div_ceil(SECTOR_SIZE as u64) as u32;
+
+        let mut refs = vec![0u32; sectors as usize];
+        refs[..2].fill(1);
+        for location in header.locations.iter().filter(|l| !l.is_empty()) {
+            let start = (location.offset as usize).clamp(2, refs.len());
+            let end = (location.offset as usize + location.sector_count as usize).min(refs.len());
+            for count in refs[start..end.max(start)].iter_mut() {
+                *count += 1;
+            }
+        }
+        let mut free: Vec<(u32, u32)> = Vec::new();
+        for (sector, _) in refs.iter().enumerate().filter(|(_, refs)| **refs == 0) {
+            let sector = sector as u32;
+            match free.last_mut() {
+                Some((start, len)) if *start + *len == sector => *len += 1,
+                _ => free.push((sector, 1)),
+            }
+        }
+
+        Ok(RegionEditor {
+            file,
+            header,
+            free,
+            refs,
+            buffer: SectorBuffer::default(),
+            sync_policy: SyncPolicy::default(),
+            options: RegionOptions::default(),
+            cancellation: None,
+        })
+    }
+
+    /// Returns the current [`SyncPolicy`].
+    pub fn sync_policy(&self) -> SyncPolicy {
+        self.sync_policy
+    }
+
+    /// Sets when edits are synced to disk.
+    ///
+    /// With [`SyncPolicy::Always`] every [`set_chunk`](Self::set_chunk) and
+    /// [`remove_chunk`](Self::remove_chunk) syncs the file before returning. There is no
+    /// commit step, so the other policies leave syncing to [`flush`](Self::flush).
+    pub fn set_sync_policy(&mut self, policy: SyncPolicy) {
+        self.sync_policy = policy;
+    }
+
+    /// Sets the limits applied when reading chunks.
+    pub fn set_options(&mut self, options: RegionOptions) {
+        self.options = options;
+    }
+
+    /// Returns the limits applied when reading chunks.
+    pub fn options(&self) -> &RegionOptions {
+        &self.options
+    }
+
+    /// Sets how chunks passed to [`set_chunk`](Self::set_chunk) are compressed
+    /// ([`CompressionPreset::Balanced`] by default).
+    pub fn set_compression(&mut self, preset: CompressionPreset) {
+        self.buffer = SectorBuffer::with_compression(preset);
+    }
+
+    /// Makes every write and removal check `token` first and fail with
+    /// [`Interrupted`](ErrorKind::Interrupted) once it is cancelled, so bulk edits such as
+    /// [`copy_chunks`] or [`Structure::paste_into_region`] stop between chunks. Chunks
+    /// written before the cancellation stay in the file.
+    ///
+    /// [`Structure::paste_into_region`]: crate::anvil::structure::Structure::paste_into_region
+    pub fn set_cancellation(&mut self, token: Option<CancellationToken>) {
+        self.cancellation = token;
+    }
+
+    /// Returns the region header as it is on disk.
+    pub fn header(&self) -> &RegionHeader {
+        &self.header
+    }
+
+    /// Returns the number of unused sectors available for reuse.
+    pub fn free_sectors(&self) -> u32 {
+        self.free.iter().map(|&(_, len)| len).sum()
+    }
+
+    /// Retrieves the decompressed NBT data for a chunk.
+    ///
+    /// See [`Region::get_chunk_data`](crate::anvil::access::Region::get_chunk_data) for
+    /// the coordinate conventions. A length prefix larger than the chunk's sectors fails
+    /// with [`InvalidData`](ErrorKind::InvalidData) before anything is allocated.
+    pub fn get_chunk_data(&mut self, x: i32, z: i32) -> Result<Option<Vec<u8>>> {
+        let location = self.header.locations[chunk_index(x, z)];
+        if location.is_empty() {
+            return Ok(None);
+        }
+        self.file
+            .seek(SeekFrom::Start(location.start_byte() as u64))?;
+        let mut prefix = [0u8; 5];
+        self.file.read_exact(&mut prefix)?;
+        let length = u32::from_be_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as usize;
+        if length < 1 {
+            return Ok(None);
+        }
+        if length + 4 > location.sector_count as usize * SECTOR_SIZE {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Chunk ({}, {}) is longer than its sectors", x, z),
+            ));
+        }
+        let compression = CompressionType::try_from(prefix[4])
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        let mut payload = vec![0u8; length - 1];
+        self.file.read_exact(&mut payload)?;
+        decompress_limited(compression, &payload, self.options.max_chunk_size).map(Some)
+    }
+
+    /// Parses the NBT data for a chunk.
+    pub fn get_chunk_nbt(&mut self, x: i32, z: i32) -> Result<Option<(String, NbtTag)>> {
+        match self.get_chunk_data(x, z)? {
+            Some(data) => parse_chunk(&data).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Replaces (or inserts) a chunk and stamps it with the current time.
+    ///
+    /// Only the chunk's new sectors and its header entries are written.
+    pub fn set_chunk(&mut self, x: i32, z: i32, name: &str, tag: &NbtTag) -> Result<()> {
+        self.set_chunk_at(x, z, name, tag, SystemTime::now())
+    }
+
+    /// Like [`set_chunk`](Self::set_chunk), but stamps the chunk with `modified`, for
+    /// example to keep the time of a chunk that was only migrated.
+    pub fn set_chunk_at(
+        &mut self,
+        x: i32,
+        z: i32,
+        name: &str,
+        tag: &NbtTag,
+        modified: SystemTime,
+    ) -> Result<()> {
+        self.buffer.encode_chunk(name, tag)?;
+        let buffer = std::mem::take(&mut self.buffer);
+        let result = self.place_chunk(chunk_index(x, z), buffer.as_bytes(), to_timestamp(modified));
+        self.buffer = buffer;
+        result
+    }
+
+    /// Replaces (or inserts) a chunk with an already-compressed payload, such as one
+    /// returned by [`Region::get_chunk_raw`], stamped with `modified`.
+    ///
+    /// `data` is stored as-is and not validated.
+    pub fn set_raw_chunk_at(
+        &mut self,
+        x: i32,
+        z: i32,
+        compression: CompressionType,
+        data: &[u8],
+        modified: SystemTime,
+    ) -> Result<()> {
+        let sectors = raw_sectors(compression as u8, data)?;
+        self.place_chunk(chunk_index(x, z), &sectors, to_timestamp(modified))
+    }
+
+    /// Writes a chunk already padded to whole sectors to free sectors, then points the
+    /// header entry at `index` to it and releases the chunk's old sectors.
+    fn place_chunk(&mut self, index: usize, sectors: &[u8], timestamp: u32) -> Result<()> {
+        cancel::check(self.cancellation.as_ref())?;
+        let count = sectors.len() / SECTOR_SIZE;
+        let sector_count = sector_count(count)?;
+
+        let offset = self.allocate(count as u32);
+        self.file
+            .seek(SeekFrom::Start(offset as u64 * SECTOR_SIZE as u64))?;
+        self.file.write_all(sectors)?;
+
+        let old = self.header.locations[index];
+        self.write_entry(
+            index,
+            ChunkLocation {
+                offset,
+                sector_count,
+            },
+            timestamp,
+        )?;
+        self.release(old);
+        if self.sync_policy == SyncPolicy::Always {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Removes a chunk, releasing its sectors for reuse.
+    pub fn remove_chunk(&mut self, x: i32, z: i32) -> Result<()> {
+        cancel::check(self.cancellation.as_ref())?;
+        let index = chunk_index(x, z);
+        let old = self.header.locations[index];
+        if !old.is_empty() {
+            self.write_entry(index, ChunkLocation::EMPTY, 0)?;
+            self.release(old);
+            if self.sync_policy == SyncPolicy::Always {
+                self.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Forces all edits made so far to stable storage.
+    pub fn flush(&mut self) -> Result<()> {
+        self.file.flush()?;
+        self.file.sync_data()
+    }
+
+    /// Forces the edits to the chunk at `(x, z)` to stable storage.
+    ///
+    /// A file can only be synced as a whole, so this syncs every edit made so far, like
+    /// [`flush`](Self::flush). Use [`MappedRegion`](crate::anvil::mapped::MappedRegion)
+    /// to sync single chunks.
+    pub fn flush_range(&mut self, _x: i32, _z: i32) -> Result<()> {
+        self.flush()
+    }
+
+    /// Finds `count` consecutive free sectors, first fit, or appends them to the file.
+    fn allocate(&mut self, count: u32) -> u32 {
+        let start = match self.free.iter().position(|&(_, len)| len >= count) {
+            Some(i) => {
+                let (start, len) = self.free[i];
+                if len == count {
+                    self.free.remove(i);
+                } else {
+                    self.free[i] = (start + count, len - count);
+                }
+                start
+            }
+            None => {
+                let start = self.refs.len() as u32;
+                self.refs.resize(self.refs.len() + count as usize, 0);
+                start
+            }
+        };
+        self.refs[start as usize..(start + count) as usize].fill(1);
+        start
+    }
+
+    /// Drops the header entry's claim on the sectors of `location`, returning those no
+    /// other entry points at to the free list.
+    ///
+    /// The header sectors and sectors past the end of the file are never released.
+    fn release(&mut self, location: ChunkLocation) {
+        if location.is_empty() {
+            return;
+        }
+        let start = (location.offset as usize).max(2);
+        let end = (location.offset as usize + location.sector_count as usize).min(self.refs.len());
+        let mut run: Option<(u32, u32)> = None;
+        for sector in start..end {
+            let count = &mut self.refs[sector];
+            if *count != 1 {
+                *count = count.saturating_sub(1);
+                continue;
+            }
+            *count = 0;
+            let sector = sector as u32;
+            match run {
+                Some((run_start, len)) if run_start + len == sector => {
+                    run = Some((run_start, len + 1));
+                }
+                _ => {
+                    if let Some((run_start, len)) = run {
+                        self.insert_free(run_start, len);
+                    }
+                    run = Some((sector, 1));
+                }
+            }
+        }
+        if let Some((run_start, len)) = run {
+            self.insert_free(run_start, len);
+        }
+    }
+
+    /// Adds a run of sectors to the free list, merging it with its neighbours.
+    fn insert_free(&mut self, start: u32, len: u32) {
+        let i = self.free.partition_point(|&(s, _)| s < start);
+        self.free.insert(i, (start, len));
+        if i + 1 < self.free.len() && start + len == self.free[i + 1].0 {
+            self.free[i].1 += self.free[i + 1].1;
+            self.free.remove(i + 1);
+        }
+        if i > 0 && self.free[i - 1].0 + self.free[i - 1].1 == start {
+            self.free[i - 1].1 += self.free[i].1;
+            self.free.remove(i);
+        }
+    }
+
+    /// Updates and writes the location and timestamp entries of one chunk.
+    fn write_entry(&mut self, index: usize, location: ChunkLocation, timestamp: u32) -> Result<()> {
+        self.header.locations[index] = location;
+        self.header.timestamps[index] = timestamp;
+        let bytes = self.header.to_bytes();
+        let entry = index * 4;
+        self.file.seek(SeekFrom::Start(entry as u64))?;
+        self.file.write_all(&bytes[entry..entry + 4])?;
+        self.file
+            .seek(SeekFrom::Start((SECTOR_SIZE + entry) as u64))?;
+        self.file
+            .write_all(&bytes[SECTOR_SIZE + entry..SECTOR_SIZE + entry + 4])
+    }
+}
+
+/// Copies the chunk at `(x, z)` from `src` to the same position in `dst`, keeping its
+/// timestamp. Returns `false` if `src` has no chunk there.
+///
+/// The compressed payload is copied as-is, so the chunk is neither decompressed nor
+/// recompressed. This includes chunks whose compression id this crate cannot decode, such
+/// as 127 ([custom](crate::anvil::codec)); a chunk flagged as stored in an external
+/// `.mcc` file keeps its flag, but the `.mcc` file itself is not copied.
+pub fn copy_chunk(src: &Region, dst: &mut RegionEditor, x: i32, z: i32) -> Result<bool> {
+    copy_raw(src, dst, (x, z), (x, z))
+}
+
+/// Copies every chunk of `src` into `dst`, at the position returned by `remap` for its
+/// region-relative coordinates, or not at all if `remap` returns `None`. Returns the
+/// number of chunks copied.
+///
+/// Stops with [`Interrupted`](ErrorKind::Interrupted) between chunks once the token set
+/// with [`RegionEditor::set_cancellation`] is cancelled.
+///
+/// Chunks are read in file order and copied without recompression, like
+/// [`copy_chunk`]. Only the header entry moves: the chunk's own `xPos` and `zPos` are
+/// left unchanged, so rewrite them with [`RegionEditor::set_chunk`] when chunks change
+/// world position.
+pub fn copy_chunks<F>(src: &Region, dst: &mut RegionEditor, mut remap: F) -> Result<usize>
+where
+    F: FnMut(i32, i32) -> Option<(i32, i32)>,
+{
+    let mut coords = src.present_chunks();
+    coords.sort_by_key(|&(x, z)| src.header().locations[chunk_index(x, z)].offset);
+    let mut copied = 0;
+    for (x, z) in coords {
+        cancel::check(dst.cancellation.as_ref())?;
+        if let Some(to) = remap(x, z)
+            && copy_raw(src, dst, (x, z), to)?
+        {
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
+
+fn copy_raw(
+    src: &Region,
+    dst: &mut RegionEditor,
+    from: (i32, i32),
+    to: (i32, i32),
+) -> Result<bool> {
+    let Some((compression, data)) = src.raw_payload(chunk_index(from.0, from.1))? else {
+        return Ok(false);
+    };
+    let sectors = raw_sectors(compression, data)?;
+    let timestamp = src.header().timestamps[chunk_index(from.0, from.1)];
+    dst.place_chunk(chunk_index(to.0, to.1), &sectors, timestamp)?;
+    Ok(true)
+}
@@ -1,24 +1,164 @@
 // Copyright 2026 driedpampas@proton.me
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use crate::anvil::{ChunkLocation, SECTOR_SIZE};
+use crate::anvil::cow::sync_parent_dir;
+#[cfg(feature = "lz4")]
+use crate::anvil::lz4::Lz4Encoder;
+use crate::anvil::{
+    ChunkLocation, CompressionPreset, CompressionType, HEADER_SIZE, RegionHeader, SECTOR_SIZE,
+    sector_count, to_timestamp,
+};
+use crate::cancel::{self, CancellationToken};
 use crate::nbt::NbtTag;
-use crate::nbt::encode::write_named_tag;
-use flate2::Compression;
-use flate2::write::ZlibEncoder;
-use std::io::{Result, Seek, SeekFrom, Write};
+use crate::nbt::encode::{KeyCache, write_named_tag_cached};
+use crate::nbt::validate::validate_chunk;
+#[cfg(feature = "compression")]
+use flate2::write::{GzEncoder, ZlibEncoder};
+use std::fs::{self, File};
+use std::io::{BufWriter, Result, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Encodes the smallest valid region file holding an empty compound at each of `coords`.
+///
+/// Useful as seed input for fuzzing corpora and as a fixture in tests. Timestamps are
+/// left at zero, so the output is the same on every run.
+pub fn minimal_region(coords: &[(i32, i32)]) -> Result<Vec<u8>> {
+    let empty = NbtTag::Compound(Default::default());
+    let mut writer = RegionWriter::new(std::io::Cursor::new(Vec::new()));
+    for &(x, z) in coords {
+        writer.write_chunk_at(x, z, "", &empty, UNIX_EPOCH)?;
+    }
+    writer.flush_header()?;
+    Ok(writer.into_inner().into_inner())
+}
+
+/// Writes a region file so that a crash never leaves a partial file at `path`.
+///
+/// The chunks are written with [`RegionWriter::write_all_chunks`] to `<path>.tmp`,
+/// synced to disk, and renamed over `path`. Readers see either the previous file or the
+/// complete new one; on failure the temporary file is removed and `path` is untouched.
+pub fn write_region_atomic<P: AsRef<Path>>(
+    path: P,
+    chunks: &[(i32, i32, String, NbtTag)],
+) -> Result<()> {
+    let path = path.as_ref();
+    let mut tmp_name = path.as_os_str().to_owned();
+    tmp_name.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_name);
+
+    let written = (|| {
+        let mut writer = RegionWriter::new(BufWriter::new(File::create(&tmp_path)?));
+        writer.write_all_chunks(chunks)?;
+        let file = writer
+            .into_inner()
+            .into_inner()
+            .map_err(|e| e.into_error())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+    sync_parent_dir(path)
+}
+
+/// How a [`RegionWriter`] or [`SectorBuffer`] compresses chunks.
+///
+/// Converting a [`CompressionPreset`] gives its codec and level. Gzip and Zlib need the
+/// `compression` feature and LZ4 the `lz4` feature; writing fails with
+/// [`Unsupported`](std::io::ErrorKind::Unsupported) without them. Only game versions
+/// from 1.20.5 on read LZ4 chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegionWriterOptions {
+    /// The codec chunks are compressed with.
+    pub compression: CompressionType,
+    /// The deflate level, from 0 (store only) to 9 (smallest). Larger values are treated
+    /// as 9; ignored for [`CompressionType::None`] and [`CompressionType::Lz4`], which
+    /// has a single level.
+    pub level: u32,
+}
+
+impl Default for RegionWriterOptions {
+    fn default() -> Self {
+        CompressionPreset::default().into()
+    }
+}
+
+impl From<CompressionPreset> for RegionWriterOptions {
+    fn from(preset: CompressionPreset) -> Self {
+        RegionWriterOptions {
+            compression: preset.chunk_compression(),
+            level: preset.level(),
+        }
+    }
+}
 
 /// A writer for creating or modifying Anvil region files.
-#[allow(dead_code)]
+///
+/// Chunks can be written all at once with [`write_all_chunks`](Self::write_all_chunks),
+/// or one at a time with [`write_chunk`](Self::write_chunk) followed by
+/// [`flush_header`](Self::flush_header).
 pub struct RegionWriter<W: Write + Seek> {
-    #[allow(dead_code)]
     writer: W,
+    validate: bool,
+    cancellation: Option<CancellationToken>,
+    header: RegionHeader,
+    buffer: SectorBuffer,
+    next_sector: u32,
 }
 
 impl<W: Write + Seek> RegionWriter<W> {
     /// Creates a new `RegionWriter` wrapping the given writer.
     pub fn new(writer: W) -> Self {
-        RegionWriter { writer }
+        Self::with_options(writer, RegionWriterOptions::default())
+    }
+
+    /// Creates a new `RegionWriter` compressing chunks as set by `options`.
+    pub fn with_options(writer: W, options: RegionWriterOptions) -> Self {
+        RegionWriter {
+            writer,
+            validate: false,
+            cancellation: None,
+            header: RegionHeader::default(),
+            buffer: SectorBuffer::with_options(options),
+            next_sector: 2,
+        }
+    }
+
+    /// Sets how chunks are compressed ([`CompressionPreset::Balanced`] by default).
+    pub fn set_compression(&mut self, preset: CompressionPreset) {
+        self.set_options(preset.into());
+    }
+
+    /// Sets the codec and level chunks are compressed with.
+    pub fn set_options(&mut self, options: RegionWriterOptions) {
+        self.buffer.options = options;
+    }
+
+    /// Enables or disables chunk validation before writing (disabled by default).
+    ///
+    /// When enabled, every chunk is checked with [`validate_chunk`] before it is
+    /// written, and the first violation aborts the write with
+    /// [`InvalidInput`](std::io::ErrorKind::InvalidInput).
+    /// [`write_all_chunks`](Self::write_all_chunks) checks every chunk before writing
+    /// anything.
+    pub fn set_validation(&mut self, enabled: bool) {
+        self.validate = enabled;
+    }
+
+    /// Makes [`write_chunk`](Self::write_chunk) and
+    /// [`write_all_chunks`](Self::write_all_chunks) check `token` before each chunk and
+    /// fail with [`Interrupted`](std::io::ErrorKind::Interrupted) once it is
+    /// cancelled. The output is incomplete after a cancelled write.
+    pub fn set_cancellation(&mut self, token: Option<CancellationToken>) {
+        self.cancellation = token;
+    }
+
+    /// Consumes the `RegionWriter`, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
     }
 
     /// Writes all provided chunks to the region file.
@@ -26,47 +166,233 @@ impl<W: Write + Seek> RegionWriter<W> {
     /// Chunks are provided as a slice of tuples containing `(x, z, name, tag)`.
     /// x and z are world coordinates (chunk units).
     ///
-    /// This method encodes and compresses each chunk using Zlib,
-    /// then writes them to the underlying writer along with the required headers.
-    /// It handles sector alignment and padding automatically.
+    /// This method encodes each chunk and compresses it as set by the writer's
+    /// [`RegionWriterOptions`], then writes them to the underlying writer along with the
+    /// required headers.
+    /// It handles sector alignment and padding automatically. Every chunk is stamped with
+    /// the current time.
+    ///
+    /// Any chunks previously written with [`write_chunk`](Self::write_chunk) are
+    /// discarded, as the region is laid out from scratch.
     pub fn write_all_chunks(&mut self, chunks: &[(i32, i32, String, NbtTag)]) -> Result<()> {
-        let mut locations = [ChunkLocation {
-            offset: 0,
-            sector_count: 0,
-        }; 1024];
-
-        // Move past header space (4096 bytes for locations + 4096 bytes for timestamps)
-        self.writer.seek(SeekFrom::Start(SECTOR_SIZE as u64 * 2))?;
-        let mut current_sector = 2u32;
+        if self.validate {
+            for (x, z, _, tag) in chunks {
+                validate_chunk(tag, *x, *z)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+            }
+        }
 
+        self.header = RegionHeader::default();
+        self.next_sector = 2;
+        let now = to_timestamp(SystemTime::now());
         for (x, z, name, tag) in chunks {
-            let rel_x = x.rem_euclid(32);
-            let rel_z = z.rem_euclid(32);
-            let index = (rel_z * 32 + rel_x) as usize;
+            self.append_chunk(*x, *z, name, tag, now)?;
+        }
+        self.flush_header()
+    }
+
+    /// Like [`write_all_chunks`](Self::write_all_chunks), but encodes and compresses the
+    /// chunks on the rayon thread pool.
+    ///
+    /// Compression dominates the time spent writing a region, and each worker reuses its
+    /// own [`SectorBuffer`]. The sector layout is then written on the calling thread in
+    /// the order of `chunks`, so the output is identical to
+    /// [`write_all_chunks`](Self::write_all_chunks). All compressed chunks are held in
+    /// memory until they are written. Call it inside
+    /// [`ThreadPool::install`](rayon::ThreadPool::install) to limit the number of threads.
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    pub fn write_all_chunks_parallel(
+        &mut self,
+        chunks: &[(i32, i32, String, NbtTag)],
+    ) -> Result<()> {
+        use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
-            // Encode and compress chunk
-            let mut raw_nbt = Vec::new();
-            write_named_tag(&mut raw_nbt, name, tag)?;
+        if self.validate {
+            for (x, z, _, tag) in chunks {
+                validate_chunk(tag, *x, *z)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+            }
+        }
 
-            let mut compressed = Vec::new();
-            let mut encoder = ZlibEncoder::new(&mut compressed, Compression::default());
-            encoder.write_all(&raw_nbt)?;
-            encoder.finish()?;
+        let options = self.buffer.options;
+        let cancellation = self.cancellation.as_ref();
+        let encoded = chunks
+            .par_iter()
+            .map_init(
+                || SectorBuffer::with_options(options),
+                |buffer, (_, _, name, tag)| {
+                    cancel::check(cancellation)?;
+                    buffer.encode_chunk(name, tag)?;
+                    Ok(buffer.as_bytes().to_vec())
+                },
+            )
+            .collect::<Result<Vec<_>>>()?;
+
+        self.header = RegionHeader::default();
+        self.next_sector = 2;
+        let now = to_timestamp(SystemTime::now());
+        for ((x, z, _, _), sectors) in chunks.iter().zip(&encoded) {
+            self.place_chunk(*x, *z, sectors, now)?;
+        }
+        self.flush_header()
+    }
 
+    /// Writes a single chunk after the chunks written so far.
+    ///
+    /// The chunk only becomes visible to readers once the header is written with
+    /// [`flush_header`](Self::flush_header), so generators can write chunks as they are
+    /// produced and flush once at the end (or periodically, to checkpoint progress).
+    /// Writing the same coordinates again points the header at the new copy; the
+    /// sectors of the old one are left unused. The chunk is stamped with the current time.
+    pub fn write_chunk(&mut self, x: i32, z: i32, name: &str, tag: &NbtTag) -> Result<()> {
+        self.write_chunk_at(x, z, name, tag, SystemTime::now())
+    }
+
+    /// Like [`write_chunk`](Self::write_chunk), but stamps the chunk with `modified`.
+    ///
+    /// Pass the time from [`Region::get_timestamp`](crate::anvil::access::Region::get_timestamp)
+    /// to carry it over when copying chunks between regions, or
+    /// [`UNIX_EPOCH`] to leave the timestamp unset.
+    pub fn write_chunk_at(
+        &mut self,
+        x: i32,
+        z: i32,
+        name: &str,
+        tag: &NbtTag,
+        modified: SystemTime,
+    ) -> Result<()> {
+        if self.validate {
+            validate_chunk(tag, x, z)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+        }
+        self.append_chunk(x, z, name, tag, to_timestamp(modified))
+    }
+
+    /// Writes an already-compressed chunk payload after the chunks written so far,
+    /// stamped with the current time.
+    ///
+    /// `data` is stored as-is, so chunks copied between regions with
+    /// [`Region::get_chunk_raw`](crate::anvil::access::Region::get_chunk_raw) skip
+    /// decompression and recompression. As with [`write_chunk`](Self::write_chunk), the
+    /// chunk becomes visible once the header is flushed. Validation does not apply to raw
+    /// payloads.
+    pub fn write_raw_chunk(
+        &mut self,
+        x: i32,
+        z: i32,
+        compression: CompressionType,
+        data: &[u8],
+    ) -> Result<()> {
+        cancel::check(self.cancellation.as_ref())?;
+        let sectors = raw_sectors(compression as u8, data)?;
+        self.place_chunk(x, z, &sectors, to_timestamp(SystemTime::now()))
+    }
+
+    /// Writes the header for every chunk written so far.
+    ///
+    /// The output is first padded with zeros to a whole number of sectors, as the game
+    /// expects, even if no chunk was written or the writer wraps a longer stream.
+    pub fn flush_header(&mut self) -> Result<()> {
+        self.pad_to_sector()?;
+        self.writer.seek(SeekFrom::Start(0))?;
+        self.header.write(&mut self.writer)?;
+        self.writer.flush()
+    }
+
+    /// Extends the output with zeros to cover every allocated sector and end on a sector
+    /// boundary.
+    fn pad_to_sector(&mut self) -> Result<()> {
+        let sector = SECTOR_SIZE as u64;
+        let end = self.writer.seek(SeekFrom::End(0))?;
+        let target = end
+            .next_multiple_of(sector)
+            .max(self.next_sector as u64 * sector);
+        if target > end {
+            self.writer.write_all(&vec![0u8; (target - end) as usize])?;
+        }
+        debug_assert_eq!(
+            self.writer.seek(SeekFrom::End(0))? % sector,
+            0,
+            "region output must end on a sector boundary"
+        );
+        Ok(())
+    }
+
+    /// Encodes a chunk into the next free sectors and records it in the header.
+    fn append_chunk(
+        &mut self,
+        x: i32,
+        z: i32,
+        name: &str,
+        tag: &NbtTag,
+        timestamp: u32,
+    ) -> Result<()> {
+        cancel::check(self.cancellation.as_ref())?;
+        self.buffer.encode_chunk(name, tag)?;
+        let buffer = std::mem::take(&mut self.buffer);
+        let placed = self.place_chunk(x, z, buffer.as_bytes(), timestamp);
+        self.buffer = buffer;
+        placed
+    }
+
+    /// Writes an encoded, sector-padded chunk to the next free sectors and records it in
+    /// the header.
+    fn place_chunk(&mut self, x: i32, z: i32, sectors: &[u8], timestamp: u32) -> Result<()> {
+        let index = (z.rem_euclid(32) * 32 + x.rem_euclid(32)) as usize;
+        let count = sectors.len() / SECTOR_SIZE;
+        let sector_count = sector_count(count)?;
+        self.writer.seek(SeekFrom::Start(
+            self.next_sector as u64 * SECTOR_SIZE as u64,
+        ))?;
+        self.writer.write_all(sectors)?;
+        self.header.locations[index] = ChunkLocation {
+            offset: self.next_sector,
+            sector_count,
+        };
+        self.header.timestamps[index] = timestamp;
+        self.next_sector += count as u32;
+        Ok(())
+    }
+
+    /// Lays out already-compressed chunk payloads in sectors and writes the headers.
+    ///
+    /// Each payload is given as `(index, compression, bytes)` where `index` is the
+    /// position in the 32x32 header table (`z * 32 + x`).
+    pub(crate) fn write_payloads(
+        &mut self,
+        payloads: &[(usize, CompressionType, &[u8])],
+        timestamps: &[u32; 1024],
+    ) -> Result<()> {
+        // Fail before writing anything if a chunk is too large for a location entry
+        for &(_, _, compressed) in payloads {
+            sector_count((compressed.len() + 5).div_ceil(SECTOR_SIZE))?;
+        }
+        self.header = RegionHeader {
+            timestamps: *timestamps,
+            ..Default::default()
+        };
+
+        // Move past header space (4096 bytes for locations + 4096 bytes for timestamps)
+        self.writer.seek(SeekFrom::Start(HEADER_SIZE as u64))?;
+        let mut current_sector = 2u32;
+
+        for &(index, compression, compressed) in payloads {
+            cancel::check(self.cancellation.as_ref())?;
             let total_len = compressed.len() + 1; // +1 for compression type byte
             let sectors_needed = (total_len + 4).div_ceil(SECTOR_SIZE);
 
-            locations[index] = ChunkLocation {
+            self.header.locations[index] = ChunkLocation {
                 offset: current_sector,
-                sector_count: sectors_needed as u8,
+                sector_count: sector_count(sectors_needed)?,
             };
 
             // Write chunk data
             self.writer
                 .seek(SeekFrom::Start(current_sector as u64 * SECTOR_SIZE as u64))?;
             self.writer.write_all(&(total_len as u32).to_be_bytes())?;
-            self.writer.write_all(&[2u8])?; // Zlib
-            self.writer.write_all(&compressed)?;
+            self.writer.write_all(&[compression as u8])?;
+            self.writer.write_all(compressed)?;
 
             // Pad to sector boundary
             let padding = (sectors_needed * SECTOR_SIZE) - (total_len + 4);
@@ -78,21 +404,139 @@ impl<W: Write + Seek> RegionWriter<W> {
         }
 
         // Write headers back at start
-        self.writer.seek(SeekFrom::Start(0))?;
-        for loc in &locations {
-            let mut buf = [0u8; 4];
-            buf[0] = ((loc.offset >> 16) & 0xFF) as u8;
-            buf[1] = ((loc.offset >> 8) & 0xFF) as u8;
-            buf[2] = (loc.offset & 0xFF) as u8;
-            buf[3] = loc.sector_count;
-            self.writer.write_all(&buf)?;
+        self.next_sector = current_sector;
+        self.flush_header()
+    }
+}
+
+/// Size of the staging buffer between the NBT encoder and the compressor, which batches
+/// the encoder's many small writes.
+#[cfg(feature = "compression")]
+const STAGING_SIZE: usize = 64 * 1024;
+
+/// Encodes a named tag and compresses it with the preset's
+/// [`chunk_compression`](CompressionPreset::chunk_compression).
+pub(crate) fn compress_chunk(
+    name: &str,
+    tag: &NbtTag,
+    keys: &mut KeyCache,
+    preset: CompressionPreset,
+) -> Result<Vec<u8>> {
+    let mut compressed = Vec::new();
+    compress_into(&mut compressed, name, tag, keys, preset.into())?;
+    Ok(compressed)
+}
+
+/// Encodes and compresses a named tag, appending the result to `out`.
+fn compress_into(
+    out: &mut Vec<u8>,
+    name: &str,
+    tag: &NbtTag,
+    keys: &mut KeyCache,
+    options: RegionWriterOptions,
+) -> Result<()> {
+    #[cfg(feature = "compression")]
+    let level = flate2::Compression::new(options.level.min(9));
+    match options.compression {
+        CompressionType::None => {
+            out.reserve(tag.serialized_size(name));
+            write_named_tag_cached(out, name, tag, keys)
         }
+        #[cfg(feature = "compression")]
+        CompressionType::Gzip => {
+            let mut staging = BufWriter::with_capacity(STAGING_SIZE, GzEncoder::new(out, level));
+            write_named_tag_cached(&mut staging, name, tag, keys)?;
+            staging.into_inner().map_err(|e| e.into_error())?.finish()?;
+            Ok(())
+        }
+        #[cfg(feature = "compression")]
+        CompressionType::Zlib => {
+            let mut staging = BufWriter::with_capacity(STAGING_SIZE, ZlibEncoder::new(out, level));
+            write_named_tag_cached(&mut staging, name, tag, keys)?;
+            staging.into_inner().map_err(|e| e.into_error())?.finish()?;
+            Ok(())
+        }
+        #[cfg(feature = "lz4")]
+        CompressionType::Lz4 => {
+            let mut encoder = Lz4Encoder::new(out);
+            write_named_tag_cached(&mut encoder, name, tag, keys)?;
+            encoder.finish()?;
+            Ok(())
+        }
+        #[cfg(not(all(feature = "compression", feature = "lz4")))]
+        compression => Err(crate::anvil::unsupported_compression(compression)),
+    }
+}
 
-        // Timestamps (just use 0 for now)
-        for _ in 0..1024 {
-            self.writer.write_all(&[0u8; 4])?;
+/// Frames an already-compressed payload as stored in a region file: length prefix,
+/// compression id and data, padded to whole sectors.
+pub(crate) fn raw_sectors(compression: u8, data: &[u8]) -> Result<Vec<u8>> {
+    let length = u32::try_from(data.len() + 1).map_err(|_| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "Chunk payload too large")
+    })?;
+    let mut sectors = Vec::with_capacity((data.len() + 5).next_multiple_of(SECTOR_SIZE));
+    sectors.extend_from_slice(&length.to_be_bytes());
+    sectors.push(compression);
+    sectors.extend_from_slice(data);
+    sectors.resize(sectors.len().next_multiple_of(SECTOR_SIZE), 0);
+    Ok(sectors)
+}
+
+/// A reusable buffer holding one chunk encoded exactly as it is stored in a region file.
+///
+/// [`encode_chunk`](Self::encode_chunk) writes the length prefix, the compression type
+/// and the compressed NBT straight into the buffer and pads it to whole sectors,
+/// so the result can be written to a sector offset as-is. The allocation is kept
+/// between chunks, and so are the encoded compound keys.
+#[derive(Debug, Default)]
+pub struct SectorBuffer {
+    data: Vec<u8>,
+    keys: KeyCache,
+    options: RegionWriterOptions,
+}
+
+impl SectorBuffer {
+    /// Creates an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty buffer compressing with the given preset.
+    pub fn with_compression(compression: CompressionPreset) -> Self {
+        Self::with_options(compression.into())
+    }
+
+    /// Creates an empty buffer compressing with the given codec and level.
+    pub fn with_options(options: RegionWriterOptions) -> Self {
+        SectorBuffer {
+            options,
+            ..Self::default()
         }
+    }
 
-        Ok(())
+    /// Encodes a chunk into the buffer, replacing its previous contents, and returns
+    /// the number of sectors it occupies.
+    pub fn encode_chunk(&mut self, name: &str, tag: &NbtTag) -> Result<usize> {
+        self.data.clear();
+        // Length placeholder and compression type
+        self.data
+            .extend_from_slice(&[0, 0, 0, 0, self.options.compression as u8]);
+        compress_into(&mut self.data, name, tag, &mut self.keys, self.options)?;
+
+        let length = (self.data.len() - 4) as u32;
+        self.data[..4].copy_from_slice(&length.to_be_bytes());
+        let sectors = self.data.len().div_ceil(SECTOR_SIZE);
+        self.data.resize(sectors * SECTOR_SIZE, 0);
+        Ok(sectors)
+    }
+
+    /// Returns the encoded chunk, padded to a whole number of sectors.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the number of sectors of the encoded chunk.
+    pub fn sector_count(&self) -> usize {
+        self.data.len() / SECTOR_SIZE
     }
 }
@@ -0,0 +1,222 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Consistency checks of a region's header and sector layout.
+//!
+//! [`Region::open`] trusts the location table, so a damaged header shows up later as
+//! chunks that fail to decompress or, worse, silently decode another chunk's bytes.
+//! [`Region::validate`] checks the table against the file up front and lists every
+//! problem it finds. When the header is beyond repair, [`Region::salvage`] ignores it and
+//! finds chunks by scanning the sectors themselves.
+
+use crate::anvil::access::{Region, parse_chunk};
+use crate::anvil::{CompressionType, HEADER_SIZE, SECTOR_SIZE};
+use crate::nbt::NbtTag;
+use thiserror::Error;
+
+/// A problem with where a chunk is stored, found by [`Region::validate`].
+///
+/// Coordinates are region-relative (0-31).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LayoutIssue {
+    /// The chunk's sectors start inside the 8 KiB header.
+    #[error("Chunk ({x}, {z}) starts at sector {offset}, inside the header")]
+    OverlapsHeader {
+        /// Chunk x coordinate.
+        x: i32,
+        /// Chunk z coordinate.
+        z: i32,
+        /// The sector offset from the location table.
+        offset: u32,
+    },
+    /// The chunk's allocated sectors extend past the end of the file.
+    #[error("Chunk ({x}, {z}) ends at byte {end}, past the end of the file at {file_len}")]
+    PastEndOfFile {
+        /// Chunk x coordinate.
+        x: i32,
+        /// Chunk z coordinate.
+        z: i32,
+        /// Byte offset just past the chunk's last allocated sector.
+        end: usize,
+        /// Length of the file in bytes.
+        file_len: usize,
+    },
+    /// Two chunks are allocated overlapping sectors.
+    #[error("Chunks {first:?} and {second:?} share sectors")]
+    Overlap {
+        /// The chunk starting first in the file.
+        first: (i32, i32),
+        /// The chunk starting inside the first one's sectors.
+        second: (i32, i32),
+    },
+    /// The chunk's length prefix claims more bytes than its allocated sectors hold.
+    #[error("Chunk ({x}, {z}) is {length} bytes long but only {allocated} bytes are allocated")]
+    LengthExceedsSectors {
+        /// Chunk x coordinate.
+        x: i32,
+        /// Chunk z coordinate.
+        z: i32,
+        /// The length prefix, which counts the compression byte and payload.
+        length: u32,
+        /// Bytes available after the 4-byte length prefix.
+        allocated: usize,
+    },
+}
+
+/// The result of [`Region::validate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayoutReport {
+    /// Every problem found, ordered by the file offset of the chunk concerned.
+    pub issues: Vec<LayoutIssue>,
+}
+
+impl LayoutReport {
+    /// Returns `true` if no problem was found.
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl Region {
+    /// Checks the location table against the file: chunks must start after the header,
+    /// end within the file, not share sectors, and have length prefixes that fit their
+    /// allocation.
+    ///
+    /// Only the header and the 4-byte length prefixes are read.
+    pub fn validate(&self) -> LayoutReport {
+        let data = self.bytes();
+        let locations = &self.header().locations;
+        let mut indices: Vec<usize> = (0..1024).filter(|&i| !locations[i].is_empty()).collect();
+        indices.sort_by_key(|&i| locations[i].offset);
+
+        let coords = |i: usize| ((i % 32) as i32, (i / 32) as i32);
+        let mut issues = Vec::new();
+        // The chunk reaching furthest into the file so far, for overlap detection
+        let mut furthest: Option<usize> = None;
+        for index in indices {
+            let (x, z) = coords(index);
+            let location = locations[index];
+            if location.start_byte() < HEADER_SIZE {
+                issues.push(LayoutIssue::OverlapsHeader {
+                    x,
+                    z,
+                    offset: location.offset,
+                });
+                continue;
+            }
+
+            if let Some(previous) = furthest
+                && locations[previous].overlaps(&location)
+            {
+                issues.push(LayoutIssue::Overlap {
+                    first: coords(previous),
+                    second: (x, z),
+                });
+            }
+            if furthest.is_none_or(|p| locations[p].end_byte() < location.end_byte()) {
+                furthest = Some(index);
+            }
+
+            let (start, end) = (location.start_byte(), location.end_byte());
+            if end > data.len() {
+                issues.push(LayoutIssue::PastEndOfFile {
+                    x,
+                    z,
+                    end,
+                    file_len: data.len(),
+                });
+            }
+            if let Some(prefix) = data.get(start..start + 4) {
+                let length = u32::from_be_bytes(prefix.try_into().unwrap());
+                let allocated = (location.sector_count as usize * SECTOR_SIZE).saturating_sub(4);
+                if length as usize > allocated {
+                    issues.push(LayoutIssue::LengthExceedsSectors {
+                        x,
+                        z,
+                        length,
+                        allocated,
+                    });
+                }
+            }
+        }
+        LayoutReport { issues }
+    }
+}
+
+/// A chunk found by [`Region::salvage`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SalvagedChunk {
+    /// The sector the chunk starts at.
+    pub sector: u32,
+    /// Region-relative coordinates (0-31), from the chunk's `xPos`/`zPos` fields or,
+    /// failing that, from a header entry pointing at the sector. `None` if neither is
+    /// available.
+    pub position: Option<(i32, i32)>,
+    /// The root tag name.
+    pub name: String,
+    /// The chunk data.
+    pub tag: NbtTag,
+}
+
+impl Region {
+    /// Finds chunks by scanning every sector for a plausible chunk start, ignoring the
+    /// location table.
+    ///
+    /// A sector holds a chunk if it starts with a length that fits in the file, a
+    /// known compression byte, and a payload that decompresses and parses as NBT. This
+    /// recovers chunks from regions whose header was zeroed or overwritten; write them
+    /// back with [`RegionWriter`](crate::anvil::encode::RegionWriter) to rebuild the
+    /// file. Chunks with custom compression are not recognized.
+    pub fn salvage(&self) -> Vec<SalvagedChunk> {
+        let data = self.bytes();
+        let locations = &self.header().locations;
+        let mut chunks = Vec::new();
+        let mut start = HEADER_SIZE;
+        while start + 5 <= data.len() {
+            let Some((length, name, tag)) = chunk_at(self, &data[start..]) else {
+                start += SECTOR_SIZE;
+                continue;
+            };
+            let sector = (start / SECTOR_SIZE) as u32;
+            let position = chunk_position(&tag).or_else(|| {
+                (0..1024)
+                    .find(|&i| locations[i].offset == sector)
+                    .map(|i| ((i % 32) as i32, (i / 32) as i32))
+            });
+            chunks.push(SalvagedChunk {
+                sector,
+                position,
+                name,
+                tag,
+            });
+            start += (length + 4).div_ceil(SECTOR_SIZE) * SECTOR_SIZE;
+        }
+        chunks
+    }
+}
+
+/// Decodes a chunk starting at the beginning of `data`, returning its length prefix.
+/// The region's [`RegionOptions`](crate::anvil::access::RegionOptions) apply.
+fn chunk_at(region: &Region, data: &[u8]) -> Option<(usize, String, NbtTag)> {
+    let length = u32::from_be_bytes(data[..4].try_into().unwrap()) as usize;
+    let payload = data.get(5..4 + length)?;
+    let compression = CompressionType::try_from(data[4]).ok()?;
+    let (name, tag) = parse_chunk(&region.decompress(compression, payload).ok()?).ok()?;
+    matches!(tag, NbtTag::Compound(_)).then_some((length, name, tag))
+}
+
+/// Reads the region-relative position of a chunk from `xPos`/`zPos`, including the
+/// pre-1.18 layout that nests them in `Level`.
+fn chunk_position(tag: &NbtTag) -> Option<(i32, i32)> {
+    let NbtTag::Compound(root) = tag else {
+        return None;
+    };
+    let fields = match root.get("Level") {
+        Some(NbtTag::Compound(level)) => level,
+        _ => root,
+    };
+    match (fields.get("xPos"), fields.get("zPos")) {
+        (Some(NbtTag::Int(x)), Some(NbtTag::Int(z))) => Some((x.rem_euclid(32), z.rem_euclid(32))),
+        _ => None,
+    }
+}
@@ -0,0 +1,302 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! The LZ4 block stream of chunks with compression id 4.
+//!
+//! Since 1.20.5 the game can store chunks with LZ4 (`region-file-compression=lz4` in
+//! `server.properties`). It writes them with lz4-java's `LZ4BlockOutputStream`: the data
+//! is cut into blocks of at most 64 KiB, each compressed on its own and preceded by a
+//! 21-byte header, and the stream ends with an empty block. A header holds the
+//! `LZ4Block` magic, a token with the block's method (raw or LZ4) and size class, the
+//! compressed and original lengths, and an XXH32 checksum of the original data, all
+//! little-endian.
+//!
+//! ```
+//! use anvil_nbt::anvil::lz4::{Lz4Decoder, Lz4Encoder};
+//! use std::io::{Read, Write};
+//!
+//! let mut encoder = Lz4Encoder::new(Vec::new());
+//! encoder.write_all(&[7; 100_000])?;
+//! let compressed = encoder.finish()?;
+//!
+//! let mut decoded = Vec::new();
+//! Lz4Decoder::new(&compressed[..]).read_to_end(&mut decoded)?;
+//! assert_eq!(decoded, [7; 100_000]);
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use twox_hash::XxHash32;
+
+const MAGIC: &[u8; 8] = b"LZ4Block";
+const HEADER_LEN: usize = 21;
+/// The block size lz4-java writes by default.
+const BLOCK_SIZE: usize = 1 << 16;
+const METHOD_RAW: u8 = 0x10;
+const METHOD_LZ4: u8 = 0x20;
+/// Block sizes are stored as a power of two above 2^10 in the token's low nibble.
+const LEVEL_BASE: u8 = 10;
+const CHECKSUM_SEED: u32 = 0x9747_b28c;
+
+/// lz4-java keeps the low 28 bits of the hash.
+fn checksum(data: &[u8]) -> u32 {
+    XxHash32::oneshot(CHECKSUM_SEED, data) & 0x0fff_ffff
+}
+
+fn corrupted(msg: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Corrupted LZ4 stream: {}", msg),
+    )
+}
+
+/// Compresses data written to it as an LZ4 block stream.
+///
+/// Like flate2's encoders, the stream is only complete once [`finish`](Self::finish)
+/// has written the last block and the end marker.
+pub struct Lz4Encoder<W: Write> {
+    inner: W,
+    block: Vec<u8>,
+    compressed: Vec<u8>,
+}
+
+impl<W: Write> Lz4Encoder<W> {
+    /// Creates an encoder writing the compressed stream to `inner`.
+    pub fn new(inner: W) -> Self {
+        Lz4Encoder {
+            inner,
+            block: Vec::with_capacity(BLOCK_SIZE),
+            compressed: Vec::new(),
+        }
+    }
+
+    /// Writes the buffered data and the end marker, and returns the inner writer.
+    pub fn finish(mut self) -> Result<W> {
+        if !self.block.is_empty() {
+            self.write_block()?;
+        }
+        self.write_header(METHOD_RAW, 0, 0, 0)?;
+        Ok(self.inner)
+    }
+
+    fn write_block(&mut self) -> Result<()> {
+        let max_len = lz4_flex::block::get_maximum_output_size(self.block.len());
+        self.compressed.resize(max_len, 0);
+        let len = lz4_flex::block::compress_into(&self.block, &mut self.compressed)
+            .map_err(|e| Error::other(e.to_string()))?;
+        let check = checksum(&self.block);
+        let original = self.block.len() as u32;
+        // Blocks that do not shrink are stored raw, as lz4-java does
+        if len < self.block.len() {
+            self.write_header(METHOD_LZ4, len as u32, original, check)?;
+            self.inner.write_all(&self.compressed[..len])?;
+        } else {
+            self.write_header(METHOD_RAW, original, original, check)?;
+            self.inner.write_all(&self.block)?;
+        }
+        self.block.clear();
+        Ok(())
+    }
+
+    fn write_header(
+        &mut self,
+        method: u8,
+        compressed: u32,
+        original: u32,
+        check: u32,
+    ) -> Result<()> {
+        let mut header = [0u8; HEADER_LEN];
+        header[..8].copy_from_slice(MAGIC);
+        // lz4-java's size class for 64 KiB blocks
+        header[8] = method | (16 - LEVEL_BASE);
+        header[9..13].copy_from_slice(&compressed.to_le_bytes());
+        header[13..17].copy_from_slice(&original.to_le_bytes());
+        header[17..].copy_from_slice(&check.to_le_bytes());
+        self.inner.write_all(&header)
+    }
+}
+
+impl<W: Write> Write for Lz4Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let len = buf.len().min(BLOCK_SIZE - self.block.len());
+        self.block.extend_from_slice(&buf[..len]);
+        if self.block.len() == BLOCK_SIZE {
+            self.write_block()?;
+        }
+        Ok(len)
+    }
+
+    /// Flushes the inner writer. Buffered data is only written once a block fills up or
+    /// the stream is finished.
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Decompresses an LZ4 block stream read from `inner`.
+///
+/// Reading stops at the end marker, so data after the stream is left in `inner`. A
+/// stream without an end marker fails with
+/// [`UnexpectedEof`](ErrorKind::UnexpectedEof), and damaged blocks or checksums with
+/// [`InvalidData`](ErrorKind::InvalidData).
+pub struct Lz4Decoder<R: Read> {
+    inner: R,
+    block: Vec<u8>,
+    compressed: Vec<u8>,
+    pos: usize,
+    finished: bool,
+}
+
+impl<R: Read> Lz4Decoder<R> {
+    /// Creates a decoder reading the compressed stream from `inner`.
+    pub fn new(inner: R) -> Self {
+        Lz4Decoder {
+            inner,
+            block: Vec::new(),
+            compressed: Vec::new(),
+            pos: 0,
+            finished: false,
+        }
+    }
+
+    /// Reads and decompresses the next block, or the end marker.
+    fn refill(&mut self) -> Result<()> {
+        let mut header = [0u8; HEADER_LEN];
+        self.inner.read_exact(&mut header)?;
+        if &header[..8] != MAGIC {
+            return Err(corrupted("bad block magic"));
+        }
+        let method = header[8] & 0xf0;
+        let max_len = 1usize << (LEVEL_BASE + (header[8] & 0x0f));
+        let compressed = u32::from_le_bytes(header[9..13].try_into().unwrap()) as usize;
+        let original = u32::from_le_bytes(header[13..17].try_into().unwrap()) as usize;
+        let check = u32::from_le_bytes(header[17..].try_into().unwrap());
+
+        if original == 0 && compressed == 0 {
+            if check != 0 {
+                return Err(corrupted("bad end marker"));
+            }
+            self.finished = true;
+            self.block.clear();
+            self.pos = 0;
+            return Ok(());
+        }
+        let valid = match method {
+            METHOD_RAW => compressed == original,
+            METHOD_LZ4 => {
+                compressed > 0 && compressed <= lz4_flex::block::get_maximum_output_size(original)
+            }
+            _ => false,
+        };
+        if !valid || original == 0 || original > max_len {
+            return Err(corrupted("bad block header"));
+        }
+
+        self.compressed.resize(compressed, 0);
+        self.inner.read_exact(&mut self.compressed)?;
+        if method == METHOD_RAW {
+            std::mem::swap(&mut self.block, &mut self.compressed);
+        } else {
+            self.block.resize(original, 0);
+            let len = lz4_flex::block::decompress_into(&self.compressed, &mut self.block)
+                .map_err(|e| corrupted(&e.to_string()))?;
+            if len != original {
+                return Err(corrupted("block length mismatch"));
+            }
+        }
+        if checksum(&self.block) != check {
+            return Err(corrupted("checksum mismatch"));
+        }
+        self.pos = 0;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for Lz4Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        while self.pos == self.block.len() && !self.finished {
+            self.refill()?;
+        }
+        let len = buf.len().min(self.block.len() - self.pos);
+        buf[..len].copy_from_slice(&self.block[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compress(data: &[u8]) -> Vec<u8> {
+        let mut encoder = Lz4Encoder::new(Vec::new());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_lz4_round_trip_blocks() {
+        // Compressible data spanning several blocks
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i / 100) as u8).collect();
+        let compressed = compress(&data);
+        assert!(compressed.len() < data.len());
+        assert_eq!(&compressed[..8], MAGIC);
+        assert_eq!(compressed[8], METHOD_LZ4 | 6);
+
+        let mut decoded = Vec::new();
+        let mut stream = compressed.clone();
+        stream.extend_from_slice(b"rest");
+        let mut input = &stream[..];
+        Lz4Decoder::new(&mut input)
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, data);
+        assert_eq!(input, b"rest");
+
+        // Incompressible data is stored raw
+        let mut state = 1u32;
+        let noise: Vec<u8> = (0..1000)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (state >> 16) as u8
+            })
+            .collect();
+        let compressed = compress(&noise);
+        assert_eq!(compressed[8], METHOD_RAW | 6);
+        let mut decoded = Vec::new();
+        Lz4Decoder::new(&compressed[..])
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, noise);
+
+        assert_eq!(compress(&[]), {
+            let mut end = MAGIC.to_vec();
+            end.push(METHOD_RAW | 6);
+            end.extend_from_slice(&[0; 12]);
+            end
+        });
+    }
+
+    #[test]
+    fn test_lz4_rejects_damaged_streams() {
+        let compressed = compress(b"some chunk data, some chunk data, some chunk data");
+        let decode = |bytes: &[u8]| Lz4Decoder::new(bytes).read_to_end(&mut Vec::new());
+
+        let truncated = decode(&compressed[..compressed.len() - HEADER_LEN]).unwrap_err();
+        assert_eq!(truncated.kind(), ErrorKind::UnexpectedEof);
+
+        let mut bad_checksum = compressed.clone();
+        bad_checksum[17] ^= 1;
+        assert_eq!(
+            decode(&bad_checksum).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+
+        let mut bad_length = compressed.clone();
+        bad_length[13..17].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            decode(&bad_length).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+}
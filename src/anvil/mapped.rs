@@ -0,0 +1,263 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Writable memory-mapped regions.
+//!
+//! A [`MappedRegion`] maps a region file read-write and patches chunks directly in the
+//! mapping. When a chunk's new encoding fits in the sectors it already occupies, it is
+//! written over the old data and only its header entries change, so small edits such as
+//! a sign or a chest cost a single page write. Chunks that grew, or whose sectors another
+//! header entry also points at, are appended to the end of the file instead.
+//!
+//! Patching in place overwrites the only copy of the chunk: an edit interrupted before
+//! [`MappedRegion::flush`] or [`MappedRegion::flush_range`] returns can leave the chunk
+//! half written. Use
+//! [`RegionEditor`](crate::anvil::editor::RegionEditor) when that matters. Sectors
+//! released by shrunk or moved chunks are not reused here, but
+//! [`RegionEditor::open`](crate::anvil::editor::RegionEditor::open) finds and reuses them.
+
+use crate::anvil::access::{RegionOptions, chunk_index, decompress_limited, parse_chunk};
+use crate::anvil::encode::SectorBuffer;
+use crate::anvil::{
+    ChunkLocation, CompressionPreset, CompressionType, HEADER_SIZE, RegionHeader, SECTOR_SIZE,
+    SyncPolicy, sector_count, to_timestamp,
+};
+use crate::nbt::NbtTag;
+use memmap2::MmapMut;
+use std::fs::{File, OpenOptions};
+use std::io::{Error, ErrorKind, Result};
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Where [`MappedRegion::set_chunk`] stored a chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    /// The chunk fit in its previous sectors and was written over them.
+    InPlace,
+    /// The chunk was new, grew or shared its sectors, and was appended to the end of the
+    /// file.
+    Appended,
+}
+
+/// A region file mapped read-write into memory.
+///
+/// Other processes must not modify the file while it is mapped.
+pub struct MappedRegion {
+    file: File,
+    map: MmapMut,
+    header: RegionHeader,
+    buffer: SectorBuffer,
+    sync_policy: SyncPolicy,
+    options: RegionOptions,
+}
+
+impl MappedRegion {
+    /// Opens an existing region file and maps it read-write.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        if file.metadata()?.len() < HEADER_SIZE as u64 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "MCA file too small for headers",
+            ));
+        }
+        let map = unsafe { MmapMut::map_mut(&file)? };
+        let header = RegionHeader::parse(map[..HEADER_SIZE].try_into().unwrap());
+        Ok(MappedRegion {
+            file,
+            map,
+            header,
+            buffer: SectorBuffer::default(),
+            sync_policy: SyncPolicy::default(),
+            options: RegionOptions::default(),
+        })
+    }
+
+    /// Returns the current [`SyncPolicy`].
+    pub fn sync_policy(&self) -> SyncPolicy {
+        self.sync_policy
+    }
+
+    /// Sets when edits are synced to disk.
+    ///
+    /// With [`SyncPolicy::Always`] every [`set_chunk`](Self::set_chunk) and
+    /// [`remove_chunk`](Self::remove_chunk) flushes the pages it touched before returning.
+    /// There is no commit step, so the other policies leave syncing to
+    /// [`flush`](Self::flush) and [`flush_range`](Self::flush_range).
+    pub fn set_sync_policy(&mut self, policy: SyncPolicy) {
+        self.sync_policy = policy;
+    }
+
+    /// Sets the limits applied when reading chunks.
+    pub fn set_options(&mut self, options: RegionOptions) {
+        self.options = options;
+    }
+
+    /// Returns the limits applied when reading chunks.
+    pub fn options(&self) -> &RegionOptions {
+        &self.options
+    }
+
+    /// Sets how chunks passed to [`set_chunk`](Self::set_chunk) are compressed
+    /// ([`CompressionPreset::Balanced`] by default).
+    pub fn set_compression(&mut self, preset: CompressionPreset) {
+        self.buffer = SectorBuffer::with_compression(preset);
+    }
+
+    /// Returns the region header.
+    pub fn header(&self) -> &RegionHeader {
+        &self.header
+    }
+
+    /// Retrieves the decompressed NBT data for a chunk.
+    ///
+    /// See [`Region::get_chunk_data`](crate::anvil::access::Region::get_chunk_data) for
+    /// the coordinate conventions.
+    pub fn get_chunk_data(&self, x: i32, z: i32) -> Result<Option<Vec<u8>>> {
+        let location = self.header.locations[chunk_index(x, z)];
+        if location.is_empty() {
+            return Ok(None);
+        }
+        let past_end = || {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Chunk ({}, {}) extends past the end of the region file",
+                    x, z
+                ),
+            )
+        };
+        let start = location.start_byte();
+        let prefix = self.map.get(start..start + 5).ok_or_else(past_end)?;
+        let length = u32::from_be_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as usize;
+        if length < 1 {
+            return Ok(None);
+        }
+        if length + 4 > location.sector_count as usize * SECTOR_SIZE {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Chunk ({}, {}) is longer than its sectors", x, z),
+            ));
+        }
+        let compression = CompressionType::try_from(prefix[4])
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        let payload = self
+            .map
+            .get(start + 5..start + 4 + length)
+            .ok_or_else(past_end)?;
+        decompress_limited(compression, payload, self.options.max_chunk_size).map(Some)
+    }
+
+    /// Parses the NBT data for a chunk.
+    pub fn get_chunk_nbt(&self, x: i32, z: i32) -> Result<Option<(String, NbtTag)>> {
+        match self.get_chunk_data(x, z)? {
+            Some(data) => parse_chunk(&data).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Replaces (or inserts) a chunk and stamps it with the current time.
+    ///
+    /// The chunk is written over its previous sectors if it still fits in them and no
+    /// other chunk's entry overlaps them, and appended to the file otherwise.
+    pub fn set_chunk(&mut self, x: i32, z: i32, name: &str, tag: &NbtTag) -> Result<Placement> {
+        self.set_chunk_at(x, z, name, tag, SystemTime::now())
+    }
+
+    /// Like [`set_chunk`](Self::set_chunk), but stamps the chunk with `modified`.
+    pub fn set_chunk_at(
+        &mut self,
+        x: i32,
+        z: i32,
+        name: &str,
+        tag: &NbtTag,
+        modified: SystemTime,
+    ) -> Result<Placement> {
+        let index = chunk_index(x, z);
+        let count = self.buffer.encode_chunk(name, tag)?;
+        let sector_count = sector_count(count)?;
+
+        let old = self.header.locations[index];
+        let fits = !old.is_empty()
+            && old.start_byte() >= HEADER_SIZE
+            && sector_count <= old.sector_count
+            && old.end_byte() <= self.map.len()
+            && !self
+                .header
+                .locations
+                .iter()
+                .enumerate()
+                .any(|(other, location)| other != index && location.overlaps(&old));
+        let (offset, placement) = if fits {
+            (old.offset, Placement::InPlace)
+        } else {
+            (self.grow(count)?, Placement::Appended)
+        };
+
+        let start = offset as usize * SECTOR_SIZE;
+        self.map[start..start + self.buffer.as_bytes().len()]
+            .copy_from_slice(self.buffer.as_bytes());
+        self.write_entry(
+            index,
+            ChunkLocation {
+                offset,
+                sector_count,
+            },
+            to_timestamp(modified),
+        );
+        if self.sync_policy == SyncPolicy::Always {
+            self.flush_range(x, z)?;
+        }
+        Ok(placement)
+    }
+
+    /// Removes a chunk by clearing its header entries.
+    pub fn remove_chunk(&mut self, x: i32, z: i32) -> Result<()> {
+        self.write_entry(chunk_index(x, z), ChunkLocation::EMPTY, 0);
+        if self.sync_policy == SyncPolicy::Always {
+            self.flush_range(x, z)?;
+        }
+        Ok(())
+    }
+
+    /// Forces all edits made so far to stable storage.
+    pub fn flush(&self) -> Result<()> {
+        self.map.flush()
+    }
+
+    /// Forces only the sectors and header entries of the chunk at `(x, z)` to stable
+    /// storage, which is much cheaper than [`flush`](Self::flush) after a single edit.
+    ///
+    /// Edits to other chunks may stay unsynced.
+    pub fn flush_range(&self, x: i32, z: i32) -> Result<()> {
+        let index = chunk_index(x, z);
+        for entry in [index * 4, SECTOR_SIZE + index * 4] {
+            self.map.flush_range(entry, 4)?;
+        }
+        let location = self.header.locations[index];
+        if !location.is_empty() && location.end_byte() <= self.map.len() {
+            let start = location.start_byte();
+            self.map.flush_range(start, location.end_byte() - start)?;
+        }
+        Ok(())
+    }
+
+    /// Extends the file by `count` sectors, remaps it and returns the first new sector.
+    fn grow(&mut self, count: usize) -> Result<u32> {
+        self.map.flush()?;
+        let offset = self.map.len().div_ceil(SECTOR_SIZE);
+        self.file.set_len(((offset + count) * SECTOR_SIZE) as u64)?;
+        self.map = unsafe { MmapMut::map_mut(&self.file)? };
+        Ok(offset as u32)
+    }
+
+    /// Updates the location and timestamp entries of one chunk in the mapping.
+    fn write_entry(&mut self, index: usize, location: ChunkLocation, timestamp: u32) {
+        self.header.locations[index] = location;
+        self.header.timestamps[index] = timestamp;
+        let bytes = self.header.to_bytes();
+        for entry in [index * 4, SECTOR_SIZE + index * 4] {
+            self.map[entry..entry + 4].copy_from_slice(&bytes[entry..entry + 4]);
+        }
+    }
+}
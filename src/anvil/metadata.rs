@@ -0,0 +1,185 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Cheap per-chunk statistics for pruning and heatmaps.
+//!
+//! [`Region::chunk_metadata`] reads only `InhabitedTime`, `LastUpdate` and `Status` from
+//! each chunk, skipping sections, entities and everything else without decoding them.
+//! [`Region::chunk_info`] goes further and reads nothing but the headers.
+//! [`Region::chunk_data_version`] and [`Region::chunk_status`] look up a single field of
+//! one chunk and stop parsing as soon as it is found.
+
+use crate::anvil::CompressionType;
+use crate::anvil::access::{Region, chunk_index};
+use crate::nbt::NbtTag;
+use crate::nbt::parse::{find_named_fields, parse_named_fields};
+use std::io::Result;
+
+/// Number of chunk slots in a region.
+const CHUNKS: usize = 1024;
+
+/// Fields read from every chunk, for both the current layout and the pre-1.18 one that
+/// nests them in a `Level` compound.
+const FIELDS: [&str; 6] = [
+    "InhabitedTime",
+    "LastUpdate",
+    "Status",
+    "Level.InhabitedTime",
+    "Level.LastUpdate",
+    "Level.Status",
+];
+
+/// Age and activity data of every chunk in a region, stored as a struct of arrays.
+///
+/// Every array has 1024 entries indexed like the region header, see [`Self::index`].
+/// Slots without a chunk have `present` set to `false`, zero times and no status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkMetadata {
+    /// Whether a chunk is stored in the slot.
+    pub present: Vec<bool>,
+    /// Total ticks players have spent near the chunk (`InhabitedTime`).
+    pub inhabited_time: Vec<i64>,
+    /// Game tick the chunk was last saved at (`LastUpdate`).
+    pub last_update: Vec<i64>,
+    /// Generation status, such as `"minecraft:full"` (`Status`).
+    pub status: Vec<Option<String>>,
+}
+
+impl ChunkMetadata {
+    fn empty() -> Self {
+        ChunkMetadata {
+            present: vec![false; CHUNKS],
+            inhabited_time: vec![0; CHUNKS],
+            last_update: vec![0; CHUNKS],
+            status: vec![None; CHUNKS],
+        }
+    }
+
+    /// Returns the array index of chunk `(x, z)`, wrapping coordinates outside the region.
+    pub fn index(x: i32, z: i32) -> usize {
+        crate::anvil::access::chunk_index(x, z)
+    }
+
+    /// Returns the number of chunks present in the region.
+    pub fn chunk_count(&self) -> usize {
+        self.present.iter().filter(|p| **p).count()
+    }
+}
+
+/// Storage details of one chunk, read from the region header and the chunk's 5-byte
+/// prefix without decompressing anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkInfo {
+    /// Offset of the chunk in sectors from the start of the file.
+    pub sector_offset: u32,
+    /// Number of sectors allocated to the chunk.
+    pub sector_count: u8,
+    /// Size of the compressed payload in bytes, excluding the 5-byte prefix.
+    pub compressed_len: usize,
+    /// Compression id byte of the payload, as stored. Ids this crate does not know, such
+    /// as [`CUSTOM_COMPRESSION_ID`](crate::anvil::codec::CUSTOM_COMPRESSION_ID), are
+    /// reported unchanged.
+    pub compression_id: u8,
+    /// Last modification time in seconds since the Unix epoch, or 0 if never set.
+    pub timestamp: u32,
+}
+
+impl ChunkInfo {
+    /// Returns the standard compression of the payload, or `None` for custom or unknown
+    /// compression ids.
+    pub fn compression(&self) -> Option<CompressionType> {
+        CompressionType::try_from(self.compression_id).ok()
+    }
+}
+
+impl Region {
+    /// Returns where and how the chunk at `(x, z)` is stored, or `Ok(None)` if it is not
+    /// present. Coordinates wrap as in [`get_chunk_data`](Self::get_chunk_data).
+    pub fn chunk_info(&self, x: i32, z: i32) -> Result<Option<ChunkInfo>> {
+        let index = chunk_index(x, z);
+        let Some((compression_id, payload)) = self.raw_payload(index)? else {
+            return Ok(None);
+        };
+        let location = self.header().locations[index];
+        Ok(Some(ChunkInfo {
+            sector_offset: location.offset,
+            sector_count: location.sector_count,
+            compressed_len: payload.len(),
+            compression_id,
+            timestamp: self.header().timestamps[index],
+        }))
+    }
+
+    /// Returns the `DataVersion` of the chunk at `(x, z)`, or `Ok(None)` if the chunk is
+    /// not present or has no `DataVersion` (chunks saved before 1.9).
+    ///
+    /// The chunk is decompressed, but parsing stops at the `DataVersion` field, so
+    /// sections and entities stored after it are never read.
+    pub fn chunk_data_version(&self, x: i32, z: i32) -> Result<Option<i32>> {
+        let Some(data) = self.get_chunk_data(x, z)? else {
+            return Ok(None);
+        };
+        match find_field(&data, "DataVersion")? {
+            Some(NbtTag::Int(version)) => Ok(Some(version)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the generation status of the chunk at `(x, z)`, such as
+    /// `"minecraft:full"`, or `Ok(None)` if the chunk is not present or has no status.
+    ///
+    /// Both the current layout and the pre-1.18 one that nests `Status` in `Level` are
+    /// understood. Parsing stops as soon as the status is found.
+    pub fn chunk_status(&self, x: i32, z: i32) -> Result<Option<String>> {
+        let Some(data) = self.get_chunk_data(x, z)? else {
+            return Ok(None);
+        };
+        let status = match find_field(&data, "Status")? {
+            Some(status) => Some(status),
+            None => find_field(&data, "Level.Status")?,
+        };
+        match status {
+            Some(NbtTag::String(status)) => Ok(Some(status)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Extracts `InhabitedTime`, `LastUpdate` and `Status` for every chunk in the region.
+    ///
+    /// Only these fields are decoded; the rest of each chunk is skipped, which makes
+    /// this far cheaper than calling [`get_chunk_nbt`](Self::get_chunk_nbt) per chunk.
+    pub fn chunk_metadata(&self) -> Result<ChunkMetadata> {
+        let mut metadata = ChunkMetadata::empty();
+        for index in 0..CHUNKS {
+            let Some((compression_type, payload)) = self.chunk_payload(index)? else {
+                continue;
+            };
+            let data = self.decompress(compression_type, payload)?;
+            let mut input = &data[..];
+            let fields = parse_named_fields(&mut input, &FIELDS)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            let (current, legacy) = fields.split_at(3);
+            let field = |i: usize| current[i].as_ref().or(legacy[i].as_ref());
+
+            metadata.present[index] = true;
+            if let Some(NbtTag::Long(t)) = field(0) {
+                metadata.inhabited_time[index] = *t;
+            }
+            if let Some(NbtTag::Long(t)) = field(1) {
+                metadata.last_update[index] = *t;
+            }
+            if let Some(NbtTag::String(status)) = field(2) {
+                metadata.status[index] = Some(status.clone());
+            }
+        }
+        Ok(metadata)
+    }
+}
+
+/// Decodes one field of a decompressed chunk, stopping as soon as it is found.
+fn find_field(data: &[u8], path: &str) -> Result<Option<NbtTag>> {
+    let mut input = data;
+    find_named_fields(&mut input, &[path])
+        .map(|mut fields| fields.pop().flatten())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
@@ -4,11 +4,51 @@
 //! Anvil region file format handling.
 
 pub mod access;
+pub mod blending;
+pub mod cache;
+pub mod chunk;
+pub mod codec;
+pub mod cow;
+pub mod editor;
 pub mod encode;
+pub mod layout;
+#[cfg(feature = "lz4")]
+#[cfg_attr(docsrs, doc(cfg(feature = "lz4")))]
+pub mod lz4;
+pub mod mapped;
+pub mod metadata;
+pub mod proto;
+#[cfg(feature = "proptest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+pub mod strategy;
+pub mod structure;
+
+use std::io::{Result, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// The size of a single sector in an Anvil region file (4096 bytes).
 pub const SECTOR_SIZE: usize = 4096;
 
+/// The size of the region header: a sector of locations followed by a sector of timestamps.
+pub const HEADER_SIZE: usize = SECTOR_SIZE * 2;
+
+/// Controls when writable regions force their changes to stable storage with `fsync`.
+///
+/// Without an explicit sync, written data may sit in the OS page cache and be lost if the
+/// machine (not just the process) crashes. Syncing more often trades throughput for
+/// durability.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SyncPolicy {
+    /// Never sync automatically; leave flushing to the OS. Fastest, but a power loss can
+    /// lose or tear recent writes.
+    Never,
+    /// Sync once when changes are committed (the default).
+    #[default]
+    OnCommit,
+    /// Sync after every individual write, and on commit.
+    Always,
+}
+
 /// Represents the location of a chunk within a region file.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ChunkLocation {
@@ -18,8 +58,41 @@ pub struct ChunkLocation {
     pub sector_count: u8,
 }
 
+impl ChunkLocation {
+    /// A location marking a chunk that is not present in the region.
+    pub const EMPTY: ChunkLocation = ChunkLocation {
+        offset: 0,
+        sector_count: 0,
+    };
+
+    /// Returns `true` if this location does not point at any chunk data.
+    pub fn is_empty(&self) -> bool {
+        self.offset == 0
+    }
+
+    /// Returns the byte offset in the file where the chunk's sectors begin.
+    pub fn start_byte(&self) -> usize {
+        self.offset as usize * SECTOR_SIZE
+    }
+
+    /// Returns the byte offset in the file just past the chunk's last allocated sector.
+    pub fn end_byte(&self) -> usize {
+        (self.offset as usize + self.sector_count as usize) * SECTOR_SIZE
+    }
+
+    /// Returns `true` if both locations are present and their sector ranges intersect.
+    pub fn overlaps(&self, other: &ChunkLocation) -> bool {
+        !self.is_empty()
+            && !other.is_empty()
+            && self.start_byte() < other.end_byte()
+            && other.start_byte() < self.end_byte()
+    }
+}
+
 /// The header of a region file, containing locations and timestamps for all 1024 chunks.
-#[derive(Debug, Clone)]
+///
+/// Entries are indexed by `z * 32 + x` with `x` and `z` relative to the region (0-31).
+#[derive(Debug, Clone, PartialEq)]
 pub struct RegionHeader {
     /// Locations for chunks at (0,0) to (31,31).
     pub locations: [ChunkLocation; 1024],
@@ -27,7 +100,157 @@ pub struct RegionHeader {
     pub timestamps: [u32; 1024],
 }
 
+impl Default for RegionHeader {
+    fn default() -> Self {
+        RegionHeader {
+            locations: [ChunkLocation::EMPTY; 1024],
+            timestamps: [0; 1024],
+        }
+    }
+}
+
+impl RegionHeader {
+    /// Parses the 8 KiB header at the start of a region file.
+    ///
+    /// Each location is a 3-byte big-endian sector offset followed by a 1-byte sector
+    /// count; each timestamp is a 4-byte big-endian Unix time in seconds.
+    pub fn parse(bytes: &[u8; HEADER_SIZE]) -> Self {
+        let mut header = RegionHeader::default();
+
+        for (i, location) in header.locations.iter_mut().enumerate() {
+            let start = i * 4;
+            let offset = ((bytes[start] as u32) << 16)
+                | ((bytes[start + 1] as u32) << 8)
+                | (bytes[start + 2] as u32);
+            let sector_count = bytes[start + 3];
+            *location = ChunkLocation {
+                offset,
+                sector_count,
+            };
+        }
+
+        for (i, timestamp_slot) in header.timestamps.iter_mut().enumerate() {
+            let start = SECTOR_SIZE + i * 4;
+            let timestamp = ((bytes[start] as u32) << 24)
+                | ((bytes[start + 1] as u32) << 16)
+                | ((bytes[start + 2] as u32) << 8)
+                | (bytes[start + 3] as u32);
+            *timestamp_slot = timestamp;
+        }
+
+        header
+    }
+
+    /// Encodes the header into its 8 KiB on-disk form.
+    pub fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut bytes = [0u8; HEADER_SIZE];
+        for (i, loc) in self.locations.iter().enumerate() {
+            let start = i * 4;
+            bytes[start] = ((loc.offset >> 16) & 0xFF) as u8;
+            bytes[start + 1] = ((loc.offset >> 8) & 0xFF) as u8;
+            bytes[start + 2] = (loc.offset & 0xFF) as u8;
+            bytes[start + 3] = loc.sector_count;
+        }
+        for (i, timestamp) in self.timestamps.iter().enumerate() {
+            let start = SECTOR_SIZE + i * 4;
+            bytes[start..start + 4].copy_from_slice(&timestamp.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// Writes the 8 KiB header to the writer.
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+}
+
+/// Returns the chunk coordinate containing block coordinate `block`.
+pub fn block_to_chunk(block: i32) -> i32 {
+    block >> 4
+}
+
+/// Returns the region coordinate containing chunk coordinate `chunk`.
+pub fn chunk_to_region(chunk: i32) -> i32 {
+    chunk >> 5
+}
+
+/// Returns the region coordinate containing block coordinate `block`.
+pub fn block_to_region(block: i32) -> i32 {
+    block >> 9
+}
+
+/// Returns the lowest block coordinate in chunk `chunk`.
+pub fn chunk_to_block(chunk: i32) -> i32 {
+    chunk << 4
+}
+
+/// Returns the lowest chunk coordinate in region `region`.
+pub fn region_to_chunk(region: i32) -> i32 {
+    region << 5
+}
+
+/// Returns the position (0-31) of chunk coordinate `chunk` within its region.
+pub fn chunk_in_region(chunk: i32) -> i32 {
+    chunk & 31
+}
+
+/// The coordinates of a region file, such as `(-3, 12)` for `r.-3.12.mca`.
+///
+/// Region `(x, z)` holds chunks `32 * x` to `32 * x + 31` along each axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RegionPos {
+    /// Region x coordinate.
+    pub x: i32,
+    /// Region z coordinate.
+    pub z: i32,
+}
+
+impl RegionPos {
+    /// Creates a region position.
+    pub fn new(x: i32, z: i32) -> Self {
+        RegionPos { x, z }
+    }
+
+    /// Returns the region holding chunk `(chunk_x, chunk_z)`.
+    pub fn from_chunk(chunk_x: i32, chunk_z: i32) -> Self {
+        RegionPos::new(chunk_to_region(chunk_x), chunk_to_region(chunk_z))
+    }
+
+    /// Returns the region holding block `(block_x, block_z)`.
+    pub fn from_block(block_x: i32, block_z: i32) -> Self {
+        RegionPos::new(block_to_region(block_x), block_to_region(block_z))
+    }
+
+    /// Parses a file name of the form `r.<x>.<z>.mca`, returning `None` for any other
+    /// name. Pass the file name only, not a whole path.
+    pub fn from_filename(name: &str) -> Option<Self> {
+        let coords = name.strip_prefix("r.")?.strip_suffix(".mca")?;
+        let (x, z) = coords.split_once('.')?;
+        Some(RegionPos::new(x.parse().ok()?, z.parse().ok()?))
+    }
+
+    /// Returns the file name of the region, such as `r.-3.12.mca`.
+    pub fn to_filename(&self) -> String {
+        format!("r.{}.{}.mca", self.x, self.z)
+    }
+
+    /// Returns the coordinates of the region's lowest chunk.
+    pub fn min_chunk(&self) -> (i32, i32) {
+        (region_to_chunk(self.x), region_to_chunk(self.z))
+    }
+
+    /// Returns `true` if chunk `(chunk_x, chunk_z)` lies in this region.
+    pub fn contains_chunk(&self, chunk_x: i32, chunk_z: i32) -> bool {
+        *self == RegionPos::from_chunk(chunk_x, chunk_z)
+    }
+}
+
 /// Supported compression types for chunk data in Anvil files.
+///
+/// Gzip and Zlib data can only be read and written with the `compression` feature
+/// (enabled by default), and LZ4 data with the `lz4` feature; other codecs fail with
+/// [`Unsupported`](std::io::ErrorKind::Unsupported). Chunks with a custom algorithm
+/// (ID: 127) are handled by [`codec`] instead.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CompressionType {
     /// Gzip compression (ID: 1). Standard for `.dat` files, less common in `.mca`.
@@ -36,16 +259,202 @@ pub enum CompressionType {
     Zlib = 2,
     /// No compression (ID: 3).
     None = 3,
+    /// LZ4 block stream compression (ID: 4), read by the game since 1.20.5. The format
+    /// is described in the `lz4` module.
+    Lz4 = 4,
+}
+
+/// Named trade-offs between compression speed and output size.
+///
+/// Presets choose the codec and level for everything this crate compresses: chunks
+/// written by [`RegionWriter`](encode::RegionWriter), [`SectorBuffer`](encode::SectorBuffer)
+/// and [`CowRegion`](cow::CowRegion), and gzipped `.dat` files. `.dat` files always use
+/// gzip, the only codec the game reads for them, so there the presets differ in level.
+/// flate2 has no setting for zlib's deflate strategies, so the level is all that tunes
+/// deflate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CompressionPreset {
+    /// Fastest compression, for temporary or frequently rewritten data. Chunks use LZ4
+    /// when the `lz4` feature is enabled, which the game reads since 1.20.5, and Zlib at
+    /// level 1 otherwise; `.dat` files use gzip at level 1.
+    Fast,
+    /// The game's own choice: Zlib chunks and gzipped `.dat` files at level 6.
+    #[default]
+    Balanced,
+    /// Smallest output, for backups and distribution: Zlib chunks and gzipped `.dat`
+    /// files at level 9.
+    Archive,
+}
+
+impl CompressionPreset {
+    /// Returns the codec used for chunks.
+    ///
+    /// Without the `compression` feature, presets that would use Zlib store chunks
+    /// uncompressed ([`CompressionType::None`]).
+    pub fn chunk_compression(self) -> CompressionType {
+        match self {
+            #[cfg(feature = "lz4")]
+            CompressionPreset::Fast => CompressionType::Lz4,
+            _ if cfg!(feature = "compression") => CompressionType::Zlib,
+            _ => CompressionType::None,
+        }
+    }
+
+    /// Returns the deflate level, from 1 (fastest) to 9 (smallest).
+    pub fn level(self) -> u32 {
+        match self {
+            CompressionPreset::Fast => 1,
+            CompressionPreset::Balanced => 6,
+            CompressionPreset::Archive => 9,
+        }
+    }
+
+    #[cfg(feature = "compression")]
+    pub(crate) fn flate2(self) -> flate2::Compression {
+        flate2::Compression::new(self.level())
+    }
+}
+
+/// Converts the number of sectors a chunk occupies to a header sector count, failing
+/// for chunks over 255 sectors (about 1 MiB compressed) that a location entry cannot
+/// describe.
+///
+/// The game stores such chunks in separate `.mcc` files, which this crate does not
+/// write.
+pub(crate) fn sector_count(count: usize) -> Result<u8> {
+    u8::try_from(count).map_err(|_| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "Chunk needs {} sectors, more than a region can address",
+                count
+            ),
+        )
+    })
+}
+
+/// Converts a time to a header timestamp in seconds since the Unix epoch, clamping times
+/// the header cannot represent.
+pub(crate) fn to_timestamp(time: SystemTime) -> u32 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs().min(u32::MAX as u64) as u32)
+}
+
+/// Converts a header timestamp to a time, or `None` for `0` (never set).
+pub(crate) fn from_timestamp(timestamp: u32) -> Option<SystemTime> {
+    (timestamp != 0).then(|| UNIX_EPOCH + Duration::from_secs(timestamp as u64))
+}
+
+/// Returns the error reported for data whose codec's feature is disabled.
+#[cfg(not(all(feature = "compression", feature = "lz4")))]
+pub(crate) fn unsupported_compression(compression: CompressionType) -> std::io::Error {
+    let feature = match compression {
+        CompressionType::Lz4 => "lz4",
+        _ => "compression",
+    };
+    std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        format!(
+            "{:?} compression requires the `{}` feature",
+            compression, feature
+        ),
+    )
 }
 
 impl TryFrom<u8> for CompressionType {
     type Error = String;
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+    fn try_from(value: u8) -> std::result::Result<Self, Self::Error> {
         match value {
             1 => Ok(CompressionType::Gzip),
             2 => Ok(CompressionType::Zlib),
             3 => Ok(CompressionType::None),
+            4 => Ok(CompressionType::Lz4),
             _ => Err(format!("Unknown compression type: {}", value)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_round_trip() {
+        let mut header = RegionHeader::default();
+        header.locations[33] = ChunkLocation {
+            offset: 0x012345,
+            sector_count: 3,
+        };
+        header.timestamps[33] = 1_700_000_000;
+
+        let bytes = header.to_bytes();
+        assert_eq!(&bytes[33 * 4..33 * 4 + 4], &[0x01, 0x23, 0x45, 3]);
+        assert_eq!(RegionHeader::parse(&bytes), header);
+    }
+
+    #[test]
+    fn test_preset_codecs() {
+        let fast = if cfg!(feature = "lz4") {
+            CompressionType::Lz4
+        } else if cfg!(feature = "compression") {
+            CompressionType::Zlib
+        } else {
+            CompressionType::None
+        };
+        assert_eq!(CompressionPreset::Fast.chunk_compression(), fast);
+        assert_eq!(
+            CompressionPreset::Archive.chunk_compression(),
+            CompressionPreset::Balanced.chunk_compression()
+        );
+        assert!(CompressionPreset::Fast.level() < CompressionPreset::Archive.level());
+    }
+
+    #[test]
+    fn test_location_overlaps() {
+        let a = ChunkLocation {
+            offset: 2,
+            sector_count: 2,
+        };
+        let b = ChunkLocation {
+            offset: 3,
+            sector_count: 1,
+        };
+        let c = ChunkLocation {
+            offset: 4,
+            sector_count: 1,
+        };
+        assert_eq!((a.start_byte(), a.end_byte()), (8192, 16384));
+        assert!(a.overlaps(&b));
+        assert!(!a.overlaps(&c));
+        assert!(!a.overlaps(&ChunkLocation::EMPTY));
+    }
+
+    #[test]
+    fn test_coordinate_conversions() {
+        assert_eq!(block_to_chunk(-1), -1);
+        assert_eq!(block_to_chunk(31), 1);
+        assert_eq!(chunk_to_region(-33), -2);
+        assert_eq!(block_to_region(-513), -2);
+        assert_eq!(block_to_region(511), 0);
+        assert_eq!(chunk_to_block(-2), -32);
+        assert_eq!(region_to_chunk(-3), -96);
+        assert_eq!(chunk_in_region(-1), 31);
+
+        let pos = RegionPos::from_filename("r.-3.12.mca").unwrap();
+        assert_eq!(pos, RegionPos::new(-3, 12));
+        assert_eq!(pos.to_filename(), "r.-3.12.mca");
+        assert_eq!(pos.min_chunk(), (-96, 384));
+        assert!(pos.contains_chunk(-65, 415));
+        assert!(!pos.contains_chunk(-64, 415));
+        assert_eq!(RegionPos::from_block(-1500, 6200), pos);
+        for name in [
+            "r.1.mca",
+            "r.a.0.mca",
+            "c.0.0.mcc",
+            "r.0.0.mcr",
+            "r.0.0.0.mca",
+        ] {
+            assert_eq!(RegionPos::from_filename(name), None);
+        }
+    }
+}
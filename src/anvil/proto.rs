@@ -0,0 +1,404 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Fields of partially generated chunks (proto-chunks).
+//!
+//! Chunks whose `Status` is not yet `full` carry generation state that full chunks do
+//! not: carving masks, pending light sources and queues of scheduled block and fluid
+//! ticks. The raw tree in [`Chunk::root`] always keeps these fields, so reading and
+//! writing a chunk never drops them; the accessors here decode and re-encode them for
+//! tools that need to look inside.
+
+use crate::anvil::chunk::Chunk;
+use crate::nbt::NbtTag;
+use indexmap::IndexMap;
+
+/// A per-block bit set recording where a carver has already run (`CarvingMasks`).
+///
+/// Bit `x | z << 4 | (y - min_y) << 8` is set for each carved block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CarvingMask {
+    /// The 1.18+ encoding, a long array.
+    Longs(Vec<i64>),
+    /// The 1.14-1.17 encoding, a byte array.
+    Bytes(Vec<u8>),
+}
+
+impl CarvingMask {
+    /// Reads a carving mask array.
+    pub fn from_nbt(tag: &NbtTag) -> Option<Self> {
+        match tag {
+            NbtTag::LongArray(bits) => Some(CarvingMask::Longs(bits.clone())),
+            NbtTag::ByteArray(bits) => Some(CarvingMask::Bytes(bits.clone())),
+            _ => None,
+        }
+    }
+
+    /// Encodes the mask in the array type it was read with.
+    pub fn to_nbt(&self) -> NbtTag {
+        match self {
+            CarvingMask::Longs(bits) => NbtTag::LongArray(bits.clone()),
+            CarvingMask::Bytes(bits) => NbtTag::ByteArray(bits.clone()),
+        }
+    }
+
+    /// Returns `true` if bit `index` is set.
+    pub fn get(&self, index: usize) -> bool {
+        match self {
+            CarvingMask::Longs(bits) => bits
+                .get(index / 64)
+                .is_some_and(|word| (word >> (index % 64)) & 1 != 0),
+            CarvingMask::Bytes(bits) => bits
+                .get(index / 8)
+                .is_some_and(|byte| (byte >> (index % 8)) & 1 != 0),
+        }
+    }
+
+    /// Sets or clears bit `index`, growing the array if needed.
+    pub fn set(&mut self, index: usize, value: bool) {
+        match self {
+            CarvingMask::Longs(bits) => {
+                if bits.len() <= index / 64 {
+                    bits.resize(index / 64 + 1, 0);
+                }
+                let mask = 1i64 << (index % 64);
+                if value {
+                    bits[index / 64] |= mask;
+                } else {
+                    bits[index / 64] &= !mask;
+                }
+            }
+            CarvingMask::Bytes(bits) => {
+                if bits.len() <= index / 8 {
+                    bits.resize(index / 8 + 1, 0);
+                }
+                let mask = 1u8 << (index % 8);
+                if value {
+                    bits[index / 8] |= mask;
+                } else {
+                    bits[index / 8] &= !mask;
+                }
+            }
+        }
+    }
+}
+
+/// Block positions grouped by chunk section, as used by `Lights`, `PostProcessing`
+/// and the pre-1.18 `ToBeTicked` queues.
+///
+/// Each position is a short packing the section-relative coordinates as
+/// `x | y << 4 | z << 8`. The first list belongs to the lowest section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SectionPositions {
+    /// Packed positions, one list per section.
+    pub sections: Vec<Vec<i16>>,
+}
+
+impl SectionPositions {
+    /// Packs section-relative coordinates (0-15) into a short.
+    pub fn pack(x: u8, y: u8, z: u8) -> i16 {
+        (x as i16 & 15) | (y as i16 & 15) << 4 | (z as i16 & 15) << 8
+    }
+
+    /// Unpacks a short into section-relative `(x, y, z)` coordinates.
+    pub fn unpack(packed: i16) -> (u8, u8, u8) {
+        (
+            (packed & 15) as u8,
+            (packed >> 4 & 15) as u8,
+            (packed >> 8 & 15) as u8,
+        )
+    }
+
+    /// Reads a list of short lists. Entries of other types are skipped.
+    pub fn from_nbt(tag: &NbtTag) -> Option<Self> {
+        let NbtTag::List(lists) = tag else {
+            return None;
+        };
+        let sections = lists
+            .iter()
+            .map(|list| match list {
+                NbtTag::List(shorts) => shorts
+                    .iter()
+                    .filter_map(|s| match s {
+                        NbtTag::Short(s) => Some(*s),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            })
+            .collect();
+        Some(SectionPositions { sections })
+    }
+
+    /// Encodes the positions as a list of short lists.
+    pub fn to_nbt(&self) -> NbtTag {
+        NbtTag::List(
+            self.sections
+                .iter()
+                .map(|shorts| NbtTag::List(shorts.iter().map(|&s| NbtTag::Short(s)).collect()))
+                .collect(),
+        )
+    }
+
+    /// Iterates over all positions as `(section index, x, y, z)`, with coordinates
+    /// relative to the section.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, u8, u8, u8)> + '_ {
+        self.sections.iter().enumerate().flat_map(|(i, shorts)| {
+            shorts.iter().map(move |&packed| {
+                let (x, y, z) = Self::unpack(packed);
+                (i, x, y, z)
+            })
+        })
+    }
+}
+
+/// A scheduled block or fluid tick (`block_ticks`, `fluid_ticks`, or `TileTicks` and
+/// `LiquidTicks` in pre-1.18 chunks).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledTick {
+    /// The block or fluid ID (`i`).
+    pub id: String,
+    /// Absolute block X coordinate (`x`).
+    pub x: i32,
+    /// Absolute block Y coordinate (`y`).
+    pub y: i32,
+    /// Absolute block Z coordinate (`z`).
+    pub z: i32,
+    /// Game ticks until the tick runs (`t`).
+    pub delay: i32,
+    /// Priority, lower runs first (`p`).
+    pub priority: i32,
+    /// Any other fields, kept as they were read.
+    pub extra: IndexMap<String, NbtTag>,
+}
+
+impl ScheduledTick {
+    /// Reads a scheduled tick compound.
+    pub fn from_nbt(tag: &NbtTag) -> Option<Self> {
+        let NbtTag::Compound(map) = tag else {
+            return None;
+        };
+        let mut extra = map.clone();
+        let Some(NbtTag::String(id)) = extra.shift_remove("i") else {
+            return None;
+        };
+        let mut int = |key: &str| match extra.shift_remove(key) {
+            Some(NbtTag::Int(v)) => v,
+            _ => 0,
+        };
+        Some(ScheduledTick {
+            id,
+            x: int("x"),
+            y: int("y"),
+            z: int("z"),
+            delay: int("t"),
+            priority: int("p"),
+            extra,
+        })
+    }
+
+    /// Encodes the tick as a compound.
+    pub fn to_nbt(&self) -> NbtTag {
+        let mut map = IndexMap::new();
+        map.insert("i".to_string(), NbtTag::String(self.id.clone()));
+        map.insert("x".to_string(), NbtTag::Int(self.x));
+        map.insert("y".to_string(), NbtTag::Int(self.y));
+        map.insert("z".to_string(), NbtTag::Int(self.z));
+        map.insert("t".to_string(), NbtTag::Int(self.delay));
+        map.insert("p".to_string(), NbtTag::Int(self.priority));
+        map.extend(self.extra.clone());
+        NbtTag::Compound(map)
+    }
+}
+
+impl Chunk {
+    /// Returns the generation status (`Status`, or `Level.Status` in old chunks).
+    pub fn status(&self) -> Option<&str> {
+        match self.root.get_path(&self.layout_path("Status")) {
+            Some(NbtTag::String(status)) => Some(status),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the chunk is only partially generated, i.e. has a status other
+    /// than `full`.
+    pub fn is_proto(&self) -> bool {
+        self.status()
+            .is_some_and(|status| !matches!(status, "full" | "minecraft:full"))
+    }
+
+    /// Returns the carving masks by carver step (`"AIR"`, `"LIQUID"`).
+    pub fn carving_masks(&self) -> IndexMap<String, CarvingMask> {
+        match self.root.get_path(&self.layout_path("CarvingMasks")) {
+            Some(NbtTag::Compound(masks)) => masks
+                .iter()
+                .filter_map(|(step, mask)| Some((step.clone(), CarvingMask::from_nbt(mask)?)))
+                .collect(),
+            _ => IndexMap::new(),
+        }
+    }
+
+    /// Sets or (with `None`) removes the carving mask of one carver step.
+    pub fn set_carving_mask(&mut self, step: &str, mask: Option<&CarvingMask>) {
+        let path = self.layout_path("CarvingMasks");
+        let mut masks = match self.root.get_path(&path) {
+            Some(NbtTag::Compound(masks)) => masks.clone(),
+            _ => IndexMap::new(),
+        };
+        match mask {
+            Some(mask) => {
+                masks.insert(step.to_string(), mask.to_nbt());
+            }
+            None => {
+                masks.shift_remove(step);
+            }
+        }
+        self.set_field(
+            &path,
+            (!masks.is_empty()).then_some(NbtTag::Compound(masks)),
+        );
+    }
+
+    /// Returns the light sources still to be propagated (`Lights`).
+    pub fn lights(&self) -> Option<SectionPositions> {
+        self.root
+            .get_path(&self.layout_path("Lights"))
+            .and_then(SectionPositions::from_nbt)
+    }
+
+    /// Sets or (with `None`) removes the pending light sources.
+    pub fn set_lights(&mut self, lights: Option<&SectionPositions>) {
+        let path = self.layout_path("Lights");
+        self.set_field(&path, lights.map(SectionPositions::to_nbt));
+    }
+
+    /// Returns the blocks awaiting a shape update after generation (`PostProcessing`).
+    pub fn post_processing(&self) -> Option<SectionPositions> {
+        self.root
+            .get_path(&self.layout_path("PostProcessing"))
+            .and_then(SectionPositions::from_nbt)
+    }
+
+    /// Sets or (with `None`) removes the blocks awaiting post-processing.
+    pub fn set_post_processing(&mut self, positions: Option<&SectionPositions>) {
+        let path = self.layout_path("PostProcessing");
+        self.set_field(&path, positions.map(SectionPositions::to_nbt));
+    }
+
+    /// Returns the scheduled block ticks (`block_ticks`, or `Level.TileTicks`).
+    pub fn block_ticks(&self) -> Vec<ScheduledTick> {
+        self.ticks(self.tick_path("block_ticks", "TileTicks"))
+    }
+
+    /// Replaces the scheduled block ticks. An empty slice removes the field.
+    pub fn set_block_ticks(&mut self, ticks: &[ScheduledTick]) {
+        let path = self.tick_path("block_ticks", "TileTicks");
+        self.set_ticks(path, ticks);
+    }
+
+    /// Returns the scheduled fluid ticks (`fluid_ticks`, or `Level.LiquidTicks`).
+    pub fn fluid_ticks(&self) -> Vec<ScheduledTick> {
+        self.ticks(self.tick_path("fluid_ticks", "LiquidTicks"))
+    }
+
+    /// Replaces the scheduled fluid ticks. An empty slice removes the field.
+    pub fn set_fluid_ticks(&mut self, ticks: &[ScheduledTick]) {
+        let path = self.tick_path("fluid_ticks", "LiquidTicks");
+        self.set_ticks(path, ticks);
+    }
+
+    /// Returns `true` if the chunk uses the pre-1.18 layout nested in `Level`.
+    fn is_legacy_layout(&self) -> bool {
+        matches!(self.root.get_path("Level"), Some(NbtTag::Compound(_)))
+    }
+
+    /// Returns the path of a field that moved out of `Level` in 1.18.
+    fn layout_path(&self, name: &str) -> String {
+        if self.is_legacy_layout() {
+            format!("Level.{}", name)
+        } else {
+            name.to_string()
+        }
+    }
+
+    fn tick_path(&self, name: &'static str, legacy: &'static str) -> String {
+        if self.is_legacy_layout() {
+            format!("Level.{}", legacy)
+        } else {
+            name.to_string()
+        }
+    }
+
+    fn ticks(&self, path: String) -> Vec<ScheduledTick> {
+        match self.root.get_path(&path) {
+            Some(NbtTag::List(ticks)) => ticks.iter().filter_map(ScheduledTick::from_nbt).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn set_ticks(&mut self, path: String, ticks: &[ScheduledTick]) {
+        let list = NbtTag::List(ticks.iter().map(ScheduledTick::to_nbt).collect());
+        self.set_field(&path, (!ticks.is_empty()).then_some(list));
+    }
+
+    fn set_field(&mut self, path: &str, value: Option<NbtTag>) {
+        match value {
+            Some(value) => {
+                self.root.set_path(path, value).ok();
+            }
+            None => {
+                self.root.remove_path(path).ok();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::snbt::parse_snbt;
+
+    #[test]
+    fn test_proto_chunk_fields() {
+        let root = parse_snbt(
+            r#"{Status: "minecraft:carvers", CarvingMasks: {AIR: [L; 0L, 4L]},
+                Lights: [[], [273s]], block_ticks: [{i: stone, x: 1, y: 2, z: 3, t: 5, p: 0}]}"#,
+        )
+        .unwrap();
+        let mut chunk = Chunk::new(String::new(), root.clone());
+        assert!(chunk.is_proto());
+
+        let mut air = chunk.carving_masks()["AIR"].clone();
+        assert!(air.get(66));
+        air.set(66, false);
+        air.set(200, true);
+        chunk.set_carving_mask("AIR", Some(&air));
+        assert_eq!(
+            chunk.carving_masks()["AIR"],
+            CarvingMask::Longs(vec![0, 0, 0, 1 << 8])
+        );
+
+        let lights = chunk.lights().unwrap();
+        assert_eq!(lights.iter().collect::<Vec<_>>(), vec![(1, 1, 1, 1)]);
+        assert_eq!(SectionPositions::pack(1, 1, 1), 273);
+
+        let ticks = chunk.block_ticks();
+        assert_eq!(ticks[0].id, "stone");
+        assert_eq!((ticks[0].x, ticks[0].delay), (1, 5));
+        chunk.set_block_ticks(&ticks);
+        chunk.set_lights(Some(&lights));
+        assert_eq!(
+            chunk.root.get_path("block_ticks"),
+            root.get_path("block_ticks")
+        );
+        assert_eq!(chunk.root.get_path("Lights"), root.get_path("Lights"));
+
+        chunk.set_fluid_ticks(&[]);
+        assert_eq!(chunk.root.get_path("fluid_ticks"), None);
+
+        let legacy = parse_snbt("{Level: {Status: full, TileTicks: [{i: water}]}}").unwrap();
+        let chunk = Chunk::new(String::new(), legacy);
+        assert!(!chunk.is_proto());
+        assert_eq!(chunk.block_ticks().len(), 1);
+    }
+}
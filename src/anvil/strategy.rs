@@ -0,0 +1,197 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! [`proptest`](mod@proptest) strategies for generating region files, intact and
+//! corrupted.
+//!
+//! [`region_file`] produces valid regions holding a few small chunks. [`corrupted_region`]
+//! damages such a region the way real-world corruption does: flipped header entries,
+//! wrong chunk lengths, unknown compression bytes and truncation. Readers should turn all
+//! of these into errors rather than panics. It requires the `proptest` feature to be
+//! enabled.
+
+#![cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+
+use crate::anvil::encode::RegionWriter;
+use crate::anvil::{HEADER_SIZE, SECTOR_SIZE};
+use crate::nbt::NbtTag;
+use crate::nbt::strategy::{TagStrategyOptions, root_compound_with};
+use proptest::collection::{btree_map, vec};
+use proptest::prelude::*;
+use std::io::Cursor;
+
+/// One way of damaging a region file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mutation {
+    /// Overwrites a byte of the location or timestamp tables.
+    HeaderByte { index: usize, value: u8 },
+    /// Overwrites the length field of the chunk at a header index.
+    ChunkLength { chunk: usize, length: u32 },
+    /// Overwrites the compression byte of the chunk at a header index.
+    CompressionByte { chunk: usize, value: u8 },
+    /// Cuts the file to a fraction (in 1/256ths) of its length.
+    Truncate { keep: u8 },
+}
+
+impl Mutation {
+    /// Applies the mutation to region bytes. Mutations of absent chunks or of bytes past
+    /// the end of the file do nothing.
+    pub fn apply(&self, data: &mut Vec<u8>) {
+        match *self {
+            Mutation::HeaderByte { index, value } => {
+                if let Some(b) = data.get_mut(index % HEADER_SIZE) {
+                    *b = value;
+                }
+            }
+            Mutation::ChunkLength { chunk, length } => {
+                if let Some(start) = chunk_start(data, chunk)
+                    && let Some(field) = data.get_mut(start..start + 4)
+                {
+                    field.copy_from_slice(&length.to_be_bytes());
+                }
+            }
+            Mutation::CompressionByte { chunk, value } => {
+                if let Some(start) = chunk_start(data, chunk)
+                    && let Some(b) = data.get_mut(start + 4)
+                {
+                    *b = value;
+                }
+            }
+            Mutation::Truncate { keep } => {
+                data.truncate(data.len() * keep as usize / 256);
+            }
+        }
+    }
+}
+
+/// Returns the byte offset of the chunk at header index `chunk % 1024`, if present.
+fn chunk_start(data: &[u8], chunk: usize) -> Option<usize> {
+    let entry = data.get((chunk % 1024) * 4..(chunk % 1024) * 4 + 3)?;
+    let offset = u32::from_be_bytes([0, entry[0], entry[1], entry[2]]) as usize;
+    (offset != 0).then_some(offset * SECTOR_SIZE)
+}
+
+/// Generates valid region files holding up to `max_chunks` small chunks.
+pub fn region_file_with(max_chunks: usize) -> BoxedStrategy<Vec<u8>> {
+    let options = TagStrategyOptions {
+        max_depth: 2,
+        desired_size: 16,
+        ..TagStrategyOptions::default()
+    };
+    btree_map(
+        (0i32..32, 0i32..32),
+        root_compound_with(options),
+        0..=max_chunks,
+    )
+    .prop_map(|chunks| {
+        let chunks: Vec<(i32, i32, String, NbtTag)> = chunks
+            .into_iter()
+            .map(|((x, z), tag)| (x, z, String::new(), tag))
+            .collect();
+        let mut writer = RegionWriter::new(Cursor::new(Vec::new()));
+        writer
+            .write_all_chunks(&chunks)
+            .expect("writing to memory cannot fail");
+        writer.into_inner().into_inner()
+    })
+    .boxed()
+}
+
+/// Generates valid region files holding up to 8 small chunks.
+pub fn region_file() -> BoxedStrategy<Vec<u8>> {
+    region_file_with(8)
+}
+
+/// Generates single mutations, biased towards chunks that are present.
+pub fn mutation() -> BoxedStrategy<Mutation> {
+    prop_oneof![
+        (0..HEADER_SIZE, any::<u8>())
+            .prop_map(|(index, value)| Mutation::HeaderByte { index, value }),
+        (0usize..1024, any::<u32>())
+            .prop_map(|(chunk, length)| Mutation::ChunkLength { chunk, length }),
+        (0usize..1024, any::<u8>())
+            .prop_map(|(chunk, value)| Mutation::CompressionByte { chunk, value }),
+        any::<u8>().prop_map(|keep| Mutation::Truncate { keep }),
+    ]
+    .boxed()
+}
+
+/// Generates region files damaged by one to four [`Mutation`]s, together with the
+/// mutations applied.
+pub fn corrupted_region() -> BoxedStrategy<(Vec<u8>, Vec<Mutation>)> {
+    (region_file(), vec(mutation(), 1..=4))
+        .prop_map(|(mut data, mutations)| {
+            for mutation in &mutations {
+                mutation.apply(&mut data);
+            }
+            (data, mutations)
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::anvil::access::Region;
+    use crate::anvil::encode::minimal_region;
+
+    #[test]
+    fn test_minimal_region() {
+        let data = minimal_region(&[(0, 0), (31, 31)]).unwrap();
+        assert_eq!(data.len(), HEADER_SIZE + 2 * SECTOR_SIZE);
+        let region = Region::from_reader(&data[..]).unwrap();
+        let empty = NbtTag::Compound(Default::default());
+        assert_eq!(region.get_chunk_nbt(31, 31).unwrap().unwrap().1, empty);
+        assert_eq!(region.get_chunk_nbt(1, 0).unwrap(), None);
+    }
+
+    proptest! {
+        #[test]
+        fn test_generated_regions_read_back(data in region_file()) {
+            let region = Region::from_reader(&data[..]).unwrap();
+            for z in 0..32 {
+                for x in 0..32 {
+                    region.get_chunk_nbt(x, z).unwrap();
+                }
+            }
+        }
+
+        #[test]
+        fn test_unknown_compression_is_an_error(
+            data in region_file_with(1),
+            value in 4u8..,
+        ) {
+            let region = Region::from_reader(&data[..]).unwrap();
+            let present = (0..1024).find(|&i| chunk_start(&data, i).is_some());
+            if let Some(chunk) = present {
+                let mut data = data;
+                Mutation::CompressionByte { chunk, value }.apply(&mut data);
+                let corrupted = Region::from_reader(&data[..]).unwrap();
+                let (x, z) = ((chunk % 32) as i32, (chunk / 32) as i32);
+                prop_assert!(region.get_chunk_data(x, z).unwrap().is_some());
+                prop_assert!(corrupted.get_chunk_data(x, z).is_err());
+            }
+        }
+
+        #[test]
+        fn test_corrupted_chunk_reads_do_not_panic(case in corrupted_region()) {
+            let (data, _) = case;
+            if let Ok(region) = Region::from_reader(&data[..]) {
+                region.validate();
+                for z in 0..32 {
+                    for x in 0..32 {
+                        let _ = region.get_chunk_data(x, z);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn test_corrupted_headers_parse(case in corrupted_region()) {
+            // Header parsing only looks at the first 8 KiB and must accept any bytes
+            let (data, _) = case;
+            let result = Region::from_reader(&data[..]);
+            prop_assert_eq!(result.is_ok(), data.len() >= HEADER_SIZE);
+        }
+    }
+}
@@ -0,0 +1,1248 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Structure and schematic files.
+//!
+//! [`Structure`] is a box of blocks shared by the common formats: structure block
+//! templates (`.nbt`), Sponge schematics (`.schem`), Litematica schematics
+//! (`.litematic`) and Bedrock structures (`.mcstructure`). Files are read with
+//! [`Structure::read`], written in any format with [`Structure::write`] and placed into
+//! terrain with [`Structure::paste_into`].
+//!
+//! Block names and states are copied as-is: converting between Java and Bedrock files
+//! does not translate block IDs, which differ between the editions for many blocks.
+//! Entities are not kept.
+
+use crate::anvil::chunk::{BlockState, Chunk, bits_for, pack, packed_len, section_bits, unpack};
+use crate::anvil::editor::RegionEditor;
+use crate::anvil::{CompressionType, RegionPos, block_to_chunk, chunk_to_block};
+use crate::nbt::NbtTag;
+use crate::nbt::encode::write_named_tag_le;
+use crate::nbt::io::{read_compressed, write_compressed};
+use crate::nbt::parse::parse_named_tag_le;
+use indexmap::IndexMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Error, ErrorKind, Result, Write};
+use std::path::Path;
+
+/// Largest volume accepted when reading, so a forged size cannot exhaust memory.
+const MAX_VOLUME: usize = 1 << 27;
+
+/// Block ID that fills positions a structure leaves untouched, in formats without void.
+const AIR: &str = "minecraft:air";
+
+/// A structure file format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructureFormat {
+    /// Structure block template (`.nbt`), gzip-compressed.
+    Template,
+    /// Sponge schematic (`.schem`), gzip-compressed. Versions 2 and 3 are read;
+    /// version 3 is written.
+    Sponge,
+    /// Litematica schematic (`.litematic`), gzip-compressed. All regions are merged
+    /// when read; a single region is written.
+    Litematic,
+    /// Bedrock Edition structure (`.mcstructure`), little-endian and uncompressed.
+    ///
+    /// Byte states are read as `true`/`false` and int states as numbers. When writing,
+    /// `true`/`false` become bytes, numbers become ints and anything else a string.
+    Bedrock,
+}
+
+impl StructureFormat {
+    /// Tells the format apart by the file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "nbt" => Some(StructureFormat::Template),
+            "schem" => Some(StructureFormat::Sponge),
+            "litematic" => Some(StructureFormat::Litematic),
+            "mcstructure" => Some(StructureFormat::Bedrock),
+            _ => None,
+        }
+    }
+
+    /// Returns a human-readable name for the format.
+    pub fn description(self) -> &'static str {
+        match self {
+            StructureFormat::Template => "Structure block template",
+            StructureFormat::Sponge => "Sponge schematic",
+            StructureFormat::Litematic => "Litematica schematic",
+            StructureFormat::Bedrock => "Bedrock structure",
+        }
+    }
+}
+
+/// A block entity inside a structure.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureBlockEntity {
+    /// Position relative to the structure's minimum corner.
+    pub pos: [i32; 3],
+    /// The block entity's data, including its `id` but not its coordinates.
+    pub data: IndexMap<String, NbtTag>,
+}
+
+/// A box of blocks, independent of the file format it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Structure {
+    /// Size along x, y and z.
+    pub size: [i32; 3],
+    /// Block states referenced by [`blocks`](Self::blocks).
+    pub palette: Vec<BlockState>,
+    /// Palette index of every position, or `None` where the structure leaves the
+    /// existing block alone. Positions are laid out as returned by
+    /// [`index`](Self::index).
+    pub blocks: Vec<Option<u32>>,
+    /// Block entities, such as chest contents and sign text.
+    pub block_entities: Vec<StructureBlockEntity>,
+    /// `DataVersion` of the blocks, if the file records one.
+    pub data_version: Option<i32>,
+}
+
+impl Structure {
+    /// Creates a structure of the given size with every position void.
+    pub fn new(size: [i32; 3]) -> Result<Self> {
+        if size.iter().any(|&s| s < 0) {
+            return Err(invalid("negative size"));
+        }
+        let volume = size
+            .iter()
+            .try_fold(1usize, |v, &s| v.checked_mul(s as usize))
+            .filter(|&v| v <= MAX_VOLUME)
+            .ok_or_else(|| invalid("structure too large"))?;
+        Ok(Structure {
+            size,
+            palette: Vec::new(),
+            blocks: vec![None; volume],
+            block_entities: Vec::new(),
+            data_version: None,
+        })
+    }
+
+    /// Returns the index into [`blocks`](Self::blocks) of a relative position, or
+    /// `None` if it lies outside the structure. X varies fastest, then z, then y.
+    pub fn index(&self, pos: [i32; 3]) -> Option<usize> {
+        let [x, y, z] = pos;
+        let [sx, sy, sz] = self.size;
+        if !(0..sx).contains(&x) || !(0..sy).contains(&y) || !(0..sz).contains(&z) {
+            return None;
+        }
+        Some(((y as usize * sz as usize) + z as usize) * sx as usize + x as usize)
+    }
+
+    /// Returns the block state at a relative position, or `None` if it is void or
+    /// outside the structure.
+    pub fn get(&self, pos: [i32; 3]) -> Option<&BlockState> {
+        let index = self.blocks[self.index(pos)?]?;
+        self.palette.get(index as usize)
+    }
+
+    /// Sets the block state at a relative position, adding it to the palette if needed.
+    /// Returns `false` if the position lies outside the structure.
+    pub fn set(&mut self, pos: [i32; 3], state: BlockState) -> bool {
+        let Some(index) = self.index(pos) else {
+            return false;
+        };
+        let id = match self.palette.iter().position(|s| *s == state) {
+            Some(id) => id,
+            None => {
+                self.palette.push(state);
+                self.palette.len() - 1
+            }
+        };
+        self.blocks[index] = Some(id as u32);
+        true
+    }
+
+    /// Reads a structure file, telling the format apart by its extension.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let format = StructureFormat::from_path(path).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unknown structure format: {}", path.display()),
+            )
+        })?;
+        let tag = if format == StructureFormat::Bedrock {
+            let data = std::fs::read(path)?;
+            parse_named_tag_le(&mut &data[..])
+                .map_err(|e| Error::new(ErrorKind::InvalidData, e))?
+                .1
+        } else {
+            read_compressed(path)?.1
+        };
+        Structure::from_nbt(format, &tag)
+    }
+
+    /// Decodes the root tag of a structure file in the given format.
+    pub fn from_nbt(format: StructureFormat, tag: &NbtTag) -> Result<Self> {
+        match format {
+            StructureFormat::Template => read_template(tag),
+            StructureFormat::Sponge => read_sponge(tag),
+            StructureFormat::Litematic => read_litematic(tag),
+            StructureFormat::Bedrock => read_bedrock(tag),
+        }
+    }
+
+    /// Writes the structure to `path` in the given format.
+    pub fn write<P: AsRef<Path>>(&self, path: P, format: StructureFormat) -> Result<()> {
+        let tag = self.to_nbt(format)?;
+        if format == StructureFormat::Bedrock {
+            let mut writer = BufWriter::new(File::create(path)?);
+            write_named_tag_le(&mut writer, "", &tag)?;
+            writer.flush()
+        } else {
+            write_compressed(path, "", &tag, CompressionType::Gzip)
+        }
+    }
+
+    /// Encodes the structure as the root tag of a file in the given format.
+    ///
+    /// Fails for Sponge schematics larger than 65535 blocks along an axis.
+    pub fn to_nbt(&self, format: StructureFormat) -> Result<NbtTag> {
+        match format {
+            StructureFormat::Template => Ok(self.write_template()),
+            StructureFormat::Sponge => self.write_sponge(),
+            StructureFormat::Litematic => Ok(self.write_litematic()),
+            StructureFormat::Bedrock => Ok(self.write_bedrock()),
+        }
+    }
+
+    /// Pastes the structure into a chunk with its minimum corner at world block
+    /// `origin`, returning the number of blocks placed.
+    ///
+    /// Only chunks saved by 1.18 or later are supported. Void positions keep the
+    /// existing block, and sections the chunk does not have are skipped. Block entities
+    /// at overwritten positions are replaced by the structure's. Light and heightmaps
+    /// are removed so the game recomputes them.
+    pub fn paste_into(&self, chunk: &mut Chunk, origin: [i32; 3]) -> Result<usize> {
+        let position = |key| match chunk.root.get_path(key) {
+            Some(NbtTag::Int(value)) => Ok(chunk_to_block(*value)),
+            _ => Err(invalid_chunk(
+                "missing xPos or zPos; chunks before 1.18 are not supported",
+            )),
+        };
+        let (base_x, base_z) = (position("xPos")?, position("zPos")?);
+        let end = [0, 1, 2].map(|axis| origin[axis].saturating_add(self.size[axis]));
+        let x_range = origin[0].max(base_x)..end[0].min(base_x + 16);
+        let z_range = origin[2].max(base_z)..end[2].min(base_z + 16);
+        if x_range.is_empty() || z_range.is_empty() {
+            return Ok(0);
+        }
+        let Some(NbtTag::List(sections)) = chunk.root.get_path_mut("sections") else {
+            return Err(invalid_chunk("missing sections list"));
+        };
+
+        let mut placed = HashSet::new();
+        for section in sections.iter_mut() {
+            let Some(section_y) = section.get_path("Y").and_then(|y| y.to_i64_checked().ok())
+            else {
+                continue;
+            };
+            let base_y = section_y as i32 * 16;
+            let y_range = origin[1].max(base_y)..end[1].min(base_y + 16);
+            if y_range.is_empty() {
+                continue;
+            }
+            let Some(states) = section.get_path_mut("block_states") else {
+                continue;
+            };
+            let (mut palette, mut indices) = decode_section(states)?;
+            let mut mapping: Vec<Option<u16>> = vec![None; self.palette.len()];
+            let before = placed.len();
+            for y in y_range {
+                for z in z_range.clone() {
+                    for x in x_range.clone() {
+                        let pos = [x - origin[0], y - origin[1], z - origin[2]];
+                        let Some(Some(id)) = self.index(pos).map(|i| self.blocks[i]) else {
+                            continue;
+                        };
+                        let Some(state) = self.palette.get(id as usize) else {
+                            return Err(invalid("block outside the palette"));
+                        };
+                        let local = match mapping[id as usize] {
+                            Some(local) => local,
+                            None => {
+                                let local = match palette.iter().position(|s| s == state) {
+                                    Some(local) => local,
+                                    None => {
+                                        palette.push(state.clone());
+                                        palette.len() - 1
+                                    }
+                                };
+                                let local = u16::try_from(local)
+                                    .map_err(|_| invalid("block palette too large"))?;
+                                mapping[id as usize] = Some(local);
+                                local
+                            }
+                        };
+                        let i = ((y - base_y) * 16 + (z - base_z)) * 16 + (x - base_x);
+                        indices[i as usize] = local;
+                        placed.insert([x, y, z]);
+                    }
+                }
+            }
+            if placed.len() > before {
+                *states = encode_section(palette, &indices);
+            }
+        }
+        if placed.is_empty() {
+            return Ok(0);
+        }
+
+        let NbtTag::Compound(root) = &mut chunk.root else {
+            return Err(invalid_chunk("root is not a compound"));
+        };
+        let mut block_entities = match root.shift_remove("block_entities") {
+            Some(NbtTag::List(entities)) => entities,
+            _ => Vec::new(),
+        };
+        block_entities.retain(|entity| {
+            let coordinate = |key| entity.get_path(key).and_then(|v| v.to_i64_checked().ok());
+            match (coordinate("x"), coordinate("y"), coordinate("z")) {
+                (Some(x), Some(y), Some(z)) => !placed.contains(&[x as i32, y as i32, z as i32]),
+                _ => true,
+            }
+        });
+        for entity in &self.block_entities {
+            let pos = [0, 1, 2].map(|axis| origin[axis] + entity.pos[axis]);
+            if placed.contains(&pos) {
+                let mut data = entity.data.clone();
+                for (key, value) in ["x", "y", "z"].into_iter().zip(pos) {
+                    data.insert(key.to_string(), NbtTag::Int(value));
+                }
+                block_entities.push(NbtTag::Compound(data));
+            }
+        }
+        root.insert("block_entities".to_string(), NbtTag::List(block_entities));
+        root.shift_remove("Heightmaps");
+        chunk.strip_lighting();
+        Ok(placed.len())
+    }
+
+    /// Pastes the structure into every chunk of a region file that it overlaps, with its
+    /// minimum corner at world block `origin`. Returns the number of blocks placed.
+    ///
+    /// Chunks that have not been generated are skipped. See
+    /// [`paste_into`](Self::paste_into) for how each chunk is changed.
+    pub fn paste_into_region(
+        &self,
+        editor: &mut RegionEditor,
+        region: RegionPos,
+        origin: [i32; 3],
+    ) -> Result<usize> {
+        if self.size.contains(&0) {
+            return Ok(0);
+        }
+        let (min_x, min_z) = region.min_chunk();
+        let last = |axis: usize| block_to_chunk(origin[axis].saturating_add(self.size[axis] - 1));
+        let chunks_x = block_to_chunk(origin[0]).max(min_x)..=last(0).min(min_x + 31);
+        let chunks_z = block_to_chunk(origin[2]).max(min_z)..=last(2).min(min_z + 31);
+        let mut placed = 0;
+        for chunk_z in chunks_z {
+            for chunk_x in chunks_x.clone() {
+                let Some((name, root)) = editor.get_chunk_nbt(chunk_x, chunk_z)? else {
+                    continue;
+                };
+                let mut chunk = Chunk::new(name, root);
+                let count = self.paste_into(&mut chunk, origin)?;
+                if count > 0 {
+                    editor.set_chunk(chunk_x, chunk_z, &chunk.name, &chunk.root)?;
+                    placed += count;
+                }
+            }
+        }
+        Ok(placed)
+    }
+
+    fn write_template(&self) -> NbtTag {
+        let mut blocks = Vec::new();
+        let entities: HashMap<[i32; 3], &StructureBlockEntity> = self
+            .block_entities
+            .iter()
+            .map(|entity| (entity.pos, entity))
+            .collect();
+        for y in 0..self.size[1] {
+            for z in 0..self.size[2] {
+                for x in 0..self.size[0] {
+                    let Some(Some(state)) = self.index([x, y, z]).map(|i| self.blocks[i]) else {
+                        continue;
+                    };
+                    let mut block = IndexMap::new();
+                    block.insert("pos".to_string(), int_list([x, y, z]));
+                    block.insert("state".to_string(), NbtTag::Int(state as i32));
+                    if let Some(entity) = entities.get(&[x, y, z]) {
+                        block.insert("nbt".to_string(), NbtTag::Compound(entity.data.clone()));
+                    }
+                    blocks.push(NbtTag::Compound(block));
+                }
+            }
+        }
+        let mut root = IndexMap::new();
+        if let Some(version) = self.data_version {
+            root.insert("DataVersion".to_string(), NbtTag::Int(version));
+        }
+        root.insert("size".to_string(), int_list(self.size));
+        let palette = self.palette.iter().map(BlockState::to_nbt).collect();
+        root.insert("palette".to_string(), NbtTag::List(palette));
+        root.insert("blocks".to_string(), NbtTag::List(blocks));
+        root.insert("entities".to_string(), NbtTag::List(Vec::new()));
+        NbtTag::Compound(root)
+    }
+
+    fn write_sponge(&self) -> Result<NbtTag> {
+        let dimension = |size: i32| {
+            u16::try_from(size)
+                .map(|size| NbtTag::Short(size as i16))
+                .map_err(|_| invalid("Sponge schematics are at most 65535 blocks wide"))
+        };
+        let mut palette = self.palette.clone();
+        let air = air_index(&mut palette);
+        let mut data = Vec::with_capacity(self.blocks.len());
+        for block in &self.blocks {
+            write_varint(&mut data, block.unwrap_or(air));
+        }
+        let palette = palette
+            .iter()
+            .enumerate()
+            .map(|(i, state)| (state.to_string(), NbtTag::Int(i as i32)))
+            .collect();
+        let block_entities = self
+            .block_entities
+            .iter()
+            .map(|entity| {
+                let mut data = entity.data.clone();
+                let id = data
+                    .shift_remove("id")
+                    .unwrap_or(NbtTag::String(String::new()));
+                let mut map = IndexMap::new();
+                map.insert("Pos".to_string(), NbtTag::IntArray(entity.pos.to_vec()));
+                map.insert("Id".to_string(), id);
+                map.insert("Data".to_string(), NbtTag::Compound(data));
+                NbtTag::Compound(map)
+            })
+            .collect();
+
+        let mut blocks = IndexMap::new();
+        blocks.insert("Palette".to_string(), NbtTag::Compound(palette));
+        blocks.insert("Data".to_string(), NbtTag::ByteArray(data));
+        blocks.insert("BlockEntities".to_string(), NbtTag::List(block_entities));
+
+        let mut schematic = IndexMap::new();
+        schematic.insert("Version".to_string(), NbtTag::Int(3));
+        if let Some(version) = self.data_version {
+            schematic.insert("DataVersion".to_string(), NbtTag::Int(version));
+        }
+        schematic.insert("Width".to_string(), dimension(self.size[0])?);
+        schematic.insert("Height".to_string(), dimension(self.size[1])?);
+        schematic.insert("Length".to_string(), dimension(self.size[2])?);
+        schematic.insert("Offset".to_string(), NbtTag::IntArray(vec![0; 3]));
+        schematic.insert("Blocks".to_string(), NbtTag::Compound(blocks));
+
+        let mut root = IndexMap::new();
+        root.insert("Schematic".to_string(), NbtTag::Compound(schematic));
+        Ok(NbtTag::Compound(root))
+    }
+
+    fn write_litematic(&self) -> NbtTag {
+        // Litematica requires air at palette index 0
+        let mut palette = vec![air()];
+        let mut mapping = Vec::with_capacity(self.palette.len());
+        for state in &self.palette {
+            match palette.iter().position(|s| s == state) {
+                Some(index) => mapping.push(index as u64),
+                None => {
+                    mapping.push(palette.len() as u64);
+                    palette.push(state.clone());
+                }
+            }
+        }
+        let values: Vec<u64> = self
+            .blocks
+            .iter()
+            .map(|block| block.map_or(0, |id| mapping[id as usize]))
+            .collect();
+        let total_blocks = values.iter().filter(|&&value| value != 0).count();
+        let states = pack(&values, bits_for(palette.len()).max(2), true);
+        let tile_entities = self
+            .block_entities
+            .iter()
+            .map(|entity| {
+                let mut data = IndexMap::new();
+                for (key, value) in ["x", "y", "z"].into_iter().zip(entity.pos) {
+                    data.insert(key.to_string(), NbtTag::Int(value));
+                }
+                data.extend(entity.data.clone());
+                NbtTag::Compound(data)
+            })
+            .collect();
+
+        let mut region = IndexMap::new();
+        region.insert("Position".to_string(), xyz([0; 3]));
+        region.insert("Size".to_string(), xyz(self.size));
+        region.insert(
+            "BlockStatePalette".to_string(),
+            NbtTag::List(palette.iter().map(BlockState::to_nbt).collect()),
+        );
+        region.insert("BlockStates".to_string(), NbtTag::LongArray(states));
+        region.insert("TileEntities".to_string(), NbtTag::List(tile_entities));
+        for empty in ["Entities", "PendingBlockTicks", "PendingFluidTicks"] {
+            region.insert(empty.to_string(), NbtTag::List(Vec::new()));
+        }
+        let mut regions = IndexMap::new();
+        regions.insert("Main".to_string(), NbtTag::Compound(region));
+
+        let mut metadata = IndexMap::new();
+        metadata.insert("Name".to_string(), NbtTag::String("Main".to_string()));
+        metadata.insert("Author".to_string(), NbtTag::String(String::new()));
+        metadata.insert("Description".to_string(), NbtTag::String(String::new()));
+        metadata.insert("RegionCount".to_string(), NbtTag::Int(1));
+        metadata.insert("TotalBlocks".to_string(), NbtTag::Int(total_blocks as i32));
+        metadata.insert(
+            "TotalVolume".to_string(),
+            NbtTag::Int(self.blocks.len() as i32),
+        );
+        metadata.insert("EnclosingSize".to_string(), xyz(self.size));
+
+        let mut root = IndexMap::new();
+        if let Some(version) = self.data_version {
+            root.insert("MinecraftDataVersion".to_string(), NbtTag::Int(version));
+        }
+        root.insert("Version".to_string(), NbtTag::Int(6));
+        root.insert("Metadata".to_string(), NbtTag::Compound(metadata));
+        root.insert("Regions".to_string(), NbtTag::Compound(regions));
+        NbtTag::Compound(root)
+    }
+
+    fn write_bedrock(&self) -> NbtTag {
+        let [sx, sy, sz] = self.size;
+        let mut indices = Vec::with_capacity(self.blocks.len());
+        for x in 0..sx {
+            for y in 0..sy {
+                for z in 0..sz {
+                    let block = self.index([x, y, z]).and_then(|i| self.blocks[i]);
+                    indices.push(NbtTag::Int(block.map_or(-1, |id| id as i32)));
+                }
+            }
+        }
+        let waterlogged = vec![NbtTag::Int(-1); indices.len()];
+        let block_palette = self
+            .palette
+            .iter()
+            .map(|state| {
+                let states = state
+                    .properties
+                    .iter()
+                    .map(|(key, value)| (key.clone(), bedrock_state_value(value)))
+                    .collect();
+                let mut map = IndexMap::new();
+                map.insert("name".to_string(), NbtTag::String(state.name.clone()));
+                map.insert("states".to_string(), NbtTag::Compound(states));
+                NbtTag::Compound(map)
+            })
+            .collect();
+        let mut position_data = IndexMap::new();
+        for entity in &self.block_entities {
+            let [x, y, z] = entity.pos;
+            if self.index(entity.pos).is_none() {
+                continue;
+            }
+            let index = (x * sy + y) * sz + z;
+            let mut data = entity.data.clone();
+            for (key, value) in ["x", "y", "z"].into_iter().zip(entity.pos) {
+                data.insert(key.to_string(), NbtTag::Int(value));
+            }
+            let mut entry = IndexMap::new();
+            entry.insert("block_entity_data".to_string(), NbtTag::Compound(data));
+            position_data.insert(index.to_string(), NbtTag::Compound(entry));
+        }
+
+        let mut default = IndexMap::new();
+        default.insert("block_palette".to_string(), NbtTag::List(block_palette));
+        default.insert(
+            "block_position_data".to_string(),
+            NbtTag::Compound(position_data),
+        );
+        let mut palette = IndexMap::new();
+        palette.insert("default".to_string(), NbtTag::Compound(default));
+        let mut structure = IndexMap::new();
+        structure.insert(
+            "block_indices".to_string(),
+            NbtTag::List(vec![NbtTag::List(indices), NbtTag::List(waterlogged)]),
+        );
+        structure.insert("entities".to_string(), NbtTag::List(Vec::new()));
+        structure.insert("palette".to_string(), NbtTag::Compound(palette));
+
+        let mut root = IndexMap::new();
+        root.insert("format_version".to_string(), NbtTag::Int(1));
+        root.insert("size".to_string(), int_list(self.size));
+        root.insert("structure".to_string(), NbtTag::Compound(structure));
+        root.insert("structure_world_origin".to_string(), int_list([0; 3]));
+        NbtTag::Compound(root)
+    }
+}
+
+fn read_template(tag: &NbtTag) -> Result<Structure> {
+    let mut structure = Structure::new(int_triple(tag.get_path("size"))?)?;
+    structure.data_version = int_at(tag, "DataVersion");
+    let palette = list_at(tag, "palette").or_else(|| list_at(tag, "palettes[0]"));
+    structure.palette = palette
+        .unwrap_or_default()
+        .iter()
+        .map(|state| {
+            BlockState::from_nbt(state).ok_or_else(|| invalid("palette entry without a Name"))
+        })
+        .collect::<Result<_>>()?;
+    for block in list_at(tag, "blocks").unwrap_or_default() {
+        let pos = int_triple(block.get_path("pos"))?;
+        let state = int_at(block, "state")
+            .filter(|&s| s >= 0 && (s as usize) < structure.palette.len())
+            .ok_or_else(|| invalid("block state outside the palette"))?;
+        let index = structure
+            .index(pos)
+            .ok_or_else(|| invalid("block outside the structure"))?;
+        structure.blocks[index] = Some(state as u32);
+        if let Some(NbtTag::Compound(data)) = block.get_path("nbt") {
+            structure.block_entities.push(StructureBlockEntity {
+                pos,
+                data: data.clone(),
+            });
+        }
+    }
+    Ok(structure)
+}
+
+fn read_sponge(tag: &NbtTag) -> Result<Structure> {
+    // Version 3 nests everything in a `Schematic` compound
+    let root = tag.get_path("Schematic").unwrap_or(tag);
+    let dimension = |key| match root.get_path(key) {
+        Some(NbtTag::Short(size)) => Ok(*size as u16 as i32),
+        _ => Err(invalid("missing Width, Height or Length")),
+    };
+    let size = [
+        dimension("Width")?,
+        dimension("Height")?,
+        dimension("Length")?,
+    ];
+    let (palette, data, entities) = match root.get_path("Blocks") {
+        Some(blocks) => (
+            blocks.get_path("Palette"),
+            blocks.get_path("Data"),
+            list_at(blocks, "BlockEntities"),
+        ),
+        None => (
+            root.get_path("Palette"),
+            root.get_path("BlockData"),
+            list_at(root, "BlockEntities").or_else(|| list_at(root, "TileEntities")),
+        ),
+    };
+    let Some(NbtTag::ByteArray(data)) = data else {
+        return Err(invalid("missing block data"));
+    };
+    let volume = size.iter().map(|&s| s as usize).product::<usize>();
+    if data.len() < volume {
+        return Err(invalid("block data too short"));
+    }
+
+    let mut structure = Structure::new(size)?;
+    structure.data_version = int_at(root, "DataVersion");
+    if let Some(NbtTag::Compound(palette)) = palette {
+        let mut states = vec![None; palette.len()];
+        for (key, index) in palette {
+            let state = BlockState::parse(key).ok_or_else(|| invalid("malformed palette key"))?;
+            let slot = index
+                .to_i64_checked()
+                .ok()
+                .and_then(|i| states.get_mut(usize::try_from(i).ok()?))
+                .ok_or_else(|| invalid("palette index out of range"))?;
+            *slot = Some(state);
+        }
+        structure.palette = states.into_iter().map(|s| s.unwrap_or_else(air)).collect();
+    }
+
+    let mut bytes = data.iter().copied();
+    for block in structure.blocks.iter_mut() {
+        let id = read_varint(&mut bytes).ok_or_else(|| invalid("block data too short"))?;
+        if id as usize >= structure.palette.len() {
+            return Err(invalid("block state outside the palette"));
+        }
+        *block = Some(id);
+    }
+
+    for entity in entities.unwrap_or_default() {
+        let NbtTag::Compound(map) = entity else {
+            continue;
+        };
+        let pos = int_triple(map.get("Pos"))?;
+        let mut data = match map.get("Data") {
+            Some(NbtTag::Compound(data)) => data.clone(),
+            // Version 2 keeps the fields next to Pos and Id
+            _ => {
+                let mut data = map.clone();
+                data.shift_remove("Pos");
+                data.shift_remove("Id");
+                data
+            }
+        };
+        if let Some(id) = map.get("Id") {
+            data.shift_insert(0, "id".to_string(), id.clone());
+        }
+        structure
+            .block_entities
+            .push(StructureBlockEntity { pos, data });
+    }
+    Ok(structure)
+}
+
+fn read_litematic(tag: &NbtTag) -> Result<Structure> {
+    let Some(NbtTag::Compound(regions)) = tag.get_path("Regions") else {
+        return Err(invalid("missing Regions"));
+    };
+    // Sizes may be negative, with the position at the opposite corner
+    let mut boxes = Vec::new();
+    for region in regions.values() {
+        let position = xyz_at(region, "Position")?.map(i64::from);
+        let size = xyz_at(region, "Size")?.map(i64::from);
+        let min = [0, 1, 2].map(|axis| position[axis] + (size[axis] + 1).min(0));
+        boxes.push((region, min, size.map(i64::abs)));
+    }
+    let min = [0, 1, 2].map(|axis| boxes.iter().map(|b| b.1[axis]).min().unwrap_or(0));
+    let max = [0, 1, 2].map(|axis| {
+        boxes
+            .iter()
+            .map(|b| b.1[axis] + b.2[axis])
+            .max()
+            .unwrap_or(0)
+    });
+    let size = [0, 1, 2].map(|axis| i32::try_from(max[axis] - min[axis]).unwrap_or(i32::MAX));
+
+    let mut structure = Structure::new(size)?;
+    structure.data_version = int_at(tag, "MinecraftDataVersion");
+    let mut ids: HashMap<BlockState, u32> = HashMap::new();
+    for (region, region_min, size) in boxes {
+        let palette = list_at(region, "BlockStatePalette")
+            .unwrap_or_default()
+            .iter()
+            .map(|state| {
+                BlockState::from_nbt(state).ok_or_else(|| invalid("palette entry without a Name"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let Some(NbtTag::LongArray(states)) = region.get_path("BlockStates") else {
+            return Err(invalid("missing BlockStates"));
+        };
+        // Both fit in i32, since the region lies inside the structure
+        let size = size.map(|s| s as i32);
+        let offset = [0, 1, 2].map(|axis| (region_min[axis] - min[axis]) as i32);
+        let bits = bits_for(palette.len()).max(2);
+        let volume = size.iter().map(|&s| s as usize).product::<usize>();
+        if states.len() < packed_len(volume, bits, true) {
+            return Err(invalid("BlockStates too short"));
+        }
+        let mut mapping: Vec<Option<u32>> = vec![None; palette.len()];
+        let mut i = 0;
+        for y in 0..size[1] {
+            for z in 0..size[2] {
+                for x in 0..size[0] {
+                    let value = unpack(states, i, bits, true) as usize;
+                    i += 1;
+                    let state = palette
+                        .get(value)
+                        .ok_or_else(|| invalid("block state outside the palette"))?;
+                    let id = *mapping[value].get_or_insert_with(|| {
+                        *ids.entry(state.clone()).or_insert_with(|| {
+                            structure.palette.push(state.clone());
+                            (structure.palette.len() - 1) as u32
+                        })
+                    });
+                    let pos = [x + offset[0], y + offset[1], z + offset[2]];
+                    if let Some(index) = structure.index(pos) {
+                        structure.blocks[index] = Some(id);
+                    }
+                }
+            }
+        }
+        for entity in list_at(region, "TileEntities").unwrap_or_default() {
+            let NbtTag::Compound(map) = entity else {
+                continue;
+            };
+            let mut data = map.clone();
+            let mut pos = [0; 3];
+            for (axis, key) in ["x", "y", "z"].into_iter().enumerate() {
+                pos[axis] = match data.shift_remove(key) {
+                    Some(NbtTag::Int(value)) => value + offset[axis],
+                    _ => return Err(invalid("block entity without coordinates")),
+                };
+            }
+            structure
+                .block_entities
+                .push(StructureBlockEntity { pos, data });
+        }
+    }
+    Ok(structure)
+}
+
+fn read_bedrock(tag: &NbtTag) -> Result<Structure> {
+    let size = int_triple(tag.get_path("size"))?;
+    let mut structure = Structure::new(size)?;
+    let volume = structure.blocks.len();
+    let indices = list_at(tag, "structure.block_indices[0]")
+        .ok_or_else(|| invalid("missing block_indices"))?;
+    if indices.len() != volume {
+        return Err(invalid("block_indices does not match the size"));
+    }
+    let default = tag.get_path("structure.palette.default");
+    for state in default
+        .and_then(|d| list_at(d, "block_palette"))
+        .unwrap_or_default()
+    {
+        let Some(NbtTag::String(name)) = state.get_path("name") else {
+            return Err(invalid("palette entry without a name"));
+        };
+        let mut properties = BTreeMap::new();
+        if let Some(NbtTag::Compound(states)) = state.get_path("states") {
+            for (key, value) in states {
+                let value = match value {
+                    NbtTag::String(value) => value.clone(),
+                    NbtTag::Byte(value) => (*value != 0).to_string(),
+                    other => other.to_i64_checked().unwrap_or_default().to_string(),
+                };
+                properties.insert(key.clone(), value);
+            }
+        }
+        structure.palette.push(BlockState {
+            name: name.clone(),
+            properties,
+        });
+    }
+
+    let [sx, sy, sz] = size;
+    let mut i = 0;
+    for x in 0..sx {
+        for y in 0..sy {
+            for z in 0..sz {
+                let id = match &indices[i] {
+                    NbtTag::Int(-1) => None,
+                    NbtTag::Int(id) if (*id as usize) < structure.palette.len() && *id >= 0 => {
+                        Some(*id as u32)
+                    }
+                    _ => return Err(invalid("block state outside the palette")),
+                };
+                i += 1;
+                if let Some(index) = structure.index([x, y, z]) {
+                    structure.blocks[index] = id;
+                }
+            }
+        }
+    }
+
+    if let Some(NbtTag::Compound(positions)) =
+        default.and_then(|d| d.get_path("block_position_data"))
+    {
+        for (key, entry) in positions {
+            let Some(NbtTag::Compound(data)) = entry.get_path("block_entity_data") else {
+                continue;
+            };
+            let index: i32 = key
+                .parse()
+                .map_err(|_| invalid("malformed block_position_data key"))?;
+            if index < 0 || index as usize >= volume {
+                return Err(invalid("block entity outside the structure"));
+            }
+            let pos = [index / (sy * sz), index / sz % sy, index % sz];
+            let mut data = data.clone();
+            for key in ["x", "y", "z"] {
+                data.shift_remove(key);
+            }
+            structure
+                .block_entities
+                .push(StructureBlockEntity { pos, data });
+        }
+    }
+    Ok(structure)
+}
+
+/// Decodes a 1.18+ `block_states` compound into its palette and one index per block.
+fn decode_section(states: &NbtTag) -> Result<(Vec<BlockState>, Vec<u16>)> {
+    let palette = list_at(states, "palette")
+        .unwrap_or_default()
+        .iter()
+        .map(|state| {
+            BlockState::from_nbt(state).ok_or_else(|| invalid_chunk("palette entry without a Name"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if palette.is_empty() {
+        return Err(invalid_chunk("empty block palette"));
+    }
+    // Indices are held as u16
+    if palette.len() > u16::MAX as usize + 1 {
+        return Err(invalid_chunk("block palette too large"));
+    }
+    if palette.len() == 1 {
+        return Ok((palette, vec![0; 4096]));
+    }
+    let Some(NbtTag::LongArray(data)) = states.get_path("data") else {
+        return Err(invalid_chunk("missing block data"));
+    };
+    let bits = section_bits(palette.len());
+    if data.len() < packed_len(4096, bits, false) {
+        return Err(invalid_chunk("block data array too short"));
+    }
+    let mut indices = Vec::with_capacity(4096);
+    for i in 0..4096 {
+        let index = unpack(data, i, bits, false);
+        if index as usize >= palette.len() {
+            return Err(invalid_chunk("palette index out of range"));
+        }
+        indices.push(index as u16);
+    }
+    Ok((palette, indices))
+}
+
+/// Encodes a section palette and indices as a `block_states` compound, dropping
+/// palette entries no block uses any more.
+fn encode_section(palette: Vec<BlockState>, indices: &[u16]) -> NbtTag {
+    let mut remap: Vec<Option<u16>> = vec![None; palette.len()];
+    let mut used = Vec::new();
+    let indices: Vec<u64> = indices
+        .iter()
+        .map(|&index| {
+            *remap[index as usize].get_or_insert_with(|| {
+                used.push(palette[index as usize].clone());
+                (used.len() - 1) as u16
+            }) as u64
+        })
+        .collect();
+
+    let mut map = IndexMap::new();
+    map.insert(
+        "palette".to_string(),
+        NbtTag::List(used.iter().map(BlockState::to_nbt).collect()),
+    );
+    if used.len() > 1 {
+        let data = pack(&indices, section_bits(used.len()), false);
+        map.insert("data".to_string(), NbtTag::LongArray(data));
+    }
+    NbtTag::Compound(map)
+}
+
+fn read_varint(bytes: &mut impl Iterator<Item = u8>) -> Option<u32> {
+    let mut value = 0u32;
+    for shift in (0..35).step_by(7) {
+        let byte = bytes.next()?;
+        value |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Chooses the Bedrock tag type for a state value (see [`StructureFormat::Bedrock`]).
+fn bedrock_state_value(value: &str) -> NbtTag {
+    match value {
+        "true" => NbtTag::Byte(1),
+        "false" => NbtTag::Byte(0),
+        _ => match value.parse() {
+            Ok(number) => NbtTag::Int(number),
+            Err(_) => NbtTag::String(value.to_string()),
+        },
+    }
+}
+
+/// Returns the palette index of air, adding it if missing.
+fn air_index(palette: &mut Vec<BlockState>) -> u32 {
+    match palette.iter().position(|s| *s == air()) {
+        Some(index) => index as u32,
+        None => {
+            palette.push(air());
+            (palette.len() - 1) as u32
+        }
+    }
+}
+
+fn air() -> BlockState {
+    BlockState {
+        name: AIR.to_string(),
+        properties: BTreeMap::new(),
+    }
+}
+
+fn list_at<'a>(tag: &'a NbtTag, path: &str) -> Option<&'a [NbtTag]> {
+    match tag.get_path(path) {
+        Some(NbtTag::List(elements)) => Some(elements),
+        _ => None,
+    }
+}
+
+fn int_at(tag: &NbtTag, path: &str) -> Option<i32> {
+    match tag.get_path(path) {
+        Some(NbtTag::Int(value)) => Some(*value),
+        _ => None,
+    }
+}
+
+/// Reads three ints from a list or int array.
+fn int_triple(tag: Option<&NbtTag>) -> Result<[i32; 3]> {
+    let values: Option<Vec<i32>> = match tag {
+        Some(NbtTag::List(list)) => list
+            .iter()
+            .map(|v| match v {
+                NbtTag::Int(v) => Some(*v),
+                _ => None,
+            })
+            .collect(),
+        Some(NbtTag::IntArray(array)) => Some(array.clone()),
+        _ => None,
+    };
+    values
+        .and_then(|v| v.try_into().ok())
+        .ok_or_else(|| invalid("expected three ints"))
+}
+
+/// Reads an `{x, y, z}` compound.
+fn xyz_at(tag: &NbtTag, path: &str) -> Result<[i32; 3]> {
+    let tag = tag.get_path(path);
+    let axis = |key| match tag.and_then(|t| t.get_path(key)) {
+        Some(NbtTag::Int(value)) => Ok(*value),
+        _ => Err(invalid("expected an {x, y, z} compound")),
+    };
+    Ok([axis("x")?, axis("y")?, axis("z")?])
+}
+
+fn int_list(values: [i32; 3]) -> NbtTag {
+    NbtTag::List(values.map(NbtTag::Int).to_vec())
+}
+
+fn xyz(values: [i32; 3]) -> NbtTag {
+    let mut map = IndexMap::new();
+    for (key, value) in ["x", "y", "z"].into_iter().zip(values) {
+        map.insert(key.to_string(), NbtTag::Int(value));
+    }
+    NbtTag::Compound(map)
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Invalid structure: {}", message),
+    )
+}
+
+fn invalid_chunk(message: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Invalid chunk: {}", message),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::snbt::parse_snbt;
+
+    fn sample() -> Structure {
+        let mut structure = Structure::new([3, 2, 4]).unwrap();
+        structure.data_version = Some(3953);
+        let stone = BlockState::parse("minecraft:stone").unwrap();
+        let chest = BlockState::parse("minecraft:chest[facing=north,waterlogged=false]").unwrap();
+        for x in 0..3 {
+            for z in 0..4 {
+                structure.set([x, 0, z], stone.clone());
+            }
+        }
+        structure.set([1, 1, 2], chest);
+        structure.set([2, 1, 3], air());
+        let mut data = IndexMap::new();
+        data.insert(
+            "id".to_string(),
+            NbtTag::String("minecraft:chest".to_string()),
+        );
+        data.insert("Items".to_string(), NbtTag::List(Vec::new()));
+        structure.block_entities.push(StructureBlockEntity {
+            pos: [1, 1, 2],
+            data,
+        });
+        structure
+    }
+
+    /// Returns the block at each position by name, so structures with differently
+    /// ordered palettes can be compared.
+    fn blocks(structure: &Structure) -> Vec<Option<String>> {
+        let mut blocks = Vec::new();
+        for y in 0..structure.size[1] {
+            for z in 0..structure.size[2] {
+                for x in 0..structure.size[0] {
+                    blocks.push(structure.get([x, y, z]).map(|s| s.to_string()));
+                }
+            }
+        }
+        blocks
+    }
+
+    #[test]
+    fn test_block_state_parse() {
+        let state = BlockState::parse("minecraft:chest[facing=north,waterlogged=false]").unwrap();
+        assert_eq!(state.name, "minecraft:chest");
+        assert_eq!(state.properties["facing"], "north");
+        assert_eq!(
+            state.to_string(),
+            "minecraft:chest[facing=north,waterlogged=false]"
+        );
+        assert_eq!(
+            BlockState::parse("minecraft:stone").unwrap().to_string(),
+            "minecraft:stone"
+        );
+        assert!(BlockState::parse("minecraft:chest[facing]").is_none());
+        assert!(BlockState::parse("[a=b]").is_none());
+    }
+
+    #[test]
+    fn test_round_trip_formats() {
+        let original = sample();
+        for format in [StructureFormat::Template, StructureFormat::Bedrock] {
+            let read = Structure::from_nbt(format, &original.to_nbt(format).unwrap()).unwrap();
+            assert_eq!(read.size, original.size, "{:?}", format);
+            assert_eq!(blocks(&read), blocks(&original), "{:?}", format);
+            assert_eq!(read.block_entities, original.block_entities, "{:?}", format);
+        }
+
+        // Sponge and Litematica have no void, so untouched positions read back as air
+        let mut filled = blocks(&original);
+        for block in filled.iter_mut().filter(|b| b.is_none()) {
+            *block = Some(AIR.to_string());
+        }
+        for format in [StructureFormat::Sponge, StructureFormat::Litematic] {
+            let read = Structure::from_nbt(format, &original.to_nbt(format).unwrap()).unwrap();
+            assert_eq!(read.size, original.size, "{:?}", format);
+            assert_eq!(read.data_version, Some(3953), "{:?}", format);
+            assert_eq!(blocks(&read), filled, "{:?}", format);
+            assert_eq!(read.block_entities, original.block_entities, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_litematic_negative_size_and_spanning() {
+        // Air and five blocks need 3 bits per entry, so entry 21 spans two longs
+        let mut structure = Structure::new([30, 1, 1]).unwrap();
+        for x in 0..30 {
+            let name = format!("minecraft:block_{}", x % 5);
+            structure.set([x, 0, 0], BlockState::parse(&name).unwrap());
+        }
+        let tag = structure.to_nbt(StructureFormat::Litematic).unwrap();
+        let read = Structure::from_nbt(StructureFormat::Litematic, &tag).unwrap();
+        assert_eq!(blocks(&read), blocks(&structure));
+
+        // The same region stored from its far corner
+        let mut tag = tag;
+        tag.set_path("Regions.Main.Position", xyz([34, 0, 0]))
+            .unwrap();
+        tag.set_path("Regions.Main.Size", xyz([-30, 1, 1])).unwrap();
+        let read = Structure::from_nbt(StructureFormat::Litematic, &tag).unwrap();
+        assert_eq!(read.size, [30, 1, 1]);
+        assert_eq!(blocks(&read), blocks(&structure));
+    }
+
+    #[test]
+    fn test_read_rejects_forged_sizes() {
+        let tag = parse_snbt("{size: [100000, 100000, 100000], palette: [], blocks: []}").unwrap();
+        assert!(Structure::from_nbt(StructureFormat::Template, &tag).is_err());
+        let tag = parse_snbt(
+            "{Width: 1000s, Height: 1000s, Length: 1000s, Palette: {}, BlockData: [B; 0b]}",
+        )
+        .unwrap();
+        assert!(Structure::from_nbt(StructureFormat::Sponge, &tag).is_err());
+    }
+
+    #[test]
+    fn test_paste_into_chunk() {
+        let mut chunk = Chunk::new(
+            String::new(),
+            parse_snbt(
+                r#"{
+                    DataVersion: 3953, xPos: 1, zPos: 0,
+                    Heightmaps: {},
+                    sections: [
+                        {Y: 0b, block_states: {palette: [{Name: "minecraft:dirt"}]}, SkyLight: [B; 0b]},
+                        {Y: 1b, block_states: {palette: [{Name: "minecraft:air"}]}}
+                    ],
+                    block_entities: [
+                        {id: "minecraft:furnace", x: 16, y: 16, z: 2},
+                        {id: "minecraft:barrel", x: 20, y: 0, z: 0}
+                    ]
+                }"#,
+            )
+            .unwrap(),
+        );
+        // The structure starts at x = 15, so its first column falls in chunk 0
+        let placed = sample().paste_into(&mut chunk, [15, 15, 0]).unwrap();
+        // Row y = 15 gets 2 x 4 stone; row y = 16 gets the chest and the air
+        assert_eq!(placed, 8 + 2);
+
+        let sections = chunk.sections();
+        let (palette, indices) =
+            decode_section(sections[0].get_path("block_states").unwrap()).unwrap();
+        assert_eq!(palette.len(), 2);
+        let at = |x: usize, y: usize, z: usize| {
+            palette[indices[(y * 16 + z) * 16 + x] as usize]
+                .name
+                .as_str()
+        };
+        assert_eq!(at(0, 15, 0), "minecraft:stone");
+        assert_eq!(at(1, 15, 3), "minecraft:stone");
+        assert_eq!(at(2, 15, 0), "minecraft:dirt");
+        assert_eq!(at(0, 14, 0), "minecraft:dirt");
+
+        let (palette, indices) =
+            decode_section(sections[1].get_path("block_states").unwrap()).unwrap();
+        assert_eq!(palette[indices[2 * 16] as usize].name, "minecraft:chest");
+        assert_eq!(palette[indices[3 * 16 + 1] as usize].name, "minecraft:air");
+
+        // The furnace under the chest is replaced; the barrel is untouched
+        let entities = list_at(&chunk.root, "block_entities").unwrap();
+        assert_eq!(entities.len(), 2);
+        assert_eq!(
+            entities[0].get_path("id"),
+            Some(&NbtTag::String("minecraft:barrel".to_string()))
+        );
+        assert_eq!(
+            entities[1].get_path("id"),
+            Some(&NbtTag::String("minecraft:chest".to_string()))
+        );
+        assert_eq!(entities[1].get_path("x"), Some(&NbtTag::Int(16)));
+        assert!(chunk.root.get_path("Heightmaps").is_none());
+        assert!(chunk.root.get_path("sections[0].SkyLight").is_none());
+
+        // Nothing overlaps chunk 3
+        chunk.root.set_path("xPos", NbtTag::Int(3)).unwrap();
+        assert_eq!(sample().paste_into(&mut chunk, [15, 15, 0]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_decode_section_rejects_huge_palette() {
+        let entry = |i: usize| {
+            let mut state = IndexMap::new();
+            state.insert("Name".to_string(), NbtTag::String(format!("b{}", i)));
+            NbtTag::Compound(state)
+        };
+        let mut states = IndexMap::new();
+        states.insert(
+            "palette".to_string(),
+            NbtTag::List((0..=u16::MAX as usize + 1).map(entry).collect()),
+        );
+        states.insert(
+            "data".to_string(),
+            NbtTag::LongArray(vec![0; packed_len(4096, 17, false)]),
+        );
+        let err = decode_section(&NbtTag::Compound(states)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -1,13 +1,19 @@
 // Copyright 2026 driedpampas@proton.me
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use anvil_nbt::anvil::RegionPos;
 use anvil_nbt::anvil::access::Region;
+use anvil_nbt::anvil::editor::RegionEditor;
+use anvil_nbt::anvil::structure::{Structure, StructureFormat};
+use anvil_nbt::client::ClientFileKind;
+#[cfg(feature = "compression")]
+use anvil_nbt::nbt::analyze::{CompressionReport, analyze_compression};
+use anvil_nbt::nbt::io::read_compressed;
 use anvil_nbt::nbt::parse::parse_named_tag;
-use clap::{Parser, Subcommand};
-use flate2::read::GzDecoder;
-use std::fs::File;
-use std::io::{Read, Write};
-use std::path::PathBuf;
+use anvil_nbt::nbt::pretty::PrettyOptions;
+use clap::{Args, Parser, Subcommand};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
 #[command(name = "mc-inspect")]
@@ -15,6 +21,38 @@ use std::path::PathBuf;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    #[command(flatten)]
+    format: FormatArgs,
+}
+
+#[derive(Args)]
+struct FormatArgs {
+    /// Maximum number of array and list elements to print
+    #[arg(long, global = true, default_value_t = 16)]
+    max_array: usize,
+    /// Maximum number of characters to print per string
+    #[arg(long, global = true, default_value_t = 128)]
+    max_string: usize,
+    /// Maximum nesting depth to print
+    #[arg(long, global = true)]
+    max_depth: Option<usize>,
+    /// Print the whole tree without truncation
+    #[arg(long, global = true)]
+    full: bool,
+}
+
+impl FormatArgs {
+    fn options(&self) -> PrettyOptions {
+        if self.full {
+            return PrettyOptions::unlimited();
+        }
+        PrettyOptions {
+            max_array_elements: Some(self.max_array),
+            max_string_len: Some(self.max_string),
+            max_depth: self.max_depth,
+            ..Default::default()
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -23,7 +61,7 @@ enum Commands {
     Nbt {
         /// Path to the .dat file
         path: PathBuf,
-        /// Force uncompressed (if not gzipped)
+        /// Skip compression detection and read the file as raw NBT
         #[arg(short, long)]
         uncompressed: bool,
     },
@@ -38,6 +76,55 @@ enum Commands {
         #[arg(short, long)]
         z: Option<i32>,
     },
+    /// Inspect structure and schematic files
+    Structure {
+        #[command(subcommand)]
+        action: StructureAction,
+    },
+    /// Report how well an NBT file or chunk compresses with each codec and preset
+    #[cfg(feature = "compression")]
+    Analyze {
+        /// Path to an NBT file, or to an .mca file together with -x and -z
+        path: PathBuf,
+        /// Chunk X coordinate
+        #[arg(short, long, requires = "z")]
+        x: Option<i32>,
+        /// Chunk Z coordinate
+        #[arg(short, long, requires = "x")]
+        z: Option<i32>,
+    },
+}
+
+#[derive(Subcommand)]
+enum StructureAction {
+    /// Show the size and block palette of a .nbt, .schem, .litematic or .mcstructure file
+    Show {
+        /// Path to the structure file
+        path: PathBuf,
+    },
+    /// Convert a structure to another format, chosen by the output file's extension
+    Convert {
+        /// Path to the structure file
+        input: PathBuf,
+        /// Path to write, ending in .nbt, .schem, .litematic or .mcstructure
+        output: PathBuf,
+    },
+    /// Paste a structure into the chunks of a region file
+    Paste {
+        /// Path to the structure file
+        path: PathBuf,
+        /// Path to the `r.<x>.<z>.mca` file
+        region: PathBuf,
+        /// Block X coordinate of the structure's minimum corner
+        #[arg(short, long, allow_negative_numbers = true)]
+        x: i32,
+        /// Block Y coordinate of the structure's minimum corner
+        #[arg(short, long, allow_negative_numbers = true)]
+        y: i32,
+        /// Block Z coordinate of the structure's minimum corner
+        #[arg(short, long, allow_negative_numbers = true)]
+        z: i32,
+    },
 }
 
 fn main() {
@@ -55,30 +142,34 @@ fn run() -> anyhow::Result<()> {
     let cli = Cli::parse();
     let stdout = std::io::stdout();
     let mut handle = stdout.lock();
+    let options = cli.format.options();
 
     match cli.command {
         Commands::Nbt { path, uncompressed } => {
-            let mut file = File::open(path)?;
-            let mut data = Vec::new();
-            if uncompressed {
-                file.read_to_end(&mut data)?;
+            let (name, tag) = if uncompressed {
+                let data = std::fs::read(path)?;
+                parse_named_tag(&mut &data[..])
+                    .map_err(|_| anyhow::anyhow!("Failed to parse NBT"))?
             } else {
-                let mut decoder = GzDecoder::new(file);
-                decoder.read_to_end(&mut data)?;
+                read_compressed(path)?
+            };
+            if let Some(kind) = ClientFileKind::detect(&tag) {
+                writeln!(
+                    handle,
+                    "File kind: {} ({})",
+                    kind.description(),
+                    kind.file_name()
+                )?;
             }
-
-            let mut input = &data[..];
-            let (name, tag) =
-                parse_named_tag(&mut input).map_err(|_| anyhow::anyhow!("Failed to parse NBT"))?;
             writeln!(handle, "Root tag name: '{}'", name)?;
-            writeln!(handle, "{:#?}", tag)?;
+            writeln!(handle, "{}", tag.pretty(options))?;
         }
         Commands::Anvil { path, x, z } => {
             let region = Region::open(path)?;
             if let (Some(x), Some(z)) = (x, z) {
                 if let Some((name, tag)) = region.get_chunk_nbt(x, z)? {
                     writeln!(handle, "Chunk ({}, {}) root tag name: '{}'", x, z, name)?;
-                    writeln!(handle, "{:#?}", tag)?;
+                    writeln!(handle, "{}", tag.pretty(options))?;
                 } else {
                     writeln!(
                         handle,
@@ -93,6 +184,118 @@ fn run() -> anyhow::Result<()> {
                 )?;
             }
         }
+        Commands::Structure { action } => match action {
+            StructureAction::Show { path } => {
+                let format = structure_format(&path)?;
+                write_structure_summary(&mut handle, format, &Structure::read(&path)?)?;
+            }
+            StructureAction::Convert { input, output } => {
+                let format = structure_format(&output)?;
+                Structure::read(&input)?.write(&output, format)?;
+                writeln!(
+                    handle,
+                    "Wrote {} ({})",
+                    output.display(),
+                    format.description()
+                )?;
+            }
+            StructureAction::Paste {
+                path,
+                region,
+                x,
+                y,
+                z,
+            } => {
+                let structure = Structure::read(&path)?;
+                let pos = region
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(RegionPos::from_filename)
+                    .ok_or_else(|| anyhow::anyhow!("Region files must be named r.<x>.<z>.mca"))?;
+                let mut editor = RegionEditor::open(&region)?;
+                let placed = structure.paste_into_region(&mut editor, pos, [x, y, z])?;
+                editor.flush()?;
+                writeln!(handle, "Placed {} blocks in {}", placed, region.display())?;
+            }
+        },
+        #[cfg(feature = "compression")]
+        Commands::Analyze { path, x, z } => {
+            let tag = if let (Some(x), Some(z)) = (x, z) {
+                let region = Region::open(path)?;
+                match region.get_chunk_nbt(x, z)? {
+                    Some((_, tag)) => tag,
+                    None => anyhow::bail!("Chunk ({}, {}) is not present in this region", x, z),
+                }
+            } else {
+                read_compressed(path)?.1
+            };
+            write_compression_report(&mut handle, &analyze_compression(&tag)?)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "compression")]
+fn write_compression_report<W: Write>(
+    out: &mut W,
+    report: &CompressionReport,
+) -> std::io::Result<()> {
+    writeln!(
+        out,
+        "{:<6} {:<9} {:>10} {:>7}",
+        "Codec", "Preset", "Bytes", "Ratio"
+    )?;
+    for sample in &report.samples {
+        let preset = sample
+            .preset
+            .map_or("-".to_string(), |p| format!("{:?}", p));
+        writeln!(
+            out,
+            "{:<6} {:<9} {:>10} {:>6.1}%",
+            format!("{:?}", sample.codec),
+            preset,
+            sample.size,
+            sample.size as f64 * 100.0 / report.uncompressed_size as f64
+        )?;
+    }
+    if !report.worst_subtrees.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "Worst-compressing subtrees (Zlib, Balanced):")?;
+        for subtree in &report.worst_subtrees {
+            writeln!(
+                out,
+                "  {:>6.1}%  {:>8} -> {:>8}  {}",
+                subtree.ratio() * 100.0,
+                subtree.encoded_size,
+                subtree.compressed_size,
+                subtree.path
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn structure_format(path: &Path) -> anyhow::Result<StructureFormat> {
+    StructureFormat::from_path(path).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unknown structure format: {} (expected .nbt, .schem, .litematic or .mcstructure)",
+            path.display()
+        )
+    })
+}
+
+fn write_structure_summary<W: Write>(
+    out: &mut W,
+    format: StructureFormat,
+    structure: &Structure,
+) -> std::io::Result<()> {
+    let [x, y, z] = structure.size.map(i64::from);
+    writeln!(out, "Format: {}", format.description())?;
+    writeln!(out, "Size: {} x {} x {} ({} blocks)", x, y, z, x * y * z)?;
+    writeln!(out, "Block entities: {}", structure.block_entities.len())?;
+    writeln!(out, "Palette ({} states):", structure.palette.len())?;
+    for state in &structure.palette {
+        writeln!(out, "  {}", state)?;
     }
     Ok(())
 }
@@ -0,0 +1,65 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Cooperative cancellation of long-running operations.
+//!
+//! A [`CancellationToken`] is handed to an operation that walks many chunks, such as
+//! [`World::block_census`](crate::world::World::block_census),
+//! [`RegionWriter::write_all_chunks`](crate::anvil::encode::RegionWriter::write_all_chunks),
+//! [`copy_chunks`](crate::anvil::editor::copy_chunks) or
+//! [`CowRegion::commit`](crate::anvil::cow::CowRegion::commit).
+//! The operation checks it between chunks and stops with
+//! [`Interrupted`](std::io::ErrorKind::Interrupted) once another thread has called
+//! [`cancel`](CancellationToken::cancel).
+//!
+//! ```
+//! use anvil_nbt::cancel::CancellationToken;
+//!
+//! let token = CancellationToken::new();
+//! let handle = token.clone();
+//! std::thread::spawn(move || handle.cancel()).join().unwrap();
+//! assert!(token.check().is_err());
+//! ```
+
+use std::io::{Error, ErrorKind, Result};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A flag shared between an operation and whoever may want to stop it.
+///
+/// Clones share the same flag, so a GUI or service can keep one clone and pass another
+/// to the operation. Once cancelled, a token stays cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks every operation holding a clone of this token to stop.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if [`cancel`](Self::cancel) has been called on any clone.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Fails with [`Interrupted`](ErrorKind::Interrupted) if the token is cancelled.
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(Error::new(ErrorKind::Interrupted, "operation cancelled"));
+        }
+        Ok(())
+    }
+}
+
+/// Checks an optional token, for operations where cancellation is opt-in.
+pub(crate) fn check(token: Option<&CancellationToken>) -> Result<()> {
+    token.map_or(Ok(()), CancellationToken::check)
+}
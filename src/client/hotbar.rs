@@ -0,0 +1,115 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Typed access to saved creative-mode hotbars, `hotbar.nbt`.
+
+use crate::anvil::CompressionType;
+use crate::nbt::NbtTag;
+use crate::nbt::io::{read_compressed, write_compressed};
+use indexmap::IndexMap;
+use std::io::{ErrorKind, Result};
+use std::path::Path;
+
+/// File name of the saved hotbars, stored in `.minecraft/`.
+pub const HOTBAR_NBT: &str = "hotbar.nbt";
+
+/// Number of hotbars that can be saved, and of slots in each.
+pub const HOTBAR_COUNT: usize = 9;
+
+/// Returns the row number for a root key `"0"` to `"8"`.
+pub(crate) fn row_index(key: &str) -> Option<usize> {
+    match key.as_bytes() {
+        [digit @ b'0'..=b'8'] => Some((digit - b'0') as usize),
+        _ => None,
+    }
+}
+
+/// The contents of `hotbar.nbt`.
+///
+/// Items are kept as raw compounds, since their layout changed with 1.20.5's item
+/// components; an empty slot is an empty compound.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Hotbars {
+    /// The saved hotbars, each a list of up to nine item compounds (keys `"0"` to `"8"`).
+    pub rows: [Vec<NbtTag>; HOTBAR_COUNT],
+    /// The `DataVersion` the file was written with, if present.
+    pub data_version: Option<i32>,
+}
+
+impl Hotbars {
+    /// Reads a `hotbar.nbt` file. A missing file yields empty hotbars.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
+        match read_compressed(path) {
+            Ok((_, root)) => Ok(Self::from_nbt(root)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Hotbars::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Decodes a parsed `hotbar.nbt` root compound.
+    pub fn from_nbt(root: NbtTag) -> Self {
+        let mut hotbars = Hotbars::default();
+        let NbtTag::Compound(root) = root else {
+            return hotbars;
+        };
+        for (key, value) in root {
+            match (row_index(&key), value) {
+                (Some(row), NbtTag::List(items)) => hotbars.rows[row] = items,
+                (None, NbtTag::Int(version)) if key == "DataVersion" => {
+                    hotbars.data_version = Some(version)
+                }
+                _ => {}
+            }
+        }
+        hotbars
+    }
+
+    /// Builds the root compound stored in `hotbar.nbt`. Every row is written, as the
+    /// game does.
+    pub fn to_nbt(&self) -> NbtTag {
+        let mut root = IndexMap::new();
+        for (row, items) in self.rows.iter().enumerate() {
+            root.insert(row.to_string(), NbtTag::List(items.clone()));
+        }
+        if let Some(version) = self.data_version {
+            root.insert("DataVersion".to_string(), NbtTag::Int(version));
+        }
+        NbtTag::Compound(root)
+    }
+
+    /// Writes the hotbars as an uncompressed `hotbar.nbt` file, as the game does.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        write_compressed(path, "", &self.to_nbt(), CompressionType::None)
+    }
+
+    /// Returns the item compounds of non-empty slots in a row, with their slot numbers.
+    pub fn items(&self, row: usize) -> impl Iterator<Item = (usize, &NbtTag)> {
+        self.rows[row]
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| !matches!(item, NbtTag::Compound(map) if map.is_empty()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt;
+
+    #[test]
+    fn test_hotbars_round_trip() {
+        let root = nbt!({
+            "0": [{ id: "minecraft:stone", count: 64 }, {}],
+            "1": [], "2": [], "3": [], "4": [], "5": [], "6": [], "7": [], "8": [],
+            DataVersion: 3955
+        });
+        let hotbars = Hotbars::from_nbt(root.clone());
+        assert_eq!(hotbars.data_version, Some(3955));
+        assert_eq!(hotbars.rows[0].len(), 2);
+        assert_eq!(
+            hotbars.items(0).map(|(slot, _)| slot).collect::<Vec<_>>(),
+            [0]
+        );
+        assert_eq!(hotbars.to_nbt(), root);
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Helpers for the NBT files the game client keeps next to its worlds, in `.minecraft/`.
+//!
+//! Unlike world data, these files are stored uncompressed.
+
+pub mod hotbar;
+pub mod servers;
+
+use crate::nbt::NbtTag;
+
+/// The kinds of client file that can be recognized from their contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClientFileKind {
+    /// The multiplayer server list, `servers.dat`.
+    ServerList,
+    /// Saved creative hotbars, `hotbar.nbt`.
+    Hotbars,
+}
+
+impl ClientFileKind {
+    /// Guesses the kind of a file from its parsed root tag.
+    ///
+    /// A server list has nothing but a `servers` list; saved hotbars have only the row
+    /// lists `"0"` to `"8"` and an optional `DataVersion`.
+    pub fn detect(root: &NbtTag) -> Option<Self> {
+        let NbtTag::Compound(root) = root else {
+            return None;
+        };
+        if root.len() == 1 && matches!(root.get("servers"), Some(NbtTag::List(_))) {
+            return Some(ClientFileKind::ServerList);
+        }
+        let is_row = |key: &str, tag: &NbtTag| {
+            hotbar::row_index(key).is_some() && matches!(tag, NbtTag::List(_))
+        };
+        let rows = root.iter().filter(|(k, v)| is_row(k, v)).count();
+        let version = matches!(root.get("DataVersion"), Some(NbtTag::Int(_))) as usize;
+        if rows > 0 && rows + version == root.len() {
+            return Some(ClientFileKind::Hotbars);
+        }
+        None
+    }
+
+    /// Returns the usual file name of this kind of file.
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ClientFileKind::ServerList => servers::SERVERS_DAT,
+            ClientFileKind::Hotbars => hotbar::HOTBAR_NBT,
+        }
+    }
+
+    /// Returns a short human-readable description.
+    pub fn description(&self) -> &'static str {
+        match self {
+            ClientFileKind::ServerList => "multiplayer server list",
+            ClientFileKind::Hotbars => "saved creative hotbars",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt;
+
+    #[test]
+    fn test_detect_client_files() {
+        let servers = nbt!({ servers: [{ name: "Home", ip: "localhost" }] });
+        assert_eq!(
+            ClientFileKind::detect(&servers),
+            Some(ClientFileKind::ServerList)
+        );
+        let hotbars = nbt!({ "0": [], "8": [], DataVersion: 3955 });
+        assert_eq!(
+            ClientFileKind::detect(&hotbars),
+            Some(ClientFileKind::Hotbars)
+        );
+        assert_eq!(ClientFileKind::detect(&nbt!({ Data: {} })), None);
+        assert_eq!(ClientFileKind::detect(&nbt!({ "9": [] })), None);
+        assert_eq!(ClientFileKind::detect(&nbt!({ DataVersion: 1 })), None);
+    }
+}
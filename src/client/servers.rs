@@ -0,0 +1,180 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Typed access to the multiplayer server list, `servers.dat`.
+
+use crate::anvil::CompressionType;
+use crate::nbt::NbtTag;
+use crate::nbt::io::{read_compressed, write_compressed};
+use indexmap::IndexMap;
+use std::io::{ErrorKind, Result};
+use std::path::Path;
+
+/// File name of the server list, stored in `.minecraft/`.
+pub const SERVERS_DAT: &str = "servers.dat";
+
+/// One entry of the multiplayer server list.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ServerEntry {
+    /// Name shown in the list (`name`).
+    pub name: String,
+    /// Address, optionally with a port (`ip`).
+    pub ip: String,
+    /// Base64-encoded PNG of the server icon, cached from the last ping (`icon`).
+    pub icon: Option<String>,
+    /// Whether to accept the server's resource pack: `Some(true)` or `Some(false)` once
+    /// the player chose, `None` to prompt (`acceptTextures`).
+    pub accept_textures: Option<bool>,
+    /// Whether the entry is hidden from the list, as for servers joined directly
+    /// (`hidden`).
+    pub hidden: bool,
+    /// Any other fields, kept as they were read.
+    pub extra: IndexMap<String, NbtTag>,
+}
+
+/// The contents of `servers.dat`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ServerList {
+    /// Entries in display order (`servers`).
+    pub servers: Vec<ServerEntry>,
+}
+
+/// Removes `key` from `map` if it holds a string.
+fn take_string(map: &mut IndexMap<String, NbtTag>, key: &str) -> Option<String> {
+    match map.shift_remove(key) {
+        Some(NbtTag::String(s)) => Some(s),
+        Some(other) => {
+            map.insert(key.to_string(), other);
+            None
+        }
+        None => None,
+    }
+}
+
+/// Removes `key` from `map` if it holds a byte, returning it as a boolean.
+fn take_bool(map: &mut IndexMap<String, NbtTag>, key: &str) -> Option<bool> {
+    match map.shift_remove(key) {
+        Some(NbtTag::Byte(v)) => Some(v != 0),
+        Some(other) => {
+            map.insert(key.to_string(), other);
+            None
+        }
+        None => None,
+    }
+}
+
+impl ServerEntry {
+    /// Creates a visible entry with no icon that prompts for resource packs.
+    pub fn new(name: impl Into<String>, ip: impl Into<String>) -> Self {
+        ServerEntry {
+            name: name.into(),
+            ip: ip.into(),
+            ..Default::default()
+        }
+    }
+
+    fn from_nbt(tag: NbtTag) -> Option<Self> {
+        let NbtTag::Compound(mut map) = tag else {
+            return None;
+        };
+        Some(ServerEntry {
+            name: take_string(&mut map, "name").unwrap_or_default(),
+            ip: take_string(&mut map, "ip").unwrap_or_default(),
+            icon: take_string(&mut map, "icon"),
+            accept_textures: take_bool(&mut map, "acceptTextures"),
+            hidden: take_bool(&mut map, "hidden").unwrap_or(false),
+            extra: map,
+        })
+    }
+
+    fn to_nbt(&self) -> NbtTag {
+        let mut map = IndexMap::new();
+        map.insert("name".to_string(), NbtTag::String(self.name.clone()));
+        map.insert("ip".to_string(), NbtTag::String(self.ip.clone()));
+        if let Some(icon) = &self.icon {
+            map.insert("icon".to_string(), NbtTag::String(icon.clone()));
+        }
+        if let Some(accept) = self.accept_textures {
+            map.insert("acceptTextures".to_string(), NbtTag::Byte(accept as i8));
+        }
+        if self.hidden {
+            map.insert("hidden".to_string(), NbtTag::Byte(1));
+        }
+        map.extend(self.extra.clone());
+        NbtTag::Compound(map)
+    }
+}
+
+impl ServerList {
+    /// Reads a `servers.dat` file. A missing file yields an empty list.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
+        match read_compressed(path) {
+            Ok((_, root)) => Ok(Self::from_nbt(root)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(ServerList::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Decodes a parsed `servers.dat` root compound. Malformed entries are skipped.
+    pub fn from_nbt(root: NbtTag) -> Self {
+        let NbtTag::Compound(mut root) = root else {
+            return ServerList::default();
+        };
+        let servers = match root.shift_remove("servers") {
+            Some(NbtTag::List(servers)) => servers
+                .into_iter()
+                .filter_map(ServerEntry::from_nbt)
+                .collect(),
+            _ => Vec::new(),
+        };
+        ServerList { servers }
+    }
+
+    /// Builds the root compound stored in `servers.dat`.
+    pub fn to_nbt(&self) -> NbtTag {
+        let servers = self.servers.iter().map(ServerEntry::to_nbt).collect();
+        let mut root = IndexMap::new();
+        root.insert("servers".to_string(), NbtTag::List(servers));
+        NbtTag::Compound(root)
+    }
+
+    /// Writes the list as an uncompressed `servers.dat` file, as the game does.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        write_compressed(path, "", &self.to_nbt(), CompressionType::None)
+    }
+
+    /// Returns the first entry with the given address.
+    pub fn find_by_ip(&self, ip: &str) -> Option<&ServerEntry> {
+        self.servers.iter().find(|s| s.ip == ip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt;
+
+    #[test]
+    fn test_server_list_round_trip() {
+        let root = nbt!({
+            servers: [
+                { name: "Home", ip: "192.168.0.2", acceptTextures: 1i8, motd: "kept" },
+                { name: "", ip: "play.example.com", hidden: 1i8 },
+            ]
+        });
+        let list = ServerList::from_nbt(root.clone());
+        assert_eq!(list.servers[0].accept_textures, Some(true));
+        assert_eq!(list.servers[0].extra.len(), 1);
+        assert!(list.servers[1].hidden);
+        assert_eq!(list.find_by_ip("play.example.com"), Some(&list.servers[1]));
+        assert_eq!(list.to_nbt(), root);
+
+        let mut list = ServerList::default();
+        list.servers
+            .push(ServerEntry::new("Local", "localhost:25565"));
+        assert_eq!(
+            list.to_nbt(),
+            nbt!({ servers: [{ name: "Local", ip: "localhost:25565" }] })
+        );
+    }
+}
@@ -16,4 +16,7 @@
 //! - Idempotent round-trips for both NBT and Anvil data
 
 pub mod anvil;
+pub mod cancel;
+pub mod client;
 pub mod nbt;
+pub mod world;
@@ -0,0 +1,254 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Measuring how well NBT data compresses.
+//!
+//! [`analyze_compression`] encodes a tree, compresses it with every codec this crate
+//! supports at every [`CompressionPreset`], and ranks its larger subtrees by how poorly
+//! they compress. High-entropy data such as packed block states or random seeds shows up
+//! at the top, which tells whether a slower preset is worth it before committing to one.
+//!
+//! LZ4, which the game accepts for chunks since 1.20.5, is measured when the `lz4`
+//! feature is enabled. It has a single level, so it is sampled once.
+
+use crate::anvil::{CompressionPreset, CompressionType};
+use crate::nbt::NbtTag;
+use crate::nbt::encode::{write_named_tag, write_tag_payload};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use std::io::{Result, Write};
+
+/// Subtrees smaller than this many encoded bytes are not ranked; their ratios are noise.
+const MIN_SUBTREE_SIZE: usize = 256;
+
+/// How many levels below the root subtrees are ranked.
+const MAX_SUBTREE_DEPTH: usize = 4;
+
+/// How many subtrees [`CompressionReport::worst_subtrees`] holds at most.
+const WORST_SUBTREES: usize = 10;
+
+/// The size of a tree under one codec and preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionSample {
+    /// The codec measured.
+    pub codec: CompressionType,
+    /// The preset measured, or `None` for codecs without levels
+    /// ([`CompressionType::None`] and [`CompressionType::Lz4`]).
+    pub preset: Option<CompressionPreset>,
+    /// Size of the output in bytes.
+    pub size: usize,
+}
+
+/// How well one subtree compresses on its own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtreeReport {
+    /// Path of the subtree, such as `sections[3].block_states.data`.
+    pub path: String,
+    /// Size of the subtree's encoded payload in bytes.
+    pub encoded_size: usize,
+    /// Size of the payload after Zlib compression with the default preset.
+    pub compressed_size: usize,
+}
+
+impl SubtreeReport {
+    /// Returns the compressed size as a fraction of the encoded size; higher is worse.
+    pub fn ratio(&self) -> f64 {
+        self.compressed_size as f64 / self.encoded_size as f64
+    }
+}
+
+/// Result of [`analyze_compression`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressionReport {
+    /// Size of the encoded tree without compression.
+    pub uncompressed_size: usize,
+    /// One sample per codec and preset, uncompressed first and LZ4, if measured, last.
+    pub samples: Vec<CompressionSample>,
+    /// The subtrees that compress worst, worst first.
+    pub worst_subtrees: Vec<SubtreeReport>,
+}
+
+impl CompressionReport {
+    /// Returns the sample with the smallest output.
+    pub fn smallest(&self) -> &CompressionSample {
+        self.samples
+            .iter()
+            .min_by_key(|s| s.size)
+            .expect("a report always has samples")
+    }
+}
+
+/// Measures the encoded size of `tag` under every codec and preset, and finds the
+/// subtrees that compress worst.
+///
+/// # Examples
+///
+/// ```
+/// use anvil_nbt::nbt::NbtTag;
+/// use anvil_nbt::nbt::analyze::analyze_compression;
+///
+/// let tag = NbtTag::LongArray(vec![0; 512]);
+/// let report = analyze_compression(&tag)?;
+/// assert!(report.smallest().size < report.uncompressed_size / 10);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn analyze_compression(tag: &NbtTag) -> Result<CompressionReport> {
+    let mut encoded = Vec::new();
+    write_named_tag(&mut encoded, "", tag)?;
+
+    let mut samples = vec![CompressionSample {
+        codec: CompressionType::None,
+        preset: None,
+        size: encoded.len(),
+    }];
+    for codec in [CompressionType::Gzip, CompressionType::Zlib] {
+        for preset in [
+            CompressionPreset::Fast,
+            CompressionPreset::Balanced,
+            CompressionPreset::Archive,
+        ] {
+            samples.push(CompressionSample {
+                codec,
+                preset: Some(preset),
+                size: compressed_size(&encoded, codec, preset)?,
+            });
+        }
+    }
+    #[cfg(feature = "lz4")]
+    samples.push(CompressionSample {
+        codec: CompressionType::Lz4,
+        preset: None,
+        size: compressed_size(&encoded, CompressionType::Lz4, CompressionPreset::default())?,
+    });
+
+    let mut subtrees = Vec::new();
+    collect_subtrees(tag, &mut String::new(), 0, &mut subtrees)?;
+    subtrees.sort_by(|a, b| b.ratio().total_cmp(&a.ratio()));
+    subtrees.truncate(WORST_SUBTREES);
+
+    Ok(CompressionReport {
+        uncompressed_size: encoded.len(),
+        samples,
+        worst_subtrees: subtrees,
+    })
+}
+
+fn compressed_size(
+    data: &[u8],
+    codec: CompressionType,
+    preset: CompressionPreset,
+) -> Result<usize> {
+    match codec {
+        CompressionType::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), preset.flate2());
+            encoder.write_all(data)?;
+            Ok(encoder.finish()?.len())
+        }
+        CompressionType::Zlib => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), preset.flate2());
+            encoder.write_all(data)?;
+            Ok(encoder.finish()?.len())
+        }
+        CompressionType::None => Ok(data.len()),
+        #[cfg(feature = "lz4")]
+        CompressionType::Lz4 => {
+            let mut encoder = crate::anvil::lz4::Lz4Encoder::new(Vec::new());
+            encoder.write_all(data)?;
+            Ok(encoder.finish()?.len())
+        }
+        #[cfg(not(feature = "lz4"))]
+        CompressionType::Lz4 => Err(crate::anvil::unsupported_compression(codec)),
+    }
+}
+
+/// Measures the children of `tag` that are large enough to rank, recursing up to
+/// [`MAX_SUBTREE_DEPTH`] levels.
+fn collect_subtrees(
+    tag: &NbtTag,
+    path: &mut String,
+    depth: usize,
+    out: &mut Vec<SubtreeReport>,
+) -> Result<()> {
+    if depth == MAX_SUBTREE_DEPTH {
+        return Ok(());
+    }
+    let children: Vec<(String, &NbtTag)> = match tag {
+        NbtTag::Compound(map) => map
+            .iter()
+            .map(|(key, value)| {
+                let sep = if path.is_empty() { "" } else { "." };
+                (format!("{}{}", sep, key), value)
+            })
+            .collect(),
+        NbtTag::List(elements) => elements
+            .iter()
+            .enumerate()
+            .map(|(i, element)| (format!("[{}]", i), element))
+            .collect(),
+        _ => return Ok(()),
+    };
+
+    for (segment, child) in children {
+        let mut payload = Vec::new();
+        write_tag_payload(&mut payload, child)?;
+        if payload.len() < MIN_SUBTREE_SIZE {
+            continue;
+        }
+        let len = path.len();
+        path.push_str(&segment);
+        out.push(SubtreeReport {
+            path: path.clone(),
+            encoded_size: payload.len(),
+            compressed_size: compressed_size(
+                &payload,
+                CompressionType::Zlib,
+                CompressionPreset::default(),
+            )?,
+        });
+        collect_subtrees(child, path, depth + 1, out)?;
+        path.truncate(len);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt;
+
+    #[test]
+    fn test_analyze_compression_ranks_random_data_worst() {
+        // A xorshift sequence compresses poorly; zeros compress to almost nothing
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let noise: Vec<i64> = (0..256)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as i64
+            })
+            .collect();
+        let tag = nbt!({
+            sections: [{ data: (NbtTag::LongArray(noise)) }],
+            heightmap: (NbtTag::LongArray(vec![0; 256])),
+        });
+
+        let report = analyze_compression(&tag).unwrap();
+        assert_eq!(report.samples.len(), 7 + cfg!(feature = "lz4") as usize);
+        #[cfg(feature = "lz4")]
+        assert_eq!(
+            report.samples.last().map(|s| (s.codec, s.preset)),
+            Some((CompressionType::Lz4, None))
+        );
+        assert_eq!(report.samples[0].size, report.uncompressed_size);
+        assert!(report.smallest().size < report.uncompressed_size);
+
+        let paths: Vec<&str> = report
+            .worst_subtrees
+            .iter()
+            .map(|s| s.path.as_str())
+            .collect();
+        assert_eq!(paths.last(), Some(&"heightmap"));
+        assert!(paths[..3].contains(&"sections[0].data"));
+        assert!(report.worst_subtrees[0].ratio() > 0.9);
+    }
+}
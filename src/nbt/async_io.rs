@@ -0,0 +1,109 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Reading and writing NBT over tokio's [`AsyncRead`] and [`AsyncWrite`].
+//!
+//! These mirror [`parse_from_reader`](crate::nbt::parse::parse_from_reader) and
+//! [`write_named_tag`] without blocking the runtime on I/O. Parsing and encoding
+//! themselves run on the calling task.
+//!
+//! ```
+//! use anvil_nbt::nbt;
+//! use anvil_nbt::nbt::async_io::{parse_from_async_reader, write_named_tag_async};
+//!
+//! # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+//! let mut bytes = Vec::new();
+//! write_named_tag_async(&mut bytes, "root", &nbt!({ "a": 1 })).await?;
+//! let (name, tag) = parse_from_async_reader(&bytes[..]).await?;
+//! assert_eq!((name.as_str(), tag), ("root", nbt!({ "a": 1 })));
+//! # Ok::<(), std::io::Error>(())
+//! # }).unwrap();
+//! ```
+
+use crate::nbt::NbtTag;
+use crate::nbt::encode::write_named_tag;
+use crate::nbt::parse::{ParseOptions, TagSource, parse_from_source};
+use std::io::Result;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Parses a named tag from an async reader with the default [`ParseOptions`].
+///
+/// Like [`parse_from_reader`](crate::nbt::parse::parse_from_reader), nothing past the
+/// tag is read, and the tag is read in many small pieces; wrap sockets and files in a
+/// [`BufReader`](tokio::io::BufReader).
+pub async fn parse_from_async_reader<R: AsyncRead + Unpin>(reader: R) -> Result<(String, NbtTag)> {
+    parse_from_async_reader_with(reader, &ParseOptions::default()).await
+}
+
+/// Like [`parse_from_async_reader`], enforcing the limits and format in `options`.
+pub async fn parse_from_async_reader_with<R: AsyncRead + Unpin>(
+    reader: R,
+    options: &ParseOptions,
+) -> Result<(String, NbtTag)> {
+    parse_from_source(AsyncSource(reader), options).await
+}
+
+/// Encodes a named tag and writes it to an async writer.
+///
+/// The tag is encoded into memory first, then written with a single
+/// [`write_all`](AsyncWriteExt::write_all). The writer is not flushed.
+pub async fn write_named_tag_async<W: AsyncWrite + Unpin>(
+    mut writer: W,
+    name: &str,
+    tag: &NbtTag,
+) -> Result<()> {
+    let mut bytes = Vec::new();
+    write_named_tag(&mut bytes, name, tag)?;
+    writer.write_all(&bytes).await
+}
+
+struct AsyncSource<R>(R);
+
+impl<R: AsyncRead + Unpin> TagSource for AsyncSource<R> {
+    async fn read_into(&mut self, buf: &mut Vec<u8>, len: usize) -> Result<()> {
+        (&mut self.0).take(len as u64).read_to_end(buf).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt;
+    use crate::nbt::parse::parse_from_reader;
+
+    #[tokio::test]
+    async fn test_async_round_trip_stops_after_tag() {
+        let tag = nbt!({ "list": [1, 2, 3], "name": "x", "nested": { "b": [B; 1, 2] } });
+        let mut bytes = Vec::new();
+        write_named_tag_async(&mut bytes, "root", &tag)
+            .await
+            .unwrap();
+        bytes.extend_from_slice(b"rest");
+
+        let mut reader = &bytes[..];
+        let parsed = parse_from_async_reader(&mut reader).await.unwrap();
+        assert_eq!(parsed, ("root".to_string(), tag));
+        assert_eq!(reader, b"rest");
+    }
+
+    #[tokio::test]
+    async fn test_async_errors_match_sync() {
+        let mut bytes = Vec::new();
+        write_named_tag(&mut bytes, "", &nbt!({ "a": "long string" })).unwrap();
+        let truncated = &bytes[..bytes.len() - 3];
+
+        let sync = parse_from_reader(truncated).unwrap_err();
+        let async_err = parse_from_async_reader(truncated).await.unwrap_err();
+        assert_eq!(async_err.kind(), sync.kind());
+
+        let options = ParseOptions {
+            max_string_length: 4,
+            ..ParseOptions::default()
+        };
+        let err = parse_from_async_reader_with(&bytes[..], &options)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
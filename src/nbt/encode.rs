@@ -1,76 +1,345 @@
 // Copyright 2026 driedpampas@proton.me
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use crate::nbt::NbtTag;
-use crate::nbt::mutf8::encode_mutf8;
-use byteorder::{BigEndian, WriteBytesExt};
-use std::io::{Result, Write};
+use crate::nbt::mutf8::{
+    Mutf8Writer, encode_cesu8, encode_mutf8, encode_mutf8_into, is_cesu8_identical, is_plain_ascii,
+    mutf8_encoded_len,
+};
+use crate::nbt::validate::validate_root;
+use crate::nbt::{NbtFormat, NbtTag};
+use byteorder::{BigEndian, ByteOrder, LittleEndian, WriteBytesExt};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{ErrorKind, Result, Write};
+use thiserror::Error;
+
+/// A tree that cannot be encoded without corrupting it, wrapped in an
+/// [`InvalidInput`](ErrorKind::InvalidInput) error by the writers in this module.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum EncodeError {
+    /// A string or key is longer than its length prefix can express.
+    #[error("String of {0} encoded bytes exceeds the 65535 byte limit")]
+    StringTooLong(usize),
+    /// A list or array has more elements than an Int length can express.
+    #[error("List or array of {0} elements exceeds the 2147483647 element limit")]
+    TooManyElements(usize),
+    /// Lists and compounds are nested deeper than [`EncodeOptions::max_depth`].
+    #[error("Nesting exceeds the depth limit of {0}")]
+    DepthLimitExceeded(usize),
+}
+
+impl From<EncodeError> for std::io::Error {
+    fn from(e: EncodeError) -> Self {
+        std::io::Error::new(ErrorKind::InvalidInput, e)
+    }
+}
+
+/// Fails if a string of `len` encoded bytes does not fit a `u16` length prefix.
+fn check_string_len(len: usize) -> Result<u16> {
+    u16::try_from(len).map_err(|_| EncodeError::StringTooLong(len).into())
+}
+
+/// Fails if a list or array of `len` elements does not fit an Int length prefix.
+fn check_list_len(len: usize) -> Result<i32> {
+    i32::try_from(len).map_err(|_| EncodeError::TooManyElements(len).into())
+}
+
+/// Number of distinct keys a [`KeyCache`] holds before it stops adding new ones.
+const MAX_CACHED_KEYS: usize = 4096;
+
+/// Memoizes the Modified UTF-8 encoding of compound keys across encodes.
+///
+/// Chunk data repeats the same few hundred keys (`Name`, `Properties`, `block_states`,
+/// ...) millions of times per region. Passing one cache to
+/// [`write_named_tag_cached`] for every chunk encodes each key once. The cache is
+/// bounded, so unusual data with many distinct keys cannot grow it without limit.
+#[derive(Debug, Default)]
+pub struct KeyCache {
+    encoded: HashMap<String, Box<[u8]>>,
+}
+
+impl KeyCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of cached keys.
+    pub fn len(&self) -> usize {
+        self.encoded.len()
+    }
+
+    /// Returns `true` if no keys are cached.
+    pub fn is_empty(&self) -> bool {
+        self.encoded.is_empty()
+    }
+
+    /// Writes a length-prefixed key, encoding it only if it is not cached yet.
+    fn write_key<W: Write>(&mut self, writer: &mut W, key: &str) -> Result<()> {
+        let bytes = match self.encoded.get(key) {
+            Some(bytes) => bytes,
+            None if self.encoded.len() < MAX_CACHED_KEYS => self
+                .encoded
+                .entry(key.to_string())
+                .or_insert_with(|| encode_mutf8(key).into_boxed_slice()),
+            None => return write_nbt_string(writer, key),
+        };
+        writer.write_u16::<BigEndian>(check_string_len(bytes.len())?)?;
+        writer.write_all(bytes)
+    }
+}
+
+/// How numbers, lengths and strings are written in one [`NbtFormat`].
+trait Flavor {
+    /// Byte order of fixed-width numbers.
+    type Order: ByteOrder;
+
+    fn encode_string(s: &str) -> Cow<'_, [u8]>;
+
+    /// Writes a length-prefixed string.
+    fn write_string<W: Write>(writer: &mut W, s: &str) -> Result<()> {
+        let bytes = Self::encode_string(s);
+        Self::write_string_len(writer, bytes.len())?;
+        writer.write_all(&bytes)
+    }
+
+    fn write_string_len<W: Write>(writer: &mut W, len: usize) -> Result<()> {
+        writer.write_u16::<Self::Order>(check_string_len(len)?)
+    }
+
+    /// Writes an Int payload or a list or array length.
+    fn write_int<W: Write>(writer: &mut W, v: i32) -> Result<()> {
+        writer.write_i32::<Self::Order>(v)
+    }
+
+    /// Writes a list or array length, failing if it does not fit an Int.
+    fn write_len<W: Write>(writer: &mut W, len: usize) -> Result<()> {
+        Self::write_int(writer, check_list_len(len)?)
+    }
+
+    fn write_long<W: Write>(writer: &mut W, v: i64) -> Result<()> {
+        writer.write_i64::<Self::Order>(v)
+    }
+}
+
+struct JavaBE;
+struct JavaCesu8;
+struct BedrockLE;
+struct BedrockNetwork;
+
+impl Flavor for JavaBE {
+    type Order = BigEndian;
+
+    fn encode_string(s: &str) -> Cow<'_, [u8]> {
+        if is_plain_ascii(s) {
+            Cow::Borrowed(s.as_bytes())
+        } else {
+            Cow::Owned(encode_mutf8(s))
+        }
+    }
+
+    /// Encodes non-ASCII strings straight into the writer instead of a temporary `Vec`.
+    fn write_string<W: Write>(writer: &mut W, s: &str) -> Result<()> {
+        if is_plain_ascii(s) {
+            Self::write_string_len(writer, s.len())?;
+            return writer.write_all(s.as_bytes());
+        }
+        Self::write_string_len(writer, mutf8_encoded_len(s))?;
+        Mutf8Writer::new(writer).write_str(s)
+    }
+}
+
+impl Flavor for JavaCesu8 {
+    type Order = BigEndian;
+
+    fn encode_string(s: &str) -> Cow<'_, [u8]> {
+        if is_cesu8_identical(s) {
+            Cow::Borrowed(s.as_bytes())
+        } else {
+            Cow::Owned(encode_cesu8(s))
+        }
+    }
+}
+
+impl Flavor for BedrockLE {
+    type Order = LittleEndian;
+
+    fn encode_string(s: &str) -> Cow<'_, [u8]> {
+        Cow::Borrowed(s.as_bytes())
+    }
+}
+
+impl Flavor for BedrockNetwork {
+    type Order = LittleEndian;
+
+    fn encode_string(s: &str) -> Cow<'_, [u8]> {
+        Cow::Borrowed(s.as_bytes())
+    }
+
+    fn write_string_len<W: Write>(writer: &mut W, len: usize) -> Result<()> {
+        let len = u32::try_from(len).map_err(|_| EncodeError::StringTooLong(len))?;
+        write_varint(writer, len as u64)
+    }
+
+    fn write_int<W: Write>(writer: &mut W, v: i32) -> Result<()> {
+        write_varint(writer, ((v << 1) ^ (v >> 31)) as u32 as u64)
+    }
+
+    fn write_long<W: Write>(writer: &mut W, v: i64) -> Result<()> {
+        write_varint(writer, ((v << 1) ^ (v >> 63)) as u64)
+    }
+}
+
+/// Writes an unsigned LEB128 VarInt.
+fn write_varint<W: Write>(writer: &mut W, mut v: u64) -> Result<()> {
+    while v >= 0x80 {
+        writer.write_u8(v as u8 | 0x80)?;
+        v >>= 7;
+    }
+    writer.write_u8(v as u8)
+}
 
 /// Writes a length-prefixed Modified UTF-8 string to the writer.
 pub fn write_nbt_string<W: Write>(writer: &mut W, s: &str) -> Result<()> {
-    let bytes = encode_mutf8(s);
-    writer.write_u16::<BigEndian>(bytes.len() as u16)?;
-    writer.write_all(&bytes)?;
-    Ok(())
+    write_string::<W, JavaBE>(writer, s)
+}
+
+fn write_string<W: Write, F: Flavor>(writer: &mut W, s: &str) -> Result<()> {
+    F::write_string(writer, s)
 }
 
 /// Writes the payload of an NBT tag to the writer.
 ///
 /// This does not include the type ID or the name of the tag.
 pub fn write_tag_payload<W: Write>(writer: &mut W, tag: &NbtTag) -> Result<()> {
+    write_payload::<W, JavaBE>(writer, tag, &mut EncodeState::unlimited(None))
+}
+
+/// Settings and progress of one encode, threaded through [`write_payload`].
+struct EncodeState<'c> {
+    /// Key cache holding Java Edition keys; must only be set for [`JavaBE`].
+    cache: Option<&'c mut KeyCache>,
+    /// Write compound entries in key order.
+    sort_keys: bool,
+    /// Lists and compounds currently open.
+    depth: usize,
+    max_depth: usize,
+}
+
+impl<'c> EncodeState<'c> {
+    fn unlimited(cache: Option<&'c mut KeyCache>) -> Self {
+        EncodeState {
+            cache,
+            sort_keys: false,
+            depth: 0,
+            max_depth: usize::MAX,
+        }
+    }
+
+    fn from_options(options: &EncodeOptions) -> Self {
+        EncodeState {
+            cache: None,
+            sort_keys: options.sort_keys,
+            depth: 0,
+            max_depth: options.max_depth,
+        }
+    }
+}
+
+impl EncodeState<'_> {
+    /// Opens a list or compound, failing if that exceeds the depth limit.
+    fn enter(&mut self) -> Result<()> {
+        if self.depth == self.max_depth {
+            return Err(EncodeError::DepthLimitExceeded(self.max_depth).into());
+        }
+        self.depth += 1;
+        Ok(())
+    }
+}
+
+/// Writes a payload in flavor `F`.
+fn write_payload<W: Write, F: Flavor>(
+    writer: &mut W,
+    tag: &NbtTag,
+    state: &mut EncodeState,
+) -> Result<()> {
     match tag {
         NbtTag::End => Ok(()),
         NbtTag::Byte(v) => writer.write_i8(*v),
-        NbtTag::Short(v) => writer.write_i16::<BigEndian>(*v),
-        NbtTag::Int(v) => writer.write_i32::<BigEndian>(*v),
-        NbtTag::Long(v) => writer.write_i64::<BigEndian>(*v),
-        NbtTag::Float(v) => writer.write_f32::<BigEndian>(*v),
-        NbtTag::Double(v) => writer.write_f64::<BigEndian>(*v),
+        NbtTag::Short(v) => writer.write_i16::<F::Order>(*v),
+        NbtTag::Int(v) => F::write_int(writer, *v),
+        NbtTag::Long(v) => F::write_long(writer, *v),
+        NbtTag::Float(v) => writer.write_f32::<F::Order>(*v),
+        NbtTag::Double(v) => writer.write_f64::<F::Order>(*v),
         NbtTag::ByteArray(v) => {
-            writer.write_i32::<BigEndian>(v.len() as i32)?;
+            F::write_len(writer, v.len())?;
+            writer.write_all(v)
+        }
+        NbtTag::String(v) => write_string::<W, F>(writer, v),
+        NbtTag::RawString(v) => {
+            F::write_string_len(writer, v.len())?;
             writer.write_all(v)
         }
-        NbtTag::String(v) => write_nbt_string(writer, v),
         NbtTag::List(v) => {
-            if v.is_empty() {
-                writer.write_u8(0)?; // Tag_End as element type
-                writer.write_i32::<BigEndian>(0)?;
-            } else {
-                let element_type = v[0].get_type_id();
-                writer.write_u8(element_type)?;
-                writer.write_i32::<BigEndian>(v.len() as i32)?;
-                for element in v {
-                    write_tag_payload(writer, element)?;
-                }
+            state.enter()?;
+            // Empty lists get element type End
+            writer.write_u8(v.first().map_or(0, NbtTag::get_type_id))?;
+            F::write_len(writer, v.len())?;
+            for element in v {
+                write_payload::<W, F>(writer, element, state)?;
             }
+            state.depth -= 1;
             Ok(())
         }
         NbtTag::Compound(v) => {
-            for (name, tag) in v {
-                writer.write_u8(tag.get_type_id())?;
-                write_nbt_string(writer, name)?;
-                write_tag_payload(writer, tag)?;
+            state.enter()?;
+            if state.sort_keys {
+                let mut entries: Vec<_> = v.iter().collect();
+                entries.sort_unstable_by_key(|&(name, _)| name);
+                for (name, tag) in entries {
+                    write_entry::<W, F>(writer, name, tag, state)?;
+                }
+            } else {
+                for (name, tag) in v {
+                    write_entry::<W, F>(writer, name, tag, state)?;
+                }
             }
             writer.write_u8(0)?; // Tag_End
+            state.depth -= 1;
             Ok(())
         }
         NbtTag::IntArray(v) => {
-            writer.write_i32::<BigEndian>(v.len() as i32)?;
+            F::write_len(writer, v.len())?;
             for &i in v {
-                writer.write_i32::<BigEndian>(i)?;
+                F::write_int(writer, i)?;
             }
             Ok(())
         }
         NbtTag::LongArray(v) => {
-            writer.write_i32::<BigEndian>(v.len() as i32)?;
+            F::write_len(writer, v.len())?;
             for &i in v {
-                writer.write_i64::<BigEndian>(i)?;
+                F::write_long(writer, i)?;
             }
             Ok(())
         }
     }
 }
 
+/// Writes one compound entry: type ID, key and payload.
+fn write_entry<W: Write, F: Flavor>(
+    writer: &mut W,
+    name: &str,
+    tag: &NbtTag,
+    state: &mut EncodeState,
+) -> Result<()> {
+    writer.write_u8(tag.get_type_id())?;
+    match state.cache.as_deref_mut() {
+        Some(cache) => cache.write_key(writer, name)?,
+        None => write_string::<W, F>(writer, name)?,
+    }
+    write_payload::<W, F>(writer, tag, state)
+}
+
 /// Writes a named tag (type ID + name + payload) to the writer.
 ///
 /// This is the standard way to encode a root NBT tag for storage.
@@ -81,9 +350,264 @@ pub fn write_named_tag<W: Write>(writer: &mut W, name: &str, tag: &NbtTag) -> Re
     Ok(())
 }
 
+/// Like [`write_named_tag`], but encodes compound keys through `cache`.
+///
+/// The output is byte-for-byte the same; reusing one cache across many tags saves
+/// re-encoding keys they share.
+pub fn write_named_tag_cached<W: Write>(
+    writer: &mut W,
+    name: &str,
+    tag: &NbtTag,
+    cache: &mut KeyCache,
+) -> Result<()> {
+    writer.write_u8(tag.get_type_id())?;
+    write_nbt_string(writer, name)?;
+    write_payload::<W, JavaBE>(writer, tag, &mut EncodeState::unlimited(Some(cache)))
+}
+
+/// Writes a little-endian named tag, as found in Bedrock Edition files.
+///
+/// Bedrock's `level.dat` additionally expects an 8-byte header (storage version and
+/// length) before the tag, which is not written here.
+pub fn write_named_tag_le<W: Write>(writer: &mut W, name: &str, tag: &NbtTag) -> Result<()> {
+    write_named_tag_as(writer, name, tag, NbtFormat::BedrockLE)
+}
+
+/// Writes a named tag in the given binary format.
+pub fn write_named_tag_as<W: Write>(
+    writer: &mut W,
+    name: &str,
+    tag: &NbtTag,
+    format: NbtFormat,
+) -> Result<()> {
+    let options = EncodeOptions {
+        format,
+        max_depth: usize::MAX,
+        ..EncodeOptions::default()
+    };
+    write_named_tag_with(writer, name, tag, &options)
+}
+
+/// How [`write_named_tag_with`] lays out and checks its output.
+///
+/// Every writer in this module fails with [`EncodeError`] rather than truncating a
+/// string or list length that does not fit its prefix. The options add a depth limit,
+/// 512 levels by default as when parsing, so a tree the game would refuse is caught
+/// before it is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncodeOptions {
+    /// Binary format of the output.
+    pub format: NbtFormat,
+    /// Write compound entries sorted by key instead of in insertion order.
+    pub sort_keys: bool,
+    /// Maximum nesting of lists and compounds. The root counts as depth 1.
+    pub max_depth: usize,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        EncodeOptions {
+            format: NbtFormat::JavaBE,
+            sort_keys: false,
+            max_depth: 512,
+        }
+    }
+}
+
+impl EncodeOptions {
+    /// Java Edition output that depends only on the contents of the tree.
+    ///
+    /// Compound entries are sorted by key, so two trees that differ only in insertion
+    /// order, such as one read by a tool that does not preserve it, encode identically
+    /// and can be hashed or diffed. Empty lists are always written with element type
+    /// End, whatever the source file used.
+    pub fn canonical() -> Self {
+        EncodeOptions {
+            sort_keys: true,
+            ..EncodeOptions::default()
+        }
+    }
+}
+
+/// Writes a named tag laid out as `options` describe.
+///
+/// ```
+/// use anvil_nbt::nbt::encode::{EncodeOptions, write_named_tag_with};
+/// use anvil_nbt::nbt::snbt::parse_snbt;
+///
+/// let a = parse_snbt("{x: 1, y: 2}").unwrap();
+/// let b = parse_snbt("{y: 2, x: 1}").unwrap();
+/// let (mut out_a, mut out_b) = (Vec::new(), Vec::new());
+/// write_named_tag_with(&mut out_a, "", &a, &EncodeOptions::canonical())?;
+/// write_named_tag_with(&mut out_b, "", &b, &EncodeOptions::canonical())?;
+/// assert_eq!(out_a, out_b);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn write_named_tag_with<W: Write>(
+    writer: &mut W,
+    name: &str,
+    tag: &NbtTag,
+    options: &EncodeOptions,
+) -> Result<()> {
+    writer.write_u8(tag.get_type_id())?;
+    let state = &mut EncodeState::from_options(options);
+    match options.format {
+        NbtFormat::JavaBE => write_named::<W, JavaBE>(writer, name, tag, state),
+        NbtFormat::JavaCesu8 => write_named::<W, JavaCesu8>(writer, name, tag, state),
+        NbtFormat::BedrockLE => write_named::<W, BedrockLE>(writer, name, tag, state),
+        NbtFormat::BedrockNetwork => write_named::<W, BedrockNetwork>(writer, name, tag, state),
+    }
+}
+
+fn write_named<W: Write, F: Flavor>(
+    writer: &mut W,
+    name: &str,
+    tag: &NbtTag,
+    state: &mut EncodeState,
+) -> Result<()> {
+    write_string::<W, F>(writer, name)?;
+    write_payload::<W, F>(writer, tag, state)
+}
+
+impl NbtTag {
+    /// Encodes this tag as a named Java Edition tag into a new `Vec`.
+    ///
+    /// The output matches [`write_named_tag`] byte for byte, but the size is computed up
+    /// front so the buffer is allocated once, and values are stored straight into it
+    /// instead of going through [`Write`]. Like [`write_named_tag`], a string or list too
+    /// long for its length prefix fails with [`EncodeError`] instead of being truncated.
+    ///
+    /// ```
+    /// use anvil_nbt::nbt::NbtTag;
+    /// use anvil_nbt::nbt::encode::write_named_tag;
+    ///
+    /// let tag = NbtTag::LongArray(vec![1, 2, 3]);
+    /// let mut written = Vec::new();
+    /// write_named_tag(&mut written, "data", &tag)?;
+    /// assert_eq!(tag.to_bytes("data")?, written);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn to_bytes(&self, name: &str) -> Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(self.serialized_size(name));
+        out.push(self.get_type_id());
+        push_string(&mut out, name)?;
+        push_payload(&mut out, self)?;
+        debug_assert_eq!(out.len(), self.serialized_size(name));
+        Ok(out)
+    }
+
+    /// Returns the size in bytes of this tag encoded as a named Java Edition tag, as
+    /// written by [`write_named_tag`] or [`to_bytes`](Self::to_bytes).
+    ///
+    /// The tree is walked without encoding anything, so this is cheap enough to size
+    /// buffers with or to reject a chunk before compressing it. Compression rarely
+    /// grows data, so it is also a close upper bound on the compressed size; zlib only
+    /// adds a few bytes per 16 KiB to incompressible input.
+    ///
+    /// ```
+    /// use anvil_nbt::nbt::NbtTag;
+    ///
+    /// // Type ID, name length, "id", then a length prefix and "stone"
+    /// assert_eq!(NbtTag::String("stone".to_string()).serialized_size("id"), 12);
+    /// ```
+    pub fn serialized_size(&self, name: &str) -> usize {
+        1 + 2 + mutf8_encoded_len(name) + payload_len(self)
+    }
+}
+
+/// Returns the encoded size of a Java Edition payload.
+fn payload_len(tag: &NbtTag) -> usize {
+    match tag {
+        NbtTag::End => 0,
+        NbtTag::Byte(_) => 1,
+        NbtTag::Short(_) => 2,
+        NbtTag::Int(_) | NbtTag::Float(_) => 4,
+        NbtTag::Long(_) | NbtTag::Double(_) => 8,
+        NbtTag::ByteArray(v) => 4 + v.len(),
+        NbtTag::String(v) => 2 + mutf8_encoded_len(v),
+        NbtTag::RawString(v) => 2 + v.len(),
+        NbtTag::List(v) => 5 + v.iter().map(payload_len).sum::<usize>(),
+        NbtTag::Compound(v) => {
+            1 + v
+                .iter()
+                .map(|(name, tag)| 3 + mutf8_encoded_len(name) + payload_len(tag))
+                .sum::<usize>()
+        }
+        NbtTag::IntArray(v) => 4 + 4 * v.len(),
+        NbtTag::LongArray(v) => 4 + 8 * v.len(),
+    }
+}
+
+fn push_string(out: &mut Vec<u8>, s: &str) -> Result<()> {
+    out.extend_from_slice(&check_string_len(mutf8_encoded_len(s))?.to_be_bytes());
+    encode_mutf8_into(s, out);
+    Ok(())
+}
+
+/// Appends a Java Edition payload; the counterpart of [`write_payload`] for `Vec`s.
+fn push_payload(out: &mut Vec<u8>, tag: &NbtTag) -> Result<()> {
+    match tag {
+        NbtTag::End => {}
+        NbtTag::Byte(v) => out.push(*v as u8),
+        NbtTag::Short(v) => out.extend_from_slice(&v.to_be_bytes()),
+        NbtTag::Int(v) => out.extend_from_slice(&v.to_be_bytes()),
+        NbtTag::Long(v) => out.extend_from_slice(&v.to_be_bytes()),
+        NbtTag::Float(v) => out.extend_from_slice(&v.to_be_bytes()),
+        NbtTag::Double(v) => out.extend_from_slice(&v.to_be_bytes()),
+        NbtTag::ByteArray(v) => {
+            out.extend_from_slice(&check_list_len(v.len())?.to_be_bytes());
+            out.extend_from_slice(v);
+        }
+        NbtTag::String(v) => push_string(out, v)?,
+        NbtTag::RawString(v) => {
+            out.extend_from_slice(&check_string_len(v.len())?.to_be_bytes());
+            out.extend_from_slice(v);
+        }
+        NbtTag::List(v) => {
+            out.push(v.first().map_or(0, NbtTag::get_type_id));
+            out.extend_from_slice(&check_list_len(v.len())?.to_be_bytes());
+            for element in v {
+                push_payload(out, element)?;
+            }
+        }
+        NbtTag::Compound(v) => {
+            for (name, tag) in v {
+                out.push(tag.get_type_id());
+                push_string(out, name)?;
+                push_payload(out, tag)?;
+            }
+            out.push(0);
+        }
+        NbtTag::IntArray(v) => {
+            out.extend_from_slice(&check_list_len(v.len())?.to_be_bytes());
+            for &i in v {
+                out.extend_from_slice(&i.to_be_bytes());
+            }
+        }
+        NbtTag::LongArray(v) => {
+            out.extend_from_slice(&check_list_len(v.len())?.to_be_bytes());
+            for &i in v {
+                out.extend_from_slice(&i.to_be_bytes());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validates a root tag with [`validate_root`] and then writes it as a named tag.
+///
+/// Nothing is written if validation fails; the error has kind
+/// [`InvalidInput`](std::io::ErrorKind::InvalidInput) and wraps the
+/// [`ValidationError`](crate::nbt::validate::ValidationError).
+pub fn write_named_tag_validated<W: Write>(writer: &mut W, name: &str, tag: &NbtTag) -> Result<()> {
+    validate_root(tag).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    write_named_tag(writer, name, tag)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use indexmap::IndexMap;
 
     #[test]
     fn test_write_string() {
@@ -92,9 +616,111 @@ mod tests {
         assert_eq!(buf, vec![0, 3, b'h', b'i', b'!']);
     }
 
+    #[test]
+    fn test_encode_rejects_oversized_and_deep_trees() {
+        let kind = |result: Result<()>| {
+            let err = result.unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+            err.into_inner().unwrap().downcast::<EncodeError>().unwrap()
+        };
+
+        let long = NbtTag::String("é".repeat(40_000));
+        assert_eq!(
+            *kind(write_named_tag(&mut Vec::new(), "", &long)),
+            EncodeError::StringTooLong(80_000)
+        );
+        let mut key = IndexMap::new();
+        key.insert("k".repeat(70_000), NbtTag::Byte(0));
+        assert_eq!(
+            *kind(write_named_tag(&mut Vec::new(), "", &NbtTag::Compound(key))),
+            EncodeError::StringTooLong(70_000)
+        );
+
+        let mut deep = NbtTag::Int(0);
+        for _ in 0..4 {
+            deep = NbtTag::List(vec![deep]);
+        }
+        let options = EncodeOptions {
+            max_depth: 3,
+            ..EncodeOptions::default()
+        };
+        assert_eq!(
+            *kind(write_named_tag_with(&mut Vec::new(), "", &deep, &options)),
+            EncodeError::DepthLimitExceeded(3)
+        );
+        let options = EncodeOptions {
+            max_depth: 4,
+            ..options
+        };
+        write_named_tag_with(&mut Vec::new(), "", &deep, &options).unwrap();
+    }
+
+    #[test]
+    fn test_to_bytes_rejects_oversized_strings() {
+        let kind = |result: Result<Vec<u8>>| {
+            let err = result.unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+            err.into_inner().unwrap().downcast::<EncodeError>().unwrap()
+        };
+
+        let mut key = IndexMap::new();
+        key.insert("k".repeat(70_000), NbtTag::Byte(0));
+        assert_eq!(
+            *kind(NbtTag::Compound(key).to_bytes("")),
+            EncodeError::StringTooLong(70_000)
+        );
+        assert_eq!(
+            *kind(NbtTag::Int(0).to_bytes(&"é".repeat(40_000))),
+            EncodeError::StringTooLong(80_000)
+        );
+        assert_eq!(
+            *kind(NbtTag::RawString(vec![b'r'; 65_536]).to_bytes("")),
+            EncodeError::StringTooLong(65_536)
+        );
+    }
+
+    #[test]
+    fn test_canonical_encoding_sorts_nested_keys() {
+        let a = crate::nbt!({ b: { z: 1, y: [{ q: 1, p: 2 }] }, a: [] });
+        let b = crate::nbt!({ a: [], b: { y: [{ p: 2, q: 1 }], z: 1 } });
+        let encode = |tag: &NbtTag, options: &EncodeOptions| {
+            let mut out = Vec::new();
+            write_named_tag_with(&mut out, "", tag, options).unwrap();
+            out
+        };
+        let canonical = EncodeOptions::canonical();
+        assert_eq!(encode(&a, &canonical), encode(&b, &canonical));
+        assert_eq!(
+            encode(&b, &canonical),
+            encode(&b, &EncodeOptions::default())
+        );
+        assert_ne!(
+            encode(&a, &EncodeOptions::default()),
+            encode(&b, &EncodeOptions::default())
+        );
+    }
+
+    #[test]
+    fn test_to_bytes_matches_writer() {
+        let tag = crate::nbt!({
+            "ascii": "plain",
+            "unicode": "é😀\0",
+            "raw": (NbtTag::RawString(vec![0xff])),
+            "numbers": [1i8, 2i8],
+            "empty": [],
+            "floats": { f: 1.5f32, d: -2.25 },
+            "arrays": [(NbtTag::ByteArray(vec![1, 2])), (NbtTag::ByteArray(vec![]))],
+            "ints": [I; 1, -1],
+            "longs": [L; 1, i64::MIN],
+        });
+        let mut written = Vec::new();
+        write_named_tag(&mut written, "rööt", &tag).unwrap();
+        assert_eq!(tag.to_bytes("rööt").unwrap(), written);
+        assert_eq!(tag.serialized_size("rööt"), written.len());
+    }
+
     #[test]
     fn test_round_trip_compound() {
-        use indexmap::IndexMap;
         let mut map = IndexMap::new();
         map.insert("byte".to_string(), NbtTag::Byte(42));
         map.insert("string".to_string(), NbtTag::String("val".to_string()));
@@ -109,4 +735,75 @@ mod tests {
         assert_eq!(name, "root");
         assert_eq!(decoded, root);
     }
+
+    #[test]
+    fn test_little_endian_round_trip() {
+        let tag = crate::nbt!({
+            Short: 0x0102i16,
+            Int: 0x01020304,
+            Float: 1.5f32,
+            "\u{1F600}": "emoji \u{1F600}",
+            Longs: [L; 1, -2],
+            List: [1, 2],
+        });
+        let mut buf = Vec::new();
+        write_named_tag_le(&mut buf, "", &tag).unwrap();
+        assert_eq!(&buf[..10], [10, 0, 0, 2, 5, 0, b'S', b'h', b'o', b'r']);
+        assert_eq!(&buf[11..13], [0x02, 0x01]);
+
+        let mut input = &buf[..];
+        let (name, decoded) = crate::nbt::parse::parse_named_tag_le(&mut input).unwrap();
+        assert_eq!((name.as_str(), &decoded), ("", &tag));
+        assert!(input.is_empty());
+        assert!(crate::nbt::parse::parse_named_tag(&mut &buf[..]).is_err());
+    }
+
+    #[test]
+    fn test_bedrock_network_round_trip() {
+        use crate::nbt::parse::{ParseOptions, parse_named_tag_with};
+
+        let tag = crate::nbt!({
+            a: -1,
+            b: 300i64,
+            c: 1.5f32,
+            ints: [I; 0, -64, i32::MIN],
+            list: ["x"],
+        });
+        let mut buf = Vec::new();
+        write_named_tag_as(&mut buf, "", &tag, NbtFormat::BedrockNetwork).unwrap();
+        // Root, empty name, then `a: -1` as ZigZag 1 and `b: 300` as ZigZag 600
+        assert_eq!(&buf[..5], [10, 0, 3, 1, b'a']);
+        assert_eq!(&buf[5..11], [1, 4, 1, b'b', 0xd8, 0x04]);
+
+        let options = ParseOptions {
+            format: NbtFormat::BedrockNetwork,
+            ..ParseOptions::default()
+        };
+        let mut input = &buf[..];
+        assert_eq!(
+            parse_named_tag_with(&mut input, &options).unwrap(),
+            ("".to_string(), tag)
+        );
+        assert!(input.is_empty());
+
+        // A VarInt may not run past 5 bytes
+        let bad = [3, 0, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+        let err = parse_named_tag_with(&mut &bad[..], &options).unwrap_err();
+        assert_eq!(err.kind, crate::nbt::parse::ParseErrorKind::InvalidVarInt);
+    }
+
+    #[test]
+    fn test_cached_matches_uncached() {
+        let tag = crate::nbt!({ Name: "a", nested: [{ Name: "b", "caf\u{e9}\0": 1 }] });
+        let mut plain = Vec::new();
+        write_named_tag(&mut plain, "root", &tag).unwrap();
+
+        let mut cache = KeyCache::new();
+        for _ in 0..2 {
+            let mut cached = Vec::new();
+            write_named_tag_cached(&mut cached, "root", &tag, &mut cache).unwrap();
+            assert_eq!(cached, plain);
+        }
+        assert_eq!(cache.len(), 3);
+    }
 }
@@ -0,0 +1,184 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Reading and writing whole NBT files, compressed or not.
+//!
+//! [`read_compressed`] recognizes gzip, zlib and LZ4 streams by their magic bytes and
+//! falls back to uncompressed NBT, so the same call handles `level.dat`, `servers.dat` and raw
+//! dumps alike.
+//!
+//! ```no_run
+//! use anvil_nbt::anvil::CompressionType;
+//! use anvil_nbt::nbt::io::{read_compressed, write_compressed};
+//!
+//! let (name, tag) = read_compressed("level.dat")?;
+//! write_compressed("level.dat.bak", &name, &tag, CompressionType::Gzip)?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+#[cfg(feature = "compression")]
+use crate::anvil::CompressionPreset;
+use crate::anvil::CompressionType;
+#[cfg(feature = "lz4")]
+use crate::anvil::lz4::{Lz4Decoder, Lz4Encoder};
+#[cfg(not(all(feature = "compression", feature = "lz4")))]
+use crate::anvil::unsupported_compression;
+use crate::nbt::NbtTag;
+use crate::nbt::encode::write_named_tag;
+use crate::nbt::parse::parse_from_reader;
+#[cfg(feature = "compression")]
+use flate2::read::{GzDecoder, ZlibDecoder};
+#[cfg(feature = "compression")]
+use flate2::write::{GzEncoder, ZlibEncoder};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Result, Write};
+use std::path::Path;
+
+/// Guesses the compression of a stream from its first bytes.
+///
+/// Gzip is recognized by its `1f 8b` magic, zlib by a deflate method byte with a valid
+/// header checksum and LZ4 by the `LZ4Block` magic of its first block. Anything else,
+/// including input shorter than two bytes, is reported as [`CompressionType::None`].
+pub fn detect_compression(header: &[u8]) -> CompressionType {
+    match header {
+        [0x1f, 0x8b, ..] => CompressionType::Gzip,
+        [b'L', b'Z', b'4', b'B', b'l', b'o', b'c', b'k', ..] => CompressionType::Lz4,
+        &[cmf, flg, ..] if cmf & 0x0f == 8 && u16::from_be_bytes([cmf, flg]) % 31 == 0 => {
+            CompressionType::Zlib
+        }
+        _ => CompressionType::None,
+    }
+}
+
+/// Reads a named tag from a file that may be gzip, zlib, LZ4 or uncompressed.
+pub fn read_compressed<P: AsRef<Path>>(path: P) -> Result<(String, NbtTag)> {
+    read_compressed_from(File::open(path)?)
+}
+
+/// Reads a named tag from a stream that may be gzip, zlib, LZ4 or uncompressed.
+///
+/// The default [`ParseOptions`](crate::nbt::parse::ParseOptions) limits apply. The
+/// stream is buffered, so it may be read past the end of the tag.
+///
+/// Compressed input fails with [`Unsupported`](std::io::ErrorKind::Unsupported) when the
+/// `compression` feature, or the `lz4` feature for LZ4, is disabled.
+pub fn read_compressed_from<R: Read>(reader: R) -> Result<(String, NbtTag)> {
+    let mut reader = BufReader::new(reader);
+    match detect_compression(reader.fill_buf()?) {
+        #[cfg(feature = "compression")]
+        CompressionType::Gzip => parse_from_reader(GzDecoder::new(reader)),
+        #[cfg(feature = "compression")]
+        CompressionType::Zlib => parse_from_reader(ZlibDecoder::new(reader)),
+        CompressionType::None => parse_from_reader(reader),
+        #[cfg(feature = "lz4")]
+        CompressionType::Lz4 => parse_from_reader(Lz4Decoder::new(reader)),
+        #[cfg(not(all(feature = "compression", feature = "lz4")))]
+        compression => Err(unsupported_compression(compression)),
+    }
+}
+
+/// Encodes a named tag and writes it to `path` with the given compression.
+pub fn write_compressed<P: AsRef<Path>>(
+    path: P,
+    name: &str,
+    tag: &NbtTag,
+    compression: CompressionType,
+) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_compressed_to(&mut writer, name, tag, compression)?;
+    writer.flush()
+}
+
+/// Encodes a named tag into `writer` with the given compression, at the default level.
+pub fn write_compressed_to<W: Write>(
+    writer: W,
+    name: &str,
+    tag: &NbtTag,
+    compression: CompressionType,
+) -> Result<()> {
+    match compression {
+        #[cfg(feature = "compression")]
+        CompressionType::Gzip => {
+            let mut encoder = GzEncoder::new(writer, CompressionPreset::default().flate2());
+            write_named_tag(&mut encoder, name, tag)?;
+            encoder.finish()?;
+        }
+        #[cfg(feature = "compression")]
+        CompressionType::Zlib => {
+            let mut encoder = ZlibEncoder::new(writer, CompressionPreset::default().flate2());
+            write_named_tag(&mut encoder, name, tag)?;
+            encoder.finish()?;
+        }
+        CompressionType::None => {
+            let mut writer = writer;
+            write_named_tag(&mut writer, name, tag)?;
+        }
+        #[cfg(feature = "lz4")]
+        CompressionType::Lz4 => {
+            let mut encoder = Lz4Encoder::new(writer);
+            write_named_tag(&mut encoder, name, tag)?;
+            encoder.finish()?;
+        }
+        #[cfg(not(all(feature = "compression", feature = "lz4")))]
+        compression => return Err(unsupported_compression(compression)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(any(feature = "compression", feature = "lz4"))]
+    use crate::nbt;
+
+    #[test]
+    #[cfg(feature = "compression")]
+    fn test_round_trip_each_compression() {
+        let tag = nbt!({ "Data": { "LevelName": "World", "Time": 42i64 } });
+        for compression in [
+            CompressionType::Gzip,
+            CompressionType::Zlib,
+            CompressionType::None,
+        ] {
+            let mut bytes = Vec::new();
+            write_compressed_to(&mut bytes, "root", &tag, compression).unwrap();
+            assert_eq!(detect_compression(&bytes), compression);
+            let (name, read) = read_compressed_from(&bytes[..]).unwrap();
+            assert_eq!(name, "root");
+            assert_eq!(read, tag);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "lz4")]
+    fn test_round_trip_lz4() {
+        let tag = nbt!({ "Data": { "LevelName": "World", "Time": 42i64 } });
+        let mut bytes = Vec::new();
+        write_compressed_to(&mut bytes, "root", &tag, CompressionType::Lz4).unwrap();
+        assert_eq!(detect_compression(&bytes), CompressionType::Lz4);
+        assert_eq!(
+            read_compressed_from(&bytes[..]).unwrap(),
+            ("root".to_string(), tag)
+        );
+    }
+
+    #[test]
+    fn test_detect_compression() {
+        assert_eq!(detect_compression(&[0x1f, 0x8b, 8]), CompressionType::Gzip);
+        assert_eq!(detect_compression(&[0x78, 0x9c]), CompressionType::Zlib);
+        assert_eq!(detect_compression(&[0x78, 0x9d]), CompressionType::None);
+        // An uncompressed root compound starts with tag id 10
+        assert_eq!(detect_compression(&[10, 0, 0]), CompressionType::None);
+        assert_eq!(detect_compression(&[]), CompressionType::None);
+    }
+
+    #[test]
+    #[cfg(feature = "compression")]
+    fn test_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("anvil-nbt-io-{}.dat", std::process::id()));
+        let tag = nbt!({ "a": 1 });
+        write_compressed(&path, "", &tag, CompressionType::Gzip).unwrap();
+        assert_eq!(read_compressed(&path).unwrap(), (String::new(), tag));
+        std::fs::remove_file(path).unwrap();
+    }
+}
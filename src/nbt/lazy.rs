@@ -0,0 +1,220 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Compounds that decode their entries only when asked.
+//!
+//! A [`LazyCompound`] borrows the encoded bytes of a compound. The first lookup walks the
+//! entries once, skipping their payloads with [`skip_tag_payload`], and remembers where
+//! each one lives; after that, any entry can be decoded on its own, and nested compounds
+//! can be opened lazily in turn. This gives tree-like access to a chunk while paying only
+//! for the parts actually read.
+//!
+//! # Examples
+//!
+//! ```
+//! use anvil_nbt::nbt::NbtTag;
+//! use anvil_nbt::nbt::encode::write_named_tag;
+//! use anvil_nbt::nbt::lazy::LazyCompound;
+//!
+//! let chunk = anvil_nbt::nbt!({ DataVersion: 3953, Level: { xPos: 4, Status: "full" } });
+//! let mut bytes = Vec::new();
+//! write_named_tag(&mut bytes, "", &chunk).unwrap();
+//!
+//! let (_, root) = LazyCompound::from_named(&bytes).unwrap();
+//! assert_eq!(root.get("DataVersion").unwrap(), Some(NbtTag::Int(3953)));
+//! let level = root.get_compound("Level").unwrap().unwrap();
+//! assert_eq!(level.keys().unwrap().collect::<Vec<_>>(), ["xPos", "Status"]);
+//! ```
+
+use crate::nbt::NbtTag;
+use crate::nbt::parse::{
+    ByteReader, ParseError, ParseErrorKind, parse_nbt_string, parse_tag_payload, skip_tag_payload,
+};
+use std::cell::OnceCell;
+
+/// One entry of a [`LazyCompound`]: its key, type and still-encoded payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LazyEntry<'a> {
+    /// The entry's key.
+    pub name: String,
+    /// The tag type ID of the entry.
+    pub type_id: u8,
+    /// The encoded payload of the entry.
+    pub payload: &'a [u8],
+}
+
+impl LazyEntry<'_> {
+    /// Decodes the entry's payload.
+    pub fn to_tag(&self) -> Result<NbtTag, ParseError> {
+        parse_tag_payload(&mut ByteReader::new(self.payload), self.type_id)
+    }
+}
+
+/// A compound over encoded bytes whose entries are decoded on access.
+#[derive(Debug, Clone)]
+pub struct LazyCompound<'a> {
+    data: &'a [u8],
+    entries: OnceCell<(Vec<LazyEntry<'a>>, usize)>,
+}
+
+impl<'a> LazyCompound<'a> {
+    /// Wraps the encoded payload of a compound. Bytes after the compound's end are
+    /// ignored.
+    pub fn new(payload: &'a [u8]) -> Self {
+        LazyCompound {
+            data: payload,
+            entries: OnceCell::new(),
+        }
+    }
+
+    /// Reads the type and name of a named tag, such as a chunk root, and wraps its
+    /// payload. Fails if the tag is not a compound.
+    pub fn from_named(input: &'a [u8]) -> Result<(String, Self), ParseError> {
+        let mut reader = ByteReader::new(input);
+        let type_id = reader.read_u8()?;
+        if type_id != 10 {
+            return Err(ParseError::from(ParseErrorKind::InvalidTag(type_id)).at(0));
+        }
+        let name = parse_nbt_string(&mut reader).map_err(|e| e.at(reader.offset()))?;
+        Ok((name, LazyCompound::new(reader.data)))
+    }
+
+    /// Returns the entries in encoded order, walking the compound on first use.
+    pub fn entries(&self) -> Result<&[LazyEntry<'a>], ParseError> {
+        if let Some((entries, _)) = self.entries.get() {
+            return Ok(entries);
+        }
+        let scanned = self.scan()?;
+        Ok(&self.entries.get_or_init(|| scanned).0)
+    }
+
+    fn scan(&self) -> Result<(Vec<LazyEntry<'a>>, usize), ParseError> {
+        let mut reader = ByteReader::new(self.data);
+        let mut entries = Vec::new();
+        read_entries(&mut reader, &mut entries).map_err(|e| e.at(reader.offset()))?;
+        Ok((entries, reader.offset()))
+    }
+
+    /// Returns the number of bytes the compound's payload takes, including its end tag.
+    pub fn encoded_len(&self) -> Result<usize, ParseError> {
+        self.entries()?;
+        Ok(self.entries.get().map_or(0, |(_, len)| *len))
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> Result<usize, ParseError> {
+        self.entries().map(<[_]>::len)
+    }
+
+    /// Returns `true` if the compound has no entries.
+    pub fn is_empty(&self) -> Result<bool, ParseError> {
+        self.entries().map(<[_]>::is_empty)
+    }
+
+    /// Returns the keys in encoded order.
+    pub fn keys(&self) -> Result<impl Iterator<Item = &str>, ParseError> {
+        Ok(self.entries()?.iter().map(|e| e.name.as_str()))
+    }
+
+    /// Returns the entry with the given key, without decoding it.
+    pub fn entry(&self, key: &str) -> Result<Option<&LazyEntry<'a>>, ParseError> {
+        Ok(self.entries()?.iter().find(|e| e.name == key))
+    }
+
+    /// Decodes the entry with the given key.
+    pub fn get(&self, key: &str) -> Result<Option<NbtTag>, ParseError> {
+        self.entry(key)?.map(LazyEntry::to_tag).transpose()
+    }
+
+    /// Opens the entry with the given key as a lazy compound. Returns `Ok(None)` if it
+    /// is absent or not a compound.
+    pub fn get_compound(&self, key: &str) -> Result<Option<LazyCompound<'a>>, ParseError> {
+        Ok(self
+            .entry(key)?
+            .filter(|e| e.type_id == 10)
+            .map(|e| LazyCompound::new(e.payload)))
+    }
+
+    /// Decodes the whole compound.
+    pub fn to_tag(&self) -> Result<NbtTag, ParseError> {
+        let map = self
+            .entries()?
+            .iter()
+            .map(|e| Ok((e.name.clone(), e.to_tag()?)))
+            .collect::<Result<_, ParseError>>()?;
+        Ok(NbtTag::Compound(map))
+    }
+}
+
+/// Reads entry headers up to the end tag, skipping payloads.
+fn read_entries<'a>(
+    reader: &mut ByteReader<'a>,
+    entries: &mut Vec<LazyEntry<'a>>,
+) -> Result<(), ParseError> {
+    loop {
+        let type_id = reader.read_u8()?;
+        if type_id == 0 {
+            return Ok(());
+        }
+        let name = parse_nbt_string(reader)?;
+        let start = reader.data;
+        skip_tag_payload(reader, type_id)?;
+        entries.push(LazyEntry {
+            name,
+            type_id,
+            payload: &start[..start.len() - reader.data.len()],
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt;
+    use crate::nbt::encode::{write_named_tag, write_tag_payload};
+
+    #[test]
+    fn test_lazy_compound() {
+        let tag = nbt!({
+            DataVersion: 3953,
+            sections: [{ Y: 0i8, data: [L; 1, 2, 3] }],
+            Heightmaps: { WORLD_SURFACE: [L; 7] },
+        });
+        let mut data = Vec::new();
+        write_tag_payload(&mut data, &tag).unwrap();
+        data.push(99);
+
+        let lazy = LazyCompound::new(&data);
+        assert_eq!(lazy.len().unwrap(), 3);
+        assert_eq!(lazy.encoded_len().unwrap(), data.len() - 1);
+        assert_eq!(lazy.entry("sections").unwrap().unwrap().type_id, 9);
+        assert_eq!(lazy.get("missing").unwrap(), None);
+        assert_eq!(lazy.get("DataVersion").unwrap(), Some(NbtTag::Int(3953)));
+        let heightmaps = lazy.get_compound("Heightmaps").unwrap().unwrap();
+        assert_eq!(
+            heightmaps.get("WORLD_SURFACE").unwrap(),
+            Some(NbtTag::LongArray(vec![7]))
+        );
+        assert!(lazy.get_compound("sections").unwrap().is_none());
+        assert_eq!(lazy.to_tag().unwrap(), tag);
+
+        let mut named = Vec::new();
+        write_named_tag(&mut named, "root", &tag).unwrap();
+        let (name, root) = LazyCompound::from_named(&named).unwrap();
+        assert_eq!((name.as_str(), root.to_tag().unwrap()), ("root", tag));
+
+        write_named_tag(&mut named, "", &NbtTag::Int(1)).unwrap();
+        assert!(LazyCompound::from_named(&named[named.len() - 7..]).is_err());
+    }
+
+    #[test]
+    fn test_lazy_compound_truncated() {
+        let mut data = Vec::new();
+        write_tag_payload(&mut data, &nbt!({ a: 1, b: [L; 1, 2] })).unwrap();
+        let lazy = LazyCompound::new(&data[..data.len() - 4]);
+        let err = lazy.get("a").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedEof);
+        // The long array's 16 bytes start after `a` (8 bytes) and the header of `b`
+        assert_eq!(err.offset, 16);
+    }
+}
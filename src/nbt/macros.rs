@@ -0,0 +1,149 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! The [`nbt!`](crate::nbt!) macro for writing NBT values inline.
+
+/// Builds an [`NbtTag`](crate::nbt::NbtTag) from a literal, SNBT-like notation.
+///
+/// - `{ key: value, "quoted key": value }` builds a compound; keys are identifiers or
+///   string literals.
+/// - `[a, b, c]` builds a list.
+/// - `[B; ...]`, `[I; ...]` and `[L; ...]` build byte, int and long arrays.
+/// - Any other expression is converted with `NbtTag::from`, so the tag type follows the
+///   Rust type: `1i8` is a byte, `1i16` a short, `1` an int, `1i64` a long, `1.0f32` a
+///   float, `1.0` a double and `true` a byte. Existing tags are inserted as they are.
+///
+/// With braces, the outer `{}` of a compound may be left out: `nbt! { id: "stone" }`.
+///
+/// # Examples
+///
+/// ```
+/// use anvil_nbt::nbt;
+/// use anvil_nbt::nbt::NbtTag;
+///
+/// let count = 3i8;
+/// let item = nbt!({
+///     id: "minecraft:diamond",
+///     Count: count,
+///     tag: { Damage: 0, Enchantments: [{ id: "unbreaking", lvl: 2i16 }] },
+///     "minecraft:data": [I; 1, -2, 3],
+/// });
+/// assert_eq!(item.get_path("tag.Enchantments[0].lvl"), Some(&NbtTag::Short(2)));
+/// assert_eq!(item.get_path("Count"), Some(&NbtTag::Byte(3)));
+/// ```
+#[macro_export]
+macro_rules! nbt {
+    // Arrays
+    ([B; $($e:expr),* $(,)?]) => {
+        $crate::nbt::NbtTag::ByteArray(::std::vec![$(($e) as i8 as u8),*])
+    };
+    ([I; $($e:expr),* $(,)?]) => {
+        $crate::nbt::NbtTag::IntArray(::std::vec![$($e),*])
+    };
+    ([L; $($e:expr),* $(,)?]) => {
+        $crate::nbt::NbtTag::LongArray(::std::vec![$($e),*])
+    };
+
+    // Lists
+    ([$($elements:tt)*]) => {
+        $crate::nbt::NbtTag::List($crate::nbt!(@list [] $($elements)*))
+    };
+    (@list [$($done:expr,)*]) => {
+        ::std::vec![$($done,)*]
+    };
+    (@list [$($done:expr,)*] {$($value:tt)*} $(, $($rest:tt)*)?) => {
+        $crate::nbt!(@list [$($done,)* $crate::nbt!({$($value)*}),] $($($rest)*)?)
+    };
+    (@list [$($done:expr,)*] [$($value:tt)*] $(, $($rest:tt)*)?) => {
+        $crate::nbt!(@list [$($done,)* $crate::nbt!([$($value)*]),] $($($rest)*)?)
+    };
+    (@list [$($done:expr,)*] $value:expr $(, $($rest:tt)*)?) => {
+        $crate::nbt!(@list [$($done,)* $crate::nbt!($value),] $($($rest)*)?)
+    };
+
+    // Compounds
+    ({$($entries:tt)*}) => {
+        $crate::nbt::NbtTag::Compound(
+            ::std::iter::IntoIterator::into_iter($crate::nbt!(@compound [] $($entries)*))
+                .collect(),
+        )
+    };
+    (@compound [$($done:expr,)*]) => {
+        <[(::std::string::String, $crate::nbt::NbtTag); _]>::from([$($done,)*])
+    };
+    (@compound [$($done:expr,)*] $key:ident : $($rest:tt)*) => {
+        $crate::nbt!(@value [$($done,)*] (::std::stringify!($key)) $($rest)*)
+    };
+    (@compound [$($done:expr,)*] $key:literal : $($rest:tt)*) => {
+        $crate::nbt!(@value [$($done,)*] ($key) $($rest)*)
+    };
+    (@value [$($done:expr,)*] ($key:expr) {$($value:tt)*} $(, $($rest:tt)*)?) => {
+        $crate::nbt!(@compound [
+            $($done,)* (::std::string::String::from($key), $crate::nbt!({$($value)*})),
+        ] $($($rest)*)?)
+    };
+    (@value [$($done:expr,)*] ($key:expr) [$($value:tt)*] $(, $($rest:tt)*)?) => {
+        $crate::nbt!(@compound [
+            $($done,)* (::std::string::String::from($key), $crate::nbt!([$($value)*])),
+        ] $($($rest)*)?)
+    };
+    (@value [$($done:expr,)*] ($key:expr) $value:expr $(, $($rest:tt)*)?) => {
+        $crate::nbt!(@compound [
+            $($done,)* (::std::string::String::from($key), $crate::nbt!($value)),
+        ] $($($rest)*)?)
+    };
+
+    // Compound without the outer braces, as in `nbt! { id: "stone" }`
+    () => {
+        $crate::nbt!({})
+    };
+    ($key:ident : $($rest:tt)*) => {
+        $crate::nbt!({$key : $($rest)*})
+    };
+    ($key:literal : $($rest:tt)*) => {
+        $crate::nbt!({$key : $($rest)*})
+    };
+
+    // Scalars and existing tags
+    ($value:expr) => {
+        <$crate::nbt::NbtTag as ::std::convert::From<_>>::from($value)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nbt::NbtTag;
+    use crate::nbt::snbt::parse_snbt;
+
+    #[test]
+    fn test_nbt_macro() {
+        let name = String::from("Steve");
+        let tag = nbt!({
+            name: name,
+            "minecraft:flag": true,
+            byte: -1i8,
+            short: 2i16,
+            int: 3,
+            long: 4i64,
+            float: 0.5f32,
+            double: 0.25,
+            bytes: [B; 1, -1],
+            ints: [I; 1, 2],
+            longs: [L; -3],
+            list: [1, 2, 3],
+            nested: [[], [{}], [{ a: [L;] }]],
+            existing: NbtTag::Short(7),
+        });
+        let expected = parse_snbt(
+            r#"{name: "Steve", "minecraft:flag": 1b, byte: -1b, short: 2s, int: 3, long: 4L,
+                float: 0.5f, double: 0.25d, bytes: [B; 1b, -1b], ints: [I; 1, 2], longs: [L; -3L],
+                list: [1, 2, 3], nested: [[], [{}], [{a: [L;]}]], existing: 7s}"#,
+        )
+        .unwrap();
+        assert_eq!(tag, expected);
+
+        assert_eq!(nbt! {}, NbtTag::Compound(Default::default()));
+        assert_eq!(nbt! { id: "stone" }, parse_snbt("{id: stone}").unwrap());
+        assert_eq!(nbt!(-5i64), NbtTag::Long(-5));
+    }
+}
@@ -3,22 +3,94 @@
 
 //! Core NBT data structures and types.
 
+#[cfg(feature = "compression")]
+#[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
+pub mod analyze;
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub mod async_io;
 pub mod encode;
+pub mod io;
+pub mod lazy;
+mod macros;
 pub mod mutf8;
+pub mod numeric;
 pub mod parse;
+pub mod path;
+pub mod pretty;
 #[cfg(feature = "serde")]
 #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
 pub mod serde_impl;
+pub mod snbt;
+pub mod stats;
+#[cfg(feature = "proptest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+pub mod strategy;
+pub mod validate;
+pub mod visit;
 
 use indexmap::IndexMap;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Binary flavor of NBT data.
+///
+/// Java Edition uses big-endian NBT with Modified UTF-8 strings. Bedrock Edition files
+/// such as `level.dat` and `.mcstructure` use little-endian NBT with standard UTF-8
+/// strings. Bedrock's network protocol additionally encodes Ints and lengths as VarInts
+/// and Longs as VarLongs, both ZigZag-encoded except for string lengths.
+///
+/// Some third-party tools write Java layout with CESU-8 strings, which differ from
+/// Modified UTF-8 only in writing NUL as a plain `0x00` byte; [`NbtFormat::JavaCesu8`]
+/// reads and writes those without normalizing them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NbtFormat {
+    /// Java Edition files and packets.
+    #[default]
+    JavaBE,
+    /// Java Edition layout with CESU-8 strings.
+    JavaCesu8,
+    /// Bedrock Edition files.
+    BedrockLE,
+    /// Bedrock Edition network packets.
+    BedrockNetwork,
+}
+
+impl NbtFormat {
+    /// Puts fixed-width bytes read in this format into big-endian order.
+    #[inline]
+    pub(crate) fn to_big<const N: usize>(self, mut bytes: [u8; N]) -> [u8; N] {
+        if !self.is_big_endian() {
+            bytes.reverse();
+        }
+        bytes
+    }
+
+    /// Returns `true` for the Java Edition formats, which store numbers big-endian.
+    pub fn is_big_endian(self) -> bool {
+        matches!(self, NbtFormat::JavaBE | NbtFormat::JavaCesu8)
+    }
+
+    /// Decodes string bytes read in this format.
+    pub(crate) fn decode_string(self, bytes: &[u8]) -> Result<String, parse::ParseError> {
+        let decoded = match self {
+            NbtFormat::JavaBE => mutf8::decode_mutf8(bytes).ok(),
+            NbtFormat::JavaCesu8 => mutf8::decode_cesu8(bytes).ok(),
+            _ => std::str::from_utf8(bytes).ok().map(str::to_string),
+        };
+        decoded.ok_or_else(|| parse::ParseErrorKind::InvalidString.into())
+    }
+}
 
 /// Represents a Minecraft NBT (Named Binary Tag).
 ///
 /// NBT is a tree-based storage format used by Minecraft for player data, level data, and chunks.
 /// This enum covers all possible tag types in the format.
 ///
+/// The enum is `#[non_exhaustive]`: a tag type can have more than one representation
+/// (see [`RawString`](Self::RawString)), so matches outside this crate need a wildcard arm.
+///
 /// # Examples
 ///
 /// ```
@@ -29,6 +101,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(untagged))]
+#[non_exhaustive]
 pub enum NbtTag {
     /// Marker tag used to signify the end of a `Compound` tag. (ID: 0)
     End,
@@ -56,6 +129,15 @@ pub enum NbtTag {
     IntArray(Vec<i32>),
     /// An array of 64-bit signed integers. (ID: 12)
     LongArray(Vec<i64>),
+    /// A string kept as its encoded bytes because they do not decode, or decode to text
+    /// that would be encoded differently. Only produced when
+    /// [`ParseOptions::preserve_raw_strings`](parse::ParseOptions::preserve_raw_strings)
+    /// is set; written back byte for byte. (ID: 8)
+    ///
+    /// Serializing one with serde fails, since serde formats have no lossless way to
+    /// express it; wrap the tag in `serde_impl::RawNbt` to carry it through.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    RawString(Vec<u8>),
 }
 
 impl NbtTag {
@@ -70,11 +152,356 @@ impl NbtTag {
             NbtTag::Float(_) => 5,
             NbtTag::Double(_) => 6,
             NbtTag::ByteArray(_) => 7,
-            NbtTag::String(_) => 8,
+            NbtTag::String(_) | NbtTag::RawString(_) => 8,
             NbtTag::List(_) => 9,
             NbtTag::Compound(_) => 10,
             NbtTag::IntArray(_) => 11,
             NbtTag::LongArray(_) => 12,
         }
     }
+
+    /// Returns the name of the tag type as used in the NBT specification (e.g. `"Compound"`).
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            NbtTag::End => "End",
+            NbtTag::Byte(_) => "Byte",
+            NbtTag::Short(_) => "Short",
+            NbtTag::Int(_) => "Int",
+            NbtTag::Long(_) => "Long",
+            NbtTag::Float(_) => "Float",
+            NbtTag::Double(_) => "Double",
+            NbtTag::ByteArray(_) => "ByteArray",
+            NbtTag::String(_) | NbtTag::RawString(_) => "String",
+            NbtTag::List(_) => "List",
+            NbtTag::Compound(_) => "Compound",
+            NbtTag::IntArray(_) => "IntArray",
+            NbtTag::LongArray(_) => "LongArray",
+        }
+    }
+
+    /// Produces a compact, value-free summary of the tag's structure.
+    ///
+    /// Arrays and lists show their length, lists also their element type. Compounds are
+    /// expanded `depth` levels deep; beyond that only their entry count is shown. A compound
+    /// whose entries are all arrays, lists or compounds with an identical summary is folded
+    /// into `N×summary`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use anvil_nbt::nbt::NbtTag;
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut heightmaps = IndexMap::new();
+    /// heightmaps.insert("WORLD_SURFACE".to_string(), NbtTag::LongArray(vec![0; 37]));
+    /// heightmaps.insert("OCEAN_FLOOR".to_string(), NbtTag::LongArray(vec![0; 37]));
+    /// let mut root = IndexMap::new();
+    /// root.insert("sections".to_string(), NbtTag::List(vec![NbtTag::Compound(IndexMap::new())]));
+    /// root.insert("Heightmaps".to_string(), NbtTag::Compound(heightmaps));
+    ///
+    /// assert_eq!(
+    ///     NbtTag::Compound(root).describe(2),
+    ///     "Compound{ sections: List<Compound>[1], Heightmaps: Compound{2×LongArray[37]} }"
+    /// );
+    /// ```
+    pub fn describe(&self, depth: usize) -> String {
+        match self {
+            NbtTag::ByteArray(v) => format!("ByteArray[{}]", v.len()),
+            NbtTag::IntArray(v) => format!("IntArray[{}]", v.len()),
+            NbtTag::LongArray(v) => format!("LongArray[{}]", v.len()),
+            NbtTag::List(v) => {
+                let element = v.first().map_or("End", NbtTag::type_name);
+                format!("List<{}>[{}]", element, v.len())
+            }
+            NbtTag::Compound(map) if map.is_empty() => "Compound{}".to_string(),
+            NbtTag::Compound(map) if depth == 0 => format!("Compound[{}]", map.len()),
+            NbtTag::Compound(map) => {
+                let entries: Vec<(&String, String)> = map
+                    .iter()
+                    .map(|(k, v)| (k, v.describe(depth - 1)))
+                    .collect();
+                let foldable = map.len() > 1
+                    && map.values().all(|v| !v.is_scalar())
+                    && entries.iter().all(|(_, d)| *d == entries[0].1);
+                if foldable {
+                    format!("Compound{{{}×{}}}", entries.len(), entries[0].1)
+                } else {
+                    let fields: Vec<String> = entries
+                        .iter()
+                        .map(|(k, d)| format!("{}: {}", k, d))
+                        .collect();
+                    format!("Compound{{ {} }}", fields.join(", "))
+                }
+            }
+            scalar => scalar.type_name().to_string(),
+        }
+    }
+
+    /// Sorts a list of compounds by the value stored under `key` in each element.
+    ///
+    /// Numeric values compare numerically (regardless of width), strings lexicographically.
+    /// Elements missing the key, or that are not compounds, are moved to the end. The sort
+    /// is stable. Does nothing if this tag is not a list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use anvil_nbt::nbt::NbtTag;
+    /// use indexmap::IndexMap;
+    ///
+    /// let slot = |n: i8| {
+    ///     let mut item = IndexMap::new();
+    ///     item.insert("Slot".to_string(), NbtTag::Byte(n));
+    ///     NbtTag::Compound(item)
+    /// };
+    /// let mut inventory = NbtTag::List(vec![slot(5), slot(0), slot(2)]);
+    /// inventory.sort_list_by_key("Slot");
+    /// assert_eq!(inventory, NbtTag::List(vec![slot(0), slot(2), slot(5)]));
+    /// ```
+    pub fn sort_list_by_key(&mut self, key: &str) {
+        self.sort_list_by(|a, b| {
+            let a = match a {
+                NbtTag::Compound(map) => map.get(key),
+                _ => None,
+            };
+            let b = match b {
+                NbtTag::Compound(map) => map.get(key),
+                _ => None,
+            };
+            match (a, b) {
+                (Some(a), Some(b)) => compare_values(a, b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+        });
+    }
+
+    /// Sorts the elements of a list with a comparator function.
+    ///
+    /// The sort is stable. Does nothing if this tag is not a list.
+    pub fn sort_list_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&NbtTag, &NbtTag) -> Ordering,
+    {
+        if let NbtTag::List(elements) = self {
+            elements.sort_by(compare);
+        }
+    }
+
+    /// Returns `true` for tags that hold a single value rather than a collection.
+    fn is_scalar(&self) -> bool {
+        !matches!(
+            self,
+            NbtTag::ByteArray(_)
+                | NbtTag::List(_)
+                | NbtTag::Compound(_)
+                | NbtTag::IntArray(_)
+                | NbtTag::LongArray(_)
+        )
+    }
+}
+
+/// Orders two tag values for sorting: numbers numerically, strings lexicographically,
+/// anything else by type ID.
+fn compare_values(a: &NbtTag, b: &NbtTag) -> Ordering {
+    fn as_integer(tag: &NbtTag) -> Option<i64> {
+        match tag {
+            NbtTag::Byte(v) => Some(*v as i64),
+            NbtTag::Short(v) => Some(*v as i64),
+            NbtTag::Int(v) => Some(*v as i64),
+            NbtTag::Long(v) => Some(*v),
+            _ => None,
+        }
+    }
+    fn as_float(tag: &NbtTag) -> Option<f64> {
+        match tag {
+            NbtTag::Float(v) => Some(*v as f64),
+            NbtTag::Double(v) => Some(*v),
+            other => as_integer(other).map(|v| v as f64),
+        }
+    }
+
+    match (a, b) {
+        (NbtTag::String(a), NbtTag::String(b)) => a.cmp(b),
+        _ => match (as_integer(a), as_integer(b)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => match (as_float(a), as_float(b)) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                _ => a.get_type_id().cmp(&b.get_type_id()),
+            },
+        },
+    }
+}
+
+macro_rules! impl_from {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$ty> for NbtTag {
+                fn from(value: $ty) -> Self {
+                    NbtTag::$variant(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_from! {
+    i8 => Byte,
+    i16 => Short,
+    i32 => Int,
+    i64 => Long,
+    f32 => Float,
+    f64 => Double,
+    String => String,
+    Vec<NbtTag> => List,
+    IndexMap<String, NbtTag> => Compound,
+    Vec<i32> => IntArray,
+    Vec<i64> => LongArray,
+}
+
+/// Booleans are stored as bytes, `1` for `true` and `0` for `false`.
+impl From<bool> for NbtTag {
+    fn from(value: bool) -> Self {
+        NbtTag::Byte(value as i8)
+    }
+}
+
+impl From<&str> for NbtTag {
+    fn from(value: &str) -> Self {
+        NbtTag::String(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compound(entries: Vec<(&str, NbtTag)>) -> NbtTag {
+        NbtTag::Compound(
+            entries
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_describe_nested() {
+        let inner = compound(vec![
+            ("Items", NbtTag::List(vec![compound(vec![])])),
+            ("Pos", NbtTag::List(vec![NbtTag::Double(0.0); 3])),
+        ]);
+        let root = compound(vec![("Level", inner), ("x", NbtTag::Int(0))]);
+        assert_eq!(
+            root.describe(2),
+            "Compound{ Level: Compound{ Items: List<Compound>[1], Pos: List<Double>[3] }, x: Int }"
+        );
+        // Equal summaries only fold when no entry is a scalar
+        let pair = compound(vec![("a", NbtTag::Int(1)), ("b", NbtTag::Int(2))]);
+        assert_eq!(pair.describe(1), "Compound{ a: Int, b: Int }");
+        let lists = compound(vec![
+            ("a", NbtTag::List(vec![NbtTag::Int(1)])),
+            ("b", NbtTag::List(vec![NbtTag::Int(2)])),
+        ]);
+        assert_eq!(lists.describe(1), "Compound{2×List<Int>[1]}");
+    }
+
+    #[test]
+    fn test_describe_empty() {
+        assert_eq!(NbtTag::List(vec![]).describe(1), "List<End>[0]");
+        assert_eq!(compound(vec![]).describe(0), "Compound{}");
+        assert_eq!(NbtTag::ByteArray(vec![]).describe(1), "ByteArray[0]");
+        assert_eq!(
+            compound(vec![("empty", NbtTag::List(vec![]))]).describe(1),
+            "Compound{ empty: List<End>[0] }"
+        );
+    }
+
+    #[test]
+    fn test_describe_truncates_at_depth() {
+        let leaf = compound(vec![("c", NbtTag::Byte(0))]);
+        let root = compound(vec![("a", compound(vec![("b", leaf)]))]);
+        assert_eq!(root.describe(0), "Compound[1]");
+        assert_eq!(root.describe(1), "Compound{ a: Compound[1] }");
+        assert_eq!(
+            root.describe(2),
+            "Compound{ a: Compound{ b: Compound[1] } }"
+        );
+        assert_eq!(
+            root.describe(usize::MAX),
+            "Compound{ a: Compound{ b: Compound{ c: Byte } } }"
+        );
+    }
+
+    #[test]
+    fn test_sort_list_by_key() {
+        let entry = |id: i32, key: Option<NbtTag>| {
+            let mut entries = vec![("id", NbtTag::Int(id))];
+            entries.extend(key.map(|k| ("k", k)));
+            compound(entries)
+        };
+        let ids = |tag: &NbtTag| match tag {
+            NbtTag::List(v) => v
+                .iter()
+                .map(|e| match e {
+                    NbtTag::Compound(map) => map["id"].clone(),
+                    other => other.clone(),
+                })
+                .collect(),
+            _ => vec![],
+        };
+
+        // Numbers compare by value across widths, strings after numbers by type ID, and
+        // missing keys and non-compounds go last in their original order
+        let mut list = NbtTag::List(vec![
+            entry(0, Some(NbtTag::String("b".into()))),
+            entry(1, None),
+            entry(2, Some(NbtTag::Double(2.5))),
+            entry(3, Some(NbtTag::Long(-4))),
+            entry(4, Some(NbtTag::String("a".into()))),
+            entry(5, Some(NbtTag::Byte(2))),
+            entry(6, None),
+            entry(7, Some(NbtTag::Short(2))),
+        ]);
+        list.sort_list_by_key("k");
+        let expected: Vec<NbtTag> = [3, 5, 7, 2, 4, 0, 1, 6].map(NbtTag::Int).to_vec();
+        assert_eq!(ids(&list), expected);
+
+        let mut mixed = NbtTag::List(vec![NbtTag::Int(1), entry(0, Some(NbtTag::Int(9)))]);
+        mixed.sort_list_by_key("k");
+        assert_eq!(
+            mixed,
+            NbtTag::List(vec![entry(0, Some(NbtTag::Int(9))), NbtTag::Int(1)])
+        );
+    }
+
+    #[test]
+    fn test_sort_list_by() {
+        let mut list = NbtTag::List(vec![
+            NbtTag::String("bb".into()),
+            NbtTag::String("a".into()),
+            NbtTag::String("cc".into()),
+            NbtTag::String("d".into()),
+        ]);
+        // Stable: equal lengths keep their order
+        list.sort_list_by(|a, b| {
+            let len = |t: &NbtTag| match t {
+                NbtTag::String(s) => s.len(),
+                _ => 0,
+            };
+            len(a).cmp(&len(b))
+        });
+        let strings: Vec<NbtTag> = ["a", "d", "bb", "cc"].map(NbtTag::from).to_vec();
+        assert_eq!(list, NbtTag::List(strings));
+
+        let mut not_list = compound(vec![("b", NbtTag::Int(1)), ("a", NbtTag::Int(2))]);
+        let before = not_list.clone();
+        not_list.sort_list_by(|_, _| Ordering::Less);
+        not_list.sort_list_by_key("a");
+        assert_eq!(not_list, before);
+        let mut scalar = NbtTag::Int(1);
+        scalar.sort_list_by_key("a");
+        assert_eq!(scalar, NbtTag::Int(1));
+    }
 }
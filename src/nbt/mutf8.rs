@@ -1,8 +1,10 @@
 // Copyright 2026 driedpampas@proton.me
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::borrow::Cow;
 use std::error::Error;
 use std::fmt;
+use std::io::{self, Write};
 
 /// Error returned when Modified UTF-8 decoding fails.
 #[derive(Debug, Clone)]
@@ -25,14 +27,17 @@ pub fn decode_mutf8(data: &[u8]) -> Result<String, Mutf8Error> {
     // Fast path: check if all bytes are ASCII (0x01..0x7F).
     // Standard UTF-8 and MUTF-8 are identical for ASCII-7 except for null (0x00).
     // In NBT, strings are often ASCII and don't contain nulls.
-    if data.iter().all(|&b| b > 0 && b < 0x80) {
+    let plain = plain_ascii_prefix_len(data);
+    if plain == data.len() {
         // SAFETY: We just checked that all bytes are in the range 0x01..0x7F,
         // which is a valid UTF-8 sequence.
         return Ok(unsafe { String::from_utf8_unchecked(data.to_vec()) });
     }
 
     let mut result = String::with_capacity(data.len());
-    let mut i = 0;
+    // SAFETY: as above, for the plain prefix only
+    result.push_str(unsafe { std::str::from_utf8_unchecked(&data[..plain]) });
+    let mut i = plain;
 
     while i < data.len() {
         let b = data[i];
@@ -107,23 +112,313 @@ pub fn decode_mutf8(data: &[u8]) -> Result<String, Mutf8Error> {
     Ok(result)
 }
 
+/// Decodes Modified UTF-8 bytes, borrowing them when no transformation is needed.
+///
+/// Input that is already valid UTF-8, which covers ASCII and nearly every string the game
+/// writes, is returned as [`Cow::Borrowed`] without allocating. Only strings holding an
+/// encoded NUL or a surrogate pair are decoded into a new [`String`]. Like the borrowing
+/// readers in [`visit`](crate::nbt::visit), this accepts standard 4-byte UTF-8 sequences
+/// that [`decode_mutf8`] rejects.
+///
+/// ```
+/// use anvil_nbt::nbt::mutf8::decode_mutf8_cow;
+/// use std::borrow::Cow;
+///
+/// assert!(matches!(decode_mutf8_cow(b"minecraft:stone"), Ok(Cow::Borrowed(_))));
+/// assert!(matches!(decode_mutf8_cow(&[b'a', 0xC0, 0x80]), Ok(Cow::Owned(s)) if s == "a\0"));
+/// ```
+pub fn decode_mutf8_cow(data: &[u8]) -> Result<Cow<'_, str>, Mutf8Error> {
+    match std::str::from_utf8(data) {
+        Ok(s) => Ok(Cow::Borrowed(s)),
+        Err(_) => decode_mutf8(data).map(Cow::Owned),
+    }
+}
+
+/// Decodes CESU-8 bytes into a standard Rust `String`.
+///
+/// CESU-8 spells supplementary characters as surrogate pairs like MUTF-8 but writes NUL
+/// as a plain `0x00` byte. [`decode_mutf8`] already accepts both spellings of NUL, so
+/// this decodes exactly the same way; it exists to pair with [`encode_cesu8`].
+pub fn decode_cesu8(data: &[u8]) -> Result<String, Mutf8Error> {
+    decode_mutf8(data)
+}
+
 /// Encodes a standard Rust string into Modified UTF-8 (MUTF-8) bytes.
 pub fn encode_mutf8(s: &str) -> Vec<u8> {
-    let mut result = Vec::new();
+    let mut result = Vec::with_capacity(mutf8_encoded_len(s));
+    encode_mutf8_into(s, &mut result);
+    result
+}
+
+/// Appends the Modified UTF-8 encoding of `s` to `out`.
+///
+/// Reusing one buffer across many strings avoids the allocation [`encode_mutf8`] makes
+/// for each.
+pub fn encode_mutf8_into(s: &str, out: &mut Vec<u8>) {
+    if is_plain_ascii(s) {
+        out.extend_from_slice(s.as_bytes());
+        return;
+    }
+    out.reserve(mutf8_encoded_len(s));
+    encode_utf16_units(s, out, true);
+}
+
+/// Encodes a standard Rust string into CESU-8 bytes, as written by some third-party
+/// tools: surrogate pairs like MUTF-8, but NUL as a single `0x00` byte.
+pub fn encode_cesu8(s: &str) -> Vec<u8> {
+    let mut result = Vec::with_capacity(s.len());
+    encode_cesu8_into(s, &mut result);
+    result
+}
+
+/// Appends the CESU-8 encoding of `s` to `out`.
+pub fn encode_cesu8_into(s: &str, out: &mut Vec<u8>) {
+    if is_cesu8_identical(s) {
+        out.extend_from_slice(s.as_bytes());
+        return;
+    }
+    encode_utf16_units(s, out, false);
+}
+
+/// Returns `true` if `s` has no supplementary characters, which makes its CESU-8 and
+/// UTF-8 encodings identical.
+pub(crate) fn is_cesu8_identical(s: &str) -> bool {
+    // Only supplementary characters take the 4-byte form, led by 0xF0..=0xF4
+    s.bytes().all(|b| b < 0xF0)
+}
+
+/// Writes each UTF-16 unit of `s` as one to three bytes, the scheme shared by MUTF-8 and
+/// CESU-8. `escape_nul` selects the MUTF-8 spelling of NUL (`0xC0 0x80`).
+fn encode_utf16_units(s: &str, out: &mut Vec<u8>, escape_nul: bool) {
+    let mut buf = [0u8; 3];
     for c in s.encode_utf16() {
-        if c == 0 {
-            result.push(0xC0);
-            result.push(0x80);
-        } else if c < 0x80 {
-            result.push(c as u8);
-        } else if c < 0x800 {
-            result.push(0xC0 | ((c >> 6) as u8));
-            result.push(0x80 | ((c & 0x3F) as u8));
-        } else {
-            result.push(0xE0 | ((c >> 12) as u8));
-            result.push(0x80 | (((c >> 6) & 0x3F) as u8));
-            result.push(0x80 | ((c & 0x3F) as u8));
+        let len = encode_unit(c, &mut buf, escape_nul);
+        out.extend_from_slice(&buf[..len]);
+    }
+}
+
+/// Writes one UTF-16 unit into the start of `out` and returns how many bytes it took.
+fn encode_unit(c: u16, out: &mut [u8], escape_nul: bool) -> usize {
+    if c == 0 && escape_nul {
+        out[..2].copy_from_slice(&[0xC0, 0x80]);
+        2
+    } else if c < 0x80 {
+        out[0] = c as u8;
+        1
+    } else if c < 0x800 {
+        out[0] = 0xC0 | ((c >> 6) as u8);
+        out[1] = 0x80 | ((c & 0x3F) as u8);
+        2
+    } else {
+        out[0] = 0xE0 | ((c >> 12) as u8);
+        out[1] = 0x80 | (((c >> 6) & 0x3F) as u8);
+        out[2] = 0x80 | ((c & 0x3F) as u8);
+        3
+    }
+}
+
+/// Size of the stack buffer [`Mutf8Writer`] encodes into between writes.
+const WRITER_BUFFER: usize = 512;
+
+/// Encodes strings as Modified UTF-8 straight into a writer.
+///
+/// Runs of plain ASCII are passed through from the input; everything else is encoded
+/// through a small stack buffer, so no string, however long, is copied into a `Vec`
+/// first. Pair it with [`mutf8_encoded_len`] when the length has to be written up front,
+/// as NBT's length prefix requires.
+///
+/// ```
+/// use anvil_nbt::nbt::mutf8::{Mutf8Writer, encode_mutf8};
+///
+/// let mut writer = Mutf8Writer::new(Vec::new());
+/// writer.write_str("{\"text\":\"é\"}")?;
+/// writer.write_str("\0")?;
+/// assert_eq!(writer.bytes_written(), 15);
+/// assert_eq!(writer.into_inner(), encode_mutf8("{\"text\":\"é\"}\0"));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Mutf8Writer<W: Write> {
+    writer: W,
+    written: u64,
+}
+
+impl<W: Write> Mutf8Writer<W> {
+    /// Wraps `writer`.
+    pub fn new(writer: W) -> Self {
+        Mutf8Writer { writer, written: 0 }
+    }
+
+    /// Encodes `s` and writes it to the underlying writer.
+    pub fn write_str(&mut self, s: &str) -> io::Result<()> {
+        let mut rest = s;
+        while !rest.is_empty() {
+            let plain = plain_ascii_prefix_len(rest.as_bytes());
+            if plain > 0 {
+                self.write_raw(&rest.as_bytes()[..plain])?;
+                rest = &rest[plain..];
+                continue;
+            }
+
+            // Encode up to the next plain ASCII run, leaving room for one 6-byte character
+            let mut buf = [0u8; WRITER_BUFFER];
+            let mut len = 0;
+            let mut consumed = 0;
+            for c in rest.chars() {
+                if is_plain_ascii_char(c) || len + 6 > WRITER_BUFFER {
+                    break;
+                }
+                let mut units = [0u16; 2];
+                for &unit in c.encode_utf16(&mut units).iter() {
+                    len += encode_unit(unit, &mut buf[len..], true);
+                }
+                consumed += c.len_utf8();
+            }
+            self.write_raw(&buf[..len])?;
+            rest = &rest[consumed..];
         }
+        Ok(())
+    }
+
+    /// Returns the number of encoded bytes written so far.
+    pub fn bytes_written(&self) -> u64 {
+        self.written
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Consumes the adapter, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_raw(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.writer.write_all(bytes)?;
+        self.written += bytes.len() as u64;
+        Ok(())
+    }
+}
+
+fn is_plain_ascii_char(c: char) -> bool {
+    ('\u{1}'..='\u{7f}').contains(&c)
+}
+
+/// Returns `true` if `s` is ASCII without NUL, which makes its MUTF-8 and UTF-8 encodings
+/// identical.
+pub(crate) fn is_plain_ascii(s: &str) -> bool {
+    plain_ascii_prefix_len(s.as_bytes()) == s.len()
+}
+
+/// Returns how many leading bytes of `data` are ASCII other than NUL.
+///
+/// Eight bytes are checked at a time: a word is plain if no byte has its high bit set
+/// and no byte is zero, using the usual `(w - 0x01..) & !w & 0x80..` zero-byte test.
+fn plain_ascii_prefix_len(data: &[u8]) -> usize {
+    const LOW: u64 = u64::from_ne_bytes([0x01; 8]);
+    const HIGH: u64 = u64::from_ne_bytes([0x80; 8]);
+
+    let mut words = data.chunks_exact(8);
+    let mut checked = 0;
+    for word in words.by_ref() {
+        let w = u64::from_ne_bytes(word.try_into().unwrap());
+        if (w | (w.wrapping_sub(LOW) & !w)) & HIGH != 0 {
+            break;
+        }
+        checked += 8;
+    }
+    checked
+        + data[checked..]
+            .iter()
+            .take_while(|&&b| b > 0 && b < 0x80)
+            .count()
+}
+
+/// Returns the number of bytes `s` occupies when encoded as Modified UTF-8.
+pub fn mutf8_encoded_len(s: &str) -> usize {
+    s.chars()
+        .map(|c| match c as u32 {
+            0 => 2,
+            0x01..=0x7F => 1,
+            0x80..=0x7FF => 2,
+            0x800..=0xFFFF => 3,
+            _ => 6,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_into_reuses_buffer() {
+        let mut buf = Vec::new();
+        for s in ["plain", "nul\0", "é", "😀", ""] {
+            buf.clear();
+            encode_mutf8_into(s, &mut buf);
+            assert_eq!(buf, encode_mutf8(s));
+            assert_eq!(buf.len(), mutf8_encoded_len(s));
+            assert_eq!(decode_mutf8(&buf).unwrap(), s);
+        }
+        assert_eq!(encode_mutf8("a\0"), [b'a', 0xC0, 0x80]);
+        assert_eq!(mutf8_encoded_len("😀"), 6);
+    }
+
+    #[test]
+    fn test_plain_ascii_prefix_len() {
+        assert_eq!(plain_ascii_prefix_len(b""), 0);
+        assert_eq!(plain_ascii_prefix_len(b"minecraft:stone"), 15);
+        // Every position of a NUL or high byte, within and after the first word
+        for len in 1..20 {
+            for at in 0..len {
+                for bad in [0x00, 0x80, 0xC0, 0xFF] {
+                    let mut data = vec![b'a'; len];
+                    data[at] = bad;
+                    assert_eq!(plain_ascii_prefix_len(&data), at);
+                }
+            }
+        }
+        let mixed = encode_mutf8("long ascii prefix, then é\0");
+        assert_eq!(decode_mutf8(&mixed).unwrap(), "long ascii prefix, then é\0");
+    }
+
+    #[test]
+    fn test_mutf8_writer_matches_encoder() {
+        // Long enough to fill the stack buffer several times over
+        let long: String = "é😀\0a".repeat(400);
+        for s in ["", "plain", "nul\0", "mixé😀 text", long.as_str()] {
+            let mut writer = Mutf8Writer::new(Vec::new());
+            writer.write_str(s).unwrap();
+            assert_eq!(writer.bytes_written(), mutf8_encoded_len(s) as u64);
+            assert_eq!(writer.into_inner(), encode_mutf8(s));
+        }
+    }
+
+    #[test]
+    fn test_cesu8_round_trip() {
+        assert_eq!(encode_cesu8("a\0b"), b"a\0b");
+        assert_eq!(encode_cesu8("é"), "é".as_bytes());
+        let emoji = encode_cesu8("😀");
+        assert_eq!(emoji, encode_mutf8("😀"));
+        assert_eq!(emoji, [0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80]);
+        for s in ["plain", "nul\0", "é😀\0"] {
+            assert_eq!(decode_cesu8(&encode_cesu8(s)).unwrap(), s);
+        }
+    }
+
+    #[test]
+    fn test_decode_cow_borrows_valid_utf8() {
+        for s in ["plain", "é", ""] {
+            let bytes = encode_mutf8(s);
+            assert!(matches!(decode_mutf8_cow(&bytes).unwrap(), Cow::Borrowed(b) if b == s));
+        }
+        for s in ["nul\0", "😀"] {
+            let bytes = encode_mutf8(s);
+            assert!(matches!(decode_mutf8_cow(&bytes).unwrap(), Cow::Owned(o) if o == s));
+        }
+        assert!(decode_mutf8_cow(&[0xC0]).is_err());
     }
-    result
 }
@@ -0,0 +1,110 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Range-checked conversions from integral tags.
+//!
+//! The same field is often stored with different widths across game versions and mods:
+//! a `Slot` may be a Byte or an Int, a `Count` a Byte or an Int. The `to_*_checked`
+//! methods accept any of Byte, Short, Int and Long and fail only if the value does not
+//! fit the requested type.
+//!
+//! ```
+//! use anvil_nbt::nbt::NbtTag;
+//!
+//! assert_eq!(NbtTag::Byte(64).to_i32_checked(), Ok(64));
+//! let err = NbtTag::Long(300).to_u8_checked().unwrap_err();
+//! assert_eq!(err.to_string(), "Value 300 is out of range for u8");
+//! ```
+
+use crate::nbt::NbtTag;
+use thiserror::Error;
+
+/// Errors from the `to_*_checked` conversions of [`NbtTag`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum NumericError {
+    /// The tag is not a Byte, Short, Int or Long.
+    #[error("Expected an integral tag, found {0}")]
+    NotIntegral(&'static str),
+    /// The value does not fit the requested type.
+    #[error("Value {value} is out of range for {target}")]
+    OutOfRange {
+        /// The stored value.
+        value: i64,
+        /// The requested Rust type, such as `"u8"`.
+        target: &'static str,
+    },
+}
+
+impl NbtTag {
+    /// Returns the value of a Byte, Short, Int or Long tag, widened to `i64`.
+    pub fn to_i64_checked(&self) -> Result<i64, NumericError> {
+        match self {
+            NbtTag::Byte(v) => Ok(*v as i64),
+            NbtTag::Short(v) => Ok(*v as i64),
+            NbtTag::Int(v) => Ok(*v as i64),
+            NbtTag::Long(v) => Ok(*v),
+            other => Err(NumericError::NotIntegral(other.type_name())),
+        }
+    }
+}
+
+macro_rules! checked_narrowing {
+    ($($name:ident => $ty:ty),* $(,)?) => {
+        impl NbtTag {
+            $(
+                #[doc = concat!(
+                    "Returns the value of a Byte, Short, Int or Long tag as `",
+                    stringify!($ty),
+                    "`, failing if it does not fit."
+                )]
+                pub fn $name(&self) -> Result<$ty, NumericError> {
+                    let value = self.to_i64_checked()?;
+                    <$ty>::try_from(value).map_err(|_| NumericError::OutOfRange {
+                        value,
+                        target: stringify!($ty),
+                    })
+                }
+            )*
+        }
+    };
+}
+
+checked_narrowing! {
+    to_i8_checked => i8,
+    to_i16_checked => i16,
+    to_i32_checked => i32,
+    to_u8_checked => u8,
+    to_u16_checked => u16,
+    to_u32_checked => u32,
+    to_u64_checked => u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_narrowing() {
+        assert_eq!(NbtTag::Long(127).to_i8_checked(), Ok(127));
+        assert_eq!(NbtTag::Byte(-1).to_i64_checked(), Ok(-1));
+        assert_eq!(NbtTag::Short(255).to_u8_checked(), Ok(255));
+        assert_eq!(
+            NbtTag::Int(-1).to_u32_checked(),
+            Err(NumericError::OutOfRange {
+                value: -1,
+                target: "u32"
+            })
+        );
+        assert_eq!(
+            NbtTag::Long(i64::MIN).to_u64_checked(),
+            Err(NumericError::OutOfRange {
+                value: i64::MIN,
+                target: "u64"
+            })
+        );
+        assert_eq!(
+            NbtTag::Float(1.0).to_i32_checked(),
+            Err(NumericError::NotIntegral("Float"))
+        );
+    }
+}
@@ -1,47 +1,137 @@
 // Copyright 2026 driedpampas@proton.me
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use crate::nbt::NbtTag;
-use crate::nbt::mutf8::decode_mutf8;
+use crate::nbt::mutf8::{encode_cesu8, encode_mutf8};
+use crate::nbt::path::{NbtPath, PathNode, resolve_all, resolve_index};
+use crate::nbt::{NbtFormat, NbtTag};
 use indexmap::IndexMap;
+use std::io::{self, Read};
+
 /// A reader that maintains a cursor over a byte slice for manual parsing.
 pub struct ByteReader<'a> {
     /// The remaining data to be read.
     pub data: &'a [u8],
+    /// Length of the whole input, to report offsets from its start.
+    len: usize,
+    /// Binary flavor of the input.
+    format: NbtFormat,
 }
 
-/// Errors that can occur during NBT parsing.
-#[derive(Debug, PartialEq, Eq)]
-pub enum ParseError {
+/// The kinds of errors that can occur during NBT parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
     /// The input ended unexpectedly before a tag or field could be fully read.
     UnexpectedEof,
     /// An unknown or invalid NBT tag type ID was encountered.
     InvalidTag(u8),
     /// A string field could not be decoded as Modified UTF-8.
     InvalidString,
+    /// A VarInt in Bedrock network NBT ran past its maximum length.
+    InvalidVarInt,
+    /// Lists and compounds are nested deeper than [`ParseOptions::max_depth`].
+    DepthLimitExceeded,
+    /// A list, array or string is longer than the limit in [`ParseOptions`]; holds the
+    /// declared length.
+    LengthLimitExceeded(usize),
+    /// The parsed data would take more memory than [`ParseOptions::max_allocation`].
+    AllocationLimitExceeded,
+    /// Bytes remain after the root tag in a strict parse; holds their count.
+    TrailingData(usize),
 }
 
-impl std::fmt::Display for ParseError {
+impl std::fmt::Display for ParseErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ParseError::UnexpectedEof => write!(f, "Unexpected EOF"),
-            ParseError::InvalidTag(t) => write!(f, "Invalid tag type: {}", t),
-            ParseError::InvalidString => write!(f, "Invalid MUTF-8 string"),
+            ParseErrorKind::UnexpectedEof => write!(f, "Unexpected EOF"),
+            ParseErrorKind::InvalidTag(t) => write!(f, "Invalid tag type: {}", t),
+            ParseErrorKind::InvalidString => write!(f, "Invalid MUTF-8 string"),
+            ParseErrorKind::InvalidVarInt => write!(f, "Invalid VarInt"),
+            ParseErrorKind::DepthLimitExceeded => write!(f, "Nesting depth limit exceeded"),
+            ParseErrorKind::LengthLimitExceeded(len) => {
+                write!(f, "Length {} exceeds limit", len)
+            }
+            ParseErrorKind::AllocationLimitExceeded => write!(f, "Allocation limit exceeded"),
+            ParseErrorKind::TrailingData(len) => {
+                write!(f, "{} unexpected bytes after the root tag", len)
+            }
+        }
+    }
+}
+
+/// An error that occurred during NBT parsing, with where it occurred.
+///
+/// Displays as e.g. `Unexpected EOF at byte 5123 in Level.Sections[2].block_states`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// What went wrong.
+    pub kind: ParseErrorKind,
+    /// Byte offset into the input at which parsing failed.
+    pub offset: usize,
+    /// Path of the tag being parsed when parsing failed, such as
+    /// `Level.Sections[2].block_states`; empty at the root.
+    pub path: String,
+}
+
+impl ParseError {
+    /// Sets the byte offset of the error.
+    pub(crate) fn at(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+impl From<ParseErrorKind> for ParseError {
+    fn from(kind: ParseErrorKind) -> Self {
+        ParseError {
+            kind,
+            offset: 0,
+            path: String::new(),
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at byte {}", self.kind, self.offset)?;
+        if !self.path.is_empty() {
+            write!(f, " in {}", self.path)?;
         }
+        Ok(())
     }
 }
 
 impl std::error::Error for ParseError {}
 
 impl<'a> ByteReader<'a> {
-    fn new(data: &'a [u8]) -> Self {
-        Self { data }
+    /// Creates a reader over Java Edition NBT.
+    pub fn new(data: &'a [u8]) -> Self {
+        Self::with_format(data, NbtFormat::JavaBE)
     }
 
+    /// Creates a reader over input in the given binary format.
+    pub fn with_format(data: &'a [u8], format: NbtFormat) -> Self {
+        Self {
+            data,
+            len: data.len(),
+            format,
+        }
+    }
+
+    /// Returns the binary format of the input.
+    pub fn format(&self) -> NbtFormat {
+        self.format
+    }
+
+    /// Returns the number of bytes consumed so far.
+    pub fn offset(&self) -> usize {
+        self.len - self.data.len()
+    }
+
+    /// Reads a single byte, such as a tag type ID.
     #[inline]
-    fn read_u8(&mut self) -> Result<u8, ParseError> {
+    pub fn read_u8(&mut self) -> Result<u8, ParseError> {
         if self.data.is_empty() {
-            return Err(ParseError::UnexpectedEof);
+            return Err(ParseErrorKind::UnexpectedEof.into());
         }
         let b = self.data[0];
         self.data = &self.data[1..];
@@ -49,59 +139,91 @@ impl<'a> ByteReader<'a> {
     }
 
     #[inline]
-    fn read_i8(&mut self) -> Result<i8, ParseError> {
+    pub(crate) fn read_i8(&mut self) -> Result<i8, ParseError> {
         self.read_u8().map(|b| b as i8)
     }
 
+    /// Reads `N` fixed-width bytes, returned in big-endian order.
     #[inline]
-    fn read_u16(&mut self) -> Result<u16, ParseError> {
-        if self.data.len() < 2 {
-            return Err(ParseError::UnexpectedEof);
+    fn read_fixed<const N: usize>(&mut self) -> Result<[u8; N], ParseError> {
+        if self.data.len() < N {
+            return Err(ParseErrorKind::UnexpectedEof.into());
+        }
+        let bytes: [u8; N] = self.data[..N].try_into().unwrap();
+        self.data = &self.data[N..];
+        Ok(self.format.to_big(bytes))
+    }
+
+    /// Reads an unsigned LEB128 VarInt of at most `max_bytes` bytes. The input is only
+    /// consumed if the whole VarInt could be read.
+    fn read_varint(&mut self, max_bytes: usize) -> Result<u64, ParseError> {
+        let mut value = 0u64;
+        for (i, &b) in self.data.iter().enumerate().take(max_bytes) {
+            value |= ((b & 0x7f) as u64) << (7 * i);
+            if b & 0x80 == 0 {
+                self.data = &self.data[i + 1..];
+                return Ok(value);
+            }
+        }
+        if self.data.len() < max_bytes {
+            Err(ParseErrorKind::UnexpectedEof.into())
+        } else {
+            Err(ParseErrorKind::InvalidVarInt.into())
         }
-        let bytes = [self.data[0], self.data[1]];
-        self.data = &self.data[2..];
-        Ok(u16::from_be_bytes(bytes))
     }
 
     #[inline]
-    fn read_i16(&mut self) -> Result<i16, ParseError> {
-        self.read_u16().map(|v| v as i16)
+    pub(crate) fn read_u16(&mut self) -> Result<u16, ParseError> {
+        self.read_fixed().map(u16::from_be_bytes)
     }
 
     #[inline]
-    fn read_i32(&mut self) -> Result<i32, ParseError> {
-        if self.data.len() < 4 {
-            return Err(ParseError::UnexpectedEof);
+    pub(crate) fn read_i16(&mut self) -> Result<i16, ParseError> {
+        self.read_fixed().map(i16::from_be_bytes)
+    }
+
+    /// Reads an Int payload or a list or array length.
+    #[inline]
+    pub(crate) fn read_i32(&mut self) -> Result<i32, ParseError> {
+        if self.format == NbtFormat::BedrockNetwork {
+            let v = self.read_varint(5)? as u32;
+            return Ok((v >> 1) as i32 ^ -((v & 1) as i32));
         }
-        let bytes = [self.data[0], self.data[1], self.data[2], self.data[3]];
-        self.data = &self.data[4..];
-        Ok(i32::from_be_bytes(bytes))
+        self.read_fixed().map(i32::from_be_bytes)
     }
 
     #[inline]
-    fn read_i64(&mut self) -> Result<i64, ParseError> {
-        if self.data.len() < 8 {
-            return Err(ParseError::UnexpectedEof);
+    pub(crate) fn read_i64(&mut self) -> Result<i64, ParseError> {
+        if self.format == NbtFormat::BedrockNetwork {
+            let v = self.read_varint(10)?;
+            return Ok((v >> 1) as i64 ^ -((v & 1) as i64));
         }
-        let bytes: [u8; 8] = self.data[..8].try_into().unwrap();
-        self.data = &self.data[8..];
-        Ok(i64::from_be_bytes(bytes))
+        self.read_fixed().map(i64::from_be_bytes)
     }
 
     #[inline]
-    fn read_f32(&mut self) -> Result<f32, ParseError> {
-        self.read_i32().map(|v| f32::from_bits(v as u32))
+    pub(crate) fn read_f32(&mut self) -> Result<f32, ParseError> {
+        self.read_fixed().map(f32::from_be_bytes)
     }
 
     #[inline]
-    fn read_f64(&mut self) -> Result<f64, ParseError> {
-        self.read_i64().map(|v| f64::from_bits(v as u64))
+    pub(crate) fn read_f64(&mut self) -> Result<f64, ParseError> {
+        self.read_fixed().map(f64::from_be_bytes)
     }
 
+    /// Reads the length prefix of a string.
     #[inline]
-    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], ParseError> {
+    pub(crate) fn read_string_len(&mut self) -> Result<usize, ParseError> {
+        if self.format == NbtFormat::BedrockNetwork {
+            return Ok(self.read_varint(5)? as u32 as usize);
+        }
+        self.read_u16().map(usize::from)
+    }
+
+    #[inline]
+    pub(crate) fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], ParseError> {
         if self.data.len() < len {
-            return Err(ParseError::UnexpectedEof);
+            return Err(ParseErrorKind::UnexpectedEof.into());
         }
         let bytes = &self.data[..len];
         self.data = &self.data[len..];
@@ -109,15 +231,290 @@ impl<'a> ByteReader<'a> {
     }
 }
 
-/// Parses a length-prefixed Modified UTF-8 string from the input.
+/// Limits applied while parsing untrusted NBT.
+///
+/// The defaults only cap nesting at 512 levels, the limit the game itself uses. Parsing
+/// itself does not recurse, but dropping, encoding or walking a tag tree does, so the
+/// depth limit still guards against input that overflows the stack later. Data from
+/// untrusted sources should also bound lengths and memory, since a few bytes of input
+/// can declare a list of billions of elements.
+///
+/// # Examples
+///
+/// ```
+/// use anvil_nbt::nbt::parse::{ParseErrorKind, ParseOptions, parse_named_tag_with};
+///
+/// // A list of 2^31 - 1 empty compounds, in 14 bytes
+/// let data = [9, 0, 0, 10, 0x7f, 0xff, 0xff, 0xff, 0, 0, 0, 0, 0, 0];
+/// let options = ParseOptions {
+///     max_length: 65536,
+///     ..ParseOptions::default()
+/// };
+/// assert_eq!(
+///     parse_named_tag_with(&mut &data[..], &options).map_err(|e| e.kind),
+///     Err(ParseErrorKind::LengthLimitExceeded(0x7fff_ffff)),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Maximum nesting of lists and compounds. The root counts as depth 1.
+    pub max_depth: usize,
+    /// Maximum number of elements in a list or array.
+    pub max_length: usize,
+    /// Maximum length of a string, in encoded bytes.
+    pub max_string_length: usize,
+    /// Maximum total size, in bytes, of the parsed tree (an estimate counting tags,
+    /// strings and array contents).
+    pub max_allocation: usize,
+    /// Binary format of the input.
+    pub format: NbtFormat,
+    /// Keep string values that are not valid or not canonically encoded as
+    /// [`NbtTag::RawString`] instead of failing or normalizing them, so that re-encoding
+    /// reproduces the input exactly. Compound keys must still decode.
+    pub preserve_raw_strings: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            max_depth: 512,
+            max_length: usize::MAX,
+            max_string_length: usize::MAX,
+            max_allocation: usize::MAX,
+            format: NbtFormat::JavaBE,
+            preserve_raw_strings: false,
+        }
+    }
+}
+
+/// Parse state tracking the limits of a [`ParseOptions`].
+struct Limits<'o> {
+    options: &'o ParseOptions,
+    depth: usize,
+    allocated: usize,
+}
+
+impl Limits<'_> {
+    fn allocate(&mut self, bytes: usize) -> Result<(), ParseError> {
+        self.allocated = self.allocated.saturating_add(bytes);
+        if self.allocated > self.options.max_allocation {
+            return Err(ParseErrorKind::AllocationLimitExceeded.into());
+        }
+        Ok(())
+    }
+
+    fn check_length(&self, len: usize) -> Result<(), ParseError> {
+        if len > self.options.max_length {
+            return Err(ParseErrorKind::LengthLimitExceeded(len).into());
+        }
+        Ok(())
+    }
+
+    fn enter(&mut self) -> Result<(), ParseError> {
+        self.depth += 1;
+        if self.depth > self.options.max_depth {
+            return Err(ParseErrorKind::DepthLimitExceeded.into());
+        }
+        Ok(())
+    }
+}
+
+/// Parses a length-prefixed string from the input.
 pub fn parse_nbt_string(reader: &mut ByteReader) -> Result<String, ParseError> {
-    let len = reader.read_u16()? as usize;
+    let len = reader.read_string_len()?;
     let bytes = reader.read_bytes(len)?;
-    decode_mutf8(bytes).map_err(|_| ParseError::InvalidString)
+    reader.format.decode_string(bytes)
+}
+
+fn parse_string_limited(
+    reader: &mut ByteReader,
+    limits: &mut Limits,
+) -> Result<String, ParseError> {
+    let bytes = read_string_bytes(reader, limits)?;
+    reader.format.decode_string(bytes)
 }
 
-/// Parses the payload of an NBT tag based on its type ID.
+/// Reads the length prefix and encoded bytes of a string, enforcing the string limits.
+fn read_string_bytes<'a>(
+    reader: &mut ByteReader<'a>,
+    limits: &mut Limits,
+) -> Result<&'a [u8], ParseError> {
+    let len = reader.read_string_len()?;
+    if len > limits.options.max_string_length {
+        return Err(ParseErrorKind::LengthLimitExceeded(len).into());
+    }
+    limits.allocate(len)?;
+    reader.read_bytes(len)
+}
+
+/// Parses a string value, keeping its raw bytes if requested and they would not survive
+/// a round trip through [`String`].
+fn parse_string_value(reader: &mut ByteReader, limits: &mut Limits) -> Result<NbtTag, ParseError> {
+    if !limits.options.preserve_raw_strings {
+        return parse_string_limited(reader, limits).map(NbtTag::String);
+    }
+    let bytes = read_string_bytes(reader, limits)?;
+    match reader.format.decode_string(bytes) {
+        Ok(s) if reencodes_to(reader.format, &s, bytes) => Ok(NbtTag::String(s)),
+        _ => Ok(NbtTag::RawString(bytes.to_vec())),
+    }
+}
+
+/// Returns `true` if writing `s` in `format` reproduces `bytes`.
+fn reencodes_to(format: NbtFormat, s: &str, bytes: &[u8]) -> bool {
+    match format {
+        NbtFormat::JavaBE => encode_mutf8(s) == bytes,
+        NbtFormat::JavaCesu8 => encode_cesu8(s) == bytes,
+        NbtFormat::BedrockLE | NbtFormat::BedrockNetwork => true,
+    }
+}
+
+/// Parses the payload of an NBT tag based on its type ID, with the default
+/// [`ParseOptions`].
 pub fn parse_tag_payload(reader: &mut ByteReader, type_id: u8) -> Result<NbtTag, ParseError> {
+    let options = ParseOptions::default();
+    let mut limits = Limits {
+        options: &options,
+        depth: 0,
+        allocated: 0,
+    };
+    parse_payload(reader, type_id, &mut limits)
+}
+
+/// A list or compound whose contents are still being parsed.
+enum Frame {
+    List {
+        element_type: u8,
+        remaining: usize,
+        elements: Vec<NbtTag>,
+    },
+    Compound {
+        map: IndexMap<String, NbtTag>,
+        /// Name of the entry whose value is being parsed, if any.
+        key: Option<String>,
+    },
+}
+
+/// Parses a payload with an explicit stack of open lists and compounds instead of
+/// recursion, so nesting depth is bounded by [`ParseOptions::max_depth`] only and never
+/// by the call stack.
+///
+/// Errors are located at the reader's offset and at the path of the open frames.
+fn parse_payload(
+    reader: &mut ByteReader,
+    type_id: u8,
+    limits: &mut Limits,
+) -> Result<NbtTag, ParseError> {
+    let mut stack: Vec<Frame> = Vec::new();
+    parse_frames(reader, type_id, limits, &mut stack).map_err(|mut e| {
+        e.path = frame_path(&stack);
+        e.at(reader.offset())
+    })
+}
+
+/// Formats the path of the open frames, e.g. `Level.Sections[2].block_states`.
+fn frame_path(stack: &[Frame]) -> String {
+    let mut path = String::new();
+    for frame in stack {
+        match frame {
+            Frame::List { elements, .. } => {
+                path.push_str(&format!("[{}]", elements.len()));
+            }
+            Frame::Compound { key, .. } => {
+                if let Some(key) = key.as_deref().filter(|k| !k.is_empty()) {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+                    path.push_str(key);
+                }
+            }
+        }
+    }
+    path
+}
+
+fn parse_frames(
+    reader: &mut ByteReader,
+    type_id: u8,
+    limits: &mut Limits,
+    stack: &mut Vec<Frame>,
+) -> Result<NbtTag, ParseError> {
+    let mut next = type_id;
+    loop {
+        let mut done = match next {
+            9 => {
+                let element_type = reader.read_u8()?;
+                let len = reader.read_i32()?.max(0) as usize;
+                if element_type == 0 && len > 0 {
+                    return Err(ParseErrorKind::InvalidTag(0).into());
+                }
+                limits.check_length(len)?;
+                limits.allocate(len.saturating_mul(std::mem::size_of::<NbtTag>()))?;
+                limits.enter()?;
+                // Every element takes at least one byte, so the input bounds the capacity
+                stack.push(Frame::List {
+                    element_type,
+                    remaining: len,
+                    elements: Vec::with_capacity(len.min(reader.data.len())),
+                });
+                None
+            }
+            10 => {
+                limits.enter()?;
+                stack.push(Frame::Compound {
+                    map: IndexMap::new(),
+                    key: None,
+                });
+                None
+            }
+            t => Some(parse_scalar(reader, t, limits)?),
+        };
+
+        // Hand finished values to their parents until another value needs parsing
+        loop {
+            let Some(frame) = stack.last_mut() else {
+                return Ok(done.expect("the root value is complete once the stack is empty"));
+            };
+            match frame {
+                Frame::List {
+                    element_type,
+                    remaining,
+                    elements,
+                } => {
+                    elements.extend(done.take());
+                    if *remaining > 0 {
+                        *remaining -= 1;
+                        next = *element_type;
+                        break;
+                    }
+                    done = Some(NbtTag::List(std::mem::take(elements)));
+                }
+                Frame::Compound { map, key } => {
+                    if let Some(tag) = done.take() {
+                        map.insert(key.take().unwrap_or_default(), tag);
+                    }
+                    let tag_type = reader.read_u8()?;
+                    if tag_type != 0 {
+                        limits.allocate(std::mem::size_of::<(String, NbtTag)>())?;
+                        *key = Some(parse_string_limited(reader, limits)?);
+                        next = tag_type;
+                        break;
+                    }
+                    done = Some(NbtTag::Compound(std::mem::take(map)));
+                }
+            }
+            stack.pop();
+            limits.depth -= 1;
+        }
+    }
+}
+
+/// Parses a payload that is not a list or compound.
+fn parse_scalar(
+    reader: &mut ByteReader,
+    type_id: u8,
+    limits: &mut Limits,
+) -> Result<NbtTag, ParseError> {
     match type_id {
         0 => Ok(NbtTag::End),
         1 => Ok(NbtTag::Byte(reader.read_i8()?)),
@@ -126,79 +523,888 @@ pub fn parse_tag_payload(reader: &mut ByteReader, type_id: u8) -> Result<NbtTag,
         4 => Ok(NbtTag::Long(reader.read_i64()?)),
         5 => Ok(NbtTag::Float(reader.read_f32()?)),
         6 => Ok(NbtTag::Double(reader.read_f64()?)),
-        7 => {
-            let len = reader.read_i32()? as usize;
-            let bytes = reader.read_bytes(len)?;
-            Ok(NbtTag::ByteArray(bytes.to_vec()))
-        }
-        8 => Ok(NbtTag::String(parse_nbt_string(reader)?)),
-        9 => {
-            let element_type = reader.read_u8()?;
-            let len = reader.read_i32()? as usize;
-            let mut elements = Vec::with_capacity(len);
-            for _ in 0..len {
-                elements.push(parse_tag_payload(reader, element_type)?);
-            }
-            Ok(NbtTag::List(elements))
+        7 => Ok(NbtTag::ByteArray(
+            read_array_bytes(reader, 1, limits)?.to_vec(),
+        )),
+        8 => parse_string_value(reader, limits),
+        11 if reader.format == NbtFormat::BedrockNetwork => {
+            let len = read_array_len(reader, 4, limits)?;
+            let ints = (0..len).map(|_| reader.read_i32());
+            Ok(NbtTag::IntArray(ints.collect::<Result<_, _>>()?))
         }
-        10 => {
-            let mut map = IndexMap::new();
-            loop {
-                let tag_type = reader.read_u8()?;
-                if tag_type == 0 {
-                    break;
-                }
-                let name = parse_nbt_string(reader)?;
-                let payload = parse_tag_payload(reader, tag_type)?;
-                map.insert(name, payload);
-            }
-            Ok(NbtTag::Compound(map))
+        12 if reader.format == NbtFormat::BedrockNetwork => {
+            let len = read_array_len(reader, 8, limits)?;
+            let longs = (0..len).map(|_| reader.read_i64());
+            Ok(NbtTag::LongArray(longs.collect::<Result<_, _>>()?))
         }
         11 => {
-            let len = reader.read_i32()? as usize;
-            let byte_len = len * 4;
-            let bytes = reader.read_bytes(byte_len)?;
-            let mut ints = Vec::with_capacity(len);
-            for chunk in bytes.chunks_exact(4) {
-                ints.push(i32::from_be_bytes(chunk.try_into().unwrap()));
-            }
-            Ok(NbtTag::IntArray(ints))
+            let format = reader.format;
+            let bytes = read_array_bytes(reader, 4, limits)?;
+            Ok(NbtTag::IntArray(
+                bytes
+                    .chunks_exact(4)
+                    .map(|b| i32::from_be_bytes(format.to_big(b.try_into().unwrap())))
+                    .collect(),
+            ))
         }
         12 => {
-            let len = reader.read_i32()? as usize;
-            let byte_len = len * 8;
-            let bytes = reader.read_bytes(byte_len)?;
-            let mut longs = Vec::with_capacity(len);
-            for chunk in bytes.chunks_exact(8) {
-                longs.push(i64::from_be_bytes(chunk.try_into().unwrap()));
-            }
-            Ok(NbtTag::LongArray(longs))
+            let format = reader.format;
+            let bytes = read_array_bytes(reader, 8, limits)?;
+            Ok(NbtTag::LongArray(
+                bytes
+                    .chunks_exact(8)
+                    .map(|b| i64::from_be_bytes(format.to_big(b.try_into().unwrap())))
+                    .collect(),
+            ))
         }
-        _ => Err(ParseError::InvalidTag(type_id)),
+        _ => Err(ParseErrorKind::InvalidTag(type_id).into()),
     }
 }
 
+/// Reads the length-prefixed contents of an array with elements of `width` bytes.
+fn read_array_bytes<'a>(
+    reader: &mut ByteReader<'a>,
+    width: usize,
+    limits: &mut Limits,
+) -> Result<&'a [u8], ParseError> {
+    let len = read_array_len(reader, width, limits)?;
+    reader.read_bytes(len.saturating_mul(width))
+}
+
+/// Reads and checks the length of an array whose elements take `width` bytes in memory.
+fn read_array_len(
+    reader: &mut ByteReader,
+    width: usize,
+    limits: &mut Limits,
+) -> Result<usize, ParseError> {
+    let len = reader.read_i32()?.max(0) as usize;
+    limits.check_length(len)?;
+    limits.allocate(len.saturating_mul(width))?;
+    Ok(len)
+}
+
 /// Parses a named tag (type ID + name + payload) from the input.
 ///
 /// This is the entry point for parsing top-level NBT data (like `level.dat`).
 /// On success, returns the name of the tag and the tag itself, and updates `input`
 /// to point to the remaining bytes.
+///
+/// The default [`ParseOptions`] apply; use [`parse_named_tag_with`] for other limits.
 pub fn parse_named_tag(input: &mut &[u8]) -> Result<(String, NbtTag), ParseError> {
-    let mut reader = ByteReader::new(input);
-    let tag_type = match reader.read_u8() {
-        Ok(t) => t,
-        Err(_) => return Err(ParseError::UnexpectedEof),
+    parse_named_tag_with(input, &ParseOptions::default())
+}
+
+/// Parses a little-endian named tag, as found in Bedrock Edition files.
+///
+/// Bedrock's `level.dat` starts with an 8-byte header (storage version and length)
+/// that must be skipped first.
+pub fn parse_named_tag_le(input: &mut &[u8]) -> Result<(String, NbtTag), ParseError> {
+    let options = ParseOptions {
+        format: NbtFormat::BedrockLE,
+        ..ParseOptions::default()
     };
+    parse_named_tag_with(input, &options)
+}
+
+/// Like [`parse_named_tag`], enforcing the limits and byte order in `options`.
+pub fn parse_named_tag_with(
+    input: &mut &[u8],
+    options: &ParseOptions,
+) -> Result<(String, NbtTag), ParseError> {
+    let mut reader = ByteReader::with_format(input, options.format);
+    let tag_type = reader.read_u8()?;
     if tag_type == 0 {
         *input = reader.data;
         return Ok(("".to_string(), NbtTag::End));
     }
-    let name = parse_nbt_string(&mut reader)?;
-    let payload = parse_tag_payload(&mut reader, tag_type)?;
+    let mut limits = Limits {
+        options,
+        depth: 0,
+        allocated: 0,
+    };
+    let name = parse_string_limited(&mut reader, &mut limits).map_err(|e| e.at(reader.offset()))?;
+    let payload = parse_payload(&mut reader, tag_type, &mut limits)?;
     *input = reader.data;
     Ok((name, payload))
 }
 
+/// Parses a named tag that must span the whole input.
+///
+/// Unlike [`parse_named_tag`], which stops after the root tag and leaves the rest of the
+/// input alone, this fails with [`ParseErrorKind::TrailingData`] if any bytes remain.
+/// Use it for files holding a single tag, where leftovers point to corruption.
+///
+/// ```
+/// use anvil_nbt::nbt::parse::{ParseErrorKind, parse_named_tag_strict};
+///
+/// let data = [10, 0, 0, 0, 0xff, 0xff];
+/// let err = parse_named_tag_strict(&data).unwrap_err();
+/// assert_eq!((err.kind, err.offset), (ParseErrorKind::TrailingData(2), 4));
+/// assert!(parse_named_tag_strict(&data[..4]).is_ok());
+/// ```
+pub fn parse_named_tag_strict(input: &[u8]) -> Result<(String, NbtTag), ParseError> {
+    let (name, tag, consumed) = parse_named_tag_prefix(input)?;
+    if consumed < input.len() {
+        return Err(
+            ParseError::from(ParseErrorKind::TrailingData(input.len() - consumed)).at(consumed),
+        );
+    }
+    Ok((name, tag))
+}
+
+/// Parses a named tag from the start of the input and returns the number of bytes it
+/// took, so that callers can decide what to do with whatever follows.
+pub fn parse_named_tag_prefix(input: &[u8]) -> Result<(String, NbtTag, usize), ParseError> {
+    let mut rest = input;
+    let (name, tag) = parse_named_tag(&mut rest)?;
+    Ok((name, tag, input.len() - rest.len()))
+}
+
+/// Iterator over back-to-back named tags, as found in some files and packet captures.
+///
+/// Yields one `(name, tag)` per root tag until the input is exhausted. After an error
+/// the iterator is fused; the error's offset counts from the start of the whole input.
+///
+/// ```
+/// use anvil_nbt::nbt::parse::NbtStreamIter;
+///
+/// // Two empty compounds named "a" and "b"
+/// let data = [10, 0, 1, b'a', 0, 10, 0, 1, b'b', 0];
+/// let names: Vec<String> = NbtStreamIter::new(&data)
+///     .map(|tag| tag.map(|(name, _)| name))
+///     .collect::<Result<_, _>>()?;
+/// assert_eq!(names, ["a", "b"]);
+/// # Ok::<(), anvil_nbt::nbt::parse::ParseError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct NbtStreamIter<'a> {
+    input: &'a [u8],
+    offset: usize,
+    options: ParseOptions,
+    failed: bool,
+}
+
+impl<'a> NbtStreamIter<'a> {
+    /// Creates an iterator over Java Edition named tags with the default limits.
+    pub fn new(input: &'a [u8]) -> Self {
+        Self::with_options(input, ParseOptions::default())
+    }
+
+    /// Creates an iterator enforcing the limits and format in `options` for every tag.
+    pub fn with_options(input: &'a [u8], options: ParseOptions) -> Self {
+        NbtStreamIter {
+            input,
+            offset: 0,
+            options,
+            failed: false,
+        }
+    }
+
+    /// Returns the number of bytes consumed so far.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the input not consumed yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.input
+    }
+}
+
+impl Iterator for NbtStreamIter<'_> {
+    type Item = Result<(String, NbtTag), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.input.is_empty() {
+            return None;
+        }
+        let mut rest = self.input;
+        match parse_named_tag_with(&mut rest, &self.options) {
+            Ok(tag) => {
+                self.offset += self.input.len() - rest.len();
+                self.input = rest;
+                Some(Ok(tag))
+            }
+            Err(e) => {
+                self.failed = true;
+                let offset = self.offset + e.offset;
+                Some(Err(e.at(offset)))
+            }
+        }
+    }
+}
+
+/// What a lenient parse had to leave out to return a tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecoveryReport {
+    /// The error that stopped parsing, or `None` if the input was intact.
+    pub error: Option<ParseError>,
+    /// Number of values left out: the one being parsed when the error occurred plus
+    /// the elements that truncated lists still declared.
+    pub fields_dropped: usize,
+    /// Paths of the lists and compounds that were closed early, innermost first. The
+    /// root is the empty path.
+    pub paths_truncated: Vec<String>,
+    /// Number of input bytes from the error onwards that were not parsed.
+    pub bytes_skipped: usize,
+}
+
+impl RecoveryReport {
+    /// Returns `true` if nothing was lost.
+    pub fn is_clean(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Parses a named tag, salvaging as much of a damaged tree as possible.
+///
+/// When parsing fails inside a list or compound, everything read so far is kept and
+/// every open container is closed at that point; the rest of the input is consumed and
+/// skipped. The [`RecoveryReport`] says what was lost. Fails only if not even the root
+/// could be read, e.g. when the input ends inside the root name or a scalar root.
+pub fn parse_named_tag_lenient(
+    input: &mut &[u8],
+    options: &ParseOptions,
+) -> Result<(String, NbtTag, RecoveryReport), ParseError> {
+    let mut reader = ByteReader::with_format(input, options.format);
+    let tag_type = reader.read_u8()?;
+    if tag_type == 0 {
+        *input = reader.data;
+        return Ok(("".to_string(), NbtTag::End, RecoveryReport::default()));
+    }
+    let mut limits = Limits {
+        options,
+        depth: 0,
+        allocated: 0,
+    };
+    let name = parse_string_limited(&mut reader, &mut limits).map_err(|e| e.at(reader.offset()))?;
+
+    let mut stack = Vec::new();
+    let error = match parse_frames(&mut reader, tag_type, &mut limits, &mut stack) {
+        Ok(payload) => {
+            *input = reader.data;
+            return Ok((name, payload, RecoveryReport::default()));
+        }
+        Err(mut e) => {
+            e.path = frame_path(&stack);
+            e.at(reader.offset())
+        }
+    };
+    if stack.is_empty() {
+        return Err(error);
+    }
+
+    let mut report = RecoveryReport {
+        bytes_skipped: input.len() - error.offset,
+        error: Some(error),
+        ..RecoveryReport::default()
+    };
+    // The innermost container was parsing a value when the error occurred
+    report.fields_dropped = match stack.last() {
+        Some(Frame::Compound { key, .. }) => key.is_some() as usize,
+        _ => 1,
+    };
+    let mut done = None;
+    while !stack.is_empty() {
+        let path = frame_path(&stack[..stack.len() - 1]);
+        report.paths_truncated.push(path);
+        done = Some(match stack.pop().unwrap() {
+            Frame::List {
+                remaining,
+                mut elements,
+                ..
+            } => {
+                report.fields_dropped += remaining;
+                elements.extend(done);
+                NbtTag::List(elements)
+            }
+            Frame::Compound { mut map, key } => {
+                if let (Some(key), Some(tag)) = (key, done) {
+                    map.insert(key, tag);
+                }
+                NbtTag::Compound(map)
+            }
+        });
+    }
+    *input = &[];
+    Ok((name, done.unwrap(), report))
+}
+
+/// Parses a named tag from a reader, such as a file, socket or decompression stream,
+/// with the default [`ParseOptions`].
+///
+/// Nothing past the tag is read, so the rest of a stream stays in `reader`. The tag is
+/// read in many small pieces; wrap unbuffered sources such as files and sockets in a
+/// [`BufReader`](std::io::BufReader). Truncated input fails with
+/// [`ErrorKind::UnexpectedEof`](io::ErrorKind::UnexpectedEof); malformed input fails with
+/// [`ErrorKind::InvalidData`](io::ErrorKind::InvalidData) wrapping a [`ParseError`].
+pub fn parse_from_reader<R: Read>(reader: R) -> io::Result<(String, NbtTag)> {
+    parse_from_reader_with(reader, &ParseOptions::default())
+}
+
+/// Like [`parse_from_reader`], enforcing the limits and format in `options`.
+///
+/// The tag is copied into memory before it is parsed, and the copy is held to the same
+/// limits, so a hostile stream is rejected before it is read in full.
+pub fn parse_from_reader_with<R: Read>(
+    reader: R,
+    options: &ParseOptions,
+) -> io::Result<(String, NbtTag)> {
+    let mut parsed = std::pin::pin!(parse_from_source(ReadSource(reader), options));
+    // A blocking reader has its data by the time `read` returns, so the future never waits
+    match parsed
+        .as_mut()
+        .poll(&mut std::task::Context::from_waker(std::task::Waker::noop()))
+    {
+        std::task::Poll::Ready(result) => result,
+        std::task::Poll::Pending => unreachable!("blocking readers never return Pending"),
+    }
+}
+
+/// Copies one named tag out of `source` and parses the copy.
+///
+/// Shared by [`parse_from_reader_with`] and the async reader, which differ only in how
+/// the bytes arrive.
+pub(crate) async fn parse_from_source<R: TagSource>(
+    source: R,
+    options: &ParseOptions,
+) -> io::Result<(String, NbtTag)> {
+    let mut copier = TagCopier {
+        reader: source,
+        bytes: Vec::new(),
+        format: options.format,
+    };
+    let mut limits = Limits {
+        options,
+        depth: 0,
+        allocated: 0,
+    };
+    let copied = copier.copy_tag(&mut limits).await;
+    let mut input = &copier.bytes[..];
+    match copied {
+        Ok(()) => parse_named_tag_with(&mut input, options).map_err(invalid_data),
+        Err(CopyError::Io(e)) => Err(e),
+        // The copy stopped where parsing fails, so parse what was read to locate the error
+        Err(CopyError::Parse(e)) => match parse_named_tag_with(&mut input, options) {
+            Err(parsed) if parsed.kind == e.kind => Err(invalid_data(parsed)),
+            _ => Err(invalid_data(e.at(copier.bytes.len()))),
+        },
+    }
+}
+
+/// A stream a [`TagCopier`] pulls the bytes of a tag from.
+pub(crate) trait TagSource {
+    /// Appends up to `len` bytes to `buf`, stopping early only at the end of the stream.
+    async fn read_into(&mut self, buf: &mut Vec<u8>, len: usize) -> io::Result<()>;
+}
+
+/// A blocking [`Read`] as a [`TagSource`].
+struct ReadSource<R>(R);
+
+impl<R: Read> TagSource for ReadSource<R> {
+    async fn read_into(&mut self, buf: &mut Vec<u8>, len: usize) -> io::Result<()> {
+        (&mut self.0).take(len as u64).read_to_end(buf)?;
+        Ok(())
+    }
+}
+
+enum CopyError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl From<io::Error> for CopyError {
+    fn from(e: io::Error) -> Self {
+        CopyError::Io(e)
+    }
+}
+
+impl From<ParseError> for CopyError {
+    fn from(e: ParseError) -> Self {
+        CopyError::Parse(e)
+    }
+}
+
+/// Copies the bytes of exactly one named tag out of a reader.
+///
+/// The tag is walked with an explicit stack and charged to [`Limits`] the same way
+/// [`parse_frames`] charges it, so the copy never outgrows what parsing allows.
+struct TagCopier<R> {
+    reader: R,
+    bytes: Vec<u8>,
+    format: NbtFormat,
+}
+
+impl<R: TagSource> TagCopier<R> {
+    /// Appends `len` bytes from the reader, without trusting `len` for the allocation.
+    async fn take(&mut self, len: usize) -> Result<&[u8], CopyError> {
+        let start = self.bytes.len();
+        self.reader.read_into(&mut self.bytes, len).await?;
+        if self.bytes.len() - start < len {
+            return Err(CopyError::Io(io::ErrorKind::UnexpectedEof.into()));
+        }
+        Ok(&self.bytes[start..])
+    }
+
+    async fn read_u8(&mut self) -> Result<u8, CopyError> {
+        Ok(self.take(1).await?[0])
+    }
+
+    async fn read_varint(&mut self, max_bytes: usize) -> Result<u64, CopyError> {
+        let mut value = 0u64;
+        for i in 0..max_bytes {
+            let b = self.read_u8().await?;
+            value |= ((b & 0x7f) as u64) << (7 * i);
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ParseError::from(ParseErrorKind::InvalidVarInt).into())
+    }
+
+    /// Reads a list or array length.
+    async fn read_len(&mut self) -> Result<usize, CopyError> {
+        if self.format == NbtFormat::BedrockNetwork {
+            let v = self.read_varint(5).await? as u32;
+            return Ok(((v >> 1) as i32 ^ -((v & 1) as i32)).max(0) as usize);
+        }
+        let bytes = self.format.to_big(self.take(4).await?.try_into().unwrap());
+        Ok(i32::from_be_bytes(bytes).max(0) as usize)
+    }
+
+    async fn skip_string(&mut self, limits: &mut Limits<'_>) -> Result<(), CopyError> {
+        let len = if self.format == NbtFormat::BedrockNetwork {
+            self.read_varint(5).await? as u32 as usize
+        } else {
+            u16::from_be_bytes(self.format.to_big(self.take(2).await?.try_into().unwrap())) as usize
+        };
+        if len > limits.options.max_string_length {
+            return Err(ParseError::from(ParseErrorKind::LengthLimitExceeded(len)).into());
+        }
+        limits.allocate(len)?;
+        self.take(len).await?;
+        Ok(())
+    }
+
+    /// Copies the contents of an array with elements of `width` bytes in memory.
+    async fn skip_array(&mut self, width: usize, limits: &mut Limits<'_>) -> Result<(), CopyError> {
+        let len = self.read_len().await?;
+        limits.check_length(len)?;
+        limits.allocate(len.saturating_mul(width))?;
+        if self.format == NbtFormat::BedrockNetwork && width > 1 {
+            let max_bytes = if width == 4 { 5 } else { 10 };
+            for _ in 0..len {
+                self.read_varint(max_bytes).await?;
+            }
+        } else {
+            self.take(len.saturating_mul(width)).await?;
+        }
+        Ok(())
+    }
+
+    async fn skip_scalar(&mut self, type_id: u8, limits: &mut Limits<'_>) -> Result<(), CopyError> {
+        match type_id {
+            0 => {}
+            1 => drop(self.take(1).await?),
+            2 => drop(self.take(2).await?),
+            3 | 4 if self.format == NbtFormat::BedrockNetwork => {
+                self.read_varint(if type_id == 3 { 5 } else { 10 }).await?;
+            }
+            3 | 5 => drop(self.take(4).await?),
+            4 | 6 => drop(self.take(8).await?),
+            7 => self.skip_array(1, limits).await?,
+            8 => self.skip_string(limits).await?,
+            11 => self.skip_array(4, limits).await?,
+            12 => self.skip_array(8, limits).await?,
+            _ => return Err(ParseError::from(ParseErrorKind::InvalidTag(type_id)).into()),
+        }
+        Ok(())
+    }
+
+    async fn copy_tag(&mut self, limits: &mut Limits<'_>) -> Result<(), CopyError> {
+        let tag_type = self.read_u8().await?;
+        if tag_type == 0 {
+            return Ok(());
+        }
+        self.skip_string(limits).await?;
+
+        // Open lists hold their element type and remaining count; compounds hold `None`
+        let mut stack: Vec<Option<(u8, usize)>> = Vec::new();
+        let mut next = tag_type;
+        loop {
+            match next {
+                9 => {
+                    let element_type = self.read_u8().await?;
+                    let len = self.read_len().await?;
+                    if element_type == 0 && len > 0 {
+                        return Err(ParseError::from(ParseErrorKind::InvalidTag(0)).into());
+                    }
+                    limits.check_length(len)?;
+                    limits.allocate(len.saturating_mul(std::mem::size_of::<NbtTag>()))?;
+                    limits.enter()?;
+                    stack.push(Some((element_type, len)));
+                }
+                10 => {
+                    limits.enter()?;
+                    stack.push(None);
+                }
+                t => self.skip_scalar(t, limits).await?,
+            }
+
+            loop {
+                match stack.last_mut() {
+                    None => return Ok(()),
+                    Some(Some((element_type, remaining))) => {
+                        if *remaining > 0 {
+                            *remaining -= 1;
+                            next = *element_type;
+                            break;
+                        }
+                    }
+                    Some(None) => {
+                        let tag_type = self.read_u8().await?;
+                        if tag_type != 0 {
+                            limits.allocate(std::mem::size_of::<(String, NbtTag)>())?;
+                            self.skip_string(limits).await?;
+                            next = tag_type;
+                            break;
+                        }
+                    }
+                }
+                stack.pop();
+                limits.depth -= 1;
+            }
+        }
+    }
+}
+
+fn invalid_data(e: ParseError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// Skips over the payload of an NBT tag without decoding it.
+///
+/// Arrays, strings and lists of numbers are skipped by length, so this is much cheaper
+/// than [`parse_tag_payload`] for data that is not needed, such as `block_states` or
+/// light arrays when only chunk metadata matters. Lists and compounds nested deeper than
+/// the default [`ParseOptions::max_depth`] fail with
+/// [`ParseErrorKind::DepthLimitExceeded`].
+///
+/// # Examples
+///
+/// ```
+/// use anvil_nbt::nbt::NbtTag;
+/// use anvil_nbt::nbt::encode::write_named_tag;
+/// use anvil_nbt::nbt::parse::{ByteReader, parse_nbt_string, parse_tag_payload, skip_tag_payload};
+///
+/// let chunk = anvil_nbt::nbt!({ sections: [{ data: [L; 1, 2, 3] }], DataVersion: 3953 });
+/// let mut bytes = Vec::new();
+/// write_named_tag(&mut bytes, "", &chunk).unwrap();
+///
+/// let mut reader = ByteReader::new(&bytes);
+/// let root_type = reader.read_u8().unwrap();
+/// parse_nbt_string(&mut reader).unwrap();
+/// assert_eq!(root_type, 10);
+/// loop {
+///     let tag_type = reader.read_u8().unwrap();
+///     let name = parse_nbt_string(&mut reader).unwrap();
+///     if name == "DataVersion" {
+///         let version = parse_tag_payload(&mut reader, tag_type).unwrap();
+///         assert_eq!(version, NbtTag::Int(3953));
+///         break;
+///     }
+///     skip_tag_payload(&mut reader, tag_type).unwrap();
+/// }
+/// ```
+pub fn skip_tag_payload(reader: &mut ByteReader, type_id: u8) -> Result<(), ParseError> {
+    let max_depth = ParseOptions::default().max_depth;
+    // Open lists hold their element type and remaining count; compounds hold `None`
+    let mut stack: Vec<Option<(u8, usize)>> = Vec::new();
+    let mut next = type_id;
+    loop {
+        match next {
+            9 | 10 if stack.len() >= max_depth => {
+                return Err(ParseErrorKind::DepthLimitExceeded.into());
+            }
+            9 => {
+                let element_type = reader.read_u8()?;
+                let len = reader.read_i32()?.max(0) as usize;
+                if let Some(width) = fixed_width(element_type, reader.format) {
+                    reader.read_bytes(len.saturating_mul(width))?;
+                } else {
+                    stack.push(Some((element_type, len)));
+                }
+            }
+            10 => stack.push(None),
+            t => skip_scalar(reader, t)?,
+        }
+
+        loop {
+            match stack.last_mut() {
+                None => return Ok(()),
+                Some(Some((element_type, remaining))) => {
+                    if *remaining > 0 {
+                        *remaining -= 1;
+                        next = *element_type;
+                        break;
+                    }
+                }
+                Some(None) => {
+                    let tag_type = reader.read_u8()?;
+                    if tag_type != 0 {
+                        let len = reader.read_string_len()?;
+                        reader.read_bytes(len)?;
+                        next = tag_type;
+                        break;
+                    }
+                }
+            }
+            stack.pop();
+        }
+    }
+}
+
+/// Skips a payload that is not a list or compound.
+fn skip_scalar(reader: &mut ByteReader, type_id: u8) -> Result<(), ParseError> {
+    match type_id {
+        0 => {}
+        1 => {
+            reader.read_bytes(1)?;
+        }
+        2 => {
+            reader.read_bytes(2)?;
+        }
+        3 => {
+            reader.read_i32()?;
+        }
+        4 => {
+            reader.read_i64()?;
+        }
+        5 => {
+            reader.read_bytes(4)?;
+        }
+        6 => {
+            reader.read_bytes(8)?;
+        }
+        7 => {
+            let len = reader.read_i32()?.max(0) as usize;
+            reader.read_bytes(len)?;
+        }
+        11 | 12 if reader.format == NbtFormat::BedrockNetwork => {
+            let len = reader.read_i32()?.max(0) as usize;
+            for _ in 0..len {
+                if type_id == 11 {
+                    reader.read_i32()?;
+                } else {
+                    reader.read_i64()?;
+                }
+            }
+        }
+        11 | 12 => {
+            let width = if type_id == 11 { 4 } else { 8 };
+            let len = reader.read_i32()?.max(0) as usize;
+            reader.read_bytes(len.saturating_mul(width))?;
+        }
+        8 => {
+            let len = reader.read_string_len()?;
+            reader.read_bytes(len)?;
+        }
+        _ => return Err(ParseErrorKind::InvalidTag(type_id).into()),
+    }
+    Ok(())
+}
+
+/// Extracts the tag at an NBT path from a named tag, without parsing the rest.
+///
+/// `path` uses the syntax of [`NbtPath`], e.g. `"Level.xPos"` or `"sections[0].Y"`.
+/// Compound keys and list indices are followed directly in the binary data, skipping
+/// unrelated siblings with [`skip_tag_payload`]; only the selected tag is decoded. A
+/// pattern or `[]` node decodes the subtree it applies to and resolves the rest of the
+/// path in memory.
+///
+/// Returns `Ok(None)` if nothing matches or the path is malformed, like
+/// [`NbtTag::get_path`]; errors come from malformed data only.
+///
+/// # Examples
+///
+/// ```
+/// use anvil_nbt::nbt::NbtTag;
+/// use anvil_nbt::nbt::encode::write_named_tag;
+/// use anvil_nbt::nbt::parse::extract_path;
+///
+/// let chunk = anvil_nbt::nbt!({ Level: { xPos: -3, Sections: [{ Y: 0i8 }, { Y: 1i8 }] } });
+/// let mut bytes = Vec::new();
+/// write_named_tag(&mut bytes, "", &chunk).unwrap();
+///
+/// assert_eq!(extract_path(&bytes, "Level.xPos").unwrap(), Some(NbtTag::Int(-3)));
+/// assert_eq!(extract_path(&bytes, "Level.Sections[-1].Y").unwrap(), Some(NbtTag::Byte(1)));
+/// assert_eq!(extract_path(&bytes, "Level.zPos").unwrap(), None);
+/// ```
+pub fn extract_path(input: &[u8], path: &str) -> Result<Option<NbtTag>, ParseError> {
+    let Ok(path) = NbtPath::parse(path) else {
+        return Ok(None);
+    };
+    let mut reader = ByteReader::new(input);
+    extract_nodes(&mut reader, path.nodes()).map_err(|e| e.at(reader.offset()))
+}
+
+fn extract_nodes(
+    reader: &mut ByteReader,
+    nodes: &[PathNode],
+) -> Result<Option<NbtTag>, ParseError> {
+    let mut type_id = reader.read_u8()?;
+    if type_id == 0 {
+        return Ok(None);
+    }
+    let len = reader.read_string_len()?;
+    reader.read_bytes(len)?;
+
+    for (i, node) in nodes.iter().enumerate() {
+        match (node, type_id) {
+            (PathNode::Key(key), 10) => loop {
+                let tag_type = reader.read_u8()?;
+                if tag_type == 0 {
+                    return Ok(None);
+                }
+                if parse_nbt_string(reader)? == *key {
+                    type_id = tag_type;
+                    break;
+                }
+                skip_tag_payload(reader, tag_type)?;
+            },
+            (PathNode::Index(index), 9) => {
+                let element_type = reader.read_u8()?;
+                let len = reader.read_i32()?.max(0) as usize;
+                let Some(index) = resolve_index(*index, len) else {
+                    return Ok(None);
+                };
+                if let Some(width) = fixed_width(element_type, reader.format) {
+                    reader.read_bytes(index * width)?;
+                } else {
+                    for _ in 0..index {
+                        skip_tag_payload(reader, element_type)?;
+                    }
+                }
+                type_id = element_type;
+            }
+            (PathNode::Key(_) | PathNode::Index(_), _) => return Ok(None),
+            _ => {
+                let tag = parse_tag_payload(reader, type_id)?;
+                return Ok(resolve_all(&tag, &nodes[i..]).first().map(|t| (*t).clone()));
+            }
+        }
+    }
+    parse_tag_payload(reader, type_id).map(Some)
+}
+
+/// Returns the encoded size of payloads of `type_id` if it does not depend on the value.
+fn fixed_width(type_id: u8, format: NbtFormat) -> Option<usize> {
+    match type_id {
+        0 => Some(0),
+        1 => Some(1),
+        2 => Some(2),
+        3 | 4 if format == NbtFormat::BedrockNetwork => None,
+        3 | 5 => Some(4),
+        4 | 6 => Some(8),
+        _ => None,
+    }
+}
+
+/// Parses only selected fields of a root compound, skipping everything else.
+///
+/// Each entry of `paths` is a dot-separated path of compound keys below the root, such as
+/// `"DataVersion"` or `"Level.InhabitedTime"`. The result holds one entry per path, in
+/// the same order, which is `None` if the field is absent. Compounds on the way to a
+/// requested field are walked but never decoded as a whole.
+pub fn parse_named_fields(
+    input: &mut &[u8],
+    paths: &[&str],
+) -> Result<Vec<Option<NbtTag>>, ParseError> {
+    let mut reader = ByteReader::new(input);
+    let mut found = vec![None; paths.len()];
+    parse_root_fields(&mut reader, paths, &mut found, false).map_err(|e| e.at(reader.offset()))?;
+    *input = reader.data;
+    Ok(found)
+}
+
+/// Like [`parse_named_fields`], but stops reading as soon as every path has been found.
+///
+/// `input` is left just after the last field found, in the middle of the root compound,
+/// unless some path is absent, in which case the whole compound is read.
+pub fn find_named_fields(
+    input: &mut &[u8],
+    paths: &[&str],
+) -> Result<Vec<Option<NbtTag>>, ParseError> {
+    let mut reader = ByteReader::new(input);
+    let mut found = vec![None; paths.len()];
+    parse_root_fields(&mut reader, paths, &mut found, true).map_err(|e| e.at(reader.offset()))?;
+    *input = reader.data;
+    Ok(found)
+}
+
+fn parse_root_fields(
+    reader: &mut ByteReader,
+    paths: &[&str],
+    found: &mut [Option<NbtTag>],
+    stop_early: bool,
+) -> Result<(), ParseError> {
+    let tag_type = reader.read_u8()?;
+    if tag_type != 0 {
+        let len = reader.read_string_len()?;
+        reader.read_bytes(len)?;
+        if tag_type == 10 {
+            parse_fields(reader, "", paths, found, stop_early, 1)?;
+        } else {
+            skip_tag_payload(reader, tag_type)?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads the fields of a compound at nesting `depth`, returning `true` if it stopped
+/// early because every path was found.
+fn parse_fields(
+    reader: &mut ByteReader,
+    prefix: &str,
+    paths: &[&str],
+    found: &mut [Option<NbtTag>],
+    stop_early: bool,
+    depth: usize,
+) -> Result<bool, ParseError> {
+    if depth > ParseOptions::default().max_depth {
+        return Err(ParseErrorKind::DepthLimitExceeded.into());
+    }
+    loop {
+        if stop_early && found.iter().all(Option::is_some) {
+            return Ok(true);
+        }
+        let tag_type = reader.read_u8()?;
+        if tag_type == 0 {
+            return Ok(false);
+        }
+        let name = parse_nbt_string(reader)?;
+        let path = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+
+        if let Some(i) = paths.iter().position(|p| *p == path) {
+            found[i] = Some(parse_tag_payload(reader, tag_type)?);
+        } else if tag_type == 10
+            && paths.iter().any(|p| {
+                p.len() > path.len() && p.starts_with(&path) && p.as_bytes()[path.len()] == b'.'
+            })
+        {
+            if parse_fields(reader, &path, paths, found, stop_early, depth + 1)? {
+                return Ok(true);
+            }
+        } else {
+            skip_tag_payload(reader, tag_type)?;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -222,4 +1428,404 @@ mod tests {
             panic!("Wrong tag type");
         }
     }
+
+    #[test]
+    fn test_skip_tag_payload() {
+        let tag = crate::nbt!({
+            shorts: [1i16, 2i16],
+            doubles: [0.5, 1.5],
+            nested: [[1, 2], [3]],
+            compounds: [{ a: "x" }],
+            longs: [L; 1, 2],
+        });
+        let mut data = Vec::new();
+        crate::nbt::encode::write_tag_payload(&mut data, &tag).unwrap();
+        data.push(42);
+        let mut reader = ByteReader::new(&data);
+        skip_tag_payload(&mut reader, 10).unwrap();
+        assert_eq!(reader.data, [42]);
+
+        // Lists of numbers are skipped by length, without looping over elements
+        let huge_ints = [3, 0x7f, 0xff, 0xff, 0xff];
+        let mut reader = ByteReader::new(&huge_ints);
+        assert_eq!(
+            skip_tag_payload(&mut reader, 9).unwrap_err().kind,
+            ParseErrorKind::UnexpectedEof
+        );
+        let huge_ends = [0, 0x7f, 0xff, 0xff, 0xff];
+        let mut reader = ByteReader::new(&huge_ends);
+        skip_tag_payload(&mut reader, 9).unwrap();
+        assert!(reader.data.is_empty());
+
+        // Deep nesting fails instead of overflowing the stack, here and in field lookups
+        let mut nested = vec![10, 0, 0, 9, 0, 1, b'a'];
+        for _ in 0..200_000 {
+            nested.extend_from_slice(&[9, 0, 0, 0, 1]);
+        }
+        let mut reader = ByteReader::new(&nested[7..]);
+        assert_eq!(
+            skip_tag_payload(&mut reader, 9).unwrap_err().kind,
+            ParseErrorKind::DepthLimitExceeded
+        );
+        assert_eq!(
+            parse_named_fields(&mut &nested[..], &["b"])
+                .unwrap_err()
+                .kind,
+            ParseErrorKind::DepthLimitExceeded
+        );
+    }
+
+    #[test]
+    fn test_extract_path() {
+        let tag = crate::nbt!({
+            DataVersion: 3953,
+            sections: [
+                { Y: -4i8, biomes: { palette: ["minecraft:plains"] } },
+                { Y: -3i8, biomes: { palette: ["minecraft:desert", "minecraft:river"] } },
+            ],
+            heights: [[1, 2], [3, 4]],
+        });
+        let mut data = Vec::new();
+        crate::nbt::encode::write_named_tag(&mut data, "", &tag).unwrap();
+
+        for path in [
+            "DataVersion",
+            "sections[1].Y",
+            "sections[-1].biomes.palette[1]",
+            "sections[{Y: -3b}].biomes",
+            "sections[].Y",
+            "heights[1][0]",
+            "{DataVersion: 3953}.heights",
+            "sections[2]",
+            "DataVersion.x",
+            "missing",
+            "sections[",
+        ] {
+            assert_eq!(
+                extract_path(&data, path).unwrap().as_ref(),
+                tag.get_path(path),
+                "{}",
+                path
+            );
+        }
+        assert!(extract_path(&data[..data.len() - 10], "heights[1][1]").is_err());
+    }
+
+    #[test]
+    fn test_parse_named_fields() {
+        // {a: 1b, skip: [I; 1, 2], Level: {x: "hi"}}
+        let data = vec![
+            10, 0, 0, //
+            1, 0, 1, b'a', 7, //
+            11, 0, 4, b's', b'k', b'i', b'p', 0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 2, //
+            10, 0, 5, b'L', b'e', b'v', b'e', b'l', //
+            8, 0, 1, b'x', 0, 2, b'h', b'i', 0, //
+            0,
+        ];
+        let mut input = &data[..];
+        let fields = parse_named_fields(&mut input, &["Level.x", "a", "missing"]).unwrap();
+        assert_eq!(
+            fields,
+            vec![
+                Some(NbtTag::String("hi".to_string())),
+                Some(NbtTag::Byte(7)),
+                None
+            ]
+        );
+        assert!(input.is_empty());
+
+        // Stops right after "Level.x" once both fields are known
+        let mut input = &data[..];
+        let fields = find_named_fields(&mut input, &["a", "Level.x"]).unwrap();
+        assert_eq!(fields[1], Some(NbtTag::String("hi".to_string())));
+        assert_eq!(input, &[0, 0]);
+    }
+
+    #[test]
+    fn test_parse_from_reader() {
+        let tag = crate::nbt!({ a: [I; 1, 2], b: [{ c: "hi" }], d: [L; 3], e: [B; 4] });
+        let mut data = Vec::new();
+        crate::nbt::encode::write_named_tag(&mut data, "root", &tag).unwrap();
+        data.push(0xff);
+
+        let mut input = &data[..];
+        let (name, parsed) = parse_from_reader(&mut input).unwrap();
+        assert_eq!((name.as_str(), &parsed), ("root", &tag));
+        assert_eq!(input, &[0xff]);
+
+        let err = parse_from_reader(&data[..data.len() - 2]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        for format in [NbtFormat::BedrockLE, NbtFormat::BedrockNetwork] {
+            let mut data = Vec::new();
+            crate::nbt::encode::write_named_tag_as(&mut data, "root", &tag, format).unwrap();
+            let options = ParseOptions {
+                format,
+                ..ParseOptions::default()
+            };
+            let (_, parsed) = parse_from_reader_with(&data[..], &options).unwrap();
+            assert_eq!(parsed, tag);
+        }
+    }
+
+    #[test]
+    fn test_parse_from_reader_limits() {
+        // Deeply nested lists are rejected without recursing
+        let depth = 200_000;
+        let mut data = vec![9, 0, 0];
+        for _ in 0..depth {
+            data.extend_from_slice(&[9, 0, 0, 0, 1]);
+        }
+        data.extend_from_slice(&[0, 0, 0, 0, 0]);
+        let err = parse_from_reader(&data[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let err = err.into_inner().unwrap().downcast::<ParseError>().unwrap();
+        assert_eq!(err.kind, ParseErrorKind::DepthLimitExceeded);
+        assert_eq!(err.path, "[0]".repeat(512));
+
+        // A huge declared array stops at the allocation limit, before it is read
+        let data = [7, 0, 0, 0x7f, 0xff, 0xff, 0xff];
+        let options = ParseOptions {
+            max_allocation: 1 << 20,
+            ..ParseOptions::default()
+        };
+        let err = parse_from_reader_with(&data[..], &options).unwrap_err();
+        let err = err.into_inner().unwrap().downcast::<ParseError>().unwrap();
+        assert_eq!(err.kind, ParseErrorKind::AllocationLimitExceeded);
+    }
+
+    #[test]
+    fn test_parse_strict_and_prefix() {
+        let tag = crate::nbt!({ a: 1 });
+        let mut data = Vec::new();
+        crate::nbt::encode::write_named_tag(&mut data, "", &tag).unwrap();
+        let len = data.len();
+        assert_eq!(parse_named_tag_strict(&data).unwrap().1, tag);
+
+        data.extend_from_slice(&[0, 0, 0]);
+        let (_, parsed, consumed) = parse_named_tag_prefix(&data).unwrap();
+        assert_eq!((parsed, consumed), (tag, len));
+        let err = parse_named_tag_strict(&data).unwrap_err();
+        assert_eq!(
+            (err.kind.clone(), err.offset),
+            (ParseErrorKind::TrailingData(3), len)
+        );
+        assert_eq!(
+            err.to_string(),
+            format!("3 unexpected bytes after the root tag at byte {}", len)
+        );
+    }
+
+    #[test]
+    fn test_nbt_stream_iter() {
+        let mut data = Vec::new();
+        for (name, tag) in [("a", crate::nbt!({ x: 1 })), ("b", NbtTag::Int(2))] {
+            crate::nbt::encode::write_named_tag(&mut data, name, &tag).unwrap();
+        }
+        let first_len = 13;
+
+        let tags: Vec<_> = NbtStreamIter::new(&data).collect::<Result<_, _>>().unwrap();
+        assert_eq!(
+            tags,
+            vec![
+                ("a".to_string(), crate::nbt!({ x: 1 })),
+                ("b".to_string(), NbtTag::Int(2))
+            ]
+        );
+
+        let mut iter = NbtStreamIter::new(&data[..data.len() - 1]);
+        assert!(iter.next().unwrap().is_ok());
+        assert_eq!(iter.offset(), first_len);
+        let err = iter.next().unwrap().unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedEof);
+        assert!(err.offset >= first_len);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_parse_options_limits() {
+        let tag = crate::nbt!({ list: [[{ s: "hello" }]], ints: [I; 1, 2, 3] });
+        let mut data = Vec::new();
+        crate::nbt::encode::write_named_tag(&mut data, "", &tag).unwrap();
+
+        let parse = |options: ParseOptions| {
+            parse_named_tag_with(&mut &data[..], &options).map_err(|e| e.kind)
+        };
+        assert_eq!(parse(ParseOptions::default()).unwrap().1, tag);
+        let limited = |f: fn(&mut ParseOptions)| {
+            let mut options = ParseOptions::default();
+            f(&mut options);
+            parse(options)
+        };
+        assert_eq!(limited(|o| o.max_depth = 4).unwrap().1, tag);
+        assert_eq!(
+            limited(|o| o.max_depth = 3),
+            Err(ParseErrorKind::DepthLimitExceeded)
+        );
+        assert_eq!(
+            limited(|o| o.max_length = 2),
+            Err(ParseErrorKind::LengthLimitExceeded(3))
+        );
+        assert_eq!(
+            limited(|o| o.max_string_length = 4),
+            Err(ParseErrorKind::LengthLimitExceeded(5))
+        );
+        assert_eq!(
+            limited(|o| o.max_allocation = 64),
+            Err(ParseErrorKind::AllocationLimitExceeded)
+        );
+
+        // Deep nesting fails cleanly instead of overflowing the stack
+        let mut deep = vec![9, 0, 0];
+        for _ in 0..100_000 {
+            deep.extend([9, 0, 0, 0, 1]);
+        }
+        assert_eq!(
+            parse_named_tag(&mut &deep[..]).map_err(|e| e.kind),
+            Err(ParseErrorKind::DepthLimitExceeded)
+        );
+    }
+
+    #[test]
+    fn test_parse_error_location() {
+        let tag = crate::nbt!({
+            Level: {
+                Sections: [
+                    { Y: 0i8 },
+                    { Y: 1i8, block_states: { data: [L; 1, 2, 3] } },
+                ],
+            },
+        });
+        let mut data = Vec::new();
+        crate::nbt::encode::write_named_tag(&mut data, "", &tag).unwrap();
+        // Cut the input in the middle of the last long
+        let cut = data.len() - 8;
+
+        let err = parse_named_tag(&mut &data[..cut]).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedEof);
+        assert_eq!(err.path, "Level.Sections[1].block_states.data");
+        // The array payload starts after its 4-byte length and the two longs before it
+        assert_eq!(err.offset, cut - 16 - 4);
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Unexpected EOF at byte {} in Level.Sections[1].block_states.data",
+                cut - 20
+            )
+        );
+
+        let err = parse_named_tag(&mut &data[..1]).unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected EOF at byte 1");
+    }
+
+    #[test]
+    fn test_preserve_raw_strings() {
+        // Compound with "a": invalid bytes, "b": a raw NUL that re-encodes as C0 80,
+        // "c": a canonical string
+        let data = [
+            10, 0, 0, //
+            8, 0, 1, b'a', 0, 2, 0xff, b'x', //
+            8, 0, 1, b'b', 0, 1, 0, //
+            8, 0, 1, b'c', 0, 2, b'o', b'k', //
+            0,
+        ];
+        assert_eq!(
+            parse_named_tag(&mut &data[..]).unwrap_err().kind,
+            ParseErrorKind::InvalidString
+        );
+
+        let options = ParseOptions {
+            preserve_raw_strings: true,
+            ..ParseOptions::default()
+        };
+        let (name, tag) = parse_named_tag_with(&mut &data[..], &options).unwrap();
+        let NbtTag::Compound(map) = &tag else {
+            panic!("expected a compound");
+        };
+        assert_eq!(map["a"], NbtTag::RawString(vec![0xff, b'x']));
+        assert_eq!(map["b"], NbtTag::RawString(vec![0]));
+        assert_eq!(map["c"], NbtTag::String("ok".to_string()));
+
+        let mut encoded = Vec::new();
+        crate::nbt::encode::write_named_tag(&mut encoded, &name, &tag).unwrap();
+        assert_eq!(encoded, data);
+    }
+
+    #[test]
+    fn test_cesu8_format_keeps_plain_nul() {
+        // "s": a raw NUL, canonical in CESU-8 but not in MUTF-8
+        let data = [10, 0, 0, 8, 0, 1, b's', 0, 1, 0, 0];
+        let options = ParseOptions {
+            format: NbtFormat::JavaCesu8,
+            preserve_raw_strings: true,
+            ..ParseOptions::default()
+        };
+        let (name, tag) = parse_named_tag_with(&mut &data[..], &options).unwrap();
+        assert_eq!(tag, crate::nbt!({ s: "\0" }));
+
+        let mut encoded = Vec::new();
+        crate::nbt::encode::write_named_tag_as(&mut encoded, &name, &tag, NbtFormat::JavaCesu8)
+            .unwrap();
+        assert_eq!(encoded, data);
+    }
+
+    #[test]
+    fn test_parse_lenient() {
+        let tag = crate::nbt!({
+            DataVersion: 3953,
+            Level: {
+                Sections: [{ Y: 0i8 }, { Y: 1i8, data: [L; 1, 2, 3] }, { Y: 2i8 }],
+                Status: "full",
+            },
+        });
+        let mut data = Vec::new();
+        crate::nbt::encode::write_named_tag(&mut data, "", &tag).unwrap();
+        let options = ParseOptions::default();
+
+        let (_, intact, report) = parse_named_tag_lenient(&mut &data[..], &options).unwrap();
+        assert_eq!(intact, tag);
+        assert!(report.is_clean());
+
+        // Cut inside the long array of the second section
+        let cut = &data[..data.len() - 30];
+        let mut input = cut;
+        let (_, salvaged, report) = parse_named_tag_lenient(&mut input, &options).unwrap();
+        assert!(input.is_empty());
+        assert_eq!(
+            salvaged,
+            crate::nbt!({ DataVersion: 3953, Level: { Sections: [{ Y: 0i8 }, { Y: 1i8 }] } })
+        );
+        let error = report.error.unwrap();
+        assert_eq!(error.path, "Level.Sections[1].data");
+        assert_eq!(report.fields_dropped, 2);
+        assert_eq!(
+            report.paths_truncated,
+            ["Level.Sections[1]", "Level.Sections", "Level", ""]
+        );
+        assert_eq!(report.bytes_skipped, cut.len() - error.offset);
+
+        assert!(parse_named_tag_lenient(&mut &data[..2], &options).is_err());
+    }
+
+    #[test]
+    fn test_parse_deep_nesting_iteratively() {
+        // Deeper than a recursive parser survives on a 2 MiB test thread in debug builds
+        let depth = 2_000;
+        let mut data = vec![9, 0, 0];
+        for _ in 0..depth {
+            data.extend([9, 0, 0, 0, 1]);
+        }
+        data.extend([0, 0, 0, 0, 0]);
+        let options = ParseOptions {
+            max_depth: usize::MAX,
+            ..ParseOptions::default()
+        };
+        let (_, mut tag) = parse_named_tag_with(&mut &data[..], &options).unwrap();
+        let mut levels = 0;
+        while let NbtTag::List(mut elements) = tag {
+            tag = elements.pop().unwrap_or(NbtTag::End);
+            levels += 1;
+        }
+        assert_eq!(levels, depth + 1);
+    }
 }
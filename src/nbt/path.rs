@@ -0,0 +1,691 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! NBT paths, the syntax `/data` commands use to address tags inside a tree.
+//!
+//! A path is a sequence of nodes separated by dots:
+//!
+//! | Syntax          | Selects                                                    |
+//! |-----------------|------------------------------------------------------------|
+//! | `key`, `"key"`  | The entry `key` of a compound                              |
+//! | `key{...}`      | The entry `key`, if it is a compound matching the SNBT     |
+//! | `{...}`         | The root, if it matches (only at the start of a path)      |
+//! | `[3]`, `[-1]`   | The element at a list index (negative counts from the end)  |
+//! | `[]`            | Every element of a list                                    |
+//! | `[{...}]`       | Every compound element of a list matching the SNBT         |
+//!
+//! A compound matches an SNBT pattern if every entry of the pattern is present and
+//! matches in turn; lists match if every pattern element matches some element.
+
+use crate::nbt::NbtTag;
+use crate::nbt::snbt::{SnbtError, parse_snbt_prefix};
+use thiserror::Error;
+
+/// Errors that can occur while parsing an NBT path. Positions are byte offsets.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PathError {
+    /// The path is empty or ends after a dot.
+    #[error("Unexpected end of path")]
+    UnexpectedEnd,
+    /// A character that cannot appear at this point.
+    #[error("Unexpected character '{found}' at position {position}")]
+    UnexpectedChar {
+        /// The offending character.
+        found: char,
+        /// Byte offset of the character.
+        position: usize,
+    },
+    /// A list index that is not an integer.
+    #[error("Invalid index at position {0}")]
+    InvalidIndex(usize),
+    /// An SNBT pattern that failed to parse or is not a compound.
+    #[error("Invalid compound pattern at position {position}: {source}")]
+    InvalidPattern {
+        /// Byte offset of the pattern.
+        position: usize,
+        /// The underlying SNBT error.
+        source: SnbtError,
+    },
+}
+
+/// One step of an [`NbtPath`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathNode {
+    /// Selects the entry with this key of a compound.
+    Key(String),
+    /// Keeps the current tag only if it matches this compound pattern.
+    Filter(NbtTag),
+    /// Selects the element at an index; negative indices count from the end.
+    Index(i32),
+    /// Selects every element of a list.
+    AllElements,
+    /// Selects every list element matching this compound pattern.
+    MatchElements(NbtTag),
+}
+
+/// A parsed NBT path such as `Level.Sections[3].Palette[0].Name`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NbtPath {
+    nodes: Vec<PathNode>,
+}
+
+impl NbtPath {
+    /// Parses a path in the syntax described in the [module documentation](self).
+    pub fn parse(path: &str) -> Result<Self, PathError> {
+        let mut nodes = Vec::new();
+        let mut pos = 0;
+        if path.starts_with('{') {
+            let (pattern, len) = parse_pattern(path, pos)?;
+            nodes.push(PathNode::Filter(pattern));
+            pos += len;
+        }
+
+        while pos < path.len() {
+            let rest = &path[pos..];
+            let c = rest.chars().next().unwrap();
+            if c == '.' && !nodes.is_empty() {
+                pos += 1;
+                match path[pos..].chars().next() {
+                    None => return Err(PathError::UnexpectedEnd),
+                    Some(c @ ('.' | '[')) => {
+                        return Err(PathError::UnexpectedChar {
+                            found: c,
+                            position: pos,
+                        });
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+            if c == '[' {
+                let inner = &rest[1..];
+                if inner.starts_with(']') {
+                    nodes.push(PathNode::AllElements);
+                    pos += 2;
+                } else if inner.starts_with('{') {
+                    let (pattern, len) = parse_pattern(path, pos + 1)?;
+                    pos += 1 + len;
+                    expect(path, pos, ']')?;
+                    nodes.push(PathNode::MatchElements(pattern));
+                    pos += 1;
+                } else {
+                    let end = inner.find(']').ok_or(PathError::UnexpectedEnd)?;
+                    let index = inner[..end]
+                        .trim()
+                        .parse()
+                        .map_err(|_| PathError::InvalidIndex(pos + 1))?;
+                    nodes.push(PathNode::Index(index));
+                    pos += end + 2;
+                }
+                continue;
+            }
+            if !nodes.is_empty() && !matches!(path.as_bytes()[pos - 1], b'.') {
+                return Err(PathError::UnexpectedChar {
+                    found: c,
+                    position: pos,
+                });
+            }
+
+            let key = if c == '"' || c == '\'' {
+                let (tag, len) =
+                    parse_snbt_prefix(rest).map_err(|source| PathError::InvalidPattern {
+                        position: pos,
+                        source,
+                    })?;
+                pos += len;
+                match tag {
+                    NbtTag::String(key) => key,
+                    _ => unreachable!("quoted SNBT is always a string"),
+                }
+            } else {
+                let len = rest
+                    .find(|c: char| {
+                        matches!(c, '.' | '[' | ']' | '{' | '}' | '"' | '\'') || c.is_whitespace()
+                    })
+                    .unwrap_or(rest.len());
+                if len == 0 {
+                    return Err(PathError::UnexpectedChar {
+                        found: c,
+                        position: pos,
+                    });
+                }
+                pos += len;
+                rest[..len].to_string()
+            };
+            nodes.push(PathNode::Key(key));
+
+            if path[pos..].starts_with('{') {
+                let (pattern, len) = parse_pattern(path, pos)?;
+                nodes.push(PathNode::Filter(pattern));
+                pos += len;
+            }
+        }
+
+        if nodes.is_empty() {
+            return Err(PathError::UnexpectedEnd);
+        }
+        Ok(NbtPath { nodes })
+    }
+
+    /// Returns the nodes of the path.
+    pub fn nodes(&self) -> &[PathNode] {
+        &self.nodes
+    }
+
+    /// Returns every tag selected by the path, in tree order.
+    pub fn get_all<'a>(&self, root: &'a NbtTag) -> Vec<&'a NbtTag> {
+        resolve_all(root, &self.nodes)
+    }
+
+    /// Returns the first tag selected by the path.
+    pub fn get<'a>(&self, root: &'a NbtTag) -> Option<&'a NbtTag> {
+        self.get_all(root).into_iter().next()
+    }
+
+    /// Returns the first tag selected by the path, mutably.
+    pub fn get_mut<'a>(&self, root: &'a mut NbtTag) -> Option<&'a mut NbtTag> {
+        resolve_mut(root, &self.nodes)
+    }
+
+    /// Splits the path into the nodes leading to the parents of the targets, the node
+    /// selecting the targets within each parent, and trailing filters on the targets.
+    fn split_target(&self) -> (&[PathNode], Option<&PathNode>, &[PathNode]) {
+        match self
+            .nodes
+            .iter()
+            .rposition(|n| !matches!(n, PathNode::Filter(_)))
+        {
+            Some(i) => (&self.nodes[..i], Some(&self.nodes[i]), &self.nodes[i + 1..]),
+            None => (&[], None, &self.nodes),
+        }
+    }
+
+    /// Replaces every tag selected by the path with `value`, returning how many were set.
+    ///
+    /// Missing compound entries along the way are created as empty compounds, and a
+    /// final key that does not exist yet is added, so `Data.GameRules.keepInventory`
+    /// can be set on an empty compound. List elements are never created.
+    pub fn set(&self, root: &mut NbtTag, value: NbtTag) -> usize {
+        let (parents, target, filters) = self.split_target();
+        let Some(target) = target else {
+            if matches_all(root, filters) {
+                *root = value;
+                return 1;
+            }
+            return 0;
+        };
+        let create = if matches!(target, PathNode::Key(_)) && filters.is_empty() {
+            Create::All
+        } else {
+            Create::Intermediate
+        };
+        let mut found = Vec::new();
+        resolve_all_mut(root, parents, create, &mut found);
+
+        let mut count = 0;
+        for parent in found {
+            if let (PathNode::Key(key), NbtTag::Compound(map)) = (target, &mut *parent)
+                && filters.is_empty()
+            {
+                map.insert(key.clone(), value.clone());
+                count += 1;
+                continue;
+            }
+            for slot in select_children_mut(parent, target, filters) {
+                *slot = value.clone();
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Inserts `value` at the path, returning how many insertions were made.
+    ///
+    /// If the path ends in a list index, the value is inserted into each selected list
+    /// before that index (`[0]` prepends; `[-1]` and an index equal to the length append).
+    /// If it ends in a key, the entry is added to each selected compound that does not
+    /// already have it. Intermediate compounds are created as with [`set`](Self::set).
+    pub fn insert(&self, root: &mut NbtTag, value: NbtTag) -> usize {
+        let (parents, Some(target), []) = self.split_target() else {
+            return 0;
+        };
+        let create = if matches!(target, PathNode::Key(_)) {
+            Create::All
+        } else {
+            Create::Intermediate
+        };
+        let mut found = Vec::new();
+        resolve_all_mut(root, parents, create, &mut found);
+
+        let mut count = 0;
+        for parent in found {
+            match (target, parent) {
+                (PathNode::Key(key), NbtTag::Compound(map)) if !map.contains_key(key) => {
+                    map.insert(key.clone(), value.clone());
+                    count += 1;
+                }
+                (PathNode::Index(i), NbtTag::List(elements)) => {
+                    let len = elements.len() as i64;
+                    let at = if *i < 0 {
+                        len + *i as i64 + 1
+                    } else {
+                        *i as i64
+                    };
+                    if (0..=len).contains(&at) {
+                        elements.insert(at as usize, value.clone());
+                        count += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+        count
+    }
+
+    /// Removes every tag selected by the path and returns them. The root itself cannot
+    /// be removed.
+    pub fn remove(&self, root: &mut NbtTag) -> Vec<NbtTag> {
+        let (parents, Some(target), filters) = self.split_target() else {
+            return Vec::new();
+        };
+        let mut found = Vec::new();
+        resolve_all_mut(root, parents, Create::Nothing, &mut found);
+
+        let mut removed = Vec::new();
+        for parent in found {
+            match (target, parent) {
+                (PathNode::Key(key), NbtTag::Compound(map))
+                    if map.get(key).is_some_and(|v| matches_all(v, filters)) =>
+                {
+                    removed.extend(map.shift_remove(key));
+                }
+                (PathNode::Index(i), NbtTag::List(elements)) => {
+                    if let Some(i) = resolve_index(*i, elements.len())
+                        && matches_all(&elements[i], filters)
+                    {
+                        removed.push(elements.remove(i));
+                    }
+                }
+                (PathNode::AllElements | PathNode::MatchElements(_), NbtTag::List(elements)) => {
+                    let pattern = match target {
+                        PathNode::MatchElements(pattern) => Some(pattern),
+                        _ => None,
+                    };
+                    let (take, keep) = std::mem::take(elements).into_iter().partition(|e| {
+                        pattern.is_none_or(|p| matches_pattern(e, p)) && matches_all(e, filters)
+                    });
+                    *elements = keep;
+                    removed.extend::<Vec<NbtTag>>(take);
+                }
+                _ => {}
+            }
+        }
+        removed
+    }
+}
+
+impl std::str::FromStr for NbtPath {
+    type Err = PathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NbtPath::parse(s)
+    }
+}
+
+impl NbtTag {
+    /// Looks up a tag by NBT path, such as `"Level.Sections[3].Palette[0].Name"` or
+    /// `"Inventory[{Slot:0b}].id"`.
+    ///
+    /// Returns the first match, or `None` if nothing matches or the path is malformed;
+    /// use [`NbtPath::parse`] to report syntax errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use anvil_nbt::nbt::snbt::parse_snbt;
+    /// use anvil_nbt::nbt::NbtTag;
+    ///
+    /// let tag = parse_snbt("{Inventory: [{Slot: 0b, id: dirt}, {Slot: 8b, id: torch}]}").unwrap();
+    /// assert_eq!(
+    ///     tag.get_path("Inventory[{Slot:8b}].id"),
+    ///     Some(&NbtTag::String("torch".to_string()))
+    /// );
+    /// assert_eq!(tag.get_path("Inventory[-2].Slot"), Some(&NbtTag::Byte(0)));
+    /// ```
+    pub fn get_path(&self, path: &str) -> Option<&NbtTag> {
+        NbtPath::parse(path).ok()?.get(self)
+    }
+
+    /// Mutable version of [`get_path`](Self::get_path).
+    pub fn get_path_mut(&mut self, path: &str) -> Option<&mut NbtTag> {
+        NbtPath::parse(path).ok()?.get_mut(self)
+    }
+
+    /// Sets every tag selected by an NBT path to `value`, creating missing intermediate
+    /// compounds. Returns how many tags were set; see [`NbtPath::set`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use anvil_nbt::nbt::NbtTag;
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut level = NbtTag::Compound(IndexMap::new());
+    /// level
+    ///     .set_path("Data.GameRules.keepInventory", NbtTag::String("true".to_string()))
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     level.get_path("Data.GameRules.keepInventory"),
+    ///     Some(&NbtTag::String("true".to_string()))
+    /// );
+    /// ```
+    pub fn set_path(&mut self, path: &str, value: NbtTag) -> Result<usize, PathError> {
+        Ok(NbtPath::parse(path)?.set(self, value))
+    }
+
+    /// Inserts `value` into lists or compounds selected by an NBT path. Returns how many
+    /// insertions were made; see [`NbtPath::insert`].
+    pub fn insert_path(&mut self, path: &str, value: NbtTag) -> Result<usize, PathError> {
+        Ok(NbtPath::parse(path)?.insert(self, value))
+    }
+
+    /// Removes every tag selected by an NBT path and returns them; see
+    /// [`NbtPath::remove`].
+    pub fn remove_path(&mut self, path: &str) -> Result<Vec<NbtTag>, PathError> {
+        Ok(NbtPath::parse(path)?.remove(self))
+    }
+}
+
+/// Parses an SNBT compound pattern starting at `pos`, returning it and its length.
+fn parse_pattern(path: &str, pos: usize) -> Result<(NbtTag, usize), PathError> {
+    match parse_snbt_prefix(&path[pos..]) {
+        Ok((pattern @ NbtTag::Compound(_), len)) => Ok((pattern, len)),
+        Ok(_) => Err(PathError::UnexpectedChar {
+            found: path[pos..].chars().next().unwrap_or(' '),
+            position: pos,
+        }),
+        Err(source) => Err(PathError::InvalidPattern {
+            position: pos,
+            source,
+        }),
+    }
+}
+
+fn expect(path: &str, pos: usize, expected: char) -> Result<(), PathError> {
+    match path[pos..].chars().next() {
+        Some(c) if c == expected => Ok(()),
+        Some(found) => Err(PathError::UnexpectedChar {
+            found,
+            position: pos,
+        }),
+        None => Err(PathError::UnexpectedEnd),
+    }
+}
+
+/// Returns `true` if `tag` matches `pattern`, see the [module documentation](self).
+fn matches_pattern(tag: &NbtTag, pattern: &NbtTag) -> bool {
+    match (tag, pattern) {
+        (NbtTag::Compound(map), NbtTag::Compound(pattern)) => pattern
+            .iter()
+            .all(|(key, p)| map.get(key).is_some_and(|v| matches_pattern(v, p))),
+        (NbtTag::List(elements), NbtTag::List(pattern)) => pattern
+            .iter()
+            .all(|p| elements.iter().any(|e| matches_pattern(e, p))),
+        _ => tag == pattern,
+    }
+}
+
+/// Resolves a possibly negative index against a length.
+pub(crate) fn resolve_index(index: i32, len: usize) -> Option<usize> {
+    let resolved = if index < 0 {
+        len as i64 + index as i64
+    } else {
+        index as i64
+    };
+    (0..len as i64)
+        .contains(&resolved)
+        .then_some(resolved as usize)
+}
+
+/// Returns every tag below `root` selected by `nodes`, in tree order.
+pub(crate) fn resolve_all<'a>(root: &'a NbtTag, nodes: &[PathNode]) -> Vec<&'a NbtTag> {
+    let mut current = vec![root];
+    for node in nodes {
+        let mut next = Vec::new();
+        for tag in current {
+            select(tag, node, &mut next);
+        }
+        current = next;
+    }
+    current
+}
+
+fn select<'a>(tag: &'a NbtTag, node: &PathNode, out: &mut Vec<&'a NbtTag>) {
+    match (node, tag) {
+        (PathNode::Key(key), NbtTag::Compound(map)) => out.extend(map.get(key)),
+        (PathNode::Filter(pattern), _) if matches_pattern(tag, pattern) => out.push(tag),
+        (PathNode::Index(i), NbtTag::List(elements)) => {
+            out.extend(resolve_index(*i, elements.len()).map(|i| &elements[i]))
+        }
+        (PathNode::AllElements, NbtTag::List(elements)) => out.extend(elements),
+        (PathNode::MatchElements(pattern), NbtTag::List(elements)) => {
+            out.extend(elements.iter().filter(|e| matches_pattern(e, pattern)))
+        }
+        // Array elements are primitives and cannot be returned by reference as tags
+        _ => {}
+    }
+}
+
+/// Returns `true` if `tag` matches every `Filter` node in `filters`.
+fn matches_all(tag: &NbtTag, filters: &[PathNode]) -> bool {
+    filters.iter().all(|f| match f {
+        PathNode::Filter(pattern) => matches_pattern(tag, pattern),
+        _ => false,
+    })
+}
+
+/// How [`resolve_all_mut`] treats missing compound entries.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Create {
+    /// Never create entries.
+    Nothing,
+    /// Create entries that are followed by another key.
+    Intermediate,
+    /// Also create the entry for a final key.
+    All,
+}
+
+/// Collects every tag selected by `nodes`, mutably, creating missing compound entries
+/// as requested by `create`.
+fn resolve_all_mut<'a>(
+    tag: &'a mut NbtTag,
+    nodes: &[PathNode],
+    create: Create,
+    out: &mut Vec<&'a mut NbtTag>,
+) {
+    let Some((node, rest)) = nodes.split_first() else {
+        out.push(tag);
+        return;
+    };
+    match (node, tag) {
+        (PathNode::Key(key), NbtTag::Compound(map)) => {
+            let needed = match rest.first() {
+                Some(next) => create != Create::Nothing && matches!(next, PathNode::Key(_)),
+                None => create == Create::All,
+            };
+            if needed && !map.contains_key(key) {
+                map.insert(key.clone(), NbtTag::Compound(Default::default()));
+            }
+            if let Some(child) = map.get_mut(key) {
+                resolve_all_mut(child, rest, create, out);
+            }
+        }
+        (PathNode::Filter(pattern), tag) if matches_pattern(tag, pattern) => {
+            resolve_all_mut(tag, rest, create, out);
+        }
+        (PathNode::Index(i), NbtTag::List(elements)) => {
+            if let Some(i) = resolve_index(*i, elements.len()) {
+                resolve_all_mut(&mut elements[i], rest, create, out);
+            }
+        }
+        (PathNode::AllElements, NbtTag::List(elements)) => {
+            for element in elements {
+                resolve_all_mut(element, rest, create, out);
+            }
+        }
+        (PathNode::MatchElements(pattern), NbtTag::List(elements)) => {
+            for element in elements.iter_mut().filter(|e| matches_pattern(e, pattern)) {
+                resolve_all_mut(element, rest, create, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the existing children of `parent` selected by `node` that match `filters`.
+fn select_children_mut<'a>(
+    parent: &'a mut NbtTag,
+    node: &PathNode,
+    filters: &[PathNode],
+) -> Vec<&'a mut NbtTag> {
+    let mut selected = Vec::new();
+    resolve_all_mut(
+        parent,
+        std::slice::from_ref(node),
+        Create::Nothing,
+        &mut selected,
+    );
+    selected.retain(|t| matches_all(t, filters));
+    selected
+}
+
+fn resolve_mut<'a>(tag: &'a mut NbtTag, nodes: &[PathNode]) -> Option<&'a mut NbtTag> {
+    let Some((node, rest)) = nodes.split_first() else {
+        return Some(tag);
+    };
+    match (node, tag) {
+        (PathNode::Key(key), NbtTag::Compound(map)) => resolve_mut(map.get_mut(key)?, rest),
+        (PathNode::Filter(pattern), tag) => {
+            if matches_pattern(tag, pattern) {
+                resolve_mut(tag, rest)
+            } else {
+                None
+            }
+        }
+        (PathNode::Index(i), NbtTag::List(elements)) => {
+            let i = resolve_index(*i, elements.len())?;
+            resolve_mut(&mut elements[i], rest)
+        }
+        (PathNode::AllElements, NbtTag::List(elements)) => {
+            elements.iter_mut().find_map(|e| resolve_mut(e, rest))
+        }
+        (PathNode::MatchElements(pattern), NbtTag::List(elements)) => elements
+            .iter_mut()
+            .filter(|e| matches_pattern(e, pattern))
+            .find_map(|e| resolve_mut(e, rest)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::snbt::parse_snbt;
+
+    #[test]
+    fn test_parse_nodes() {
+        let path = NbtPath::parse(r#"Level.Sections[3]."Block Light"[{Y:1b}][].x{a:1}"#).unwrap();
+        assert_eq!(
+            path.nodes(),
+            &[
+                PathNode::Key("Level".to_string()),
+                PathNode::Key("Sections".to_string()),
+                PathNode::Index(3),
+                PathNode::Key("Block Light".to_string()),
+                PathNode::MatchElements(parse_snbt("{Y:1b}").unwrap()),
+                PathNode::AllElements,
+                PathNode::Key("x".to_string()),
+                PathNode::Filter(parse_snbt("{a:1}").unwrap()),
+            ]
+        );
+        assert_eq!(NbtPath::parse(""), Err(PathError::UnexpectedEnd));
+        assert_eq!(NbtPath::parse("a."), Err(PathError::UnexpectedEnd));
+        assert_eq!(NbtPath::parse("a[x]"), Err(PathError::InvalidIndex(2)));
+    }
+
+    #[test]
+    fn test_get_path() {
+        let mut tag = parse_snbt(
+            "{Level: {Sections: [{Y: 0b, Palette: [{Name: air}]}, {Y: 1b, Palette: [{Name: stone}, {Name: dirt}]}]}}",
+        )
+        .unwrap();
+        let name = |s: &str| NbtTag::String(s.to_string());
+
+        assert_eq!(
+            tag.get_path("Level.Sections[1].Palette[0].Name"),
+            Some(&name("stone"))
+        );
+        assert_eq!(
+            tag.get_path("Level.Sections[{Y:1b}].Palette[-1].Name"),
+            Some(&name("dirt"))
+        );
+        assert_eq!(tag.get_path("{Level:{}}.Level.Sections[5]"), None);
+        assert_eq!(tag.get_path("Level.Missing"), None);
+
+        let all = NbtPath::parse("Level.Sections[].Palette[].Name").unwrap();
+        assert_eq!(
+            all.get_all(&tag),
+            vec![&name("air"), &name("stone"), &name("dirt")]
+        );
+
+        *tag.get_path_mut("Level.Sections[{Y:0b}].Palette[0].Name")
+            .unwrap() = name("water");
+        assert_eq!(
+            tag.get_path("Level.Sections[0].Palette[0].Name"),
+            Some(&name("water"))
+        );
+    }
+
+    #[test]
+    fn test_set_insert_remove() {
+        let mut tag = parse_snbt("{Items: [{Slot: 0b, id: dirt}, {Slot: 1b, id: stone}]}").unwrap();
+
+        assert_eq!(
+            tag.set_path(
+                "Data.GameRules.keepInventory",
+                NbtTag::String("true".into())
+            ),
+            Ok(1)
+        );
+        assert_eq!(
+            tag.get_path("Data.GameRules.keepInventory"),
+            Some(&NbtTag::String("true".into()))
+        );
+        assert_eq!(tag.set_path("Items[].Count", NbtTag::Byte(2)), Ok(2));
+        assert_eq!(
+            tag.set_path("Items[{Slot:1b}].id", NbtTag::String("gold".into())),
+            Ok(1)
+        );
+        assert_eq!(
+            tag.set_path("Items[5].id", NbtTag::String("x".into())),
+            Ok(0)
+        );
+
+        let item = parse_snbt("{Slot: 2b, id: torch}").unwrap();
+        assert_eq!(tag.insert_path("Items[-1]", item.clone()), Ok(1));
+        assert_eq!(tag.get_path("Items[2]"), Some(&item));
+        assert_eq!(
+            tag.insert_path("Items[0].id", NbtTag::String("x".into())),
+            Ok(0)
+        );
+
+        let removed = tag.remove_path("Items[{Count:2b}]").unwrap();
+        assert_eq!(removed.len(), 2);
+        assert_eq!(tag.get_path("Items[0]"), Some(&item));
+        assert_eq!(tag.remove_path("Data.GameRules").unwrap().len(), 1);
+        assert_eq!(tag.get_path("Data"), Some(&parse_snbt("{}").unwrap()));
+        assert!(tag.remove_path("Items[").is_err());
+        assert!(tag.remove_path("Missing.Deeper.Key").unwrap().is_empty());
+        assert_eq!(tag.get_path("Missing"), None);
+    }
+}
@@ -0,0 +1,221 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Human-readable, size-limited formatting of NBT trees.
+//!
+//! `{:#?}` on a chunk prints every element of every `LongArray`, which quickly runs into
+//! tens of thousands of lines. [`Pretty`] renders the same tree but truncates long arrays,
+//! long strings and deep nesting according to [`PrettyOptions`].
+
+use crate::nbt::NbtTag;
+use std::fmt::{self, Display, Formatter, Write};
+
+/// Limits applied when pretty-printing an [`NbtTag`]. `None` means unlimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrettyOptions {
+    /// Maximum number of array (and list) elements printed before eliding the rest.
+    pub max_array_elements: Option<usize>,
+    /// Maximum number of characters printed for a string value.
+    pub max_string_len: Option<usize>,
+    /// Maximum nesting depth printed; deeper compounds and lists are collapsed.
+    pub max_depth: Option<usize>,
+    /// Number of spaces used per indentation level.
+    pub indent: usize,
+}
+
+impl Default for PrettyOptions {
+    fn default() -> Self {
+        PrettyOptions {
+            max_array_elements: Some(16),
+            max_string_len: Some(128),
+            max_depth: None,
+            indent: 2,
+        }
+    }
+}
+
+impl PrettyOptions {
+    /// Options that print the whole tree without truncation.
+    pub fn unlimited() -> Self {
+        PrettyOptions {
+            max_array_elements: None,
+            max_string_len: None,
+            max_depth: None,
+            indent: 2,
+        }
+    }
+}
+
+/// A [`Display`] adapter that pretty-prints an [`NbtTag`] with the given [`PrettyOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use anvil_nbt::nbt::NbtTag;
+/// use anvil_nbt::nbt::pretty::PrettyOptions;
+///
+/// let tag = NbtTag::LongArray((0..100).collect());
+/// let options = PrettyOptions { max_array_elements: Some(3), ..Default::default() };
+/// assert_eq!(tag.pretty(options).to_string(), "LongArray(100) [0, 1, 2, … +97 more]");
+/// ```
+pub struct Pretty<'a> {
+    tag: &'a NbtTag,
+    options: PrettyOptions,
+}
+
+impl NbtTag {
+    /// Returns a [`Display`] adapter that formats this tag according to `options`.
+    pub fn pretty(&self, options: PrettyOptions) -> Pretty<'_> {
+        Pretty { tag: self, options }
+    }
+}
+
+impl Display for Pretty<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write_tag(f, self.tag, 0)
+    }
+}
+
+impl Pretty<'_> {
+    fn write_tag(&self, f: &mut Formatter<'_>, tag: &NbtTag, depth: usize) -> fmt::Result {
+        match tag {
+            NbtTag::End => write!(f, "End"),
+            NbtTag::Byte(v) => write!(f, "Byte({})", v),
+            NbtTag::Short(v) => write!(f, "Short({})", v),
+            NbtTag::Int(v) => write!(f, "Int({})", v),
+            NbtTag::Long(v) => write!(f, "Long({})", v),
+            NbtTag::Float(v) => write!(f, "Float({:?})", v),
+            NbtTag::Double(v) => write!(f, "Double({:?})", v),
+            NbtTag::String(v) => self.write_string(f, v),
+            NbtTag::RawString(v) => {
+                f.write_str("Raw")?;
+                self.write_string(f, &String::from_utf8_lossy(v))
+            }
+            NbtTag::ByteArray(v) => self.write_array(f, "ByteArray", v),
+            NbtTag::IntArray(v) => self.write_array(f, "IntArray", v),
+            NbtTag::LongArray(v) => self.write_array(f, "LongArray", v),
+            NbtTag::List(v) => {
+                write!(f, "List({}) [", v.len())?;
+                if v.is_empty() {
+                    return f.write_char(']');
+                }
+                if self.depth_exceeded(depth) {
+                    return f.write_str(" … ]");
+                }
+                let shown = self.options.max_array_elements.unwrap_or(usize::MAX);
+                for element in v.iter().take(shown) {
+                    self.newline(f, depth + 1)?;
+                    self.write_tag(f, element, depth + 1)?;
+                    f.write_char(',')?;
+                }
+                if v.len() > shown {
+                    self.newline(f, depth + 1)?;
+                    write!(f, "… +{} more", v.len() - shown)?;
+                }
+                self.newline(f, depth)?;
+                f.write_char(']')
+            }
+            NbtTag::Compound(map) => {
+                write!(f, "Compound({}) {{", map.len())?;
+                if map.is_empty() {
+                    return f.write_char('}');
+                }
+                if self.depth_exceeded(depth) {
+                    return f.write_str(" … }");
+                }
+                for (key, value) in map {
+                    self.newline(f, depth + 1)?;
+                    write!(f, "{}: ", key)?;
+                    self.write_tag(f, value, depth + 1)?;
+                    f.write_char(',')?;
+                }
+                self.newline(f, depth)?;
+                f.write_char('}')
+            }
+        }
+    }
+
+    fn write_string(&self, f: &mut Formatter<'_>, s: &str) -> fmt::Result {
+        let total = s.chars().count();
+        match self.options.max_string_len {
+            Some(max) if total > max => {
+                let truncated: String = s.chars().take(max).collect();
+                write!(f, "String({:?}… +{} chars)", truncated, total - max)
+            }
+            _ => write!(f, "String({:?})", s),
+        }
+    }
+
+    fn write_array<T: Display>(&self, f: &mut Formatter<'_>, name: &str, v: &[T]) -> fmt::Result {
+        let shown = self.options.max_array_elements.unwrap_or(usize::MAX);
+        write!(f, "{}({}) [", name, v.len())?;
+        for (i, element) in v.iter().take(shown).enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", element)?;
+        }
+        if v.len() > shown {
+            if shown > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "… +{} more", v.len() - shown)?;
+        }
+        f.write_char(']')
+    }
+
+    fn depth_exceeded(&self, depth: usize) -> bool {
+        self.options.max_depth.is_some_and(|max| depth >= max)
+    }
+
+    fn newline(&self, f: &mut Formatter<'_>, depth: usize) -> fmt::Result {
+        f.write_char('\n')?;
+        for _ in 0..depth * self.options.indent {
+            f.write_char(' ')?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_pretty_compound() {
+        let mut inner = IndexMap::new();
+        inner.insert("name".to_string(), NbtTag::String("abcdef".to_string()));
+        let mut root = IndexMap::new();
+        root.insert("inner".to_string(), NbtTag::Compound(inner));
+        root.insert("list".to_string(), NbtTag::List(vec![]));
+        let tag = NbtTag::Compound(root);
+
+        let options = PrettyOptions {
+            max_string_len: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(
+            tag.pretty(options).to_string(),
+            "Compound(2) {\n  inner: Compound(1) {\n    name: String(\"abc\"… +3 chars),\n  },\n  list: List(0) [],\n}"
+        );
+    }
+
+    #[test]
+    fn test_pretty_max_depth() {
+        let mut inner = IndexMap::new();
+        inner.insert("x".to_string(), NbtTag::Int(1));
+        let mut root = IndexMap::new();
+        root.insert("inner".to_string(), NbtTag::Compound(inner));
+        let tag = NbtTag::Compound(root);
+
+        let options = PrettyOptions {
+            max_depth: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            tag.pretty(options).to_string(),
+            "Compound(1) {\n  inner: Compound(1) { … },\n}"
+        );
+    }
+}
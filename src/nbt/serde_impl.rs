@@ -9,8 +9,15 @@
 #![cfg_attr(docsrs, doc(cfg(feature = "serde")))]
 
 use crate::nbt::NbtTag;
+use crate::nbt::encode::write_named_tag;
+use crate::nbt::mutf8::{decode_mutf8, decode_mutf8_cow};
+use crate::nbt::parse::{
+    ByteReader, ParseError, ParseErrorKind, ParseOptions, parse_named_tag_with, parse_tag_payload,
+    skip_tag_payload,
+};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize, de, ser};
+use std::borrow::Cow;
 use std::fmt;
 use thiserror::Error;
 
@@ -29,6 +36,17 @@ pub enum SerdeError {
     /// A required field was missing during deserialization.
     #[error("Missing field: {0}")]
     MissingField(String),
+    /// The binary input is malformed.
+    #[error("Parse error: {0}")]
+    Parse(#[from] ParseError),
+    /// The root tag of the binary input does not have the required name.
+    #[error("Expected root tag named '{expected}', found '{found}'")]
+    UnexpectedRootName {
+        /// The required root name.
+        expected: String,
+        /// The root name in the input.
+        found: String,
+    },
 }
 
 impl ser::Error for SerdeError {
@@ -61,6 +79,190 @@ pub fn from_nbt<'a, T: Deserialize<'a>>(tag: NbtTag) -> Result<T, SerdeError> {
     T::deserialize(NbtDeserializer::new(tag))
 }
 
+/// How the name of the root tag is treated when deserializing from bytes.
+///
+/// Conventions differ between files: `level.dat` and chunks use an empty root name,
+/// while other files and tools use names of their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RootName<'a> {
+    /// Accept any root name.
+    Any,
+    /// Fail with [`SerdeError::UnexpectedRootName`] unless the root has this name.
+    Exact(&'a str),
+}
+
+/// Serializes a value as uncompressed binary NBT with an empty root name.
+///
+/// # Errors
+///
+/// Returns a [`SerdeError`] if the type cannot be represented as NBT.
+pub fn to_bytes<T: Serialize>(value: &T) -> Result<Vec<u8>, SerdeError> {
+    to_named_bytes("", value)
+}
+
+/// Serializes a value as uncompressed binary NBT with the given root name.
+///
+/// # Errors
+///
+/// Returns a [`SerdeError`] if the type cannot be represented as NBT.
+pub fn to_named_bytes<T: Serialize>(name: &str, value: &T) -> Result<Vec<u8>, SerdeError> {
+    let tag = to_nbt(value)?;
+    let mut bytes = Vec::new();
+    write_named_tag(&mut bytes, name, &tag).map_err(|e| SerdeError::Custom(e.to_string()))?;
+    Ok(bytes)
+}
+
+/// Deserializes a type directly from uncompressed binary NBT (a named root tag),
+/// ignoring the root name.
+///
+/// Unlike parsing into an [`NbtTag`] and calling [`from_nbt`], no tag tree is built:
+/// compound keys are matched against the struct's field names on the raw bytes, fields
+/// the struct does not declare are skipped without decoding, and strings and byte
+/// arrays are borrowed from the input where possible.
+///
+/// Since unknown fields are skipped, `#[serde(deny_unknown_fields)]` has no effect.
+///
+/// # Errors
+///
+/// Returns a [`SerdeError`] if the input is malformed or does not match `T`.
+pub fn from_bytes<'de, T: Deserialize<'de>>(input: &'de [u8]) -> Result<T, SerdeError> {
+    from_named_bytes(input, RootName::Any)
+}
+
+/// Like [`from_bytes`], with control over the root name.
+///
+/// # Errors
+///
+/// Returns [`SerdeError::UnexpectedRootName`] if `root` requires a name the input does
+/// not have, or another [`SerdeError`] as for [`from_bytes`].
+pub fn from_named_bytes<'de, T: Deserialize<'de>>(
+    input: &'de [u8],
+    root: RootName,
+) -> Result<T, SerdeError> {
+    let mut reader = ByteReader::new(input);
+    let type_id = reader.read_u8()?;
+    let len = reader.read_u16()? as usize;
+    let name = reader.read_bytes(len)?;
+    if let RootName::Exact(expected) = root
+        && name != expected.as_bytes()
+    {
+        let found =
+            decode_mutf8(name).map_err(|_| ParseError::from(ParseErrorKind::InvalidString))?;
+        if found != expected {
+            return Err(SerdeError::UnexpectedRootName {
+                expected: expected.to_string(),
+                found,
+            });
+        }
+    }
+    T::deserialize(BinaryDeserializer {
+        reader: &mut reader,
+        type_id,
+        depth: 0,
+    })
+}
+
+/// Newtype name that marks a [`RawNbt`] to the serializer and deserializer.
+const RAW_NBT_TOKEN: &str = "$anvil_nbt::RawNbt";
+
+/// An NBT subtree kept verbatim inside a serde model.
+///
+/// Fields of this type take whatever tag is stored, without interpreting it, and write
+/// it back unchanged, including the exact array and number types that a plain
+/// [`NbtTag`] field would lose through serde. Use it for data the model does not cover,
+/// such as modded or future-version fields, so that a read-modify-write never drops them.
+///
+/// With [`to_nbt`] and [`from_nbt`] the tag passes through as-is. Other serde formats
+/// store it as the binary encoding of the tag (a named tag with an empty name).
+///
+/// # Examples
+///
+/// ```
+/// use anvil_nbt::nbt;
+/// use anvil_nbt::nbt::serde_impl::{RawNbt, from_nbt, to_nbt};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Entity {
+///     id: String,
+///     #[serde(rename = "UUID")]
+///     uuid: RawNbt,
+/// }
+///
+/// let tag = nbt!({ id: "minecraft:pig", UUID: [I; 1, 2, 3, 4] });
+/// let entity: Entity = from_nbt(tag.clone()).unwrap();
+/// assert_eq!(to_nbt(&entity).unwrap(), tag);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawNbt(pub NbtTag);
+
+impl From<NbtTag> for RawNbt {
+    fn from(tag: NbtTag) -> Self {
+        RawNbt(tag)
+    }
+}
+
+impl Serialize for RawNbt {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes = encode_raw(&self.0).map_err(ser::Error::custom)?;
+        serializer.serialize_newtype_struct(RAW_NBT_TOKEN, &RawBytes(&bytes))
+    }
+}
+
+impl<'de> Deserialize<'de> for RawNbt {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_newtype_struct(RAW_NBT_TOKEN, RawNbtVisitor)
+    }
+}
+
+/// Serializes a byte slice with `serialize_bytes`.
+struct RawBytes<'a>(&'a [u8]);
+
+impl Serialize for RawBytes<'_> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+struct RawNbtVisitor;
+
+impl<'de> de::Visitor<'de> for RawNbtVisitor {
+    type Value = RawNbt;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an encoded NBT tag")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<RawNbt, E> {
+        decode_raw(v).map(RawNbt).map_err(E::custom)
+    }
+
+    fn visit_newtype_struct<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<RawNbt, D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        self.visit_bytes(&bytes)
+    }
+}
+
+fn encode_raw(tag: &NbtTag) -> Result<Vec<u8>, SerdeError> {
+    let mut bytes = Vec::new();
+    write_named_tag(&mut bytes, "", tag).map_err(|e| SerdeError::Custom(e.to_string()))?;
+    Ok(bytes)
+}
+
+/// Raw strings are preserved, so a [`RawNbt`] passes them through unchanged.
+fn decode_raw(mut bytes: &[u8]) -> Result<NbtTag, SerdeError> {
+    let options = ParseOptions {
+        preserve_raw_strings: true,
+        ..ParseOptions::default()
+    };
+    parse_named_tag_with(&mut bytes, &options)
+        .map(|(_, tag)| tag)
+        .map_err(|e| SerdeError::Custom(e.to_string()))
+}
+
 /// Internal serializer for converting Rust types to [`NbtTag`].
 struct NbtSerializer;
 
@@ -159,9 +361,15 @@ impl ser::Serializer for NbtSerializer {
 
     fn serialize_newtype_struct<T: ?Sized + Serialize>(
         self,
-        _name: &'static str,
+        name: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error> {
+        if name == RAW_NBT_TOKEN {
+            return match value.serialize(self)? {
+                NbtTag::ByteArray(bytes) => decode_raw(&bytes),
+                _ => Err(SerdeError::UnsupportedType),
+            };
+        }
         value.serialize(self)
     }
 
@@ -398,6 +606,9 @@ impl<'de> de::Deserializer<'de> for NbtDeserializer {
             NbtTag::Double(v) => visitor.visit_f64(v),
             NbtTag::ByteArray(v) => visitor.visit_byte_buf(v),
             NbtTag::String(v) => visitor.visit_string(v),
+            NbtTag::RawString(_) => Err(SerdeError::Custom(
+                "Raw strings can only be deserialized into RawNbt".to_string(),
+            )),
             NbtTag::List(v) => visitor.visit_seq(SeqAccess {
                 iter: v.into_iter(),
             }),
@@ -438,9 +649,12 @@ impl<'de> de::Deserializer<'de> for NbtDeserializer {
 
     fn deserialize_newtype_struct<V: de::Visitor<'de>>(
         self,
-        _name: &'static str,
+        name: &'static str,
         visitor: V,
     ) -> Result<V::Value, Self::Error> {
+        if name == RAW_NBT_TOKEN {
+            return visitor.visit_byte_buf(encode_raw(&self.tag)?);
+        }
         visitor.visit_newtype_struct(self)
     }
 
@@ -512,7 +726,7 @@ impl<'de> de::MapAccess<'de> for MapAccess {
         match self.iter.next() {
             Some((k, v)) => {
                 self.next_value = Some(v);
-                seed.deserialize(de::value::StringDeserializer::new(k))
+                seed.deserialize(de::value::StringDeserializer::<SerdeError>::new(k))
                     .map(Some)
             }
             None => Ok(None),
@@ -541,7 +755,9 @@ impl<'de> de::EnumAccess<'de> for EnumAccess {
         self,
         seed: V,
     ) -> Result<(V::Value, Self::Variant), Self::Error> {
-        let variant = seed.deserialize(de::value::StringDeserializer::new(self.variant))?;
+        let variant = seed.deserialize(de::value::StringDeserializer::<SerdeError>::new(
+            self.variant,
+        ))?;
         Ok((variant, VariantAccess { value: self.value }))
     }
 }
@@ -597,3 +813,277 @@ impl<'de> de::VariantAccess<'de> for VariantAccess {
         }
     }
 }
+
+/// Deserializer reading one payload of a known type straight from binary NBT.
+struct BinaryDeserializer<'a, 'de> {
+    reader: &'a mut ByteReader<'de>,
+    type_id: u8,
+    /// Number of lists and compounds enclosing the payload.
+    depth: usize,
+}
+
+impl<'de> BinaryDeserializer<'_, 'de> {
+    /// Returns the depth of the payload's children, enforcing the default
+    /// [`ParseOptions::max_depth`].
+    fn enter(&self) -> Result<usize, SerdeError> {
+        if self.depth >= ParseOptions::default().max_depth {
+            return Err(ParseError::from(ParseErrorKind::DepthLimitExceeded).into());
+        }
+        Ok(self.depth + 1)
+    }
+
+    /// Reads a string, borrowing it from the input when it is also valid UTF-8.
+    fn visit_str<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        let len = self.reader.read_u16()? as usize;
+        let bytes = self.reader.read_bytes(len)?;
+        match decode_mutf8_cow(bytes)
+            .map_err(|_| SerdeError::Parse(ParseErrorKind::InvalidString.into()))?
+        {
+            Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+            Cow::Owned(s) => visitor.visit_string(s),
+        }
+    }
+
+    fn visit_compound<V: de::Visitor<'de>>(
+        self,
+        fields: Option<&'static [&'static str]>,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        if self.type_id != 10 {
+            return Err(SerdeError::ExpectedCompound);
+        }
+        let depth = self.enter()?;
+        visitor.visit_map(BinaryMapAccess {
+            reader: self.reader,
+            fields,
+            value_type: 0,
+            depth,
+        })
+    }
+}
+
+impl<'de> de::Deserializer<'de> for BinaryDeserializer<'_, 'de> {
+    type Error = SerdeError;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let depth = self.depth;
+        let children = self.enter();
+        let reader = self.reader;
+        match self.type_id {
+            0 => visitor.visit_unit(),
+            1 => visitor.visit_i8(reader.read_i8()?),
+            2 => visitor.visit_i16(reader.read_i16()?),
+            3 => visitor.visit_i32(reader.read_i32()?),
+            4 => visitor.visit_i64(reader.read_i64()?),
+            5 => visitor.visit_f32(reader.read_f32()?),
+            6 => visitor.visit_f64(reader.read_f64()?),
+            7 => {
+                let len = reader.read_i32()?.max(0) as usize;
+                visitor.visit_borrowed_bytes(reader.read_bytes(len)?)
+            }
+            8 => BinaryDeserializer {
+                reader,
+                type_id: 8,
+                depth,
+            }
+            .visit_str(visitor),
+            9 => {
+                let depth = children?;
+                let element_type = reader.read_u8()?;
+                let remaining = reader.read_i32()?.max(0) as usize;
+                visitor.visit_seq(BinarySeqAccess {
+                    reader,
+                    element_type,
+                    remaining,
+                    depth,
+                })
+            }
+            10 => BinaryDeserializer {
+                reader,
+                type_id: 10,
+                depth,
+            }
+            .visit_compound(None, visitor),
+            11 | 12 => {
+                let remaining = reader.read_i32()?.max(0) as usize;
+                visitor.visit_seq(BinarySeqAccess {
+                    reader,
+                    element_type: self.type_id - 8,
+                    remaining,
+                    depth: children?,
+                })
+            }
+            t => Err(ParseError::from(ParseErrorKind::InvalidTag(t)).into()),
+        }
+    }
+
+    fn deserialize_bool<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.type_id {
+            1 => visitor.visit_bool(self.reader.read_i8()? != 0),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.type_id {
+            0 => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.visit_compound(Some(fields), visitor)
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        if name == RAW_NBT_TOKEN {
+            let tag = parse_tag_payload(self.reader, self.type_id)?;
+            return visitor.visit_byte_buf(encode_raw(&tag)?);
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.type_id {
+            8 => {
+                let variant: String = de::Deserialize::deserialize(self)?;
+                visitor.visit_enum(EnumAccess {
+                    variant,
+                    value: None,
+                })
+            }
+            10 => {
+                // Single-entry compounds are small; decode the entry as a tag
+                match parse_tag_payload(self.reader, 10)? {
+                    NbtTag::Compound(m) if m.len() == 1 => {
+                        let (variant, value) = m.into_iter().next().unwrap();
+                        visitor.visit_enum(EnumAccess {
+                            variant,
+                            value: Some(value),
+                        })
+                    }
+                    _ => Err(de::Error::custom(
+                        "Expected compound with single key for enum",
+                    )),
+                }
+            }
+            _ => Err(de::Error::custom("Expected string or compound for enum")),
+        }
+    }
+
+    fn deserialize_ignored_any<V: de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        skip_tag_payload(self.reader, self.type_id)?;
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map identifier
+    }
+}
+
+/// Elements of a list, or of an int or long array (read as ints or longs).
+struct BinarySeqAccess<'a, 'de> {
+    reader: &'a mut ByteReader<'de>,
+    element_type: u8,
+    remaining: usize,
+    depth: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for BinarySeqAccess<'_, 'de> {
+    type Error = SerdeError;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(BinaryDeserializer {
+            reader: self.reader,
+            type_id: self.element_type,
+            depth: self.depth,
+        })
+        .map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+/// Entries of a compound. With `fields` set, entries whose key is not one of them are
+/// skipped without decoding.
+struct BinaryMapAccess<'a, 'de> {
+    reader: &'a mut ByteReader<'de>,
+    fields: Option<&'static [&'static str]>,
+    value_type: u8,
+    depth: usize,
+}
+
+impl<'de> de::MapAccess<'de> for BinaryMapAccess<'_, 'de> {
+    type Error = SerdeError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        loop {
+            let type_id = self.reader.read_u8()?;
+            if type_id == 0 {
+                return Ok(None);
+            }
+            self.value_type = type_id;
+            let Some(fields) = self.fields else {
+                let key = BinaryDeserializer {
+                    reader: self.reader,
+                    type_id: 8,
+                    depth: self.depth,
+                };
+                return seed.deserialize(key).map(Some);
+            };
+            // Field names are ASCII in practice, where MUTF-8 and UTF-8 agree, so the raw
+            // key bytes can be compared without decoding
+            let len = self.reader.read_u16()? as usize;
+            let name = self.reader.read_bytes(len)?;
+            match fields.iter().find(|field| field.as_bytes() == name) {
+                Some(field) => {
+                    let key = de::value::BorrowedStrDeserializer::<SerdeError>::new(field);
+                    return seed.deserialize(key).map(Some);
+                }
+                None => skip_tag_payload(self.reader, type_id)?,
+            }
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        seed.deserialize(BinaryDeserializer {
+            reader: self.reader,
+            type_id: self.value_type,
+            depth: self.depth,
+        })
+    }
+}
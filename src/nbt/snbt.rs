@@ -0,0 +1,727 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Stringified NBT (SNBT), the text format used by `/data` commands and datapacks.
+//!
+//! [`parse_snbt`] reads SNBT into an [`NbtTag`]; [`NbtTag::to_snbt`] and the `Display`
+//! implementation write it back out.
+//!
+//! ```
+//! use anvil_nbt::nbt::NbtTag;
+//! use anvil_nbt::nbt::snbt::parse_snbt;
+//!
+//! let tag = parse_snbt("{Count: 3b, id: \"minecraft:stone\"}").unwrap();
+//! if let NbtTag::Compound(map) = tag {
+//!     assert_eq!(map["Count"], NbtTag::Byte(3));
+//! }
+//! ```
+
+use crate::nbt::NbtTag;
+use crate::nbt::mutf8::{encode_mutf8, encode_mutf8_into};
+use crate::nbt::parse::ParseOptions;
+use indexmap::IndexMap;
+use std::fmt::{self, Display, Write};
+use thiserror::Error;
+
+/// Errors that can occur while parsing SNBT. Positions are byte offsets into the input.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SnbtError {
+    /// The input ended in the middle of a value.
+    #[error("Unexpected end of input")]
+    UnexpectedEnd,
+    /// A character that cannot appear at this point.
+    #[error("Unexpected character '{found}' at position {position}")]
+    UnexpectedChar {
+        /// The offending character.
+        found: char,
+        /// Byte offset of the character.
+        position: usize,
+    },
+    /// A number with a type suffix that is out of range for its type.
+    #[error("Invalid number '{text}' at position {position}")]
+    InvalidNumber {
+        /// The number as written.
+        text: String,
+        /// Byte offset of the number.
+        position: usize,
+    },
+    /// A list whose elements are not all of the same type.
+    #[error("List at position {position} mixes {expected} and {found} elements")]
+    MixedList {
+        /// Byte offset of the opening bracket.
+        position: usize,
+        /// Type of the list's first element.
+        expected: &'static str,
+        /// Type of the first element that differs.
+        found: &'static str,
+    },
+    /// An array prefix other than `B`, `I` or `L`.
+    #[error("Invalid array type '{found}' at position {position}")]
+    InvalidArrayType {
+        /// The prefix found before `;`.
+        found: String,
+        /// Byte offset of the prefix.
+        position: usize,
+    },
+    /// Input left over after a complete value.
+    #[error("Trailing data at position {0}")]
+    TrailingData(usize),
+    /// Lists, arrays and compounds nested deeper than the default
+    /// [`ParseOptions::max_depth`] of binary NBT.
+    #[error("Nesting too deep at position {0}")]
+    DepthLimitExceeded(usize),
+}
+
+/// Parses a single SNBT value, such as `{Items: [{id: "minecraft:dirt", Count: 1b}]}`.
+///
+/// Supported syntax:
+/// - Numbers with type suffixes: `1b`, `2s`, `3`, `4L`, `5.0f`, `6.0d` (an unsuffixed
+///   number is an `Int`, or a `Double` if it has a fraction or exponent).
+/// - `true` and `false`, which become the bytes `1` and `0`.
+/// - Quoted strings (`"..."` or `'...'` with `\` escapes) and unquoted strings. A quoted
+///   value containing a `\xNN` byte escape is a [`NbtTag::RawString`] holding those bytes
+///   and the MUTF-8 encoding of its other characters.
+/// - Compounds with quoted or unquoted keys, lists, and the typed arrays `[B; ...]`,
+///   `[I; ...]` and `[L; ...]`.
+///
+/// Nesting is limited to 512 levels, as in binary NBT.
+pub fn parse_snbt(input: &str) -> Result<NbtTag, SnbtError> {
+    let mut parser = Parser {
+        input,
+        pos: 0,
+        depth: 0,
+    };
+    let tag = parser.parse_value()?;
+    parser.skip_whitespace();
+    if parser.pos < input.len() {
+        return Err(SnbtError::TrailingData(parser.pos));
+    }
+    Ok(tag)
+}
+
+/// Parses one SNBT value at the start of `input`, returning it with the number of bytes
+/// consumed. Used to embed SNBT in other syntaxes such as NBT paths.
+pub(crate) fn parse_snbt_prefix(input: &str) -> Result<(NbtTag, usize), SnbtError> {
+    let mut parser = Parser {
+        input,
+        pos: 0,
+        depth: 0,
+    };
+    let tag = parser.parse_value()?;
+    Ok((tag, parser.pos))
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    /// Number of lists, arrays and compounds currently open.
+    depth: usize,
+}
+
+fn is_unquoted_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+')
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+    }
+
+    /// Skips whitespace and returns the next character without consuming it.
+    fn peek_token(&mut self) -> Result<char, SnbtError> {
+        self.skip_whitespace();
+        self.peek().ok_or(SnbtError::UnexpectedEnd)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), SnbtError> {
+        let found = self.peek_token()?;
+        if found != expected {
+            return Err(self.unexpected(found));
+        }
+        self.pos += expected.len_utf8();
+        Ok(())
+    }
+
+    fn unexpected(&self, found: char) -> SnbtError {
+        SnbtError::UnexpectedChar {
+            found,
+            position: self.pos,
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<NbtTag, SnbtError> {
+        match self.peek_token()? {
+            c @ ('{' | '[') => {
+                if self.depth >= ParseOptions::default().max_depth {
+                    return Err(SnbtError::DepthLimitExceeded(self.pos));
+                }
+                self.depth += 1;
+                let tag = if c == '{' {
+                    self.parse_compound()
+                } else {
+                    self.parse_list_or_array()
+                }?;
+                self.depth -= 1;
+                Ok(tag)
+            }
+            '"' | '\'' => self.parse_quoted(true),
+            c if is_unquoted_char(c) => {
+                let start = self.pos;
+                let token = self.parse_unquoted();
+                parse_scalar(token, start)
+            }
+            c => Err(self.unexpected(c)),
+        }
+    }
+
+    fn parse_unquoted(&mut self) -> &'a str {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if !is_unquoted_char(c) {
+                break;
+            }
+            self.pos += 1;
+        }
+        &self.input[start..self.pos]
+    }
+
+    /// Parses a quoted string. Byte escapes, which turn it into a raw string, are only
+    /// accepted if `allow_raw` is set.
+    fn parse_quoted(&mut self, allow_raw: bool) -> Result<NbtTag, SnbtError> {
+        let quote = self.peek().ok_or(SnbtError::UnexpectedEnd)?;
+        self.pos += 1;
+        let mut out = String::new();
+        // Set by the first byte escape; from then on every character is encoded into it
+        let mut raw: Option<Vec<u8>> = None;
+        loop {
+            let c = self.peek().ok_or(SnbtError::UnexpectedEnd)?;
+            self.pos += c.len_utf8();
+            let c = match c {
+                '\\' => {
+                    let escaped = self.peek().ok_or(SnbtError::UnexpectedEnd)?;
+                    if escaped == 'x' && allow_raw {
+                        self.pos += 1;
+                        let byte = self.parse_hex_byte()?;
+                        raw.get_or_insert_with(|| encode_mutf8(&out)).push(byte);
+                        continue;
+                    }
+                    if escaped != '\\' && escaped != '"' && escaped != '\'' {
+                        return Err(self.unexpected(escaped));
+                    }
+                    self.pos += 1;
+                    escaped
+                }
+                c if c == quote => {
+                    return Ok(match raw {
+                        Some(bytes) => NbtTag::RawString(bytes),
+                        None => NbtTag::String(out),
+                    });
+                }
+                c => c,
+            };
+            match &mut raw {
+                Some(bytes) => encode_mutf8_into(c.encode_utf8(&mut [0; 4]), bytes),
+                None => out.push(c),
+            }
+        }
+    }
+
+    /// Parses the two hex digits of a `\xNN` escape.
+    fn parse_hex_byte(&mut self) -> Result<u8, SnbtError> {
+        let mut byte = 0;
+        for _ in 0..2 {
+            let c = self.peek().ok_or(SnbtError::UnexpectedEnd)?;
+            let Some(digit) = c.to_digit(16) else {
+                return Err(self.unexpected(c));
+            };
+            self.pos += 1;
+            byte = byte << 4 | digit as u8;
+        }
+        Ok(byte)
+    }
+
+    fn parse_key(&mut self) -> Result<String, SnbtError> {
+        match self.peek_token()? {
+            '"' | '\'' => match self.parse_quoted(false)? {
+                NbtTag::String(key) => Ok(key),
+                _ => unreachable!("keys are parsed without byte escapes"),
+            },
+            c if is_unquoted_char(c) => Ok(self.parse_unquoted().to_string()),
+            c => Err(self.unexpected(c)),
+        }
+    }
+
+    fn parse_compound(&mut self) -> Result<NbtTag, SnbtError> {
+        self.expect('{')?;
+        let mut map = IndexMap::new();
+        if self.peek_token()? == '}' {
+            self.pos += 1;
+            return Ok(NbtTag::Compound(map));
+        }
+        loop {
+            let key = self.parse_key()?;
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            map.insert(key, value);
+            match self.peek_token()? {
+                ',' => self.pos += 1,
+                '}' => {
+                    self.pos += 1;
+                    return Ok(NbtTag::Compound(map));
+                }
+                c => return Err(self.unexpected(c)),
+            }
+        }
+    }
+
+    fn parse_list_or_array(&mut self) -> Result<NbtTag, SnbtError> {
+        let start = self.pos;
+        self.expect('[')?;
+
+        // A typed array starts with a single letter followed by ';'
+        let rest = &self.input[self.pos..];
+        let prefix_len = rest
+            .find(|c: char| !is_unquoted_char(c))
+            .unwrap_or(rest.len());
+        if prefix_len > 0 && rest[prefix_len..].trim_start().starts_with(';') {
+            let prefix_pos = self.pos;
+            let prefix = self.parse_unquoted();
+            self.expect(';')?;
+            return match prefix {
+                "B" => self
+                    .parse_array_elements(|tag| match tag {
+                        NbtTag::Byte(v) => Some(v),
+                        _ => None,
+                    })
+                    .map(|v| NbtTag::ByteArray(v.into_iter().map(|b| b as u8).collect())),
+                "I" => self
+                    .parse_array_elements(|tag| match tag {
+                        NbtTag::Int(v) => Some(v),
+                        _ => None,
+                    })
+                    .map(NbtTag::IntArray),
+                "L" => self
+                    .parse_array_elements(|tag| match tag {
+                        NbtTag::Long(v) => Some(v),
+                        _ => None,
+                    })
+                    .map(NbtTag::LongArray),
+                _ => Err(SnbtError::InvalidArrayType {
+                    found: prefix.to_string(),
+                    position: prefix_pos,
+                }),
+            };
+        }
+
+        let mut elements: Vec<NbtTag> = Vec::new();
+        if self.peek_token()? == ']' {
+            self.pos += 1;
+            return Ok(NbtTag::List(elements));
+        }
+        loop {
+            let element = self.parse_value()?;
+            if let Some(first) = elements.first()
+                && first.get_type_id() != element.get_type_id()
+            {
+                return Err(SnbtError::MixedList {
+                    position: start,
+                    expected: first.type_name(),
+                    found: element.type_name(),
+                });
+            }
+            elements.push(element);
+            match self.peek_token()? {
+                ',' => self.pos += 1,
+                ']' => {
+                    self.pos += 1;
+                    return Ok(NbtTag::List(elements));
+                }
+                c => return Err(self.unexpected(c)),
+            }
+        }
+    }
+
+    /// Parses the comma-separated elements of a typed array up to the closing bracket.
+    /// `extract` returns `None` for values of the wrong type.
+    fn parse_array_elements<T>(
+        &mut self,
+        extract: impl Fn(NbtTag) -> Option<T>,
+    ) -> Result<Vec<T>, SnbtError> {
+        let mut values = Vec::new();
+        if self.peek_token()? == ']' {
+            self.pos += 1;
+            return Ok(values);
+        }
+        loop {
+            self.skip_whitespace();
+            let position = self.pos;
+            let element = self.parse_value()?;
+            match extract(element) {
+                Some(value) => values.push(value),
+                None => {
+                    return Err(SnbtError::InvalidNumber {
+                        text: self.input[position..self.pos].to_string(),
+                        position,
+                    });
+                }
+            }
+            match self.peek_token()? {
+                ',' => self.pos += 1,
+                ']' => {
+                    self.pos += 1;
+                    return Ok(values);
+                }
+                c => return Err(self.unexpected(c)),
+            }
+        }
+    }
+}
+
+fn is_integer(s: &str) -> bool {
+    let digits = s.strip_prefix(['-', '+']).unwrap_or(s);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_float(s: &str) -> bool {
+    s.bytes().any(|b| b.is_ascii_digit())
+        && s.bytes()
+            .all(|b| b.is_ascii_digit() || matches!(b, b'.' | b'e' | b'E' | b'-' | b'+'))
+        && s.parse::<f64>().is_ok()
+}
+
+/// Interprets an unquoted token as a number or boolean, falling back to a string.
+fn parse_scalar(token: &str, position: usize) -> Result<NbtTag, SnbtError> {
+    let invalid = || SnbtError::InvalidNumber {
+        text: token.to_string(),
+        position,
+    };
+    match token {
+        "true" => return Ok(NbtTag::Byte(1)),
+        "false" => return Ok(NbtTag::Byte(0)),
+        _ => {}
+    }
+
+    if let Some(suffix) = token.chars().last()
+        && token.len() > 1
+    {
+        let body = &token[..token.len() - 1];
+        match suffix {
+            'b' | 'B' if is_integer(body) => {
+                return body.parse().map(NbtTag::Byte).map_err(|_| invalid());
+            }
+            's' | 'S' if is_integer(body) => {
+                return body.parse().map(NbtTag::Short).map_err(|_| invalid());
+            }
+            'l' | 'L' if is_integer(body) => {
+                return body.parse().map(NbtTag::Long).map_err(|_| invalid());
+            }
+            'f' | 'F' if is_float(body) => {
+                return body.parse().map(NbtTag::Float).map_err(|_| invalid());
+            }
+            'd' | 'D' if is_float(body) => {
+                return body.parse().map(NbtTag::Double).map_err(|_| invalid());
+            }
+            _ => {}
+        }
+    }
+
+    if is_integer(token) {
+        return token.parse().map(NbtTag::Int).map_err(|_| invalid());
+    }
+    if is_float(token) {
+        return token.parse().map(NbtTag::Double).map_err(|_| invalid());
+    }
+    Ok(NbtTag::String(token.to_string()))
+}
+
+impl NbtTag {
+    /// Formats the tag as compact SNBT, e.g. `{id:"minecraft:stone",Count:1b}`.
+    ///
+    /// The output parses back to an equal tag with [`parse_snbt`], except for NaN and
+    /// infinite floats, which SNBT cannot express, and `End` tags, which are written as
+    /// an empty string. Raw strings are written with `\xNN` escapes for their bytes
+    /// outside printable ASCII, which only this crate's parser reads back as bytes.
+    pub fn to_snbt(&self) -> String {
+        let mut out = String::new();
+        write_snbt(&mut out, self, None, 0).expect("writing to a String cannot fail");
+        out
+    }
+
+    /// Formats the tag as SNBT with compounds and nested lists spread over multiple
+    /// lines, indented by `indent` spaces per level.
+    pub fn to_snbt_pretty(&self, indent: usize) -> String {
+        let mut out = String::new();
+        write_snbt(&mut out, self, Some(indent), 0).expect("writing to a String cannot fail");
+        out
+    }
+}
+
+/// Writes the tag as SNBT. The alternate flag (`{:#}`) selects the pretty form with an
+/// indent of two spaces.
+impl Display for NbtTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let indent = if f.alternate() { Some(2) } else { None };
+        write_snbt(f, self, indent, 0)
+    }
+}
+
+/// Writes a string, quoting it unless it can be written bare without being mistaken for
+/// a number or boolean.
+fn write_string<W: Write>(out: &mut W, s: &str, is_key: bool) -> fmt::Result {
+    let bare = !s.is_empty()
+        && s.chars().all(is_unquoted_char)
+        && (is_key || matches!(parse_scalar(s, 0), Ok(NbtTag::String(_))));
+    if bare {
+        return out.write_str(s);
+    }
+    let quote = if s.contains('"') && !s.contains('\'') {
+        '\''
+    } else {
+        '"'
+    };
+    out.write_char(quote)?;
+    for c in s.chars() {
+        if c == '\\' || c == quote {
+            out.write_char('\\')?;
+        }
+        out.write_char(c)?;
+    }
+    out.write_char(quote)
+}
+
+/// Writes a raw string as a quoted string with byte escapes. At least one byte is always
+/// escaped so that it parses back as a raw string.
+fn write_raw_string<W: Write>(out: &mut W, bytes: &[u8]) -> fmt::Result {
+    let plain = |b: u8| (b' '..=b'~').contains(&b);
+    let force_first = bytes.iter().copied().all(plain);
+    out.write_char('"')?;
+    for (i, &b) in bytes.iter().enumerate() {
+        if !plain(b) || (force_first && i == 0) {
+            write!(out, "\\x{:02x}", b)?;
+        } else {
+            if b == b'"' || b == b'\\' {
+                out.write_char('\\')?;
+            }
+            out.write_char(b as char)?;
+        }
+    }
+    out.write_char('"')
+}
+
+fn write_snbt<W: Write>(
+    out: &mut W,
+    tag: &NbtTag,
+    indent: Option<usize>,
+    level: usize,
+) -> fmt::Result {
+    let separator = if indent.is_some() { ", " } else { "," };
+    match tag {
+        NbtTag::End => out.write_str("\"\""),
+        NbtTag::Byte(v) => write!(out, "{}b", v),
+        NbtTag::Short(v) => write!(out, "{}s", v),
+        NbtTag::Int(v) => write!(out, "{}", v),
+        NbtTag::Long(v) => write!(out, "{}L", v),
+        NbtTag::Float(v) => write!(out, "{:?}f", v),
+        NbtTag::Double(v) => write!(out, "{:?}d", v),
+        NbtTag::String(s) => write_string(out, s, false),
+        NbtTag::RawString(v) => write_raw_string(out, v),
+        NbtTag::ByteArray(v) => write_array(
+            out,
+            "B",
+            v.iter().map(|b| format!("{}b", *b as i8)),
+            separator,
+        ),
+        NbtTag::IntArray(v) => write_array(out, "I", v.iter().map(|i| i.to_string()), separator),
+        NbtTag::LongArray(v) => {
+            write_array(out, "L", v.iter().map(|l| format!("{}L", l)), separator)
+        }
+        NbtTag::List(elements) => {
+            let nested = elements
+                .iter()
+                .any(|e| matches!(e, NbtTag::Compound(_) | NbtTag::List(_)));
+            out.write_char('[')?;
+            write_entries(
+                out,
+                elements.iter().map(|e| (None, e)),
+                indent.filter(|_| nested),
+                separator,
+                level,
+            )?;
+            out.write_char(']')
+        }
+        NbtTag::Compound(map) => {
+            out.write_char('{')?;
+            write_entries(
+                out,
+                map.iter().map(|(k, v)| (Some(k.as_str()), v)),
+                indent,
+                separator,
+                level,
+            )?;
+            out.write_char('}')
+        }
+    }
+}
+
+/// Writes the entries of a list or compound, one per line when `indent` is set.
+fn write_entries<'a, W: Write>(
+    out: &mut W,
+    entries: impl ExactSizeIterator<Item = (Option<&'a str>, &'a NbtTag)>,
+    indent: Option<usize>,
+    separator: &str,
+    level: usize,
+) -> fmt::Result {
+    let len = entries.len();
+    for (i, (key, value)) in entries.enumerate() {
+        if let Some(indent) = indent {
+            write!(out, "\n{:width$}", "", width = indent * (level + 1))?;
+        }
+        if let Some(key) = key {
+            write_string(out, key, true)?;
+            out.write_str(if indent.is_some() { ": " } else { ":" })?;
+        }
+        write_snbt(out, value, indent, level + 1)?;
+        if i + 1 < len {
+            out.write_str(if indent.is_some() { "," } else { separator })?;
+        }
+    }
+    if let Some(indent) = indent
+        && len > 0
+    {
+        write!(out, "\n{:width$}", "", width = indent * level)?;
+    }
+    Ok(())
+}
+
+fn write_array<W: Write>(
+    out: &mut W,
+    prefix: &str,
+    values: impl Iterator<Item = String>,
+    separator: &str,
+) -> fmt::Result {
+    write!(out, "[{};", prefix)?;
+    for (i, value) in values.enumerate() {
+        if i > 0 {
+            out.write_str(separator)?;
+        } else if separator.len() > 1 {
+            out.write_char(' ')?;
+        }
+        out.write_str(&value)?;
+    }
+    out.write_char(']')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scalars() {
+        assert_eq!(parse_snbt("1b"), Ok(NbtTag::Byte(1)));
+        assert_eq!(parse_snbt("-2s"), Ok(NbtTag::Short(-2)));
+        assert_eq!(parse_snbt("3"), Ok(NbtTag::Int(3)));
+        assert_eq!(parse_snbt("4L"), Ok(NbtTag::Long(4)));
+        assert_eq!(parse_snbt("3.5f"), Ok(NbtTag::Float(3.5)));
+        assert_eq!(parse_snbt("1.5"), Ok(NbtTag::Double(1.5)));
+        assert_eq!(parse_snbt("2d"), Ok(NbtTag::Double(2.0)));
+        assert_eq!(parse_snbt("true"), Ok(NbtTag::Byte(1)));
+        assert_eq!(
+            parse_snbt("minecraft:stone"),
+            Err(SnbtError::TrailingData(9))
+        );
+        assert_eq!(parse_snbt("stone"), Ok(NbtTag::String("stone".to_string())));
+        assert_eq!(
+            parse_snbt(r#"'it\'s "quoted"'"#),
+            Ok(NbtTag::String("it's \"quoted\"".to_string()))
+        );
+        assert_eq!(
+            parse_snbt("300b"),
+            Err(SnbtError::InvalidNumber {
+                text: "300b".to_string(),
+                position: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_collections() {
+        let tag = parse_snbt(
+            r#"{ id: "minecraft:chest", "Custom Key": [I; 1, 2, 3], b: [B; 1b, -1b],
+                 l: [L;], Items: [{Slot: 0b}, {Slot: 1b}], empty: [] }"#,
+        )
+        .unwrap();
+        let NbtTag::Compound(map) = tag else {
+            panic!("expected a compound");
+        };
+        assert_eq!(map["id"], NbtTag::String("minecraft:chest".to_string()));
+        assert_eq!(map["Custom Key"], NbtTag::IntArray(vec![1, 2, 3]));
+        assert_eq!(map["b"], NbtTag::ByteArray(vec![1, 255]));
+        assert_eq!(map["l"], NbtTag::LongArray(vec![]));
+        assert_eq!(map["empty"], NbtTag::List(vec![]));
+        assert!(matches!(&map["Items"], NbtTag::List(items) if items.len() == 2));
+
+        assert_eq!(
+            parse_snbt("[1, 2b]"),
+            Err(SnbtError::MixedList {
+                position: 0,
+                expected: "Int",
+                found: "Byte",
+            })
+        );
+        assert!(matches!(
+            parse_snbt("[X; 1]"),
+            Err(SnbtError::InvalidArrayType { .. })
+        ));
+        assert_eq!(parse_snbt("{a: 1"), Err(SnbtError::UnexpectedEnd));
+        assert_eq!(
+            parse_snbt(&"[".repeat(200_000)),
+            Err(SnbtError::DepthLimitExceeded(512))
+        );
+        let nested = format!("{}{}", "[".repeat(512), "]".repeat(512));
+        assert!(parse_snbt(&nested).is_ok());
+    }
+
+    #[test]
+    fn test_to_snbt_round_trip() {
+        let text = r#"{id:"minecraft:chest","Custom Name":'say "hi"',n:"123",b:[B;1b,-1b],l:[L;],i:[I;7],f:0.1f,d:1e300d,s:-3s,Items:[{Slot:0b,Count:64L}],e:[],words:[a,"true"]}"#;
+        let tag = parse_snbt(text).unwrap();
+        assert_eq!(tag.to_snbt(), text);
+        assert_eq!(tag.to_string(), text);
+        assert_eq!(parse_snbt(&tag.to_snbt_pretty(4)).unwrap(), tag);
+    }
+
+    #[test]
+    fn test_raw_string_round_trip() {
+        for bytes in [vec![0xff, b'x'], vec![0], b"say \"hi\"".to_vec()] {
+            let tag = NbtTag::RawString(bytes);
+            assert_eq!(parse_snbt(&tag.to_snbt()), Ok(tag));
+        }
+        // Encodes to the same bytes as the empty string
+        assert_eq!(NbtTag::RawString(vec![]).to_snbt(), r#""""#);
+        assert_eq!(NbtTag::RawString(vec![0xff, b'x']).to_snbt(), r#""\xffx""#);
+        assert_eq!(
+            parse_snbt(r#""é\x00""#),
+            Ok(NbtTag::RawString(vec![0xc3, 0xa9, 0]))
+        );
+        assert!(parse_snbt(r#""\xg0""#).is_err());
+        assert!(parse_snbt(r#"{"\x00": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_to_snbt_pretty() {
+        let tag = parse_snbt("{a: 1b, list: [{x: 1}, {}], arr: [I; 1, 2], empty: {}}").unwrap();
+        assert_eq!(
+            format!("{:#}", tag),
+            "{\n  a: 1b,\n  list: [\n    {\n      x: 1\n    },\n    {}\n  ],\n  arr: [I; 1, 2],\n  empty: {}\n}"
+        );
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Tag census statistics for NBT trees.
+
+use crate::nbt::NbtTag;
+
+/// Aggregate statistics about an NBT tree, as returned by [`NbtTag::stats`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagStats {
+    /// Number of tags of each type, indexed by type ID (`counts[10]` is compounds).
+    pub counts: [usize; 13],
+    /// Total UTF-8 length of all `String` values, counting raw strings by their bytes.
+    pub string_bytes: usize,
+    /// Total UTF-8 length of all compound entry names.
+    pub key_bytes: usize,
+    /// Total number of elements across all `ByteArray`, `IntArray` and `LongArray` tags.
+    pub array_elements: usize,
+    /// Deepest nesting level reached; a lone scalar has depth 1.
+    pub max_depth: usize,
+}
+
+impl TagStats {
+    /// Returns the number of tags with the given type ID.
+    pub fn count(&self, type_id: u8) -> usize {
+        self.counts.get(type_id as usize).copied().unwrap_or(0)
+    }
+
+    /// Returns the total number of tags in the tree.
+    pub fn total_tags(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    fn visit(&mut self, tag: &NbtTag, depth: usize) {
+        self.counts[tag.get_type_id() as usize] += 1;
+        self.max_depth = self.max_depth.max(depth);
+        match tag {
+            NbtTag::String(s) => self.string_bytes += s.len(),
+            NbtTag::RawString(bytes) => self.string_bytes += bytes.len(),
+            NbtTag::ByteArray(v) => self.array_elements += v.len(),
+            NbtTag::IntArray(v) => self.array_elements += v.len(),
+            NbtTag::LongArray(v) => self.array_elements += v.len(),
+            NbtTag::List(v) => {
+                for element in v {
+                    self.visit(element, depth + 1);
+                }
+            }
+            NbtTag::Compound(map) => {
+                for (key, value) in map {
+                    self.key_bytes += key.len();
+                    self.visit(value, depth + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl NbtTag {
+    /// Walks the tree and collects per-type tag counts, string and array sizes, and the
+    /// maximum nesting depth.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use anvil_nbt::nbt::NbtTag;
+    ///
+    /// let tag = NbtTag::List(vec![NbtTag::LongArray(vec![0; 37]), NbtTag::LongArray(vec![])]);
+    /// let stats = tag.stats();
+    /// assert_eq!(stats.count(12), 2);
+    /// assert_eq!(stats.array_elements, 37);
+    /// assert_eq!(stats.max_depth, 2);
+    /// ```
+    pub fn stats(&self) -> TagStats {
+        let mut stats = TagStats::default();
+        stats.visit(self, 1);
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_stats() {
+        let mut inner = IndexMap::new();
+        inner.insert("name".to_string(), NbtTag::String("stone".to_string()));
+        inner.insert("raw".to_string(), NbtTag::RawString(vec![0xed, 0xa0, 0x80]));
+        inner.insert("ids".to_string(), NbtTag::IntArray(vec![1, 2]));
+        let mut root = IndexMap::new();
+        root.insert("x".to_string(), NbtTag::Int(0));
+        root.insert(
+            "list".to_string(),
+            NbtTag::List(vec![
+                NbtTag::Compound(inner),
+                NbtTag::Compound(IndexMap::new()),
+            ]),
+        );
+        let stats = NbtTag::Compound(root).stats();
+
+        let mut counts = [0; 13];
+        counts[3] = 1;
+        counts[8] = 2;
+        counts[9] = 1;
+        counts[10] = 3;
+        counts[11] = 1;
+        assert_eq!(stats.counts, counts);
+        assert_eq!(stats.count(8), 2);
+        assert_eq!(stats.count(99), 0);
+        assert_eq!(stats.total_tags(), 8);
+        assert_eq!(stats.string_bytes, 8);
+        assert_eq!(stats.key_bytes, 15);
+        assert_eq!(stats.array_elements, 2);
+        // Compound > List > Compound > String
+        assert_eq!(stats.max_depth, 4);
+        assert_eq!(NbtTag::Byte(1).stats().max_depth, 1);
+    }
+}
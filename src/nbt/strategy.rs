@@ -0,0 +1,194 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! [`proptest`](mod@proptest) strategies for generating [`NbtTag`] trees.
+//!
+//! Every generated tree is valid NBT: lists are homogeneous, `End` only appears as the
+//! element type of empty lists, and all strings fit in a `u16` length prefix. This makes
+//! the strategies suitable for round-trip properties (encode → parse → compare).
+//! It requires the `proptest` feature to be enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use anvil_nbt::nbt::strategy::root_compound;
+//! use proptest::strategy::{Strategy, ValueTree};
+//! use proptest::test_runner::TestRunner;
+//!
+//! let mut runner = TestRunner::default();
+//! let tree = root_compound().new_tree(&mut runner).unwrap().current();
+//! assert_eq!(tree.get_type_id(), 10);
+//! ```
+
+#![cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+
+use crate::nbt::NbtTag;
+use indexmap::IndexMap;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+/// Controls the shape of trees produced by [`tag_with`] and [`root_compound_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TagStrategyOptions {
+    /// Maximum nesting depth of lists and compounds.
+    pub max_depth: u32,
+    /// Target total number of tags in a generated tree.
+    pub desired_size: u32,
+    /// Maximum number of elements in a list, array or compound.
+    pub max_collection_len: usize,
+    /// Maximum number of characters in generated strings and compound keys.
+    pub max_string_len: usize,
+    /// Generate adversarial values: arbitrary Unicode (including NUL and supplementary
+    /// characters) in strings and keys, empty keys, and non-finite floats.
+    ///
+    /// Trees containing `NaN` do not compare equal to themselves, so round-trip
+    /// properties should compare encoded bytes rather than trees in this mode.
+    pub adversarial: bool,
+}
+
+impl Default for TagStrategyOptions {
+    fn default() -> Self {
+        TagStrategyOptions {
+            max_depth: 4,
+            desired_size: 64,
+            max_collection_len: 8,
+            max_string_len: 16,
+            adversarial: false,
+        }
+    }
+}
+
+/// Generates arbitrary tags (excluding `End`) with the default [`TagStrategyOptions`].
+pub fn any_tag() -> BoxedStrategy<NbtTag> {
+    tag_with(TagStrategyOptions::default())
+}
+
+/// Generates compound tags, the usual root of an NBT file, with the default options.
+pub fn root_compound() -> BoxedStrategy<NbtTag> {
+    root_compound_with(TagStrategyOptions::default())
+}
+
+/// Generates arbitrary tags (excluding `End`) shaped by `options`.
+pub fn tag_with(options: TagStrategyOptions) -> BoxedStrategy<NbtTag> {
+    let len = options.max_collection_len;
+    let leaf = scalar(options);
+    leaf.prop_recursive(
+        options.max_depth,
+        options.desired_size,
+        len.max(1) as u32,
+        move |inner| prop_oneof![compound_of(inner.clone(), options), list_of(inner, options)],
+    )
+    .boxed()
+}
+
+/// Generates compound tags whose entries are shaped by `options`.
+pub fn root_compound_with(options: TagStrategyOptions) -> BoxedStrategy<NbtTag> {
+    compound_of(tag_with(options), options)
+}
+
+fn compound_of(inner: BoxedStrategy<NbtTag>, options: TagStrategyOptions) -> BoxedStrategy<NbtTag> {
+    vec((key(options), inner), 0..=options.max_collection_len)
+        .prop_map(|entries| NbtTag::Compound(entries.into_iter().collect::<IndexMap<_, _>>()))
+        .boxed()
+}
+
+/// Lists must be homogeneous, so pick the element type first and generate only that.
+fn list_of(inner: BoxedStrategy<NbtTag>, options: TagStrategyOptions) -> BoxedStrategy<NbtTag> {
+    let len = 0..=options.max_collection_len;
+    let scalars = (1u8..=12)
+        .filter(|id| *id != 9 && *id != 10)
+        .map(|id| vec(of_type(id, options), len.clone()).boxed())
+        .collect::<Vec<_>>();
+    let compounds = vec(compound_of(inner.clone(), options), len.clone());
+    let nested = vec(inner.prop_map(|tag| NbtTag::List(vec![tag])), len);
+    prop_oneof![proptest::strategy::Union::new(scalars), compounds, nested]
+        .prop_map(NbtTag::List)
+        .boxed()
+}
+
+fn scalar(options: TagStrategyOptions) -> BoxedStrategy<NbtTag> {
+    let all = (1u8..=12)
+        .filter(|id| *id != 9 && *id != 10)
+        .map(|id| of_type(id, options))
+        .collect::<Vec<_>>();
+    proptest::strategy::Union::new(all).boxed()
+}
+
+/// Generates non-container tags of a single type ID.
+fn of_type(type_id: u8, options: TagStrategyOptions) -> BoxedStrategy<NbtTag> {
+    let len = 0..=options.max_collection_len;
+    match type_id {
+        1 => any::<i8>().prop_map(NbtTag::Byte).boxed(),
+        2 => any::<i16>().prop_map(NbtTag::Short).boxed(),
+        3 => any::<i32>().prop_map(NbtTag::Int).boxed(),
+        4 => any::<i64>().prop_map(NbtTag::Long).boxed(),
+        5 if options.adversarial => any::<f32>().prop_map(NbtTag::Float).boxed(),
+        5 => any::<f32>()
+            .prop_filter("finite", |v| v.is_finite())
+            .prop_map(NbtTag::Float)
+            .boxed(),
+        6 if options.adversarial => any::<f64>().prop_map(NbtTag::Double).boxed(),
+        6 => any::<f64>()
+            .prop_filter("finite", |v| v.is_finite())
+            .prop_map(NbtTag::Double)
+            .boxed(),
+        7 => vec(any::<u8>(), len).prop_map(NbtTag::ByteArray).boxed(),
+        8 => string(options, 0).prop_map(NbtTag::String).boxed(),
+        11 => vec(any::<i32>(), len).prop_map(NbtTag::IntArray).boxed(),
+        12 => vec(any::<i64>(), len).prop_map(NbtTag::LongArray).boxed(),
+        _ => unreachable!("not a scalar type ID: {}", type_id),
+    }
+}
+
+fn key(options: TagStrategyOptions) -> BoxedStrategy<String> {
+    if options.adversarial {
+        string(options, 0)
+    } else {
+        string(options, 1)
+    }
+}
+
+fn string(options: TagStrategyOptions, min_len: usize) -> BoxedStrategy<String> {
+    let max = options.max_string_len.max(min_len);
+    if options.adversarial {
+        vec(any::<char>(), min_len..=max)
+            .prop_map(|chars| chars.into_iter().collect())
+            .boxed()
+    } else {
+        proptest::string::string_regex(&format!("[A-Za-z0-9_:.]{{{},{}}}", min_len, max))
+            .expect("valid regex")
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::encode::write_named_tag;
+    use crate::nbt::parse::parse_named_tag;
+
+    proptest! {
+        #[test]
+        fn test_generated_trees_round_trip(tag in root_compound()) {
+            let mut buf = Vec::new();
+            write_named_tag(&mut buf, "root", &tag).unwrap();
+            let mut input = &buf[..];
+            let (name, decoded) = parse_named_tag(&mut input).unwrap();
+            prop_assert_eq!(name, "root");
+            prop_assert_eq!(decoded, tag);
+        }
+
+        #[test]
+        fn test_adversarial_trees_encode_stably(
+            tag in root_compound_with(TagStrategyOptions { adversarial: true, ..Default::default() })
+        ) {
+            let mut first = Vec::new();
+            write_named_tag(&mut first, "", &tag).unwrap();
+            let mut input = &first[..];
+            let (_, decoded) = parse_named_tag(&mut input).unwrap();
+            let mut second = Vec::new();
+            write_named_tag(&mut second, "", &decoded).unwrap();
+            prop_assert_eq!(first, second);
+        }
+    }
+}
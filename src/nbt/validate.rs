@@ -0,0 +1,230 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Validation of NBT trees against the constraints Minecraft relies on.
+//!
+//! The encoder happily writes any [`NbtTag`], including trees the game will refuse to
+//! load (mixed-type lists, oversized strings, chunks without position fields). These
+//! checks are opt-in and meant to run right before data is written to disk.
+
+use crate::nbt::NbtTag;
+use crate::nbt::mutf8::mutf8_encoded_len;
+use thiserror::Error;
+
+/// A constraint violation found by one of the validation functions.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ValidationError {
+    /// The root of a file or chunk must be a compound.
+    #[error("Root tag must be a compound, found {0}")]
+    RootNotCompound(&'static str),
+    /// A list contains elements of more than one type.
+    #[error("List at '{path}' mixes {expected} and {found} elements")]
+    MixedList {
+        /// Path of the offending list.
+        path: String,
+        /// Type of the list's first element.
+        expected: &'static str,
+        /// Type of the first element that differs.
+        found: &'static str,
+    },
+    /// A string value or compound key does not fit in a `u16` length prefix.
+    #[error("String at '{path}' is {len} bytes when encoded, over the 65535 byte limit")]
+    StringTooLong {
+        /// Path of the string (or of the compound entry whose key is too long).
+        path: String,
+        /// Encoded length in bytes.
+        len: usize,
+    },
+    /// An `End` tag appears as a value, which cannot be encoded meaningfully.
+    #[error("End tag used as a value at '{0}'")]
+    UnexpectedEnd(String),
+    /// A field the game requires in every chunk is absent or has the wrong type.
+    #[error("Chunk is missing required field '{0}'")]
+    MissingField(&'static str),
+    /// A chunk's stored position does not match the slot it is written to.
+    #[error("Chunk field '{field}' is {found}, expected {expected}")]
+    PositionMismatch {
+        /// Either `xPos` or `zPos`.
+        field: &'static str,
+        /// Position implied by the write.
+        expected: i32,
+        /// Position stored in the chunk.
+        found: i32,
+    },
+}
+
+/// Checks that a tree can be encoded and read back by the game: lists are homogeneous,
+/// strings and keys fit in 65535 encoded bytes, and `End` is never used as a value.
+pub fn validate_tag(tag: &NbtTag) -> Result<(), ValidationError> {
+    check(tag, &mut Vec::new())
+}
+
+/// Validates a tree that will be written as the root of a file, which must be a compound.
+pub fn validate_root(tag: &NbtTag) -> Result<(), ValidationError> {
+    if !matches!(tag, NbtTag::Compound(_)) {
+        return Err(ValidationError::RootNotCompound(tag.type_name()));
+    }
+    validate_tag(tag)
+}
+
+/// Validates a chunk root before it is written to a region at chunk coordinates `(x, z)`.
+///
+/// In addition to [`validate_root`], the chunk must carry an `Int` `DataVersion` and
+/// `xPos`/`zPos` fields that match `x` and `z`. Pre-1.18 chunks, which keep their
+/// position inside a `Level` compound, are accepted as well.
+pub fn validate_chunk(tag: &NbtTag, x: i32, z: i32) -> Result<(), ValidationError> {
+    validate_root(tag)?;
+    let NbtTag::Compound(root) = tag else {
+        unreachable!("validate_root only accepts compounds");
+    };
+    if !matches!(root.get("DataVersion"), Some(NbtTag::Int(_))) {
+        return Err(ValidationError::MissingField("DataVersion"));
+    }
+
+    let level = match root.get("Level") {
+        Some(NbtTag::Compound(level)) => level,
+        _ => root,
+    };
+    for (field, expected) in [("xPos", x), ("zPos", z)] {
+        match level.get(field) {
+            Some(NbtTag::Int(found)) if *found == expected => {}
+            Some(NbtTag::Int(found)) => {
+                return Err(ValidationError::PositionMismatch {
+                    field,
+                    expected,
+                    found: *found,
+                });
+            }
+            _ => return Err(ValidationError::MissingField(field)),
+        }
+    }
+    Ok(())
+}
+
+/// One step of the path to the tag being checked, used for error messages.
+enum Segment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+fn format_path(path: &[Segment]) -> String {
+    let mut out = String::new();
+    for segment in path {
+        match segment {
+            Segment::Key(key) => {
+                if !out.is_empty() {
+                    out.push('.');
+                }
+                out.push_str(key);
+            }
+            Segment::Index(i) => out.push_str(&format!("[{}]", i)),
+        }
+    }
+    out
+}
+
+fn check<'a>(tag: &'a NbtTag, path: &mut Vec<Segment<'a>>) -> Result<(), ValidationError> {
+    match tag {
+        NbtTag::End => Err(ValidationError::UnexpectedEnd(format_path(path))),
+        NbtTag::String(s) => check_string(s, path),
+        NbtTag::RawString(bytes) if bytes.len() > u16::MAX as usize => {
+            Err(ValidationError::StringTooLong {
+                path: format_path(path),
+                len: bytes.len(),
+            })
+        }
+        NbtTag::List(elements) => {
+            let expected = elements.first().map(NbtTag::get_type_id);
+            for (i, element) in elements.iter().enumerate() {
+                path.push(Segment::Index(i));
+                if Some(element.get_type_id()) != expected {
+                    return Err(ValidationError::MixedList {
+                        path: format_path(&path[..path.len() - 1]),
+                        expected: elements[0].type_name(),
+                        found: element.type_name(),
+                    });
+                }
+                check(element, path)?;
+                path.pop();
+            }
+            Ok(())
+        }
+        NbtTag::Compound(map) => {
+            for (key, value) in map {
+                path.push(Segment::Key(key));
+                check_string(key, path)?;
+                check(value, path)?;
+                path.pop();
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_string(s: &str, path: &[Segment]) -> Result<(), ValidationError> {
+    // MUTF-8 is at most twice as long as UTF-8, so short strings skip the exact count
+    if s.len() <= u16::MAX as usize / 2 {
+        return Ok(());
+    }
+    let len = mutf8_encoded_len(s);
+    if len > u16::MAX as usize {
+        return Err(ValidationError::StringTooLong {
+            path: format_path(path),
+            len,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_mixed_list_path() {
+        let mut inner = IndexMap::new();
+        inner.insert(
+            "Palette".to_string(),
+            NbtTag::List(vec![NbtTag::Int(1), NbtTag::Long(2)]),
+        );
+        let mut root = IndexMap::new();
+        root.insert(
+            "Sections".to_string(),
+            NbtTag::List(vec![NbtTag::Compound(inner)]),
+        );
+
+        assert_eq!(
+            validate_root(&NbtTag::Compound(root)),
+            Err(ValidationError::MixedList {
+                path: "Sections[0].Palette".to_string(),
+                expected: "Int",
+                found: "Long",
+            })
+        );
+    }
+
+    #[test]
+    fn test_chunk_fields() {
+        let mut root = IndexMap::new();
+        root.insert("DataVersion".to_string(), NbtTag::Int(3953));
+        root.insert("xPos".to_string(), NbtTag::Int(-1));
+        root.insert("zPos".to_string(), NbtTag::Int(4));
+        let chunk = NbtTag::Compound(root);
+
+        assert_eq!(validate_chunk(&chunk, -1, 4), Ok(()));
+        assert_eq!(
+            validate_chunk(&chunk, 0, 4),
+            Err(ValidationError::PositionMismatch {
+                field: "xPos",
+                expected: 0,
+                found: -1,
+            })
+        );
+        assert_eq!(
+            validate_chunk(&NbtTag::Compound(IndexMap::new()), 0, 0),
+            Err(ValidationError::MissingField("DataVersion"))
+        );
+    }
+}
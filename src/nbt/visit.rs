@@ -0,0 +1,383 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Event-based NBT parsing that never builds a tag tree.
+//!
+//! [`parse_with_visitor`] walks binary NBT and reports each value to an [`NbtVisitor`].
+//! Strings and arrays are handed out as borrowed views of the input, and subtrees the
+//! visitor is not interested in are skipped by length, so pulling a few fields out of
+//! a large chunk allocates nothing.
+
+use crate::nbt::mutf8::decode_mutf8_cow;
+use crate::nbt::parse::{ByteReader, ParseError, ParseErrorKind, ParseOptions, skip_tag_payload};
+use std::borrow::Cow;
+use std::iter::Map;
+use std::slice::ChunksExact;
+
+/// What the parser should do after a visitor callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visit {
+    /// Keep going, descending into the current value.
+    Continue,
+    /// Skip the current value (or the rest of the current list or compound) without
+    /// reporting what it contains.
+    Skip,
+    /// Stop parsing altogether.
+    Stop,
+}
+
+/// The values of an int array, decoded lazily from the input.
+pub type IntValues<'a> = Map<ChunksExact<'a, u8>, fn(&[u8]) -> i32>;
+
+/// The values of a long array, decoded lazily from the input.
+pub type LongValues<'a> = Map<ChunksExact<'a, u8>, fn(&[u8]) -> i64>;
+
+/// Callbacks for [`parse_with_visitor`].
+///
+/// Every method has a default that continues parsing, so implementations only override
+/// what they need. Returning [`Visit::Skip`] from [`visit_key`](Self::visit_key) skips
+/// that entry's value; from [`start_list`](Self::start_list) or
+/// [`start_compound`](Self::start_compound) it skips the contents, and the matching
+/// `end_` callback is not made.
+pub trait NbtVisitor<'a> {
+    /// Called with the name and type ID of the root tag and of every compound entry,
+    /// before its value.
+    fn visit_key(&mut self, _name: &str, _type_id: u8) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called for a byte value.
+    fn visit_byte(&mut self, _value: i8) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called for a short value.
+    fn visit_short(&mut self, _value: i16) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called for an int value.
+    fn visit_int(&mut self, _value: i32) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called for a long value.
+    fn visit_long(&mut self, _value: i64) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called for a float value.
+    fn visit_float(&mut self, _value: f32) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called for a double value.
+    fn visit_double(&mut self, _value: f64) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called for a string value. Borrowed from the input unless the string uses
+    /// encodings specific to Modified UTF-8.
+    fn visit_string(&mut self, _value: Cow<'a, str>) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called for a byte array, borrowed from the input.
+    fn visit_byte_array(&mut self, _value: &'a [u8]) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called for an int array.
+    fn visit_int_array(&mut self, _values: IntValues<'a>) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called for a long array.
+    fn visit_long_array(&mut self, _values: LongValues<'a>) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called at the start of a list with its element type and length.
+    fn start_list(&mut self, _element_type: u8, _len: usize) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called after the last element of a list.
+    fn end_list(&mut self) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called at the start of a compound.
+    fn start_compound(&mut self) -> Visit {
+        Visit::Continue
+    }
+
+    /// Called after the last entry of a compound.
+    fn end_compound(&mut self) -> Visit {
+        Visit::Continue
+    }
+}
+
+/// Parses a named tag from `input`, reporting its contents to `visitor`.
+///
+/// On success `input` is advanced past the tag, or up to the point where the visitor
+/// returned [`Visit::Stop`]. Nesting deeper than the default
+/// [`ParseOptions::max_depth`] fails with [`ParseErrorKind::DepthLimitExceeded`].
+///
+/// # Examples
+///
+/// ```
+/// use anvil_nbt::nbt;
+/// use anvil_nbt::nbt::encode::write_named_tag;
+/// use anvil_nbt::nbt::visit::{NbtVisitor, Visit, parse_with_visitor};
+///
+/// #[derive(Default)]
+/// struct DataVersion {
+///     depth: usize,
+///     is_version: bool,
+///     version: Option<i32>,
+/// }
+///
+/// impl NbtVisitor<'_> for DataVersion {
+///     fn visit_key(&mut self, name: &str, _type_id: u8) -> Visit {
+///         self.is_version = self.depth == 1 && name == "DataVersion";
+///         if self.depth == 1 && !self.is_version { Visit::Skip } else { Visit::Continue }
+///     }
+///     fn visit_int(&mut self, value: i32) -> Visit {
+///         self.version = Some(value);
+///         Visit::Stop
+///     }
+///     fn start_compound(&mut self) -> Visit {
+///         self.depth += 1;
+///         Visit::Continue
+///     }
+/// }
+///
+/// let mut bytes = Vec::new();
+/// let chunk = nbt!({ sections: [{ Y: 0i8 }], DataVersion: 3953 });
+/// write_named_tag(&mut bytes, "", &chunk).unwrap();
+///
+/// let mut visitor = DataVersion::default();
+/// parse_with_visitor(&mut &bytes[..], &mut visitor).unwrap();
+/// assert_eq!(visitor.version, Some(3953));
+/// ```
+pub fn parse_with_visitor<'a, V: NbtVisitor<'a>>(
+    input: &mut &'a [u8],
+    visitor: &mut V,
+) -> Result<(), ParseError> {
+    let mut reader = ByteReader::new(input);
+    let result = visit_root(&mut reader, visitor).map_err(|e| e.at(reader.offset()));
+    *input = reader.data;
+    result
+}
+
+fn visit_root<'a, V: NbtVisitor<'a>>(
+    reader: &mut ByteReader<'a>,
+    visitor: &mut V,
+) -> Result<(), ParseError> {
+    let tag_type = reader.read_u8()?;
+    if tag_type == 0 {
+        return Ok(());
+    }
+    let name = read_string(reader)?;
+    match visitor.visit_key(&name, tag_type) {
+        Visit::Continue => {
+            visit_payload(reader, tag_type, visitor, 0)?;
+        }
+        Visit::Skip => skip_tag_payload(reader, tag_type)?,
+        Visit::Stop => {}
+    }
+    Ok(())
+}
+
+/// Reports one payload, enclosed in `depth` lists and compounds, to the visitor.
+/// Returns `false` once the visitor asked to stop.
+fn visit_payload<'a, V: NbtVisitor<'a>>(
+    reader: &mut ByteReader<'a>,
+    type_id: u8,
+    visitor: &mut V,
+    depth: usize,
+) -> Result<bool, ParseError> {
+    if matches!(type_id, 9 | 10) && depth >= ParseOptions::default().max_depth {
+        return Err(ParseErrorKind::DepthLimitExceeded.into());
+    }
+    let visit = match type_id {
+        1 => visitor.visit_byte(reader.read_i8()?),
+        2 => visitor.visit_short(reader.read_i16()?),
+        3 => visitor.visit_int(reader.read_i32()?),
+        4 => visitor.visit_long(reader.read_i64()?),
+        5 => visitor.visit_float(reader.read_f32()?),
+        6 => visitor.visit_double(reader.read_f64()?),
+        7 => {
+            let len = reader.read_i32()?.max(0) as usize;
+            visitor.visit_byte_array(reader.read_bytes(len)?)
+        }
+        8 => visitor.visit_string(read_string(reader)?),
+        9 => {
+            let element_type = reader.read_u8()?;
+            let len = reader.read_i32()?.max(0) as usize;
+            match visitor.start_list(element_type, len) {
+                Visit::Continue => {
+                    for _ in 0..len {
+                        if !visit_payload(reader, element_type, visitor, depth + 1)? {
+                            return Ok(false);
+                        }
+                    }
+                    visitor.end_list()
+                }
+                Visit::Skip => {
+                    for _ in 0..len {
+                        skip_tag_payload(reader, element_type)?;
+                    }
+                    Visit::Continue
+                }
+                Visit::Stop => Visit::Stop,
+            }
+        }
+        10 => match visitor.start_compound() {
+            Visit::Continue => loop {
+                let tag_type = reader.read_u8()?;
+                if tag_type == 0 {
+                    break visitor.end_compound();
+                }
+                let name = read_string(reader)?;
+                match visitor.visit_key(&name, tag_type) {
+                    Visit::Continue => {
+                        if !visit_payload(reader, tag_type, visitor, depth + 1)? {
+                            return Ok(false);
+                        }
+                    }
+                    Visit::Skip => skip_tag_payload(reader, tag_type)?,
+                    Visit::Stop => return Ok(false),
+                }
+            },
+            Visit::Skip => {
+                skip_tag_payload(reader, 10)?;
+                Visit::Continue
+            }
+            Visit::Stop => Visit::Stop,
+        },
+        11 => {
+            let len = reader.read_i32()?.max(0) as usize;
+            let bytes = reader.read_bytes(len * 4)?;
+            let decode: fn(&[u8]) -> i32 = |b| i32::from_be_bytes(b.try_into().unwrap());
+            visitor.visit_int_array(bytes.chunks_exact(4).map(decode))
+        }
+        12 => {
+            let len = reader.read_i32()?.max(0) as usize;
+            let bytes = reader.read_bytes(len * 8)?;
+            let decode: fn(&[u8]) -> i64 = |b| i64::from_be_bytes(b.try_into().unwrap());
+            visitor.visit_long_array(bytes.chunks_exact(8).map(decode))
+        }
+        _ => return Err(ParseErrorKind::InvalidTag(type_id).into()),
+    };
+    Ok(visit != Visit::Stop)
+}
+
+/// Reads a string, borrowing it from the input when it is also valid UTF-8.
+fn read_string<'a>(reader: &mut ByteReader<'a>) -> Result<Cow<'a, str>, ParseError> {
+    let len = reader.read_u16()? as usize;
+    let bytes = reader.read_bytes(len)?;
+    decode_mutf8_cow(bytes).map_err(|_| ParseErrorKind::InvalidString.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt;
+    use crate::nbt::encode::write_named_tag;
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+        skip: &'static str,
+        stop_at: Option<i32>,
+    }
+
+    impl<'a> NbtVisitor<'a> for Recorder {
+        fn visit_key(&mut self, name: &str, type_id: u8) -> Visit {
+            self.events.push(format!("{}:{}", name, type_id));
+            if name == self.skip {
+                Visit::Skip
+            } else {
+                Visit::Continue
+            }
+        }
+        fn visit_int(&mut self, value: i32) -> Visit {
+            self.events.push(value.to_string());
+            if self.stop_at == Some(value) {
+                Visit::Stop
+            } else {
+                Visit::Continue
+            }
+        }
+        fn visit_string(&mut self, value: Cow<'a, str>) -> Visit {
+            assert!(matches!(value, Cow::Borrowed(_)));
+            self.events.push(value.into_owned());
+            Visit::Continue
+        }
+        fn visit_long_array(&mut self, values: LongValues<'a>) -> Visit {
+            self.events
+                .push(format!("{:?}", values.collect::<Vec<_>>()));
+            Visit::Continue
+        }
+        fn start_list(&mut self, element_type: u8, len: usize) -> Visit {
+            self.events.push(format!("[{}x{}", len, element_type));
+            Visit::Continue
+        }
+        fn end_list(&mut self) -> Visit {
+            self.events.push("]".to_string());
+            Visit::Continue
+        }
+        fn start_compound(&mut self) -> Visit {
+            self.events.push("{".to_string());
+            Visit::Continue
+        }
+        fn end_compound(&mut self) -> Visit {
+            self.events.push("}".to_string());
+            Visit::Continue
+        }
+    }
+
+    #[test]
+    fn test_parse_with_visitor() {
+        let tag = nbt!({ a: [1, 2], b: { c: "hi" }, d: [L; -1], e: 5 });
+        let mut bytes = Vec::new();
+        write_named_tag(&mut bytes, "root", &tag).unwrap();
+
+        let mut visitor = Recorder::default();
+        let mut input = &bytes[..];
+        parse_with_visitor(&mut input, &mut visitor).unwrap();
+        assert!(input.is_empty());
+        assert_eq!(
+            visitor.events.join(" "),
+            "root:10 { a:9 [2x3 1 2 ] b:10 { c:8 hi } d:12 [-1] e:3 5 }"
+        );
+
+        let mut visitor = Recorder {
+            skip: "b",
+            stop_at: Some(1),
+            ..Default::default()
+        };
+        parse_with_visitor(&mut &bytes[..], &mut visitor).unwrap();
+        assert_eq!(visitor.events.join(" "), "root:10 { a:9 [2x3 1");
+
+        let mut visitor = Recorder {
+            skip: "b",
+            ..Default::default()
+        };
+        parse_with_visitor(&mut &bytes[..], &mut visitor).unwrap();
+        assert!(visitor.events.contains(&"b:10".to_string()));
+        assert!(!visitor.events.contains(&"hi".to_string()));
+    }
+
+    #[test]
+    fn test_visitor_depth_limit() {
+        let mut data = vec![9, 0, 0];
+        for _ in 0..100_000 {
+            data.extend_from_slice(&[9, 0, 0, 0, 1]);
+        }
+        let err = parse_with_visitor(&mut &data[..], &mut Recorder::default()).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::DepthLimitExceeded);
+    }
+}
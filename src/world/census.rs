@@ -0,0 +1,69 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Counting blocks across an area of a world.
+
+use crate::anvil::chunk::BlockState;
+use crate::world::World;
+use crate::world::dimension::{Dimension, RegionKind};
+use crate::world::scan::{ChunkArea, ScanReport};
+use std::collections::BTreeMap;
+use std::io::Result;
+
+/// Selects which chunks and blocks [`World::block_census`] counts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CensusFilter {
+    /// The dimension to scan.
+    pub dimension: Dimension,
+    /// The chunks to scan, or `None` for every chunk in the dimension.
+    pub area: Option<ChunkArea>,
+    /// Block IDs to count, such as `"minecraft:spawner"`. Empty counts every block.
+    pub blocks: Vec<String>,
+}
+
+impl CensusFilter {
+    /// Counts every block in every chunk of `dimension`.
+    pub fn new(dimension: Dimension) -> Self {
+        CensusFilter {
+            dimension,
+            area: None,
+            blocks: Vec::new(),
+        }
+    }
+
+    fn accepts(&self, state: &BlockState) -> bool {
+        self.blocks.is_empty() || self.blocks.contains(&state.name)
+    }
+}
+
+impl World {
+    /// Counts blocks per block state in the chunks selected by `filter`.
+    ///
+    /// Counting works from section palettes: sections holding a single state, or none of
+    /// the requested blocks, are never unpacked. Chunks that cannot be read are not
+    /// counted and are listed in [`ScanReport::skipped`].
+    pub fn block_census(
+        &self,
+        filter: &CensusFilter,
+    ) -> Result<ScanReport<BTreeMap<BlockState, u64>>> {
+        let mut totals = BTreeMap::new();
+        let skipped = self.scan_chunks(
+            &filter.dimension,
+            RegionKind::Terrain,
+            filter.area,
+            |region, x, z| {
+                let Some(chunk) = region.get_chunk(x, z)? else {
+                    return Ok(());
+                };
+                for (state, count) in chunk.block_counts_matching(|s| filter.accepts(s))? {
+                    *totals.entry(state).or_insert(0) += count;
+                }
+                Ok(())
+            },
+        )?;
+        Ok(ScanReport {
+            found: totals,
+            skipped,
+        })
+    }
+}
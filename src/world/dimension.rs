@@ -0,0 +1,100 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::fmt;
+use std::path::PathBuf;
+
+/// A dimension of a world and where its data lives inside the world directory.
+///
+/// Vanilla keeps the Overworld at the root, the Nether in `DIM-1` and the End in `DIM1`.
+/// Dimensions added by datapacks live in `dimensions/<namespace>/<path>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Dimension {
+    /// `minecraft:overworld`, stored at the world root.
+    Overworld,
+    /// `minecraft:the_nether`, stored in `DIM-1`.
+    Nether,
+    /// `minecraft:the_end`, stored in `DIM1`.
+    End,
+    /// A datapack dimension, stored in `dimensions/<namespace>/<path>`.
+    Custom {
+        /// Namespace of the dimension ID, e.g. `"mypack"`.
+        namespace: String,
+        /// Path of the dimension ID, e.g. `"mining"` or `"worlds/sky"`.
+        path: String,
+    },
+}
+
+impl Dimension {
+    /// Parses a namespaced dimension ID such as `"minecraft:the_nether"`.
+    ///
+    /// IDs without a namespace default to `minecraft`, as in the game.
+    pub fn from_id(id: &str) -> Self {
+        let (namespace, path) = id.split_once(':').unwrap_or(("minecraft", id));
+        match (namespace, path) {
+            ("minecraft", "overworld") => Dimension::Overworld,
+            ("minecraft", "the_nether") => Dimension::Nether,
+            ("minecraft", "the_end") => Dimension::End,
+            _ => Dimension::Custom {
+                namespace: namespace.to_string(),
+                path: path.to_string(),
+            },
+        }
+    }
+
+    /// Returns the namespaced ID of the dimension.
+    pub fn id(&self) -> String {
+        match self {
+            Dimension::Overworld => "minecraft:overworld".to_string(),
+            Dimension::Nether => "minecraft:the_nether".to_string(),
+            Dimension::End => "minecraft:the_end".to_string(),
+            Dimension::Custom { namespace, path } => format!("{}:{}", namespace, path),
+        }
+    }
+
+    /// Returns the dimension's folder relative to the world root (empty for the Overworld).
+    pub fn relative_dir(&self) -> PathBuf {
+        match self {
+            Dimension::Overworld => PathBuf::new(),
+            Dimension::Nether => PathBuf::from("DIM-1"),
+            Dimension::End => PathBuf::from("DIM1"),
+            Dimension::Custom { namespace, path } => {
+                let mut dir = PathBuf::from("dimensions");
+                dir.push(namespace);
+                dir.extend(path.split('/'));
+                dir
+            }
+        }
+    }
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.id())
+    }
+}
+
+/// The kinds of region files kept per dimension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegionKind {
+    /// Terrain chunks in `region/`.
+    Terrain,
+    /// Entity chunks in `entities/` (1.17+).
+    Entities,
+    /// Points of interest in `poi/`.
+    Poi,
+}
+
+impl RegionKind {
+    /// All region kinds, in the order they are usually processed.
+    pub const ALL: [RegionKind; 3] = [RegionKind::Terrain, RegionKind::Entities, RegionKind::Poi];
+
+    /// Returns the name of the folder holding this kind of region file.
+    pub fn dir_name(&self) -> &'static str {
+        match self {
+            RegionKind::Terrain => "region",
+            RegionKind::Entities => "entities",
+            RegionKind::Poi => "poi",
+        }
+    }
+}
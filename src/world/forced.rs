@@ -0,0 +1,100 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::anvil::CompressionPreset;
+use crate::nbt::NbtTag;
+use crate::world::{read_gzip_nbt, write_gzip_nbt};
+use indexmap::IndexMap;
+use std::collections::BTreeSet;
+use std::io::{ErrorKind, Result};
+use std::path::Path;
+
+/// File name of the per-dimension force-loaded chunk list, stored in `<dimension>/data/`.
+pub const CHUNKS_DAT: &str = "chunks.dat";
+
+/// Packs chunk coordinates into the `long` form used by the game (`x` in the low 32 bits).
+pub fn pack_chunk_pos(x: i32, z: i32) -> i64 {
+    (x as u32 as i64) | ((z as i64) << 32)
+}
+
+/// Unpacks a `long` chunk position into `(x, z)` chunk coordinates.
+pub fn unpack_chunk_pos(packed: i64) -> (i32, i32) {
+    (packed as i32, (packed >> 32) as i32)
+}
+
+/// The force-loaded chunks of one dimension, as stored in `data/chunks.dat`.
+///
+/// Chunks added with `/forceload` stay loaded (and must survive pruning) regardless of
+/// player proximity.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ForcedChunks {
+    /// Chunk coordinates of all force-loaded chunks.
+    pub chunks: BTreeSet<(i32, i32)>,
+    /// The `DataVersion` the file was written with, if present.
+    pub data_version: Option<i32>,
+}
+
+impl ForcedChunks {
+    /// Reads a `chunks.dat` file. A missing file yields an empty list.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let root = match read_gzip_nbt(path.as_ref()) {
+            Ok((_, root)) => root,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(ForcedChunks::default()),
+            Err(e) => return Err(e),
+        };
+        Ok(Self::from_nbt(&root))
+    }
+
+    /// Extracts the force-loaded chunks from a parsed `chunks.dat` root compound.
+    pub fn from_nbt(root: &NbtTag) -> Self {
+        let mut forced = ForcedChunks::default();
+        let NbtTag::Compound(root) = root else {
+            return forced;
+        };
+        if let Some(NbtTag::Int(version)) = root.get("DataVersion") {
+            forced.data_version = Some(*version);
+        }
+        if let Some(NbtTag::Compound(data)) = root.get("data")
+            && let Some(NbtTag::LongArray(packed)) = data.get("Forced")
+        {
+            forced.chunks = packed.iter().map(|&p| unpack_chunk_pos(p)).collect();
+        }
+        forced
+    }
+
+    /// Builds the root compound stored in `chunks.dat`.
+    pub fn to_nbt(&self) -> NbtTag {
+        let packed = self
+            .chunks
+            .iter()
+            .map(|&(x, z)| pack_chunk_pos(x, z))
+            .collect();
+        let mut data = IndexMap::new();
+        data.insert("Forced".to_string(), NbtTag::LongArray(packed));
+        let mut root = IndexMap::new();
+        root.insert("data".to_string(), NbtTag::Compound(data));
+        if let Some(version) = self.data_version {
+            root.insert("DataVersion".to_string(), NbtTag::Int(version));
+        }
+        NbtTag::Compound(root)
+    }
+
+    /// Writes the list as a gzipped `chunks.dat` file.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.write_with_compression(path, CompressionPreset::default())
+    }
+
+    /// Like [`write`](Self::write), compressing with the given preset.
+    pub fn write_with_compression<P: AsRef<Path>>(
+        &self,
+        path: P,
+        preset: CompressionPreset,
+    ) -> Result<()> {
+        write_gzip_nbt(path.as_ref(), "", &self.to_nbt(), preset)
+    }
+
+    /// Returns `true` if the chunk at `(x, z)` is force-loaded.
+    pub fn contains(&self, x: i32, z: i32) -> bool {
+        self.chunks.contains(&(x, z))
+    }
+}
@@ -0,0 +1,288 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Typed, validated access to the game rules stored in `level.dat`.
+//!
+//! The game keeps every rule as a string in `Data.GameRules` and silently falls back to
+//! the default when a value does not parse, so writing `"1"` to a boolean rule quietly
+//! does nothing. [`GameRules`] checks values of known rules against their type.
+
+use crate::nbt::NbtTag;
+use crate::world::level::LevelDat;
+use indexmap::IndexMap;
+use std::fmt;
+use thiserror::Error;
+
+/// The value type of a game rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameRuleKind {
+    /// `true` or `false`.
+    Boolean,
+    /// A 32-bit integer.
+    Integer,
+}
+
+/// Vanilla game rules and their value types.
+pub const KNOWN_GAME_RULES: &[(&str, GameRuleKind)] = &[
+    ("announceAdvancements", GameRuleKind::Boolean),
+    ("blockExplosionDropDecay", GameRuleKind::Boolean),
+    ("commandBlockOutput", GameRuleKind::Boolean),
+    ("commandModificationBlockLimit", GameRuleKind::Integer),
+    ("disableElytraMovementCheck", GameRuleKind::Boolean),
+    ("disablePlayerMovementCheck", GameRuleKind::Boolean),
+    ("disableRaids", GameRuleKind::Boolean),
+    ("doDaylightCycle", GameRuleKind::Boolean),
+    ("doEntityDrops", GameRuleKind::Boolean),
+    ("doFireTick", GameRuleKind::Boolean),
+    ("doImmediateRespawn", GameRuleKind::Boolean),
+    ("doInsomnia", GameRuleKind::Boolean),
+    ("doLimitedCrafting", GameRuleKind::Boolean),
+    ("doMobLoot", GameRuleKind::Boolean),
+    ("doMobSpawning", GameRuleKind::Boolean),
+    ("doPatrolSpawning", GameRuleKind::Boolean),
+    ("doTileDrops", GameRuleKind::Boolean),
+    ("doTraderSpawning", GameRuleKind::Boolean),
+    ("doVinesSpread", GameRuleKind::Boolean),
+    ("doWardenSpawning", GameRuleKind::Boolean),
+    ("doWeatherCycle", GameRuleKind::Boolean),
+    ("drowningDamage", GameRuleKind::Boolean),
+    ("enderPearlsVanishOnDeath", GameRuleKind::Boolean),
+    ("fallDamage", GameRuleKind::Boolean),
+    ("fireDamage", GameRuleKind::Boolean),
+    ("forgiveDeadPlayers", GameRuleKind::Boolean),
+    ("freezeDamage", GameRuleKind::Boolean),
+    ("globalSoundEvents", GameRuleKind::Boolean),
+    ("keepInventory", GameRuleKind::Boolean),
+    ("lavaSourceConversion", GameRuleKind::Boolean),
+    ("logAdminCommands", GameRuleKind::Boolean),
+    ("maxCommandChainLength", GameRuleKind::Integer),
+    ("maxCommandForkCount", GameRuleKind::Integer),
+    ("maxEntityCramming", GameRuleKind::Integer),
+    ("mobExplosionDropDecay", GameRuleKind::Boolean),
+    ("mobGriefing", GameRuleKind::Boolean),
+    ("naturalRegeneration", GameRuleKind::Boolean),
+    ("playersNetherPortalCreativeDelay", GameRuleKind::Integer),
+    ("playersNetherPortalDefaultDelay", GameRuleKind::Integer),
+    ("playersSleepingPercentage", GameRuleKind::Integer),
+    ("projectilesCanBreakBlocks", GameRuleKind::Boolean),
+    ("randomTickSpeed", GameRuleKind::Integer),
+    ("reducedDebugInfo", GameRuleKind::Boolean),
+    ("sendCommandFeedback", GameRuleKind::Boolean),
+    ("showDeathMessages", GameRuleKind::Boolean),
+    ("snowAccumulationHeight", GameRuleKind::Integer),
+    ("spawnChunkRadius", GameRuleKind::Integer),
+    ("spawnRadius", GameRuleKind::Integer),
+    ("spectatorsGenerateChunks", GameRuleKind::Boolean),
+    ("tntExplodes", GameRuleKind::Boolean),
+    ("tntExplosionDropDecay", GameRuleKind::Boolean),
+    ("universalAnger", GameRuleKind::Boolean),
+    ("waterSourceConversion", GameRuleKind::Boolean),
+];
+
+/// Returns the value type of a vanilla game rule, or `None` for unknown rules.
+pub fn known_rule_kind(name: &str) -> Option<GameRuleKind> {
+    KNOWN_GAME_RULES
+        .iter()
+        .find(|(rule, _)| *rule == name)
+        .map(|(_, kind)| *kind)
+}
+
+/// A typed game rule value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameRuleValue {
+    /// The value of a boolean rule.
+    Boolean(bool),
+    /// The value of an integer rule.
+    Integer(i32),
+}
+
+impl GameRuleValue {
+    /// Returns the type of the value.
+    pub fn kind(&self) -> GameRuleKind {
+        match self {
+            GameRuleValue::Boolean(_) => GameRuleKind::Boolean,
+            GameRuleValue::Integer(_) => GameRuleKind::Integer,
+        }
+    }
+
+    /// Parses a stored string as a value of the given type, the way the game does.
+    pub fn parse(value: &str, kind: GameRuleKind) -> Option<Self> {
+        match kind {
+            GameRuleKind::Boolean => match value {
+                "true" => Some(GameRuleValue::Boolean(true)),
+                "false" => Some(GameRuleValue::Boolean(false)),
+                _ => None,
+            },
+            GameRuleKind::Integer => value.parse().ok().map(GameRuleValue::Integer),
+        }
+    }
+}
+
+impl fmt::Display for GameRuleValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameRuleValue::Boolean(v) => write!(f, "{}", v),
+            GameRuleValue::Integer(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+/// A game rule value rejected by validation.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum GameRuleError {
+    /// A typed value of the wrong type for a known rule.
+    #[error("Game rule '{rule}' expects a {expected:?} value")]
+    WrongType {
+        /// Name of the rule.
+        rule: String,
+        /// The rule's value type.
+        expected: GameRuleKind,
+    },
+    /// A stored or supplied string that does not parse as the rule's type.
+    #[error("Invalid value '{value}' for game rule '{rule}' ({expected:?})")]
+    InvalidValue {
+        /// Name of the rule.
+        rule: String,
+        /// The offending value.
+        value: String,
+        /// The rule's value type.
+        expected: GameRuleKind,
+    },
+}
+
+/// The game rules of a world, keyed by rule name.
+///
+/// Values are kept as the strings the game stores. Known rules are type-checked on
+/// write; rules added by mods or newer versions are accepted as-is.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameRules {
+    rules: IndexMap<String, String>,
+}
+
+impl GameRules {
+    /// Reads game rules from a `GameRules` compound. Non-string entries are ignored.
+    pub fn from_nbt(tag: &NbtTag) -> Self {
+        let mut rules = IndexMap::new();
+        if let NbtTag::Compound(map) = tag {
+            for (name, value) in map {
+                if let NbtTag::String(value) = value {
+                    rules.insert(name.clone(), value.clone());
+                }
+            }
+        }
+        GameRules { rules }
+    }
+
+    /// Encodes the rules as a `GameRules` compound.
+    pub fn to_nbt(&self) -> NbtTag {
+        NbtTag::Compound(
+            self.rules
+                .iter()
+                .map(|(name, value)| (name.clone(), NbtTag::String(value.clone())))
+                .collect(),
+        )
+    }
+
+    /// Iterates over all rules as stored, in file order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.rules.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Returns the stored string of a rule.
+    pub fn get_raw(&self, name: &str) -> Option<&str> {
+        self.rules.get(name).map(String::as_str)
+    }
+
+    /// Returns the typed value of a known rule, or `None` if it is unset, unknown or
+    /// does not parse.
+    pub fn get(&self, name: &str) -> Option<GameRuleValue> {
+        GameRuleValue::parse(self.get_raw(name)?, known_rule_kind(name)?)
+    }
+
+    /// Sets a rule to a typed value. Known rules must receive a value of their type.
+    pub fn set(&mut self, name: &str, value: GameRuleValue) -> Result<(), GameRuleError> {
+        if let Some(expected) = known_rule_kind(name)
+            && expected != value.kind()
+        {
+            return Err(GameRuleError::WrongType {
+                rule: name.to_string(),
+                expected,
+            });
+        }
+        self.rules.insert(name.to_string(), value.to_string());
+        Ok(())
+    }
+
+    /// Sets a rule from a string. For known rules the string must parse as the rule's
+    /// type; unknown rules are stored unchecked.
+    pub fn set_raw(&mut self, name: &str, value: &str) -> Result<(), GameRuleError> {
+        check(name, value)?;
+        self.rules.insert(name.to_string(), value.to_string());
+        Ok(())
+    }
+
+    /// Removes a rule, so the game uses its default. Returns the previous string.
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        self.rules.shift_remove(name)
+    }
+
+    /// Checks every stored value of a known rule, returning all that the game would ignore.
+    pub fn validate(&self) -> Vec<GameRuleError> {
+        self.rules
+            .iter()
+            .filter_map(|(name, value)| check(name, value).err())
+            .collect()
+    }
+}
+
+fn check(name: &str, value: &str) -> Result<(), GameRuleError> {
+    match known_rule_kind(name) {
+        Some(expected) if GameRuleValue::parse(value, expected).is_none() => {
+            Err(GameRuleError::InvalidValue {
+                rule: name.to_string(),
+                value: value.to_string(),
+                expected,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+impl LevelDat {
+    /// Returns the game rules stored in `Data.GameRules`.
+    pub fn game_rules(&self) -> GameRules {
+        match self.data().and_then(|data| data.get("GameRules")) {
+            Some(rules) => GameRules::from_nbt(rules),
+            None => GameRules::default(),
+        }
+    }
+
+    /// Replaces `Data.GameRules`, creating the `Data` compound if needed.
+    pub fn set_game_rules(&mut self, rules: &GameRules) {
+        if !matches!(self.root, NbtTag::Compound(_)) {
+            self.root = NbtTag::Compound(IndexMap::new());
+        }
+        let NbtTag::Compound(root) = &mut self.root else {
+            unreachable!();
+        };
+        let data = root
+            .entry("Data".to_string())
+            .or_insert_with(|| NbtTag::Compound(IndexMap::new()));
+        if !matches!(data, NbtTag::Compound(_)) {
+            *data = NbtTag::Compound(IndexMap::new());
+        }
+        if let NbtTag::Compound(data) = data {
+            data.insert("GameRules".to_string(), rules.to_nbt());
+        }
+    }
+
+    fn data(&self) -> Option<&IndexMap<String, NbtTag>> {
+        match &self.root {
+            NbtTag::Compound(root) => match root.get("Data") {
+                Some(NbtTag::Compound(data)) => Some(data),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::anvil::CompressionPreset;
+use crate::nbt::NbtTag;
+use crate::world::{read_gzip_nbt, write_gzip_nbt};
+use std::fs;
+use std::io::Result;
+use std::path::Path;
+
+/// File name of the primary world metadata file.
+pub const LEVEL_DAT: &str = "level.dat";
+/// File name of the backup copy kept by the game from the previous save.
+pub const LEVEL_DAT_OLD: &str = "level.dat_old";
+/// Temporary file name used while a new `level.dat` is being written.
+const LEVEL_DAT_NEW: &str = "level.dat_new";
+
+/// The contents of a world's `level.dat`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelDat {
+    /// The name of the root tag (usually empty).
+    pub name: String,
+    /// The root tag, normally a compound holding a single `Data` compound.
+    pub root: NbtTag,
+    /// Whether `level.dat` was unreadable and the data came from `level.dat_old`.
+    pub from_backup: bool,
+}
+
+impl LevelDat {
+    /// Reads `level.dat` from a world directory.
+    ///
+    /// If the primary file is missing, truncated or fails to parse (common after a crash
+    /// mid-save), `level.dat_old` is read instead and [`from_backup`](Self::from_backup)
+    /// is set. When both files are unusable the error from the primary file is returned.
+    pub fn read<P: AsRef<Path>>(world_dir: P) -> Result<Self> {
+        let dir = world_dir.as_ref();
+        match read_gzip_nbt(&dir.join(LEVEL_DAT)) {
+            Ok((name, root)) => Ok(LevelDat {
+                name,
+                root,
+                from_backup: false,
+            }),
+            Err(primary) => match read_gzip_nbt(&dir.join(LEVEL_DAT_OLD)) {
+                Ok((name, root)) => Ok(LevelDat {
+                    name,
+                    root,
+                    from_backup: true,
+                }),
+                Err(_) => Err(primary),
+            },
+        }
+    }
+
+    /// Writes this `level.dat` into a world directory the same way the game does.
+    ///
+    /// The new data is first written to `level.dat_new`. The current `level.dat` is then
+    /// rotated to `level.dat_old` and the new file renamed into place. If the current
+    /// `level.dat` is missing or unreadable it is not rotated, so a good backup is never
+    /// replaced by a broken file: an existing `level.dat_old` is left alone, and a
+    /// missing one receives a copy of the new data.
+    pub fn write<P: AsRef<Path>>(&self, world_dir: P) -> Result<()> {
+        self.write_with_compression(world_dir, CompressionPreset::default())
+    }
+
+    /// Like [`write`](Self::write), compressing with the given preset.
+    pub fn write_with_compression<P: AsRef<Path>>(
+        &self,
+        world_dir: P,
+        preset: CompressionPreset,
+    ) -> Result<()> {
+        let dir = world_dir.as_ref();
+        let new_path = dir.join(LEVEL_DAT_NEW);
+        let current_path = dir.join(LEVEL_DAT);
+        let old_path = dir.join(LEVEL_DAT_OLD);
+
+        write_gzip_nbt(&new_path, &self.name, &self.root, preset)?;
+
+        if read_gzip_nbt(&current_path).is_ok() {
+            fs::rename(&current_path, &old_path)?;
+        } else if !old_path.exists() {
+            fs::copy(&new_path, &old_path)?;
+        }
+        fs::rename(&new_path, &current_path)
+    }
+}
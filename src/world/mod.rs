@@ -0,0 +1,270 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Helpers for the files that make up a Minecraft world directory.
+
+pub mod census;
+pub mod dimension;
+pub mod forced;
+pub mod gamerules;
+pub mod level;
+pub mod player;
+pub mod scan;
+pub mod scoreboard;
+pub mod search;
+pub mod tickets;
+pub mod transaction;
+
+use crate::anvil::access::Region;
+use crate::anvil::{CompressionPreset, RegionPos, chunk_to_region};
+use crate::cancel::CancellationToken;
+use crate::nbt::NbtTag;
+#[cfg(feature = "compression")]
+use crate::nbt::encode::write_named_tag;
+use crate::nbt::io::read_compressed;
+use dimension::{Dimension, RegionKind};
+#[cfg(feature = "compression")]
+use flate2::write::GzEncoder;
+use forced::{CHUNKS_DAT, ForcedChunks};
+use level::LevelDat;
+use scoreboard::{SCOREBOARD_DAT, Scoreboard};
+#[cfg(feature = "compression")]
+use std::fs::File;
+#[cfg(feature = "compression")]
+use std::io::{BufWriter, Write};
+use std::io::{ErrorKind, Result};
+use std::path::{Path, PathBuf};
+
+/// A Minecraft world directory.
+///
+/// `World` resolves where each dimension keeps its region files, so chunk lookups work
+/// the same way for the Overworld, the Nether (`DIM-1`), the End (`DIM1`) and datapack
+/// dimensions (`dimensions/<namespace>/<path>`).
+#[derive(Debug, Clone)]
+pub struct World {
+    root: PathBuf,
+    cancellation: Option<CancellationToken>,
+}
+
+impl World {
+    /// Opens a world directory. Fails if `path` is not a directory.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let root = path.as_ref().to_path_buf();
+        if !root.is_dir() {
+            return Err(std::io::Error::new(
+                ErrorKind::NotFound,
+                format!("World directory not found: {}", root.display()),
+            ));
+        }
+        Ok(World {
+            root,
+            cancellation: None,
+        })
+    }
+
+    /// Makes scans over the world's chunks, such as [`block_census`](Self::block_census)
+    /// and [`find_items`](Self::find_items), check `token` between chunks and fail with
+    /// [`Interrupted`](ErrorKind::Interrupted) once it is cancelled. Transactions check it
+    /// between regions while committing.
+    pub fn set_cancellation(&mut self, token: Option<CancellationToken>) {
+        self.cancellation = token;
+    }
+
+    /// Returns the token set with [`set_cancellation`](Self::set_cancellation).
+    pub fn cancellation(&self) -> Option<&CancellationToken> {
+        self.cancellation.as_ref()
+    }
+
+    /// Returns the world's root directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Reads the world's `level.dat`, falling back to `level.dat_old` if needed.
+    pub fn level_dat(&self) -> Result<LevelDat> {
+        LevelDat::read(&self.root)
+    }
+
+    /// Returns the directory holding a dimension's data.
+    pub fn dimension_dir(&self, dimension: &Dimension) -> PathBuf {
+        self.root.join(dimension.relative_dir())
+    }
+
+    /// Returns the directory holding a dimension's region files of the given kind.
+    pub fn region_dir(&self, dimension: &Dimension, kind: RegionKind) -> PathBuf {
+        self.dimension_dir(dimension).join(kind.dir_name())
+    }
+
+    /// Returns the path of the `r.<x>.<z>.mca` file for region coordinates `(x, z)`.
+    pub fn region_path(
+        &self,
+        dimension: &Dimension,
+        kind: RegionKind,
+        region_x: i32,
+        region_z: i32,
+    ) -> PathBuf {
+        self.region_dir(dimension, kind)
+            .join(RegionPos::new(region_x, region_z).to_filename())
+    }
+
+    /// Returns the directory holding a dimension's saved data files (`chunks.dat`, ...).
+    pub fn data_dir(&self, dimension: &Dimension) -> PathBuf {
+        self.dimension_dir(dimension).join("data")
+    }
+
+    /// Reads the force-loaded chunks of a dimension. Dimensions without a `chunks.dat`
+    /// have none.
+    pub fn forced_chunks(&self, dimension: &Dimension) -> Result<ForcedChunks> {
+        ForcedChunks::read(self.data_dir(dimension).join(CHUNKS_DAT))
+    }
+
+    /// Writes the force-loaded chunks of a dimension, creating the `data` folder if needed.
+    pub fn write_forced_chunks(&self, dimension: &Dimension, forced: &ForcedChunks) -> Result<()> {
+        let dir = self.data_dir(dimension);
+        std::fs::create_dir_all(&dir)?;
+        forced.write(dir.join(CHUNKS_DAT))
+    }
+
+    /// Reads the world's scoreboard. Worlds without a `scoreboard.dat` have an empty one.
+    pub fn scoreboard(&self) -> Result<Scoreboard> {
+        Scoreboard::read(self.data_dir(&Dimension::Overworld).join(SCOREBOARD_DAT))
+    }
+
+    /// Writes the world's scoreboard, creating the `data` folder if needed.
+    pub fn write_scoreboard(&self, scoreboard: &Scoreboard) -> Result<()> {
+        let dir = self.data_dir(&Dimension::Overworld);
+        std::fs::create_dir_all(&dir)?;
+        scoreboard.write(dir.join(SCOREBOARD_DAT))
+    }
+
+    /// Lists the dimensions that have terrain data in this world.
+    ///
+    /// Vanilla dimensions are listed first, followed by datapack dimensions sorted by ID.
+    pub fn dimensions(&self) -> Result<Vec<Dimension>> {
+        let mut found: Vec<Dimension> = [Dimension::Overworld, Dimension::Nether, Dimension::End]
+            .into_iter()
+            .filter(|d| self.region_dir(d, RegionKind::Terrain).is_dir())
+            .collect();
+
+        let mut custom = Vec::new();
+        let dimensions_dir = self.root.join("dimensions");
+        if dimensions_dir.is_dir() {
+            for namespace in std::fs::read_dir(&dimensions_dir)? {
+                let namespace = namespace?;
+                if !namespace.file_type()?.is_dir() {
+                    continue;
+                }
+                let ns = namespace.file_name().to_string_lossy().into_owned();
+                find_custom_dimensions(&namespace.path(), &ns, String::new(), &mut custom)?;
+            }
+        }
+        custom.sort_by_key(|d| d.id());
+        found.extend(custom);
+        Ok(found)
+    }
+
+    /// Opens the region file of the given kind that contains chunk `(chunk_x, chunk_z)`.
+    ///
+    /// Returns `Ok(None)` if the region file does not exist.
+    pub fn open_region(
+        &self,
+        dimension: &Dimension,
+        kind: RegionKind,
+        chunk_x: i32,
+        chunk_z: i32,
+    ) -> Result<Option<Region>> {
+        let path = self.region_path(
+            dimension,
+            kind,
+            chunk_to_region(chunk_x),
+            chunk_to_region(chunk_z),
+        );
+        match Region::open(path) {
+            Ok(region) => Ok(Some(region)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Parses a chunk by absolute chunk coordinates in any dimension.
+    ///
+    /// Returns `Ok(None)` if the region file or the chunk does not exist.
+    pub fn get_chunk_nbt(
+        &self,
+        dimension: &Dimension,
+        kind: RegionKind,
+        chunk_x: i32,
+        chunk_z: i32,
+    ) -> Result<Option<(String, NbtTag)>> {
+        match self.open_region(dimension, kind, chunk_x, chunk_z)? {
+            Some(region) => region.get_chunk_nbt(chunk_x, chunk_z),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Recursively collects datapack dimensions below `dimensions/<namespace>`. Any folder
+/// with a `region` subfolder is a dimension; its path relative to the namespace is the ID.
+fn find_custom_dimensions(
+    dir: &Path,
+    namespace: &str,
+    prefix: String,
+    found: &mut Vec<Dimension>,
+) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        if entry.path().join(RegionKind::Terrain.dir_name()).is_dir() {
+            found.push(Dimension::Custom {
+                namespace: namespace.to_string(),
+                path: path.clone(),
+            });
+        }
+        if !RegionKind::ALL
+            .iter()
+            .any(|k| entry.file_name() == k.dir_name())
+        {
+            find_custom_dimensions(&entry.path(), namespace, path, found)?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads and parses a gzipped NBT file such as `level.dat`.
+pub(crate) fn read_gzip_nbt(path: &Path) -> Result<(String, NbtTag)> {
+    read_compressed(path)
+}
+
+/// Encodes a named tag and writes it to `path` with gzip compression.
+#[cfg(feature = "compression")]
+pub(crate) fn write_gzip_nbt(
+    path: &Path,
+    name: &str,
+    tag: &NbtTag,
+    preset: CompressionPreset,
+) -> Result<()> {
+    let mut encoder = GzEncoder::new(BufWriter::new(File::create(path)?), preset.flate2());
+    write_named_tag(&mut encoder, name, tag)?;
+    encoder.finish()?.flush()
+}
+
+/// Reports that gzipped files cannot be written without the `compression` feature.
+#[cfg(not(feature = "compression"))]
+pub(crate) fn write_gzip_nbt(
+    _path: &Path,
+    _name: &str,
+    _tag: &NbtTag,
+    _preset: CompressionPreset,
+) -> Result<()> {
+    Err(crate::anvil::unsupported_compression(
+        crate::anvil::CompressionType::Gzip,
+    ))
+}
@@ -0,0 +1,108 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::anvil::CompressionPreset;
+use crate::nbt::NbtTag;
+use crate::world::{read_gzip_nbt, write_gzip_nbt};
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+
+/// Directory inside a world that holds per-player `.dat` files.
+pub const PLAYERDATA_DIR: &str = "playerdata";
+
+/// Options controlling how [`write_player_data`] treats the existing file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerWriteOptions {
+    /// Preserve the previous contents as `<uuid>.dat_old`, as the game does.
+    pub keep_backup: bool,
+    /// Overwrite the existing file even if it cannot be parsed.
+    pub force: bool,
+    /// How to compress the new file.
+    pub compression: CompressionPreset,
+}
+
+impl Default for PlayerWriteOptions {
+    fn default() -> Self {
+        PlayerWriteOptions {
+            keep_backup: true,
+            force: false,
+            compression: CompressionPreset::default(),
+        }
+    }
+}
+
+/// Returns the path of `playerdata/<uuid>.dat` inside a world directory.
+///
+/// `uuid` must be in the hyphenated form the game uses for file names, such as
+/// `069a79f4-44e9-4726-a5be-fca90e38aaf5`; anything else fails with
+/// [`ErrorKind::InvalidInput`], so a name like `../level` cannot reach outside
+/// `playerdata`.
+pub fn player_data_path<P: AsRef<Path>>(world_dir: P, uuid: &str) -> Result<PathBuf> {
+    if !is_uuid(uuid) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Invalid player UUID: {:?}", uuid),
+        ));
+    }
+    Ok(world_dir
+        .as_ref()
+        .join(PLAYERDATA_DIR)
+        .join(format!("{}.dat", uuid)))
+}
+
+fn is_uuid(uuid: &str) -> bool {
+    uuid.len() == 36
+        && uuid.bytes().enumerate().all(|(i, b)| match i {
+            8 | 13 | 18 | 23 => b == b'-',
+            _ => b.is_ascii_hexdigit(),
+        })
+}
+
+/// Reads and parses `playerdata/<uuid>.dat` from a world directory.
+pub fn read_player_data<P: AsRef<Path>>(world_dir: P, uuid: &str) -> Result<(String, NbtTag)> {
+    read_gzip_nbt(&player_data_path(world_dir, uuid)?)
+}
+
+/// Writes `playerdata/<uuid>.dat` into a world directory.
+///
+/// If a file already exists it must parse as valid NBT, otherwise the write is refused
+/// with [`ErrorKind::InvalidData`] unless [`PlayerWriteOptions::force`] is set. This
+/// guards against blindly replacing data that the caller never managed to read.
+///
+/// With [`PlayerWriteOptions::keep_backup`] the previous file is kept as `<uuid>.dat_old`,
+/// following the same rules as [`LevelDat::write`](crate::world::level::LevelDat::write):
+/// a forced write over an unreadable file leaves an existing backup alone, and a missing
+/// backup receives a copy of the new data.
+pub fn write_player_data<P: AsRef<Path>>(
+    world_dir: P,
+    uuid: &str,
+    name: &str,
+    tag: &NbtTag,
+    options: &PlayerWriteOptions,
+) -> Result<()> {
+    let path = player_data_path(&world_dir, uuid)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let readable = read_gzip_nbt(&path).is_ok();
+    if path.exists() && !readable && !options.force {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Existing player data is unreadable; refusing to overwrite",
+        ));
+    }
+
+    let tmp_path = path.with_extension("dat_tmp");
+    write_gzip_nbt(&tmp_path, name, tag, options.compression)?;
+    if options.keep_backup {
+        let old_path = path.with_extension("dat_old");
+        if readable {
+            fs::rename(&path, &old_path)?;
+        } else if !old_path.exists() {
+            fs::copy(&tmp_path, &old_path)?;
+        }
+    }
+    fs::rename(&tmp_path, &path)
+}
@@ -0,0 +1,159 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Walking the chunks of a dimension, optionally limited to an area.
+
+use crate::anvil::access::Region;
+use crate::anvil::{HEADER_SIZE, RegionPos, block_to_chunk, chunk_to_region};
+use crate::cancel;
+use crate::world::World;
+use crate::world::dimension::{Dimension, RegionKind};
+use std::io::{Error, Result};
+use std::path::PathBuf;
+
+/// An inclusive rectangle of chunk coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkArea {
+    /// Smallest chunk X coordinate.
+    pub min_x: i32,
+    /// Smallest chunk Z coordinate.
+    pub min_z: i32,
+    /// Largest chunk X coordinate.
+    pub max_x: i32,
+    /// Largest chunk Z coordinate.
+    pub max_z: i32,
+}
+
+impl ChunkArea {
+    /// Creates the area spanning two corner chunks, in any order.
+    pub fn new(x1: i32, z1: i32, x2: i32, z2: i32) -> Self {
+        ChunkArea {
+            min_x: x1.min(x2),
+            min_z: z1.min(z2),
+            max_x: x1.max(x2),
+            max_z: z1.max(z2),
+        }
+    }
+
+    /// Creates the area of chunks covering block coordinates `(x1, z1)` to `(x2, z2)`.
+    pub fn from_blocks(x1: i32, z1: i32, x2: i32, z2: i32) -> Self {
+        Self::new(
+            block_to_chunk(x1),
+            block_to_chunk(z1),
+            block_to_chunk(x2),
+            block_to_chunk(z2),
+        )
+    }
+
+    /// Returns `true` if chunk `(x, z)` lies inside the area.
+    pub fn contains(&self, x: i32, z: i32) -> bool {
+        (self.min_x..=self.max_x).contains(&x) && (self.min_z..=self.max_z).contains(&z)
+    }
+
+    /// Returns `true` if region `(x, z)` overlaps the area.
+    pub fn overlaps_region(&self, x: i32, z: i32) -> bool {
+        (chunk_to_region(self.min_x)..=chunk_to_region(self.max_x)).contains(&x)
+            && (chunk_to_region(self.min_z)..=chunk_to_region(self.max_z)).contains(&z)
+    }
+}
+
+/// A file or chunk that a scan could not read and left out of its results.
+#[derive(Debug)]
+pub struct ScanFailure {
+    /// The region file, or other file such as a player's `.dat`, that failed.
+    pub path: PathBuf,
+    /// Absolute coordinates of the failed chunk, or `None` if the whole file failed.
+    pub chunk: Option<(i32, i32)>,
+    /// Why it could not be read.
+    pub error: Error,
+}
+
+/// The results of a scan over a world, along with what it had to skip.
+///
+/// Real worlds routinely contain a truncated region or a corrupt chunk, so scans carry on
+/// past them and list them in `skipped` instead of failing as a whole.
+#[derive(Debug)]
+pub struct ScanReport<T> {
+    /// What the scan found in the data it could read.
+    pub found: T,
+    /// Files and chunks that could not be read, in scan order.
+    pub skipped: Vec<ScanFailure>,
+}
+
+impl World {
+    /// Calls `visit` with the region and absolute coordinates of every chunk slot of the
+    /// given kind inside `area` (or the whole dimension). Missing region folders are
+    /// treated as empty; `visit` decides how to read each slot. The world's cancellation
+    /// token is checked before each slot.
+    ///
+    /// Region files shorter than a header are treated as empty, as the game does. Regions
+    /// that fail to open and slots for which `visit` fails are returned as failures
+    /// instead of ending the scan.
+    pub(crate) fn scan_chunks<F>(
+        &self,
+        dimension: &Dimension,
+        kind: RegionKind,
+        area: Option<ChunkArea>,
+        mut visit: F,
+    ) -> Result<Vec<ScanFailure>>
+    where
+        F: FnMut(&Region, i32, i32) -> Result<()>,
+    {
+        let mut failures = Vec::new();
+        let dir = self.region_dir(dimension, kind);
+        if !dir.is_dir() {
+            return Ok(failures);
+        }
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let Some(RegionPos {
+                x: region_x,
+                z: region_z,
+            }) = RegionPos::from_filename(&name.to_string_lossy())
+            else {
+                continue;
+            };
+            if area.is_some_and(|a| !a.overlaps_region(region_x, region_z)) {
+                continue;
+            }
+
+            let path = entry.path();
+            let opened = entry.metadata().and_then(|metadata| {
+                if metadata.len() < HEADER_SIZE as u64 {
+                    return Ok(None);
+                }
+                Region::open(&path).map(Some)
+            });
+            let region = match opened {
+                Ok(Some(region)) => region,
+                Ok(None) => continue,
+                Err(error) => {
+                    failures.push(ScanFailure {
+                        path,
+                        chunk: None,
+                        error,
+                    });
+                    continue;
+                }
+            };
+            for rel_z in 0..32 {
+                for rel_x in 0..32 {
+                    let (x, z) = (region_x * 32 + rel_x, region_z * 32 + rel_z);
+                    if area.is_some_and(|a| !a.contains(x, z)) {
+                        continue;
+                    }
+                    cancel::check(self.cancellation())?;
+                    if let Err(error) = visit(&region, x, z) {
+                        failures.push(ScanFailure {
+                            path: path.clone(),
+                            chunk: Some((x, z)),
+                            error,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(failures)
+    }
+}
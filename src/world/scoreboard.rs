@@ -0,0 +1,358 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Typed access to `data/scoreboard.dat`.
+
+use crate::anvil::CompressionPreset;
+use crate::nbt::NbtTag;
+use crate::world::{read_gzip_nbt, write_gzip_nbt};
+use indexmap::IndexMap;
+use std::io::{ErrorKind, Result};
+use std::path::Path;
+use thiserror::Error;
+
+/// File name of the scoreboard, stored in the Overworld's `data/` folder.
+pub const SCOREBOARD_DAT: &str = "scoreboard.dat";
+
+/// Errors returned by the scoreboard editing helpers.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ScoreboardError {
+    /// An objective with this name already exists.
+    #[error("Objective '{0}' already exists")]
+    DuplicateObjective(String),
+    /// No objective with this name exists.
+    #[error("Unknown objective '{0}'")]
+    UnknownObjective(String),
+    /// Objective names must be non-empty and contain no whitespace.
+    #[error("Invalid objective name '{0}'")]
+    InvalidName(String),
+}
+
+/// A scoreboard objective.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Objective {
+    /// The objective's internal name, used by commands (`Name`).
+    pub name: String,
+    /// The criterion that updates scores, e.g. `"dummy"` or `"deathCount"` (`CriteriaName`).
+    pub criteria: String,
+    /// The display name as stored by the game, a text component (`DisplayName`).
+    pub display_name: NbtTag,
+    /// `"integer"` or `"hearts"` (`RenderType`).
+    pub render_type: String,
+    /// Any other fields, kept as they were read.
+    pub extra: IndexMap<String, NbtTag>,
+}
+
+/// A score held by a player or entity for one objective.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Score {
+    /// The score holder: a player name or an entity UUID (`Name`).
+    pub holder: String,
+    /// Name of the objective (`Objective`).
+    pub objective: String,
+    /// The score value (`Score`).
+    pub score: i32,
+    /// Whether `/trigger` is disabled for this score (`Locked`).
+    pub locked: bool,
+    /// Any other fields, kept as they were read.
+    pub extra: IndexMap<String, NbtTag>,
+}
+
+/// The contents of `scoreboard.dat`.
+///
+/// Objectives and scores are decoded; teams and everything else are kept as raw tags so
+/// that reading and writing a scoreboard never loses data.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scoreboard {
+    /// All objectives (`Objectives`).
+    pub objectives: Vec<Objective>,
+    /// All scores (`PlayerScores`).
+    pub scores: Vec<Score>,
+    /// Display slots mapped to objective names (`DisplaySlots`).
+    pub display_slots: IndexMap<String, String>,
+    /// Other entries of the `data` compound, such as `Teams`.
+    pub extra: IndexMap<String, NbtTag>,
+    /// The `DataVersion` the file was written with, if present.
+    pub data_version: Option<i32>,
+}
+
+/// Removes `key` from `map` if it holds a string.
+fn take_string(map: &mut IndexMap<String, NbtTag>, key: &str) -> String {
+    match map.shift_remove(key) {
+        Some(NbtTag::String(s)) => s,
+        Some(other) => {
+            map.insert(key.to_string(), other);
+            String::new()
+        }
+        None => String::new(),
+    }
+}
+
+impl Objective {
+    fn from_nbt(tag: NbtTag) -> Option<Self> {
+        let NbtTag::Compound(mut map) = tag else {
+            return None;
+        };
+        Some(Objective {
+            name: take_string(&mut map, "Name"),
+            criteria: take_string(&mut map, "CriteriaName"),
+            display_name: map
+                .shift_remove("DisplayName")
+                .unwrap_or(NbtTag::String(String::new())),
+            render_type: take_string(&mut map, "RenderType"),
+            extra: map,
+        })
+    }
+
+    fn to_nbt(&self) -> NbtTag {
+        let mut map = IndexMap::new();
+        map.insert("Name".to_string(), NbtTag::String(self.name.clone()));
+        map.insert(
+            "CriteriaName".to_string(),
+            NbtTag::String(self.criteria.clone()),
+        );
+        map.insert("DisplayName".to_string(), self.display_name.clone());
+        map.insert(
+            "RenderType".to_string(),
+            NbtTag::String(self.render_type.clone()),
+        );
+        map.extend(self.extra.clone());
+        NbtTag::Compound(map)
+    }
+}
+
+impl Score {
+    fn from_nbt(tag: NbtTag) -> Option<Self> {
+        let NbtTag::Compound(mut map) = tag else {
+            return None;
+        };
+        let score = match map.shift_remove("Score") {
+            Some(NbtTag::Int(v)) => v,
+            _ => 0,
+        };
+        let locked = matches!(map.shift_remove("Locked"), Some(NbtTag::Byte(v)) if v != 0);
+        Some(Score {
+            holder: take_string(&mut map, "Name"),
+            objective: take_string(&mut map, "Objective"),
+            score,
+            locked,
+            extra: map,
+        })
+    }
+
+    fn to_nbt(&self) -> NbtTag {
+        let mut map = IndexMap::new();
+        map.insert("Score".to_string(), NbtTag::Int(self.score));
+        map.insert("Name".to_string(), NbtTag::String(self.holder.clone()));
+        map.insert(
+            "Objective".to_string(),
+            NbtTag::String(self.objective.clone()),
+        );
+        map.insert("Locked".to_string(), NbtTag::Byte(self.locked as i8));
+        map.extend(self.extra.clone());
+        NbtTag::Compound(map)
+    }
+}
+
+impl Scoreboard {
+    /// Reads a `scoreboard.dat` file. A missing file yields an empty scoreboard.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
+        match read_gzip_nbt(path.as_ref()) {
+            Ok((_, root)) => Ok(Self::from_nbt(root)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Scoreboard::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Decodes a parsed `scoreboard.dat` root compound.
+    pub fn from_nbt(root: NbtTag) -> Self {
+        let mut scoreboard = Scoreboard::default();
+        let NbtTag::Compound(mut root) = root else {
+            return scoreboard;
+        };
+        if let Some(NbtTag::Int(version)) = root.get("DataVersion") {
+            scoreboard.data_version = Some(*version);
+        }
+        let Some(NbtTag::Compound(mut data)) = root.shift_remove("data") else {
+            return scoreboard;
+        };
+
+        if let Some(NbtTag::List(objectives)) = data.shift_remove("Objectives") {
+            scoreboard.objectives = objectives
+                .into_iter()
+                .filter_map(Objective::from_nbt)
+                .collect();
+        }
+        if let Some(NbtTag::List(scores)) = data.shift_remove("PlayerScores") {
+            scoreboard.scores = scores.into_iter().filter_map(Score::from_nbt).collect();
+        }
+        match data.shift_remove("DisplaySlots") {
+            Some(NbtTag::Compound(slots)) => {
+                for (slot, objective) in slots {
+                    if let NbtTag::String(objective) = objective {
+                        scoreboard.display_slots.insert(slot, objective);
+                    }
+                }
+            }
+            Some(other) => {
+                data.insert("DisplaySlots".to_string(), other);
+            }
+            None => {}
+        }
+        scoreboard.extra = data;
+        scoreboard
+    }
+
+    /// Encodes the scoreboard as a `scoreboard.dat` root compound.
+    pub fn to_nbt(&self) -> NbtTag {
+        let mut data = IndexMap::new();
+        data.insert(
+            "Objectives".to_string(),
+            NbtTag::List(self.objectives.iter().map(Objective::to_nbt).collect()),
+        );
+        data.insert(
+            "PlayerScores".to_string(),
+            NbtTag::List(self.scores.iter().map(Score::to_nbt).collect()),
+        );
+        if !self.display_slots.is_empty() {
+            let slots = self
+                .display_slots
+                .iter()
+                .map(|(slot, objective)| (slot.clone(), NbtTag::String(objective.clone())))
+                .collect();
+            data.insert("DisplaySlots".to_string(), NbtTag::Compound(slots));
+        }
+        data.extend(self.extra.clone());
+
+        let mut root = IndexMap::new();
+        root.insert("data".to_string(), NbtTag::Compound(data));
+        if let Some(version) = self.data_version {
+            root.insert("DataVersion".to_string(), NbtTag::Int(version));
+        }
+        NbtTag::Compound(root)
+    }
+
+    /// Writes the scoreboard as a gzipped `scoreboard.dat` file.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.write_with_compression(path, CompressionPreset::default())
+    }
+
+    /// Like [`write`](Self::write), compressing with the given preset.
+    pub fn write_with_compression<P: AsRef<Path>>(
+        &self,
+        path: P,
+        preset: CompressionPreset,
+    ) -> Result<()> {
+        write_gzip_nbt(path.as_ref(), "", &self.to_nbt(), preset)
+    }
+
+    /// Returns the objective with the given name.
+    pub fn objective(&self, name: &str) -> Option<&Objective> {
+        self.objectives.iter().find(|o| o.name == name)
+    }
+
+    /// Adds a new objective with `"integer"` rendering.
+    ///
+    /// The display name is stored as a plain text component, like
+    /// `/scoreboard objectives add <name> <criteria> "<display name>"` would.
+    pub fn create_objective(
+        &mut self,
+        name: &str,
+        criteria: &str,
+        display_name: &str,
+    ) -> std::result::Result<&mut Objective, ScoreboardError> {
+        if name.is_empty() || name.chars().any(char::is_whitespace) {
+            return Err(ScoreboardError::InvalidName(name.to_string()));
+        }
+        if self.objective(name).is_some() {
+            return Err(ScoreboardError::DuplicateObjective(name.to_string()));
+        }
+        self.objectives.push(Objective {
+            name: name.to_string(),
+            criteria: criteria.to_string(),
+            display_name: NbtTag::String(json_string(display_name)),
+            render_type: "integer".to_string(),
+            extra: IndexMap::new(),
+        });
+        Ok(self.objectives.last_mut().unwrap())
+    }
+
+    /// Removes an objective together with all its scores and display slots.
+    pub fn remove_objective(&mut self, name: &str) -> std::result::Result<(), ScoreboardError> {
+        let before = self.objectives.len();
+        self.objectives.retain(|o| o.name != name);
+        if self.objectives.len() == before {
+            return Err(ScoreboardError::UnknownObjective(name.to_string()));
+        }
+        self.scores.retain(|s| s.objective != name);
+        self.display_slots.retain(|_, objective| objective != name);
+        Ok(())
+    }
+
+    /// Returns the score of `holder` for `objective`, if set.
+    pub fn score(&self, holder: &str, objective: &str) -> Option<i32> {
+        self.scores
+            .iter()
+            .find(|s| s.holder == holder && s.objective == objective)
+            .map(|s| s.score)
+    }
+
+    /// Sets the score of `holder` for an existing objective, adding it if needed.
+    pub fn set_score(
+        &mut self,
+        holder: &str,
+        objective: &str,
+        value: i32,
+    ) -> std::result::Result<(), ScoreboardError> {
+        if self.objective(objective).is_none() {
+            return Err(ScoreboardError::UnknownObjective(objective.to_string()));
+        }
+        match self
+            .scores
+            .iter_mut()
+            .find(|s| s.holder == holder && s.objective == objective)
+        {
+            Some(score) => score.score = value,
+            None => self.scores.push(Score {
+                holder: holder.to_string(),
+                objective: objective.to_string(),
+                score: value,
+                locked: false,
+                extra: IndexMap::new(),
+            }),
+        }
+        Ok(())
+    }
+
+    /// Removes the score of `holder` for `objective`. Returns `true` if it existed.
+    pub fn reset_score(&mut self, holder: &str, objective: &str) -> bool {
+        let before = self.scores.len();
+        self.scores
+            .retain(|s| s.holder != holder || s.objective != objective);
+        self.scores.len() != before
+    }
+
+    /// Removes every score held by `holder`. Returns the number of scores removed.
+    pub fn remove_player(&mut self, holder: &str) -> usize {
+        let before = self.scores.len();
+        self.scores.retain(|s| s.holder != holder);
+        before - self.scores.len()
+    }
+}
+
+/// Quotes a string as a JSON string literal, which is a valid text component.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,318 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Searching a world for block entities and items.
+
+use crate::cancel;
+use crate::nbt::NbtTag;
+use crate::world::World;
+use crate::world::dimension::{Dimension, RegionKind};
+use crate::world::player::{PLAYERDATA_DIR, read_player_data};
+use crate::world::scan::{ChunkArea, ScanFailure, ScanReport};
+use std::io::Result;
+
+/// Chunk fields holding block entities, in the current and pre-1.18 layouts.
+const BLOCK_ENTITY_FIELDS: [&str; 2] = ["block_entities", "Level.TileEntities"];
+
+/// Chunk fields holding entities: entity chunks (1.17+) and pre-1.17 terrain chunks.
+const ENTITY_FIELDS: [&str; 2] = ["Entities", "Level.Entities"];
+
+/// A block entity found by [`World::find_block_entities`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockEntityMatch {
+    /// Coordinates of the chunk holding the block entity.
+    pub chunk: (i32, i32),
+    /// Block position from the entry's `x`, `y` and `z` fields.
+    pub position: (i32, i32, i32),
+    /// The full block entity compound.
+    pub entry: NbtTag,
+}
+
+impl World {
+    /// Finds all block entities with the given ID (such as `"minecraft:chest"`) in a
+    /// dimension, limited to `area` if given.
+    ///
+    /// Only the block entity list of each chunk is decoded; terrain and entity data are
+    /// skipped. IDs without a namespace are taken to be in `minecraft`. Chunks that
+    /// cannot be read are listed in [`ScanReport::skipped`] and the search carries on.
+    pub fn find_block_entities(
+        &self,
+        dimension: &Dimension,
+        id: &str,
+        area: Option<ChunkArea>,
+    ) -> Result<ScanReport<Vec<BlockEntityMatch>>> {
+        let id = if id.contains(':') {
+            id.to_string()
+        } else {
+            format!("minecraft:{}", id)
+        };
+        let mut found = Vec::new();
+        let skipped = self.scan_chunks(dimension, RegionKind::Terrain, area, |region, x, z| {
+            let Some(fields) = region.get_chunk_fields(x, z, &BLOCK_ENTITY_FIELDS)? else {
+                return Ok(());
+            };
+            for list in fields.into_iter().flatten() {
+                let NbtTag::List(entries) = list else {
+                    continue;
+                };
+                for entry in entries {
+                    let NbtTag::Compound(map) = &entry else {
+                        continue;
+                    };
+                    if !matches!(map.get("id"), Some(NbtTag::String(entry_id)) if *entry_id == id) {
+                        continue;
+                    }
+                    found.push(BlockEntityMatch {
+                        chunk: (x, z),
+                        position: block_position(&entry),
+                        entry,
+                    });
+                }
+            }
+            Ok(())
+        })?;
+        Ok(ScanReport { found, skipped })
+    }
+}
+
+/// What holds an item found by [`World::find_items`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ItemOwner {
+    /// A player, identified by the UUID their `playerdata` file is named after.
+    Player {
+        /// The player's UUID in hyphenated form.
+        uuid: String,
+    },
+    /// An entity, such as a dropped item, an item frame or a chest minecart.
+    Entity {
+        /// Dimension of the entity.
+        dimension: Dimension,
+        /// Coordinates of the chunk storing the entity.
+        chunk: (i32, i32),
+        /// The entity ID, e.g. `"minecraft:item_frame"`.
+        id: String,
+    },
+    /// A block entity, such as a chest or a shulker box placed in the world.
+    BlockEntity {
+        /// Dimension of the block entity.
+        dimension: Dimension,
+        /// Block position of the block entity.
+        position: (i32, i32, i32),
+        /// The block entity ID, e.g. `"minecraft:chest"`.
+        id: String,
+    },
+}
+
+/// An item stack found by [`World::find_items`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemMatch {
+    /// The player, entity or block entity holding the item.
+    pub owner: ItemOwner,
+    /// Path of the stack inside the owner, e.g. `Inventory[3]` or
+    /// `Items[0].components.minecraft:container[2].item` for an item in a shulker box.
+    pub path: String,
+    /// The item stack compound.
+    pub item: NbtTag,
+}
+
+/// Returns `true` if `tag` looks like an item stack: a compound with a string `id` and a
+/// `count` (1.20.5+) or `Count` field.
+fn is_item_stack(tag: &NbtTag) -> bool {
+    let NbtTag::Compound(map) = tag else {
+        return false;
+    };
+    matches!(map.get("id"), Some(NbtTag::String(_)))
+        && (map.contains_key("count") || map.contains_key("Count"))
+}
+
+/// Collects every item stack below `tag` accepted by `predicate`, including items nested
+/// in other items (shulker boxes, bundles) in both the component and the legacy format.
+fn collect_items<F>(
+    tag: &NbtTag,
+    path: &mut String,
+    owner: &ItemOwner,
+    predicate: &mut F,
+    found: &mut Vec<ItemMatch>,
+) where
+    F: FnMut(&NbtTag) -> bool,
+{
+    if is_item_stack(tag) && predicate(tag) {
+        found.push(ItemMatch {
+            owner: owner.clone(),
+            path: path.clone(),
+            item: tag.clone(),
+        });
+    }
+    let len = path.len();
+    match tag {
+        NbtTag::Compound(map) => {
+            for (key, value) in map {
+                if matches!(value, NbtTag::Compound(_) | NbtTag::List(_)) {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+                    path.push_str(key);
+                    collect_items(value, path, owner, predicate, found);
+                    path.truncate(len);
+                }
+            }
+        }
+        NbtTag::List(elements) => {
+            for (i, element) in elements.iter().enumerate() {
+                if matches!(element, NbtTag::Compound(_) | NbtTag::List(_)) {
+                    path.push_str(&format!("[{}]", i));
+                    collect_items(element, path, owner, predicate, found);
+                    path.truncate(len);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the `x`, `y` and `z` fields of a block entity.
+fn block_position(tag: &NbtTag) -> (i32, i32, i32) {
+    let coord = |key: &str| match tag {
+        NbtTag::Compound(map) => match map.get(key) {
+            Some(NbtTag::Int(v)) => *v,
+            _ => 0,
+        },
+        _ => 0,
+    };
+    (coord("x"), coord("y"), coord("z"))
+}
+
+/// Returns the string `id` of a compound, or an empty string.
+fn tag_id(tag: &NbtTag) -> String {
+    match tag {
+        NbtTag::Compound(map) => match map.get("id") {
+            Some(NbtTag::String(id)) => id.clone(),
+            _ => String::new(),
+        },
+        _ => String::new(),
+    }
+}
+
+impl World {
+    /// Finds every item stack accepted by `predicate` held by players, entities or block
+    /// entities anywhere in the world.
+    ///
+    /// Items inside other items, such as shulker boxes and bundles, are searched too.
+    /// Player files and chunks that cannot be read are listed in
+    /// [`ScanReport::skipped`] and the search carries on.
+    ///
+    /// `predicate` receives the item stack compound; a typical check compares its `id`:
+    ///
+    /// ```no_run
+    /// use anvil_nbt::nbt::NbtTag;
+    /// use anvil_nbt::world::World;
+    ///
+    /// let world = World::open("saves/MyWorld").unwrap();
+    /// let beacons = world
+    ///     .find_items(|item| match item {
+    ///         NbtTag::Compound(map) => {
+    ///             map.get("id") == Some(&NbtTag::String("minecraft:beacon".to_string()))
+    ///         }
+    ///         _ => false,
+    ///     })
+    ///     .unwrap();
+    /// for found in beacons.found {
+    ///     println!("{:?} {}", found.owner, found.path);
+    /// }
+    /// ```
+    pub fn find_items<F>(&self, mut predicate: F) -> Result<ScanReport<Vec<ItemMatch>>>
+    where
+        F: FnMut(&NbtTag) -> bool,
+    {
+        let mut found = Vec::new();
+        let mut skipped = Vec::new();
+
+        let playerdata = self.root().join(PLAYERDATA_DIR);
+        if playerdata.is_dir() {
+            let mut uuids = Vec::new();
+            for entry in std::fs::read_dir(&playerdata)? {
+                let name = entry?.file_name().to_string_lossy().into_owned();
+                if let Some(uuid) = name.strip_suffix(".dat") {
+                    uuids.push(uuid.to_string());
+                }
+            }
+            uuids.sort();
+            for uuid in uuids {
+                cancel::check(self.cancellation())?;
+                let root = match read_player_data(self.root(), &uuid) {
+                    Ok((_, root)) => root,
+                    Err(error) => {
+                        skipped.push(ScanFailure {
+                            path: playerdata.join(format!("{}.dat", uuid)),
+                            chunk: None,
+                            error,
+                        });
+                        continue;
+                    }
+                };
+                let owner = ItemOwner::Player { uuid };
+                collect_items(
+                    &root,
+                    &mut String::new(),
+                    &owner,
+                    &mut predicate,
+                    &mut found,
+                );
+            }
+        }
+
+        for dimension in self.dimensions()? {
+            // Entity chunks hold entities only; terrain chunks hold block entities and,
+            // before 1.17, entities as well
+            let scans: [(RegionKind, &[&str]); 2] = [
+                (RegionKind::Entities, &ENTITY_FIELDS[..1]),
+                (
+                    RegionKind::Terrain,
+                    &[
+                        ENTITY_FIELDS[1],
+                        BLOCK_ENTITY_FIELDS[0],
+                        BLOCK_ENTITY_FIELDS[1],
+                    ],
+                ),
+            ];
+            for (kind, paths) in scans {
+                let failures = self.scan_chunks(&dimension, kind, None, |region, x, z| {
+                    let Some(fields) = region.get_chunk_fields(x, z, paths)? else {
+                        return Ok(());
+                    };
+                    for (path, list) in paths.iter().zip(fields) {
+                        let is_entity = ENTITY_FIELDS.contains(path);
+                        for holder in list.into_iter().flat_map(list_elements) {
+                            let owner = if is_entity {
+                                ItemOwner::Entity {
+                                    dimension: dimension.clone(),
+                                    chunk: (x, z),
+                                    id: tag_id(&holder),
+                                }
+                            } else {
+                                ItemOwner::BlockEntity {
+                                    dimension: dimension.clone(),
+                                    position: block_position(&holder),
+                                    id: tag_id(&holder),
+                                }
+                            };
+                            let mut path = String::new();
+                            collect_items(&holder, &mut path, &owner, &mut predicate, &mut found);
+                        }
+                    }
+                    Ok(())
+                })?;
+                skipped.extend(failures);
+            }
+        }
+        Ok(ScanReport { found, skipped })
+    }
+}
+
+/// Returns the elements of a list tag, or nothing for other tags.
+fn list_elements(tag: NbtTag) -> Vec<NbtTag> {
+    match tag {
+        NbtTag::List(elements) => elements,
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Collecting what keeps chunks loaded or claimed.
+//!
+//! [`World::tickets`] gathers the force-loaded chunks from `data/chunks.dat` and the
+//! points of interest from the `poi/` region files of a dimension into one map keyed by
+//! chunk, so a chunk that refuses to unload can be explained with a single lookup.
+
+use crate::nbt::NbtTag;
+use crate::world::World;
+use crate::world::dimension::{Dimension, RegionKind};
+use crate::world::scan::ScanReport;
+use std::collections::BTreeMap;
+use std::io::Result;
+
+/// A point of interest record from a POI chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoiRecord {
+    /// The POI type, such as `"minecraft:home"` for a bed or `"minecraft:nether_portal"`.
+    pub kind: String,
+    /// Block position of the POI.
+    pub position: (i32, i32, i32),
+    /// How many more villagers may claim the POI. Beds and job sites with `0` are taken.
+    pub free_tickets: i32,
+}
+
+/// Something attached to a chunk by [`World::tickets`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChunkTicket {
+    /// The chunk was force-loaded with `/forceload` and is listed in `chunks.dat`.
+    Forced,
+    /// The chunk holds a point of interest.
+    Poi(PoiRecord),
+}
+
+/// The tickets of one dimension, grouped by chunk.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChunkTickets {
+    /// Tickets per chunk coordinates. Chunks without tickets are absent.
+    pub chunks: BTreeMap<(i32, i32), Vec<ChunkTicket>>,
+}
+
+impl ChunkTickets {
+    /// Returns the tickets of chunk `(x, z)`, forced first and POIs in file order.
+    pub fn get(&self, x: i32, z: i32) -> &[ChunkTicket] {
+        self.chunks.get(&(x, z)).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns `true` if chunk `(x, z)` is force-loaded.
+    pub fn is_forced(&self, x: i32, z: i32) -> bool {
+        self.get(x, z).contains(&ChunkTicket::Forced)
+    }
+
+    /// Returns the POIs of chunk `(x, z)` that have no free tickets left.
+    pub fn claimed_pois(&self, x: i32, z: i32) -> impl Iterator<Item = &PoiRecord> {
+        self.get(x, z).iter().filter_map(|ticket| match ticket {
+            ChunkTicket::Poi(record) if record.free_tickets == 0 => Some(record),
+            _ => None,
+        })
+    }
+}
+
+impl World {
+    /// Collects the force-loaded chunks and points of interest of a dimension.
+    ///
+    /// POI chunks are read through their `Sections` field only; sections marked invalid
+    /// are skipped, as the game rebuilds them on load. POI chunks that cannot be read are
+    /// listed in [`ScanReport::skipped`] and the scan carries on.
+    pub fn tickets(&self, dimension: &Dimension) -> Result<ScanReport<ChunkTickets>> {
+        let mut tickets = ChunkTickets::default();
+        for &chunk in &self.forced_chunks(dimension)?.chunks {
+            tickets
+                .chunks
+                .entry(chunk)
+                .or_default()
+                .push(ChunkTicket::Forced);
+        }
+
+        let skipped = self.scan_chunks(dimension, RegionKind::Poi, None, |region, x, z| {
+            let Some(fields) = region.get_chunk_fields(x, z, &["Sections"])? else {
+                return Ok(());
+            };
+            let Some(NbtTag::Compound(sections)) = fields.into_iter().flatten().next() else {
+                return Ok(());
+            };
+            for section in sections.values() {
+                let NbtTag::Compound(section) = section else {
+                    continue;
+                };
+                if matches!(section.get("Valid"), Some(NbtTag::Byte(0))) {
+                    continue;
+                }
+                let Some(NbtTag::List(records)) = section.get("Records") else {
+                    continue;
+                };
+                for record in records {
+                    if let Some(record) = poi_record(record) {
+                        tickets
+                            .chunks
+                            .entry((x, z))
+                            .or_default()
+                            .push(ChunkTicket::Poi(record));
+                    }
+                }
+            }
+            Ok(())
+        })?;
+        Ok(ScanReport {
+            found: tickets,
+            skipped,
+        })
+    }
+}
+
+/// Reads a POI record, skipping entries without a type or an `[I; x, y, z]` position.
+fn poi_record(tag: &NbtTag) -> Option<PoiRecord> {
+    let NbtTag::Compound(map) = tag else {
+        return None;
+    };
+    let Some(NbtTag::String(kind)) = map.get("type") else {
+        return None;
+    };
+    let Some(NbtTag::IntArray(pos)) = map.get("pos") else {
+        return None;
+    };
+    let &[x, y, z] = pos.as_slice() else {
+        return None;
+    };
+    let free_tickets = match map.get("free_tickets") {
+        Some(NbtTag::Int(n)) => *n,
+        _ => 0,
+    };
+    Some(PoiRecord {
+        kind: kind.clone(),
+        position: (x, y, z),
+        free_tickets,
+    })
+}
@@ -0,0 +1,354 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Edits spanning several region files, committed together.
+//!
+//! Deleting a chunk properly means touching its terrain, entity and POI regions; a crash
+//! between those writes leaves entities floating over missing terrain or POIs pointing
+//! at blocks that no longer exist. A [`WorldTransaction`] stages every edit in memory and
+//! [`commit`](WorldTransaction::commit)s in two phases:
+//!
+//! 1. Each modified region is written in full to `<file>.mca.tmp` and synced.
+//! 2. A manifest listing those files is written to the world root, then every temporary
+//!    file is renamed over its original and the manifest is removed.
+//!
+//! A crash during phase 1 leaves the originals untouched. A crash during phase 2 is
+//! rolled forward by [`World::recover_transaction`], which finishes the listed renames.
+
+use crate::anvil::access::{Region, chunk_index, decompress, parse_chunk};
+use crate::anvil::cow::sync_parent_dir;
+use crate::anvil::encode::{RegionWriter, compress_chunk};
+use crate::anvil::{CompressionPreset, CompressionType, SyncPolicy, chunk_to_region, to_timestamp};
+use crate::cancel;
+use crate::nbt::NbtTag;
+use crate::nbt::encode::KeyCache;
+use crate::world::World;
+use crate::world::dimension::{Dimension, RegionKind};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{BufWriter, Error, ErrorKind, Result, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Name of the manifest written to the world root while a commit renames its files.
+pub const TRANSACTION_MANIFEST: &str = "anvil-nbt.txn";
+
+/// Pending edits to one region file.
+struct StagedRegion {
+    /// The region as it is on disk, or `None` if the file does not exist yet.
+    base: Option<Region>,
+    /// Pending edits by header index; `None` marks a removed chunk.
+    overlay: HashMap<usize, Option<(CompressionType, Vec<u8>)>>,
+}
+
+/// A set of chunk edits across any number of region files, applied all at once.
+///
+/// Reads through the transaction see its pending edits. Nothing on disk changes until
+/// [`commit`](Self::commit); dropping the transaction discards every edit.
+pub struct WorldTransaction<'w> {
+    world: &'w World,
+    /// Staged regions keyed by path, so commits always touch files in the same order.
+    regions: BTreeMap<PathBuf, StagedRegion>,
+    sync_policy: SyncPolicy,
+    keys: KeyCache,
+    compression: CompressionPreset,
+}
+
+impl World {
+    /// Starts a transaction for editing chunks across several region files.
+    pub fn transaction(&self) -> WorldTransaction<'_> {
+        WorldTransaction {
+            world: self,
+            regions: BTreeMap::new(),
+            sync_policy: SyncPolicy::default(),
+            keys: KeyCache::new(),
+            compression: CompressionPreset::default(),
+        }
+    }
+
+    /// Finishes a commit that was interrupted while renaming files.
+    ///
+    /// If a transaction manifest is present, every temporary file it lists is renamed
+    /// over its original and the manifest is removed. Returns `true` if a manifest was
+    /// found. Call this before reading or editing a world that may have crashed mid-commit.
+    pub fn recover_transaction(&self) -> Result<bool> {
+        let manifest_path = self.root().join(TRANSACTION_MANIFEST);
+        let manifest = match fs::read_to_string(&manifest_path) {
+            Ok(manifest) => manifest,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        for line in manifest.lines().filter(|l| !l.is_empty()) {
+            let path = self.root().join(line);
+            let tmp_path = tmp_path_for(&path);
+            if tmp_path.exists() {
+                fs::rename(&tmp_path, &path)?;
+                sync_parent_dir(&path)?;
+            }
+        }
+        fs::remove_file(&manifest_path)?;
+        Ok(true)
+    }
+}
+
+impl WorldTransaction<'_> {
+    /// Sets whether the commit syncs files to disk ([`SyncPolicy::OnCommit`] by default).
+    ///
+    /// [`SyncPolicy::Always`] behaves like `OnCommit`, since nothing is written before the
+    /// commit. With [`SyncPolicy::Never`] the ordering of the two phases still holds for
+    /// process crashes but not for power loss.
+    pub fn set_sync_policy(&mut self, policy: SyncPolicy) {
+        self.sync_policy = policy;
+    }
+
+    /// Sets how chunks passed to [`set_chunk`](Self::set_chunk) are compressed
+    /// ([`CompressionPreset::Balanced`] by default).
+    pub fn set_compression(&mut self, preset: CompressionPreset) {
+        self.compression = preset;
+    }
+
+    /// Returns `true` if any edit has been staged.
+    pub fn is_modified(&self) -> bool {
+        self.regions.values().any(|r| !r.overlay.is_empty())
+    }
+
+    /// Parses a chunk by absolute chunk coordinates, with pending edits applied.
+    ///
+    /// Returns `Ok(None)` if the region file or the chunk does not exist.
+    pub fn get_chunk_nbt(
+        &self,
+        dimension: &Dimension,
+        kind: RegionKind,
+        chunk_x: i32,
+        chunk_z: i32,
+    ) -> Result<Option<(String, NbtTag)>> {
+        let path = self.region_path(dimension, kind, chunk_x, chunk_z);
+        let Some(staged) = self.regions.get(&path) else {
+            return self.world.get_chunk_nbt(dimension, kind, chunk_x, chunk_z);
+        };
+        match staged.overlay.get(&chunk_index(chunk_x, chunk_z)) {
+            Some(Some((compression, data))) => {
+                parse_chunk(&decompress(*compression, data)?).map(Some)
+            }
+            Some(None) => Ok(None),
+            None => match &staged.base {
+                Some(region) => region.get_chunk_nbt(chunk_x, chunk_z),
+                None => Ok(None),
+            },
+        }
+    }
+
+    /// Replaces (or inserts) a chunk, creating its region file on commit if needed.
+    pub fn set_chunk(
+        &mut self,
+        dimension: &Dimension,
+        kind: RegionKind,
+        chunk_x: i32,
+        chunk_z: i32,
+        name: &str,
+        tag: &NbtTag,
+    ) -> Result<()> {
+        let compression = self.compression.chunk_compression();
+        let compressed = compress_chunk(name, tag, &mut self.keys, self.compression)?;
+        let staged = self.stage(dimension, kind, chunk_x, chunk_z)?;
+        staged.overlay.insert(
+            chunk_index(chunk_x, chunk_z),
+            Some((compression, compressed)),
+        );
+        Ok(())
+    }
+
+    /// Removes a chunk so that it will be absent after [`commit`](Self::commit).
+    ///
+    /// Removing a chunk from a region file that does not exist is a no-op.
+    pub fn remove_chunk(
+        &mut self,
+        dimension: &Dimension,
+        kind: RegionKind,
+        chunk_x: i32,
+        chunk_z: i32,
+    ) -> Result<()> {
+        let path = self.region_path(dimension, kind, chunk_x, chunk_z);
+        if !self.regions.contains_key(&path) && !path.exists() {
+            return Ok(());
+        }
+        let staged = self.stage(dimension, kind, chunk_x, chunk_z)?;
+        staged.overlay.insert(chunk_index(chunk_x, chunk_z), None);
+        Ok(())
+    }
+
+    /// Removes a chunk from its terrain, entity and POI regions alike.
+    pub fn delete_chunk(
+        &mut self,
+        dimension: &Dimension,
+        chunk_x: i32,
+        chunk_z: i32,
+    ) -> Result<()> {
+        for kind in RegionKind::ALL {
+            self.remove_chunk(dimension, kind, chunk_x, chunk_z)?;
+        }
+        Ok(())
+    }
+
+    /// Writes every modified region and swaps them in together.
+    ///
+    /// Unmodified chunks are copied as raw compressed bytes along with their timestamps;
+    /// edited chunks are stamped with the current time. If a write fails before the
+    /// renames start, the temporary files are removed and the world is left unchanged.
+    /// The world's cancellation token is checked before each region is written, and
+    /// cancelling counts as such a failure.
+    pub fn commit(self) -> Result<()> {
+        let sync = self.sync_policy != SyncPolicy::Never;
+        let regions: Vec<_> = self
+            .regions
+            .into_iter()
+            .filter(|(_, staged)| !staged.overlay.is_empty())
+            .collect();
+        if regions.is_empty() {
+            return Ok(());
+        }
+
+        let mut written = Vec::with_capacity(regions.len());
+        for (path, staged) in &regions {
+            let tmp_path = tmp_path_for(path);
+            let result = cancel::check(self.world.cancellation())
+                .and_then(|()| write_region(&tmp_path, staged, sync));
+            written.push(tmp_path);
+            if let Err(e) = result {
+                for tmp_path in &written {
+                    let _ = fs::remove_file(tmp_path);
+                }
+                return Err(e);
+            }
+        }
+        // Release the memory maps before replacing the files underneath them
+        let paths: Vec<PathBuf> = regions.into_iter().map(|(path, _)| path).collect();
+
+        let root = self.world.root();
+        let manifest_path = root.join(TRANSACTION_MANIFEST);
+        let mut manifest = String::new();
+        for path in &paths {
+            let relative = path
+                .strip_prefix(root)
+                .ok()
+                .and_then(Path::to_str)
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Cannot record region path: {}", path.display()),
+                    )
+                })?;
+            manifest.push_str(relative);
+            manifest.push('\n');
+        }
+        let mut file = File::create(&manifest_path)?;
+        file.write_all(manifest.as_bytes())?;
+        if sync {
+            file.sync_all()?;
+            sync_parent_dir(&manifest_path)?;
+        }
+        drop(file);
+
+        for (path, tmp_path) in paths.iter().zip(&written) {
+            fs::rename(tmp_path, path)?;
+            if sync {
+                sync_parent_dir(path)?;
+            }
+        }
+        fs::remove_file(&manifest_path)?;
+        if sync {
+            sync_parent_dir(&manifest_path)?;
+        }
+        Ok(())
+    }
+
+    /// Discards all pending edits. Equivalent to dropping the transaction.
+    pub fn discard(self) {}
+
+    fn region_path(
+        &self,
+        dimension: &Dimension,
+        kind: RegionKind,
+        chunk_x: i32,
+        chunk_z: i32,
+    ) -> PathBuf {
+        self.world.region_path(
+            dimension,
+            kind,
+            chunk_to_region(chunk_x),
+            chunk_to_region(chunk_z),
+        )
+    }
+
+    /// Returns the staged region holding chunk `(chunk_x, chunk_z)`, opening it first if
+    /// it has not been touched yet.
+    fn stage(
+        &mut self,
+        dimension: &Dimension,
+        kind: RegionKind,
+        chunk_x: i32,
+        chunk_z: i32,
+    ) -> Result<&mut StagedRegion> {
+        let path = self.region_path(dimension, kind, chunk_x, chunk_z);
+        if !self.regions.contains_key(&path) {
+            let base = match Region::open(&path) {
+                Ok(region) => Some(region),
+                Err(e) if e.kind() == ErrorKind::NotFound => None,
+                Err(e) => return Err(e),
+            };
+            self.regions.insert(
+                path.clone(),
+                StagedRegion {
+                    base,
+                    overlay: HashMap::new(),
+                },
+            );
+        }
+        Ok(self.regions.get_mut(&path).expect("region was just staged"))
+    }
+}
+
+/// Returns the path a region is written to before being renamed over `path`.
+fn tmp_path_for(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".tmp");
+    PathBuf::from(name)
+}
+
+/// Writes the merged contents of a staged region to `tmp_path`.
+fn write_region(tmp_path: &Path, staged: &StagedRegion, sync: bool) -> Result<()> {
+    let now = to_timestamp(SystemTime::now());
+    let mut timestamps = [0u32; 1024];
+    let mut payloads = Vec::new();
+    for (index, timestamp) in timestamps.iter_mut().enumerate() {
+        match staged.overlay.get(&index) {
+            Some(Some((compression, data))) => {
+                payloads.push((index, *compression, &data[..]));
+                *timestamp = now;
+            }
+            Some(None) => {}
+            None => {
+                if let Some(base) = &staged.base
+                    && let Some((compression, data)) = base.chunk_payload(index)?
+                {
+                    payloads.push((index, compression, data));
+                    *timestamp = base.header().timestamps[index];
+                }
+            }
+        }
+    }
+
+    if let Some(dir) = tmp_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut writer = RegionWriter::new(BufWriter::new(File::create(tmp_path)?));
+    writer.write_payloads(&payloads, &timestamps)?;
+    let file = writer
+        .into_inner()
+        .into_inner()
+        .map_err(|e| e.into_error())?;
+    if sync {
+        file.sync_all()?;
+    }
+    Ok(())
+}
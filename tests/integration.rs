@@ -97,3 +97,1698 @@ fn test_anvil_round_trip() {
 
     std::fs::remove_file(mca_path).ok();
 }
+
+#[test]
+fn test_region_from_reader() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use std::io::Cursor;
+
+    let chunks = vec![(3, 4, "".to_string(), NbtTag::Int(7))];
+    let mut cursor = Cursor::new(Vec::new());
+    RegionWriter::new(&mut cursor)
+        .write_all_chunks(&chunks)
+        .unwrap();
+    cursor.set_position(0);
+
+    let region = Region::from_reader(cursor).unwrap();
+    assert_eq!(
+        region.get_chunk_nbt(3, 4).unwrap(),
+        Some(("".to_string(), NbtTag::Int(7)))
+    );
+    assert_eq!(region.get_chunk_nbt(0, 0).unwrap(), None);
+}
+
+#[test]
+fn test_region_writer_single_chunks() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use std::io::Cursor;
+
+    let mut writer = RegionWriter::new(Cursor::new(Vec::new()));
+    for i in 0..3 {
+        writer.write_chunk(i, 0, "", &NbtTag::Int(i)).unwrap();
+    }
+    writer.flush_header().unwrap();
+    writer.write_chunk(1, 0, "", &NbtTag::Int(10)).unwrap();
+    writer.flush_header().unwrap();
+
+    let region = Region::from_reader(Cursor::new(writer.into_inner().into_inner())).unwrap();
+    assert_eq!(
+        region.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::Int(0)
+    );
+    assert_eq!(
+        region.get_chunk_nbt(1, 0).unwrap().unwrap().1,
+        NbtTag::Int(10)
+    );
+    assert_eq!(
+        region.get_chunk_nbt(2, 0).unwrap().unwrap().1,
+        NbtTag::Int(2)
+    );
+    assert_eq!(region.get_chunk_nbt(3, 0).unwrap(), None);
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn test_region_writer_parallel() {
+    use anvil_nbt::anvil::SECTOR_SIZE;
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use std::io::Cursor;
+
+    let chunks: Vec<_> = (0..40)
+        .map(|i| {
+            let tag = anvil_nbt::nbt!({ xPos: (NbtTag::Int(i % 32)), data: (NbtTag::LongArray(vec![i as i64; 600])) });
+            (i % 32, i / 32, "".to_string(), tag)
+        })
+        .collect();
+    let write = |threads: Option<usize>| {
+        let mut writer = RegionWriter::new(Cursor::new(Vec::new()));
+        match threads {
+            Some(threads) => rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| writer.write_all_chunks_parallel(&chunks))
+                .unwrap(),
+            None => writer.write_all_chunks(&chunks).unwrap(),
+        }
+        writer.into_inner().into_inner()
+    };
+
+    let serial = write(None);
+    for threads in [1, 3, 64] {
+        let parallel = write(Some(threads));
+        // Same locations and sectors; timestamps may differ by a second
+        assert_eq!(parallel[..SECTOR_SIZE], serial[..SECTOR_SIZE]);
+        assert_eq!(parallel[2 * SECTOR_SIZE..], serial[2 * SECTOR_SIZE..]);
+    }
+}
+
+#[test]
+fn test_region_writer_pads_to_sectors() {
+    use anvil_nbt::anvil::SECTOR_SIZE;
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use std::io::Cursor;
+
+    let write = |initial: Vec<u8>, chunks: &[(i32, i32, String, NbtTag)]| {
+        let mut writer = RegionWriter::new(Cursor::new(initial));
+        writer.write_all_chunks(chunks).unwrap();
+        writer.into_inner().into_inner()
+    };
+
+    assert_eq!(write(Vec::new(), &[]).len(), 2 * SECTOR_SIZE);
+    // Leftovers from a longer stream are padded out rather than left mid-sector
+    let reused = write(vec![1; 9000], &[]);
+    assert_eq!(reused.len(), 3 * SECTOR_SIZE);
+    assert!(reused[9000..].iter().all(|&b| b == 0));
+
+    let bytes = write(Vec::new(), &[(0, 0, "".to_string(), NbtTag::Int(1))]);
+    assert_eq!(bytes.len() % SECTOR_SIZE, 0);
+    let region = Region::from_reader(&bytes[..]).unwrap();
+    assert_eq!(
+        region.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::Int(1)
+    );
+}
+
+#[test]
+fn test_region_writer_rejects_oversized_chunks() {
+    use anvil_nbt::anvil::CompressionType;
+    use anvil_nbt::anvil::SECTOR_SIZE;
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use std::io::{Cursor, ErrorKind};
+
+    // 255 sectors is the most a location entry can describe
+    let largest = vec![0u8; 255 * SECTOR_SIZE - 5];
+    let oversized = vec![0u8; 255 * SECTOR_SIZE - 4];
+
+    let mut cursor = Cursor::new(Vec::new());
+    let mut writer = RegionWriter::new(&mut cursor);
+    writer
+        .write_raw_chunk(0, 0, CompressionType::None, &largest)
+        .unwrap();
+    let err = writer
+        .write_raw_chunk(1, 0, CompressionType::None, &oversized)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    writer.flush_header().unwrap();
+
+    let region = Region::from_bytes(cursor.into_inner()).unwrap();
+    assert_eq!(region.chunk_info(0, 0).unwrap().unwrap().sector_count, 255);
+    assert_eq!(region.chunk_info(1, 0).unwrap(), None);
+    assert!(region.validate().is_valid());
+}
+
+#[test]
+fn test_region_writer_raw_chunks() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::{RegionWriter, minimal_region};
+    use std::io::Cursor;
+
+    let source = Region::from_reader(&minimal_region(&[(3, 7)]).unwrap()[..]).unwrap();
+    let (compression, data) = source.get_chunk_raw(3, 7).unwrap().unwrap();
+    assert!(source.get_chunk_raw(0, 0).unwrap().is_none());
+
+    let mut writer = RegionWriter::new(Cursor::new(Vec::new()));
+    writer.write_raw_chunk(3, 7, compression, data).unwrap();
+    writer.flush_header().unwrap();
+    let copy = Region::from_reader(Cursor::new(writer.into_inner().into_inner())).unwrap();
+    assert_eq!(copy.get_chunk_raw(3, 7).unwrap(), Some((compression, data)));
+    assert_eq!(
+        copy.get_chunk_nbt(3, 7).unwrap(),
+        source.get_chunk_nbt(3, 7).unwrap()
+    );
+
+    // Merge two regions without recompressing
+    let other = Region::from_reader(&minimal_region(&[(0, 0), (3, 7)]).unwrap()[..]).unwrap();
+    let mut writer = RegionWriter::new(Cursor::new(Vec::new()));
+    for region in [&source, &other] {
+        for (x, z) in [(0, 0), (3, 7)] {
+            if let Some((compression, data)) = region.get_chunk_raw(x, z).unwrap() {
+                writer.write_raw_chunk(x, z, compression, data).unwrap();
+            }
+        }
+    }
+    writer.flush_header().unwrap();
+    let merged = Region::from_reader(Cursor::new(writer.into_inner().into_inner())).unwrap();
+    assert!(merged.get_chunk_nbt(0, 0).unwrap().is_some());
+    assert_eq!(merged.chunk_info(3, 7).unwrap().unwrap().sector_offset, 4);
+}
+
+#[test]
+fn test_region_timestamps() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use std::io::Cursor;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    let saved = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut writer = RegionWriter::new(Cursor::new(Vec::new()));
+    writer
+        .write_chunk_at(0, 0, "", &NbtTag::Int(0), saved)
+        .unwrap();
+    writer.write_chunk(1, 0, "", &NbtTag::Int(1)).unwrap();
+    writer
+        .write_chunk_at(2, 0, "", &NbtTag::Int(2), UNIX_EPOCH)
+        .unwrap();
+    writer.flush_header().unwrap();
+    let region = Region::from_reader(Cursor::new(writer.into_inner().into_inner())).unwrap();
+
+    assert_eq!(region.get_timestamp(0, 0), Some(saved));
+    let age = SystemTime::now().duration_since(region.get_timestamp(1, 0).unwrap());
+    assert!(age.unwrap() < Duration::from_secs(60));
+    assert_eq!(region.get_timestamp(2, 0), None);
+    assert_eq!(region.get_timestamp(3, 0), None);
+
+    // Carry the timestamp through a read-modify-write
+    let (name, _) = region.get_chunk_nbt(0, 0).unwrap().unwrap();
+    let mut copy = RegionWriter::new(Cursor::new(Vec::new()));
+    copy.write_chunk_at(
+        0,
+        0,
+        &name,
+        &NbtTag::Int(10),
+        region.get_timestamp(0, 0).unwrap(),
+    )
+    .unwrap();
+    copy.flush_header().unwrap();
+    let copied = Region::from_reader(Cursor::new(copy.into_inner().into_inner())).unwrap();
+    assert_eq!(copied.get_timestamp(0, 0), Some(saved));
+}
+
+#[test]
+fn test_region_from_bytes() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::minimal_region;
+
+    let bytes = minimal_region(&[(1, 2)]).unwrap();
+    let borrowed = Region::from_slice(&bytes).unwrap();
+    let owned = Region::from_bytes(bytes).unwrap();
+    assert_eq!(borrowed.present_chunks(), vec![(1, 2)]);
+    assert_eq!(
+        owned.get_chunk_nbt(1, 2).unwrap(),
+        borrowed.get_chunk_nbt(1, 2).unwrap()
+    );
+    assert!(Region::from_slice(&[0; 16]).is_err());
+}
+
+#[test]
+fn test_region_from_source() {
+    use anvil_nbt::anvil::access::{Region, RegionSource};
+    use anvil_nbt::anvil::encode::minimal_region;
+    use std::sync::Arc;
+
+    // A buffer shared with some other owner, such as a download cache
+    struct Shared(Arc<Vec<u8>>);
+    impl RegionSource for Shared {
+        fn as_bytes(&self) -> &[u8] {
+            &self.0
+        }
+    }
+
+    let bytes = Arc::new(minimal_region(&[(7, 7)]).unwrap());
+    let region = Region::from_source(Shared(bytes.clone())).unwrap();
+    assert!(region.get_chunk_nbt(7, 7).unwrap().is_some());
+    let boxed = Region::from_source(bytes.to_vec().into_boxed_slice()).unwrap();
+    assert_eq!(boxed.present_chunks(), vec![(7, 7)]);
+    assert!(Region::from_source(vec![0u8; 100]).is_err());
+}
+
+#[test]
+fn test_region_chunk_reader() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use anvil_nbt::nbt::parse::parse_from_reader;
+    use std::io::Cursor;
+
+    let tag = anvil_nbt::nbt!({ DataVersion: 3953, sections: [{ Y: 0i8 }, { Y: 1i8 }] });
+    let chunks = vec![(1, 2, "".to_string(), tag.clone())];
+    let mut cursor = Cursor::new(Vec::new());
+    RegionWriter::new(&mut cursor)
+        .write_all_chunks(&chunks)
+        .unwrap();
+    let region = Region::from_reader(Cursor::new(cursor.into_inner())).unwrap();
+
+    let reader = region.chunk_reader(1, 2).unwrap().unwrap();
+    assert_eq!(parse_from_reader(reader).unwrap(), ("".to_string(), tag));
+    assert!(region.chunk_reader(0, 0).unwrap().is_none());
+}
+
+#[test]
+fn test_region_custom_compression() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::codec::CodecRegistry;
+    use anvil_nbt::anvil::{ChunkLocation, RegionHeader};
+    use std::sync::Arc;
+
+    // A made-up codec that stores the NBT bytes reversed
+    let mut nbt = Vec::new();
+    write_named_tag(&mut nbt, "", &NbtTag::Int(7)).unwrap();
+    let algorithm = b"example:reversed";
+    let mut payload = vec![127, 0, algorithm.len() as u8];
+    payload.extend_from_slice(algorithm);
+    payload.extend(nbt.iter().rev());
+
+    let mut header = RegionHeader::default();
+    header.locations[0] = ChunkLocation {
+        offset: 2,
+        sector_count: 1,
+    };
+    let mut file = header.to_bytes().to_vec();
+    file.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    file.extend_from_slice(&payload);
+    file.resize(3 * 4096, 0);
+
+    let mut region = Region::from_reader(&file[..]).unwrap();
+    let info = region.chunk_info(0, 0).unwrap().unwrap();
+    assert_eq!(info.compression_id, 127);
+    assert_eq!(info.compression(), None);
+    assert_eq!(info.compressed_len, payload.len() - 1);
+    let raw = region.custom_chunk_payload(0, 0).unwrap().unwrap();
+    assert_eq!(raw.algorithm, "example:reversed");
+    assert_eq!(raw.data.len(), nbt.len());
+    let err = region.get_chunk_nbt(0, 0).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+
+    let mut codecs = CodecRegistry::new();
+    codecs.register("example:reversed", |data: &[u8]| {
+        Ok(data.iter().rev().copied().collect())
+    });
+    region.set_codecs(Arc::new(codecs));
+    assert_eq!(
+        region.get_chunk_nbt(0, 0).unwrap(),
+        Some(("".to_string(), NbtTag::Int(7)))
+    );
+    let mut decoded = Vec::new();
+    region
+        .chunk_reader(0, 0)
+        .unwrap()
+        .unwrap()
+        .read_to_end(&mut decoded)
+        .unwrap();
+    assert_eq!(decoded, nbt);
+}
+
+#[test]
+fn test_region_get_chunks() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use std::io::Cursor;
+
+    let chunks: Vec<_> = (0..6)
+        .map(|i| (i, 31 - i, "".to_string(), NbtTag::Int(i)))
+        .collect();
+    let mut cursor = Cursor::new(Vec::new());
+    RegionWriter::new(&mut cursor)
+        .write_all_chunks(&chunks)
+        .unwrap();
+    let region = Region::from_reader(Cursor::new(cursor.into_inner())).unwrap();
+
+    let coords = [(5, 26), (0, 0), (2, 29), (0, 31), (4, 27)];
+    let expected: Vec<_> = [Some(5), None, Some(2), Some(0), Some(4)]
+        .into_iter()
+        .map(|v| v.map(|v| ("".to_string(), NbtTag::Int(v))))
+        .collect();
+    assert_eq!(region.get_chunks(&coords).unwrap(), expected);
+    for threads in [1, 2, 8] {
+        assert_eq!(
+            region.get_chunks_parallel(&coords, threads).unwrap(),
+            expected
+        );
+    }
+    assert!(region.get_chunks_parallel(&[], 4).unwrap().is_empty());
+}
+
+#[cfg(feature = "zip")]
+#[test]
+fn test_region_from_zip() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use std::io::Cursor;
+
+    let chunks = vec![(0, 0, "".to_string(), NbtTag::Int(1))];
+    let mut mca = Cursor::new(Vec::new());
+    RegionWriter::new(&mut mca)
+        .write_all_chunks(&chunks)
+        .unwrap();
+
+    let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    archive
+        .start_file(
+            "world/region/r.0.0.mca",
+            zip::write::SimpleFileOptions::default(),
+        )
+        .unwrap();
+    archive.write_all(mca.get_ref()).unwrap();
+    let zipped = archive.finish().unwrap();
+
+    let mut archive = zip::ZipArchive::new(zipped).unwrap();
+    let region = Region::from_zip(&mut archive, "world/region/r.0.0.mca").unwrap();
+    assert_eq!(
+        region.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::Int(1)
+    );
+    assert!(Region::from_zip(&mut archive, "missing.mca").is_err());
+}
+
+#[test]
+fn test_cow_region_commit_and_discard() {
+    use anvil_nbt::anvil::SyncPolicy;
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::cow::CowRegion;
+    use anvil_nbt::anvil::encode::RegionWriter;
+
+    let mca_path = std::env::temp_dir().join("test_cow.mca");
+    let chunks = vec![
+        (0, 0, "".to_string(), NbtTag::Int(1)),
+        (1, 0, "".to_string(), NbtTag::Int(2)),
+    ];
+    RegionWriter::new(std::fs::File::create(&mca_path).unwrap())
+        .write_all_chunks(&chunks)
+        .unwrap();
+    let original = std::fs::read(&mca_path).unwrap();
+
+    // Edits are journaled and visible, but the original file is untouched
+    {
+        let mut cow = CowRegion::open(&mca_path).unwrap();
+        cow.set_sync_policy(SyncPolicy::Always);
+        cow.set_chunk(0, 0, "", &NbtTag::Int(10)).unwrap();
+        cow.remove_chunk(1, 0).unwrap();
+        cow.set_chunk(5, 5, "", &NbtTag::Int(55)).unwrap();
+        cow.flush().unwrap();
+        assert_eq!(cow.get_chunk_nbt(0, 0).unwrap().unwrap().1, NbtTag::Int(10));
+        assert_eq!(cow.get_chunk_nbt(1, 0).unwrap(), None);
+        assert_eq!(std::fs::read(&mca_path).unwrap(), original);
+    }
+
+    // Reopening replays the journal; undo one edit and commit the rest
+    let mut cow = CowRegion::open(&mca_path).unwrap();
+    assert_eq!(cow.modified_chunks(), vec![(0, 0), (1, 0), (5, 5)]);
+    cow.revert_chunk(1, 0).unwrap();
+    let journal = cow.journal_path().to_path_buf();
+    cow.commit().unwrap();
+    assert!(!journal.exists());
+
+    let region = Region::open(&mca_path).unwrap();
+    assert_eq!(
+        region.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::Int(10)
+    );
+    assert_eq!(
+        region.get_chunk_nbt(1, 0).unwrap().unwrap().1,
+        NbtTag::Int(2)
+    );
+    assert_eq!(
+        region.get_chunk_nbt(5, 5).unwrap().unwrap().1,
+        NbtTag::Int(55)
+    );
+
+    // Discarding leaves the committed file as it was
+    let mut cow = CowRegion::open(&mca_path).unwrap();
+    cow.remove_chunk(0, 0).unwrap();
+    let committed = std::fs::read(&mca_path).unwrap();
+    cow.discard().unwrap();
+    assert_eq!(std::fs::read(&mca_path).unwrap(), committed);
+
+    std::fs::remove_file(mca_path).ok();
+}
+
+#[test]
+fn test_region_recover_journal() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::cow::{CowRegion, JournalRecovery};
+    use anvil_nbt::anvil::encode::RegionWriter;
+
+    let mca_path = std::env::temp_dir().join("test_recover.mca");
+    let chunks = vec![(0, 0, "".to_string(), NbtTag::Int(1))];
+    RegionWriter::new(std::fs::File::create(&mca_path).unwrap())
+        .write_all_chunks(&chunks)
+        .unwrap();
+    let original = std::fs::read(&mca_path).unwrap();
+    // Dropping a session without committing leaves its journal behind, as a crash would
+    let crash = || {
+        let mut cow = CowRegion::open(&mca_path).unwrap();
+        cow.set_chunk(0, 0, "", &NbtTag::Int(2)).unwrap();
+    };
+
+    crash();
+    std::fs::write(mca_path.with_extension("mca.cow.tmp"), b"partial").unwrap();
+    // A file staged under the plain temporary name belongs to someone else
+    std::fs::write(mca_path.with_extension("mca.tmp"), b"staged").unwrap();
+    assert!(Region::recover(&mca_path, JournalRecovery::Discard).unwrap());
+    assert_eq!(std::fs::read(&mca_path).unwrap(), original);
+    assert!(!mca_path.with_extension("mca.cow.tmp").exists());
+    assert_eq!(
+        std::fs::read(mca_path.with_extension("mca.tmp")).unwrap(),
+        b"staged"
+    );
+    std::fs::remove_file(mca_path.with_extension("mca.tmp")).unwrap();
+    assert!(!Region::recover(&mca_path, JournalRecovery::Discard).unwrap());
+
+    crash();
+    assert!(Region::recover(&mca_path, JournalRecovery::Replay).unwrap());
+    let region = Region::open(&mca_path).unwrap();
+    assert_eq!(
+        region.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::Int(2)
+    );
+    assert!(!mca_path.with_extension("mca.cow").exists());
+
+    std::fs::remove_file(mca_path).ok();
+}
+
+#[test]
+fn test_cow_commit_failure_removes_tmp() {
+    use anvil_nbt::anvil::cow::CowRegion;
+    use anvil_nbt::anvil::encode::RegionWriter;
+
+    let dir = std::env::temp_dir().join("test_cow_commit_failure");
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    let mca_path = dir.join("r.0.0.mca");
+    let chunks = vec![(0, 0, "".to_string(), NbtTag::Int(1))];
+    RegionWriter::new(std::fs::File::create(&mca_path).unwrap())
+        .write_all_chunks(&chunks)
+        .unwrap();
+
+    let mut cow = CowRegion::open(&mca_path).unwrap();
+    cow.set_chunk(0, 0, "", &NbtTag::Int(2)).unwrap();
+    let journal = cow.journal_path().to_path_buf();
+    // A non-empty directory in the way makes the final rename fail
+    std::fs::remove_file(&mca_path).unwrap();
+    std::fs::create_dir_all(mca_path.join("blocker")).unwrap();
+    assert!(cow.commit().is_err());
+    assert!(!mca_path.with_extension("mca.cow.tmp").exists());
+    assert!(journal.exists());
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_cow_journal_torn_tail() {
+    use anvil_nbt::anvil::cow::CowRegion;
+    use anvil_nbt::anvil::encode::RegionWriter;
+
+    let mca_path = std::env::temp_dir().join("test_cow_torn.mca");
+    let chunks = vec![(0, 0, "".to_string(), NbtTag::Int(1))];
+    RegionWriter::new(std::fs::File::create(&mca_path).unwrap())
+        .write_all_chunks(&chunks)
+        .unwrap();
+    let journal_path = mca_path.with_extension("mca.cow");
+    std::fs::remove_file(&journal_path).ok();
+    {
+        let mut cow = CowRegion::open(&mca_path).unwrap();
+        cow.set_chunk(0, 0, "", &NbtTag::Int(2)).unwrap();
+        cow.set_chunk(1, 0, "", &NbtTag::Int(3)).unwrap();
+    }
+    let journal = std::fs::read(&journal_path).unwrap();
+    let first_len = {
+        let cow = CowRegion::open(&mca_path).unwrap();
+        assert_eq!(cow.modified_chunks(), vec![(0, 0), (1, 0)]);
+        drop(cow);
+        // The first record: kind, index, compression, length, payload, checksum
+        8 + 8 + u32::from_be_bytes(journal[12..16].try_into().unwrap()) as usize + 4
+    };
+
+    // A zero-filled tail, as left by a crash after the file grew, is not an error
+    let mut zeroed = journal.clone();
+    zeroed.extend_from_slice(&[0; 64]);
+    std::fs::write(&journal_path, &zeroed).unwrap();
+    let cow = CowRegion::open(&mca_path).unwrap();
+    assert_eq!(cow.modified_chunks(), vec![(0, 0), (1, 0)]);
+    drop(cow);
+    assert_eq!(std::fs::read(&journal_path).unwrap(), journal);
+
+    // A record whose payload never reached the disk is dropped, not committed
+    let mut damaged = journal.clone();
+    damaged[first_len + 8..journal.len() - 4].fill(0);
+    std::fs::write(&journal_path, &damaged).unwrap();
+    let cow = CowRegion::open(&mca_path).unwrap();
+    assert_eq!(cow.modified_chunks(), vec![(0, 0)]);
+    assert_eq!(cow.get_chunk_nbt(0, 0).unwrap().unwrap().1, NbtTag::Int(2));
+    assert_eq!(cow.get_chunk_nbt(1, 0).unwrap(), None);
+    cow.discard().unwrap();
+
+    std::fs::remove_file(mca_path).ok();
+}
+
+#[test]
+fn test_region_editor_reuses_sectors() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::editor::RegionEditor;
+    use anvil_nbt::anvil::encode::RegionWriter;
+
+    let mca_path = std::env::temp_dir().join("test_editor.mca");
+    let chunks: Vec<_> = (0..3)
+        .map(|i| (i, 0, "".to_string(), NbtTag::Int(i)))
+        .collect();
+    RegionWriter::new(std::fs::File::create(&mca_path).unwrap())
+        .write_all_chunks(&chunks)
+        .unwrap();
+    let original_len = std::fs::metadata(&mca_path).unwrap().len();
+
+    let mut editor = RegionEditor::open(&mca_path).unwrap();
+    assert_eq!(editor.free_sectors(), 0);
+    editor.remove_chunk(1, 0).unwrap();
+    assert_eq!(editor.free_sectors(), 1);
+    editor.set_chunk(5, 5, "", &NbtTag::Int(55)).unwrap();
+    assert_eq!(editor.free_sectors(), 0);
+    editor.set_chunk(0, 0, "", &NbtTag::Int(100)).unwrap();
+    assert_eq!(
+        editor.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::Int(100)
+    );
+    editor.flush().unwrap();
+    drop(editor);
+
+    // The replaced chunk's old sector is free again and found on reopen
+    assert_eq!(RegionEditor::open(&mca_path).unwrap().free_sectors(), 1);
+    assert_eq!(
+        std::fs::metadata(&mca_path).unwrap().len(),
+        original_len + 4096
+    );
+    let region = Region::open(&mca_path).unwrap();
+    assert_eq!(
+        region.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::Int(100)
+    );
+    assert_eq!(region.get_chunk_nbt(1, 0).unwrap(), None);
+    assert_eq!(
+        region.get_chunk_nbt(2, 0).unwrap().unwrap().1,
+        NbtTag::Int(2)
+    );
+    assert_eq!(
+        region.get_chunk_nbt(5, 5).unwrap().unwrap().1,
+        NbtTag::Int(55)
+    );
+    assert!(region.chunk_info(0, 0).unwrap().unwrap().timestamp > 0);
+
+    std::fs::remove_file(mca_path).unwrap();
+}
+
+#[test]
+fn test_write_region_atomic() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::write_region_atomic;
+
+    let mca_path = std::env::temp_dir().join("test_atomic.mca");
+    write_region_atomic(&mca_path, &[(0, 0, "".to_string(), NbtTag::Int(1))]).unwrap();
+    let before = Region::open(&mca_path).unwrap();
+
+    write_region_atomic(&mca_path, &[(0, 0, "".to_string(), NbtTag::Int(2))]).unwrap();
+    assert!(!mca_path.with_extension("mca.tmp").exists());
+    // A region opened before the rename keeps reading the old file
+    assert_eq!(
+        before.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::Int(1)
+    );
+    let after = Region::open(&mca_path).unwrap();
+    assert_eq!(
+        after.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::Int(2)
+    );
+
+    // A failed write leaves the target untouched
+    let too_long = NbtTag::String("a".repeat(70_000));
+    assert!(write_region_atomic(&mca_path, &[(0, 0, "".to_string(), too_long)]).is_err());
+    assert!(!mca_path.with_extension("mca.tmp").exists());
+    let unchanged = Region::open(&mca_path).unwrap();
+    assert_eq!(
+        unchanged.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::Int(2)
+    );
+
+    drop((before, after, unchanged));
+    std::fs::remove_file(mca_path).unwrap();
+}
+
+#[test]
+fn test_region_snapshot_survives_commit() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::cow::CowRegion;
+    use anvil_nbt::anvil::encode::RegionWriter;
+
+    let mca_path = std::env::temp_dir().join("test_snapshot.mca");
+    let chunks = vec![(0, 0, "".to_string(), NbtTag::Int(1))];
+    RegionWriter::new(std::fs::File::create(&mca_path).unwrap())
+        .write_all_chunks(&chunks)
+        .unwrap();
+
+    let region = Region::open(&mca_path).unwrap();
+    let snapshot = region.snapshot();
+    let mut cow = CowRegion::open(&mca_path).unwrap();
+    cow.set_chunk(0, 0, "", &NbtTag::Int(2)).unwrap();
+    cow.commit().unwrap();
+
+    // Readers on other threads still see the version they were handed
+    let worker = std::thread::spawn(move || snapshot.get_chunk_nbt(0, 0).unwrap().unwrap().1);
+    assert_eq!(worker.join().unwrap(), NbtTag::Int(1));
+    drop(region);
+    let reopened = Region::open(&mca_path).unwrap();
+    assert_eq!(
+        reopened.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::Int(2)
+    );
+
+    std::fs::remove_file(mca_path).ok();
+}
+
+#[test]
+fn test_copy_chunks_between_regions() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::editor::{RegionEditor, copy_chunk, copy_chunks};
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use std::io::Cursor;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let modified = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut cursor = Cursor::new(Vec::new());
+    let mut writer = RegionWriter::new(&mut cursor);
+    for x in 0..3 {
+        writer
+            .write_chunk_at(x, 0, "", &NbtTag::Int(x), modified)
+            .unwrap();
+    }
+    writer.flush_header().unwrap();
+    let src = Region::from_bytes(cursor.into_inner()).unwrap();
+
+    let mca_path = std::env::temp_dir().join("test_copy_chunks.mca");
+    RegionWriter::new(std::fs::File::create(&mca_path).unwrap())
+        .write_all_chunks(&[(0, 0, "".to_string(), NbtTag::Int(-1))])
+        .unwrap();
+    let mut dst = RegionEditor::open(&mca_path).unwrap();
+
+    assert!(copy_chunk(&src, &mut dst, 1, 0).unwrap());
+    assert!(!copy_chunk(&src, &mut dst, 9, 9).unwrap());
+    // Move chunks (0, 0) and (2, 0) one row down, skipping (1, 0)
+    let copied = copy_chunks(&src, &mut dst, |x, z| (x != 1).then_some((x, z + 1))).unwrap();
+    assert_eq!(copied, 2);
+    dst.flush().unwrap();
+    drop(dst);
+
+    let region = Region::open(&mca_path).unwrap();
+    let nbt = |x, z| region.get_chunk_nbt(x, z).unwrap().map(|(_, tag)| tag);
+    assert_eq!(nbt(0, 0), Some(NbtTag::Int(-1)));
+    assert_eq!(nbt(1, 0), Some(NbtTag::Int(1)));
+    assert_eq!(nbt(0, 1), Some(NbtTag::Int(0)));
+    assert_eq!(nbt(2, 1), Some(NbtTag::Int(2)));
+    assert_eq!(nbt(1, 1), None);
+    assert_eq!(region.get_timestamp(2, 1), Some(modified));
+    assert_eq!(
+        region.get_chunk_raw(1, 0).unwrap(),
+        src.get_chunk_raw(1, 0).unwrap()
+    );
+
+    std::fs::remove_file(mca_path).unwrap();
+}
+
+#[test]
+fn test_copy_chunks_custom_compression() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::editor::{RegionEditor, copy_chunks};
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use anvil_nbt::anvil::{ChunkLocation, RegionHeader};
+
+    // Chunk (0, 0) uses a custom codec and (1, 0) an id no codec has
+    let algorithm = b"example:opaque";
+    let mut custom = vec![127, 0, algorithm.len() as u8];
+    custom.extend_from_slice(algorithm);
+    custom.extend_from_slice(b"data");
+    let unknown = vec![42, 1, 2, 3];
+    let mut header = RegionHeader::default();
+    let mut file = vec![0u8; 8192];
+    for (index, payload) in [&custom, &unknown].into_iter().enumerate() {
+        header.locations[index] = ChunkLocation {
+            offset: 2 + index as u32,
+            sector_count: 1,
+        };
+        let mut sector = (payload.len() as u32).to_be_bytes().to_vec();
+        sector.extend_from_slice(payload);
+        sector.resize(4096, 0);
+        file.extend_from_slice(&sector);
+    }
+    file[..8192].copy_from_slice(&header.to_bytes());
+    let src = Region::from_bytes(file).unwrap();
+
+    let mca_path = std::env::temp_dir().join("test_copy_chunks_custom.mca");
+    RegionWriter::new(std::fs::File::create(&mca_path).unwrap())
+        .write_all_chunks(&[])
+        .unwrap();
+    let mut dst = RegionEditor::open(&mca_path).unwrap();
+    let copied = copy_chunks(&src, &mut dst, |x, z| Some((x, z + 1))).unwrap();
+    assert_eq!(copied, 2);
+    drop(dst);
+
+    let region = Region::open(&mca_path).unwrap();
+    let raw = region.custom_chunk_payload(0, 1).unwrap().unwrap();
+    assert_eq!(raw.algorithm, "example:opaque");
+    assert_eq!(raw.data, b"data");
+    let info = region.chunk_info(1, 1).unwrap().unwrap();
+    assert_eq!(info.compression_id, 42);
+    assert_eq!(info.compressed_len, 3);
+
+    std::fs::remove_file(mca_path).unwrap();
+}
+
+#[test]
+fn test_cancelled_copy_and_commit() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::cow::CowRegion;
+    use anvil_nbt::anvil::editor::{RegionEditor, copy_chunks};
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use anvil_nbt::cancel::CancellationToken;
+    use std::io::ErrorKind;
+
+    let mca_path = std::env::temp_dir().join("test_cancelled_copy.mca");
+    RegionWriter::new(std::fs::File::create(&mca_path).unwrap())
+        .write_all_chunks(&[(0, 0, "".to_string(), NbtTag::Int(1))])
+        .unwrap();
+    let original = std::fs::read(&mca_path).unwrap();
+    let src = Region::from_bytes(original.clone()).unwrap();
+    let token = CancellationToken::new();
+    token.cancel();
+
+    let mut dst = RegionEditor::open(&mca_path).unwrap();
+    dst.set_cancellation(Some(token.clone()));
+    let err = copy_chunks(&src, &mut dst, |x, z| Some((x + 1, z))).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Interrupted);
+    let err = dst.set_chunk(1, 1, "", &NbtTag::Int(2)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Interrupted);
+    drop(dst);
+    assert_eq!(std::fs::read(&mca_path).unwrap(), original);
+
+    let mut cow = CowRegion::open(&mca_path).unwrap();
+    cow.set_chunk(0, 0, "", &NbtTag::Int(2)).unwrap();
+    cow.set_cancellation(Some(token));
+    let journal = cow.journal_path().to_path_buf();
+    let err = cow.commit().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Interrupted);
+    assert_eq!(std::fs::read(&mca_path).unwrap(), original);
+    assert!(journal.exists());
+    assert!(!mca_path.with_extension("mca.cow.tmp").exists());
+
+    std::fs::remove_file(journal).unwrap();
+    std::fs::remove_file(mca_path).unwrap();
+}
+
+#[test]
+fn test_mapped_region_shared_sectors() {
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use anvil_nbt::anvil::mapped::{MappedRegion, Placement};
+    use anvil_nbt::anvil::{ChunkLocation, RegionHeader};
+
+    let mca_path = std::env::temp_dir().join("test_mapped_shared.mca");
+    let chunks = vec![(0, 0, "".to_string(), NbtTag::Int(1))];
+    RegionWriter::new(std::fs::File::create(&mca_path).unwrap())
+        .write_all_chunks(&chunks)
+        .unwrap();
+    // Point chunk (1, 0) at the sectors of chunk (0, 0), as a damaged header can
+    let mut bytes = std::fs::read(&mca_path).unwrap();
+    let mut header = RegionHeader::parse(bytes[..8192].try_into().unwrap());
+    header.locations[1] = header.locations[0];
+    bytes[..8192].copy_from_slice(&header.to_bytes());
+    std::fs::write(&mca_path, bytes).unwrap();
+
+    let mut mapped = MappedRegion::open(&mca_path).unwrap();
+    assert_eq!(
+        mapped.set_chunk(1, 0, "", &NbtTag::Int(2)).unwrap(),
+        Placement::Appended
+    );
+    assert_eq!(
+        mapped.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::Int(1)
+    );
+    assert_eq!(
+        mapped.get_chunk_nbt(1, 0).unwrap().unwrap().1,
+        NbtTag::Int(2)
+    );
+
+    // Once nothing else points at them, the sectors are patched in place again
+    let moved: ChunkLocation = mapped.header().locations[1];
+    assert_eq!(
+        mapped.set_chunk(1, 0, "", &NbtTag::Int(3)).unwrap(),
+        Placement::InPlace
+    );
+    assert_eq!(mapped.header().locations[1], moved);
+    drop(mapped);
+
+    std::fs::remove_file(mca_path).ok();
+}
+
+#[test]
+fn test_mapped_region_patches_in_place() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use anvil_nbt::anvil::mapped::{MappedRegion, Placement};
+
+    let mca_path = std::env::temp_dir().join("test_mapped.mca");
+    let chunks: Vec<_> = (0..2)
+        .map(|i| (i, 0, "".to_string(), NbtTag::Int(i)))
+        .collect();
+    RegionWriter::new(std::fs::File::create(&mca_path).unwrap())
+        .write_all_chunks(&chunks)
+        .unwrap();
+    let original_len = std::fs::metadata(&mca_path).unwrap().len();
+
+    let mut mapped = MappedRegion::open(&mca_path).unwrap();
+    let offset = mapped.header().locations[0].offset;
+    assert_eq!(
+        mapped.set_chunk(0, 0, "", &NbtTag::Int(100)).unwrap(),
+        Placement::InPlace
+    );
+    assert_eq!(mapped.header().locations[0].offset, offset);
+
+    // Incompressible data no longer fits in one sector and moves to the end
+    let noise: Vec<i64> = (0..2048i64)
+        .map(|i| i.wrapping_mul(6364136223846793005).rotate_left(17))
+        .collect();
+    let grown = NbtTag::LongArray(noise);
+    assert_eq!(
+        mapped.set_chunk(1, 0, "", &grown).unwrap(),
+        Placement::Appended
+    );
+    assert_eq!(
+        mapped.set_chunk(7, 7, "", &NbtTag::Int(77)).unwrap(),
+        Placement::Appended
+    );
+    assert_eq!(
+        mapped.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::Int(100)
+    );
+    mapped.remove_chunk(7, 7).unwrap();
+    mapped.flush().unwrap();
+    drop(mapped);
+
+    assert!(std::fs::metadata(&mca_path).unwrap().len() > original_len);
+    let region = Region::open(&mca_path).unwrap();
+    assert_eq!(
+        region.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::Int(100)
+    );
+    assert_eq!(region.get_chunk_nbt(1, 0).unwrap().unwrap().1, grown);
+    assert_eq!(region.get_chunk_nbt(7, 7).unwrap(), None);
+    assert!(region.validate().is_valid());
+
+    std::fs::remove_file(mca_path).unwrap();
+}
+
+#[test]
+fn test_region_editor_damaged_header() {
+    use anvil_nbt::anvil::access::{Region, RegionOptions};
+    use anvil_nbt::anvil::editor::RegionEditor;
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let mca_path = std::env::temp_dir().join("test_editor_damaged.mca");
+    let modified = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut writer = RegionWriter::new(std::fs::File::create(&mca_path).unwrap());
+    for x in 0..3 {
+        writer
+            .write_chunk_at(x, 0, "", &NbtTag::Int(x), modified)
+            .unwrap();
+    }
+    writer.flush_header().unwrap();
+    drop(writer);
+
+    // Point chunk 1 into the timestamp table and chunk 2 at chunk 0's sector
+    let mut bytes = std::fs::read(&mca_path).unwrap();
+    let chunk0: [u8; 4] = bytes[0..4].try_into().unwrap();
+    bytes[4..8].copy_from_slice(&[0, 0, 1, 1]);
+    bytes[8..12].copy_from_slice(&chunk0);
+    std::fs::write(&mca_path, &bytes).unwrap();
+
+    let mut editor = RegionEditor::open(&mca_path).unwrap();
+    // The sectors of the old chunks 1 and 2 are the only unused ones
+    assert_eq!(editor.free_sectors(), 2);
+    editor.set_chunk(1, 0, "", &NbtTag::Int(10)).unwrap();
+    editor.set_chunk(2, 0, "", &NbtTag::Int(20)).unwrap();
+    assert_eq!(editor.free_sectors(), 0);
+    for z in 1..4 {
+        editor.set_chunk(0, z, "", &NbtTag::Int(z)).unwrap();
+    }
+    assert_eq!(
+        editor.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::Int(0)
+    );
+    drop(editor);
+
+    let region = Region::open(&mca_path).unwrap();
+    assert_eq!(region.get_timestamp(0, 0), Some(modified));
+    let value = |x, z| region.get_chunk_nbt(x, z).unwrap().map(|(_, tag)| tag);
+    assert_eq!(value(0, 0), Some(NbtTag::Int(0)));
+    assert_eq!(value(1, 0), Some(NbtTag::Int(10)));
+    assert_eq!(value(2, 0), Some(NbtTag::Int(20)));
+    assert_eq!(value(0, 3), Some(NbtTag::Int(3)));
+    drop(region);
+
+    // A length prefix longer than the chunk's sectors is rejected before reading
+    let mut bytes = std::fs::read(&mca_path).unwrap();
+    let start = (u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]) * 4096) as usize;
+    bytes[start..start + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+    std::fs::write(&mca_path, &bytes).unwrap();
+    let mut editor = RegionEditor::open(&mca_path).unwrap();
+    let err = editor.get_chunk_data(0, 0).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    editor.set_options(RegionOptions {
+        max_chunk_size: Some(4),
+    });
+    let err = editor.get_chunk_data(1, 0).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    std::fs::remove_file(mca_path).unwrap();
+}
+
+#[test]
+fn test_region_flush_range() {
+    use anvil_nbt::anvil::SyncPolicy;
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::editor::RegionEditor;
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use anvil_nbt::anvil::mapped::MappedRegion;
+
+    let mca_path = std::env::temp_dir().join("test_flush_range.mca");
+    let chunks: Vec<_> = (0..4)
+        .map(|i| (i, 0, "".to_string(), NbtTag::Int(i)))
+        .collect();
+    RegionWriter::new(std::fs::File::create(&mca_path).unwrap())
+        .write_all_chunks(&chunks)
+        .unwrap();
+
+    // Sync one chunk in the middle of the file, one appended at its end and one absent
+    let mut mapped = MappedRegion::open(&mca_path).unwrap();
+    mapped.set_chunk(2, 0, "", &NbtTag::Int(20)).unwrap();
+    mapped.flush_range(2, 0).unwrap();
+    mapped.set_chunk(9, 9, "", &NbtTag::Int(99)).unwrap();
+    mapped.flush_range(9, 9).unwrap();
+    mapped.flush_range(31, 31).unwrap();
+    mapped.set_sync_policy(SyncPolicy::Always);
+    mapped.remove_chunk(3, 0).unwrap();
+    drop(mapped);
+
+    let mut editor = RegionEditor::open(&mca_path).unwrap();
+    editor.set_sync_policy(SyncPolicy::Always);
+    editor.set_chunk(0, 0, "", &NbtTag::Int(10)).unwrap();
+    editor.flush_range(0, 0).unwrap();
+    drop(editor);
+
+    let region = Region::open(&mca_path).unwrap();
+    let value = |x, z| region.get_chunk_nbt(x, z).unwrap().map(|(_, tag)| tag);
+    assert_eq!(value(0, 0), Some(NbtTag::Int(10)));
+    assert_eq!(value(1, 0), Some(NbtTag::Int(1)));
+    assert_eq!(value(2, 0), Some(NbtTag::Int(20)));
+    assert_eq!(value(3, 0), None);
+    assert_eq!(value(9, 9), Some(NbtTag::Int(99)));
+
+    std::fs::remove_file(mca_path).unwrap();
+}
+
+#[test]
+fn test_cached_region_lru() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::cache::{CacheStats, CachedRegion, ChunkCache};
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use std::io::Cursor;
+    use std::sync::Arc;
+
+    let chunks: Vec<_> = (0..3)
+        .map(|x| (x, 0, "".to_string(), NbtTag::Int(x)))
+        .collect();
+    let mut cursor = Cursor::new(Vec::new());
+    RegionWriter::new(&mut cursor)
+        .write_all_chunks(&chunks)
+        .unwrap();
+    let region = Region::from_reader(Cursor::new(cursor.into_inner())).unwrap();
+
+    let cache = Arc::new(ChunkCache::new(2));
+    let cached = CachedRegion::new(region, "r.0.0.mca", cache.clone());
+
+    let first = cached.get_chunk_nbt(0, 0).unwrap().unwrap();
+    assert_eq!(first.1, NbtTag::Int(0));
+    let again = cached.get_chunk_nbt(0, 0).unwrap().unwrap();
+    assert!(Arc::ptr_eq(&first, &again));
+
+    // Touch (1, 0) and (2, 0); (0, 0) is the least recently used and gets evicted
+    cached.get_chunk_nbt(1, 0).unwrap();
+    cached.get_chunk_nbt(2, 0).unwrap();
+    assert_eq!(cache.len(), 2);
+    let reloaded = cached.get_chunk_nbt(0, 0).unwrap().unwrap();
+    assert!(!Arc::ptr_eq(&first, &reloaded));
+    assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 4 });
+}
+
+#[test]
+fn test_cached_region_rewrite_same_second() {
+    use anvil_nbt::anvil::cache::{CachedRegion, ChunkCache};
+    use anvil_nbt::anvil::editor::RegionEditor;
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use std::sync::Arc;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let mca_path = std::env::temp_dir().join("test_cache_same_second.mca");
+    let modified = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut writer = RegionWriter::new(std::fs::File::create(&mca_path).unwrap());
+    writer
+        .write_chunk_at(0, 0, "", &NbtTag::Int(1), modified)
+        .unwrap();
+    writer.flush_header().unwrap();
+    drop(writer);
+
+    let cache = Arc::new(ChunkCache::new(8));
+    let cached = CachedRegion::open(&mca_path, cache.clone()).unwrap();
+    assert_eq!(
+        cached.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::Int(1)
+    );
+    drop(cached);
+
+    // Rewrites keeping the timestamp, as set_chunk does twice within one second
+    let mut editor = RegionEditor::open(&mca_path).unwrap();
+    editor
+        .set_chunk_at(0, 0, "", &NbtTag::Int(2), modified)
+        .unwrap();
+    drop(editor);
+    let cached = CachedRegion::open(&mca_path, cache.clone()).unwrap();
+    assert_eq!(
+        cached.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::Int(2)
+    );
+    drop(cached);
+
+    let mut editor = RegionEditor::open(&mca_path).unwrap();
+    editor
+        .set_chunk_at(0, 0, "", &NbtTag::String("longer".to_string()), modified)
+        .unwrap();
+    drop(editor);
+    let cached = CachedRegion::open(&mca_path, cache).unwrap();
+    assert_eq!(
+        cached.get_chunk_nbt(0, 0).unwrap().unwrap().1,
+        NbtTag::String("longer".to_string())
+    );
+
+    std::fs::remove_file(mca_path).unwrap();
+}
+
+#[test]
+fn test_cached_region_byte_budget() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::cache::{CachedRegion, ChunkCache};
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use std::io::Cursor;
+    use std::sync::Arc;
+
+    // Chunks 0 and 1 are small, chunk 2 is larger than the whole budget
+    let chunks: Vec<_> = (0..3)
+        .map(|x| {
+            let len = if x == 2 { 4096 } else { 16 };
+            (x, 0, "".to_string(), NbtTag::ByteArray(vec![0; len]))
+        })
+        .collect();
+    let mut cursor = Cursor::new(Vec::new());
+    RegionWriter::new(&mut cursor)
+        .write_all_chunks(&chunks)
+        .unwrap();
+    let region = Region::from_reader(Cursor::new(cursor.into_inner())).unwrap();
+    let small = region.get_chunk_data(0, 0).unwrap().unwrap().len();
+
+    let cache = Arc::new(ChunkCache::with_byte_budget(small * 3 / 2));
+    assert_eq!(cache.byte_budget(), Some(small * 3 / 2));
+    let cached = CachedRegion::new(region, "r.0.0.mca", cache.clone());
+
+    let first = cached.get_chunk_nbt(0, 0).unwrap().unwrap();
+    assert_eq!(cache.bytes(), small);
+    assert!(Arc::ptr_eq(
+        &first,
+        &cached.get_chunk_nbt(0, 0).unwrap().unwrap()
+    ));
+
+    // Only one small chunk fits, so caching (1, 0) evicts (0, 0)
+    cached.get_chunk_nbt(1, 0).unwrap();
+    assert_eq!((cache.len(), cache.bytes()), (1, small));
+    assert!(!Arc::ptr_eq(
+        &first,
+        &cached.get_chunk_nbt(0, 0).unwrap().unwrap()
+    ));
+
+    // A chunk over the budget is returned but not cached
+    let large = cached.get_chunk_nbt(2, 0).unwrap().unwrap();
+    assert_eq!(large.1, NbtTag::ByteArray(vec![0; 4096]));
+    assert_eq!((cache.len(), cache.bytes()), (1, small));
+
+    cache.clear();
+    assert_eq!(cache.bytes(), 0);
+}
+
+#[test]
+fn test_region_writer_validation() {
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use std::io::Cursor;
+
+    let mut map = IndexMap::new();
+    map.insert("DataVersion".to_string(), NbtTag::Int(3953));
+    map.insert("xPos".to_string(), NbtTag::Int(2));
+    map.insert("zPos".to_string(), NbtTag::Int(3));
+    let good = vec![(2, 3, "".to_string(), NbtTag::Compound(map))];
+    let bad = vec![(0, 0, "".to_string(), NbtTag::Int(1))];
+
+    let mut writer = RegionWriter::new(Cursor::new(Vec::new()));
+    writer.set_validation(true);
+    writer.write_all_chunks(&good).unwrap();
+    let err = writer.write_all_chunks(&bad).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+#[cfg(feature = "compression")]
+fn test_region_writer_compression_presets() {
+    use anvil_nbt::anvil::CompressionPreset;
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::{RegionWriter, SectorBuffer};
+    use std::io::Cursor;
+
+    let longs: Vec<i64> = (0..2048).map(|i| (i * 7919) % 1000).collect();
+    let tag = anvil_nbt::nbt!({ data: (NbtTag::LongArray(longs)) });
+    let mut sizes = Vec::new();
+    for preset in [CompressionPreset::Fast, CompressionPreset::Archive] {
+        let mut buffer = SectorBuffer::with_compression(preset);
+        buffer.encode_chunk("", &tag).unwrap();
+        sizes.push(u32::from_be_bytes(
+            buffer.as_bytes()[..4].try_into().unwrap(),
+        ));
+
+        let mut writer = RegionWriter::new(Cursor::new(Vec::new()));
+        writer.set_compression(preset);
+        writer
+            .write_all_chunks(&[(0, 0, "".to_string(), tag.clone())])
+            .unwrap();
+        let region = Region::from_reader(Cursor::new(writer.into_inner().into_inner())).unwrap();
+        assert_eq!(region.get_chunk_nbt(0, 0).unwrap().unwrap().1, tag);
+    }
+    assert!(sizes[1] < sizes[0], "{:?}", sizes);
+}
+
+#[test]
+#[cfg(feature = "compression")]
+fn test_region_writer_options() {
+    use anvil_nbt::anvil::CompressionType;
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::{RegionWriter, RegionWriterOptions};
+    use std::io::Cursor;
+
+    let tag = anvil_nbt::nbt!({ DataVersion: 3953, Status: "minecraft:full" });
+    for compression in [
+        CompressionType::Gzip,
+        CompressionType::Zlib,
+        CompressionType::None,
+    ] {
+        let options = RegionWriterOptions {
+            compression,
+            level: 9,
+        };
+        let mut writer = RegionWriter::with_options(Cursor::new(Vec::new()), options);
+        writer.write_chunk(0, 0, "", &tag).unwrap();
+        writer.flush_header().unwrap();
+        let region = Region::from_reader(Cursor::new(writer.into_inner().into_inner())).unwrap();
+        assert_eq!(
+            region.chunk_info(0, 0).unwrap().unwrap().compression(),
+            Some(compression)
+        );
+        assert_eq!(region.get_chunk_nbt(0, 0).unwrap().unwrap().1, tag);
+    }
+}
+
+#[test]
+#[cfg(feature = "lz4")]
+fn test_region_writer_lz4() {
+    use anvil_nbt::anvil::CompressionType;
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::{RegionWriter, RegionWriterOptions};
+    use std::io::Cursor;
+
+    let longs: Vec<i64> = (0..20_000).map(|i| i % 64).collect();
+    let tag = anvil_nbt::nbt!({ DataVersion: 3953, data: (NbtTag::LongArray(longs)) });
+    let options = RegionWriterOptions {
+        compression: CompressionType::Lz4,
+        level: 0,
+    };
+    let mut writer = RegionWriter::with_options(Cursor::new(Vec::new()), options);
+    writer.write_chunk(1, 2, "", &tag).unwrap();
+    writer.flush_header().unwrap();
+    let region = Region::from_reader(Cursor::new(writer.into_inner().into_inner())).unwrap();
+
+    let (compression, data) = region.get_chunk_raw(1, 2).unwrap().unwrap();
+    assert_eq!(compression, CompressionType::Lz4);
+    assert!(data.starts_with(b"LZ4Block"));
+    assert!(data.len() < tag.serialized_size("") / 4);
+    assert_eq!(region.get_chunk_nbt(1, 2).unwrap().unwrap().1, tag);
+}
+
+#[test]
+#[cfg(not(feature = "lz4"))]
+fn test_region_lz4_needs_feature() {
+    use anvil_nbt::anvil::CompressionType;
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::{RegionWriter, RegionWriterOptions};
+    use std::io::Cursor;
+
+    let mut writer = RegionWriter::new(Cursor::new(Vec::new()));
+    writer
+        .write_raw_chunk(0, 0, CompressionType::Lz4, b"LZ4Block")
+        .unwrap();
+    writer.flush_header().unwrap();
+    let region = Region::from_reader(Cursor::new(writer.into_inner().into_inner())).unwrap();
+    let err = region.get_chunk_data(0, 0).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+
+    let options = RegionWriterOptions {
+        compression: CompressionType::Lz4,
+        level: 0,
+    };
+    let mut writer = RegionWriter::with_options(Cursor::new(Vec::new()), options);
+    let err = writer.write_chunk(0, 0, "", &NbtTag::Int(0)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+}
+
+#[test]
+#[cfg(feature = "compression")]
+fn test_region_chunk_info() {
+    use anvil_nbt::anvil::CompressionType;
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::minimal_region;
+
+    let region = Region::from_reader(&minimal_region(&[(0, 0), (4, 1)]).unwrap()[..]).unwrap();
+    let info = region.chunk_info(4, 1).unwrap().unwrap();
+    assert_eq!(info.sector_offset, 3);
+    assert_eq!(info.sector_count, 1);
+    assert_eq!(info.compression(), Some(CompressionType::Zlib));
+    assert_eq!(info.timestamp, 0);
+    let raw = region.get_chunk_data(4, 1).unwrap().unwrap();
+    assert!(info.compressed_len > 0 && info.compressed_len < raw.len() + 16);
+    assert_eq!(region.chunk_info(1, 1).unwrap(), None);
+}
+
+#[test]
+fn test_region_iter_chunks_in_time_range() {
+    use anvil_nbt::anvil::SECTOR_SIZE;
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::minimal_region;
+
+    let mut bytes = minimal_region(&[(0, 0), (5, 0), (2, 3)]).unwrap();
+    for (index, timestamp) in [(0, 100u32), (5, 200), (3 * 32 + 2, 300)] {
+        let start = SECTOR_SIZE + index * 4;
+        bytes[start..start + 4].copy_from_slice(&timestamp.to_be_bytes());
+    }
+    let region = Region::from_reader(&bytes[..]).unwrap();
+
+    let coords = |start, end| {
+        region
+            .iter_chunks_in_time_range(start, end)
+            .map(|chunk| chunk.map(|(x, z, _, _)| (x, z)))
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap()
+    };
+    assert_eq!(coords(150, u32::MAX), vec![(5, 0), (2, 3)]);
+    assert_eq!(coords(100, 300), vec![(0, 0), (5, 0)]);
+    assert!(coords(301, u32::MAX).is_empty());
+}
+
+#[test]
+fn test_region_iter_chunks() {
+    use anvil_nbt::anvil::SECTOR_SIZE;
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::minimal_region;
+
+    let mut bytes = minimal_region(&[(2, 3), (0, 0), (5, 0)]).unwrap();
+    // Corrupt the compression byte of (0, 0), the second chunk in the file
+    bytes[3 * SECTOR_SIZE + 4] = 99;
+    let region = Region::from_reader(&bytes[..]).unwrap();
+
+    assert_eq!(region.present_chunks(), vec![(0, 0), (5, 0), (2, 3)]);
+    let chunks: Vec<_> = region.iter_chunks().collect();
+    let coords: Vec<_> = chunks.iter().map(|(x, z, _)| (*x, *z)).collect();
+    assert_eq!(coords, vec![(2, 3), (0, 0), (5, 0)]);
+    assert!(chunks[0].2.is_ok());
+    assert!(chunks[1].2.is_err());
+    assert!(chunks[2].2.is_ok());
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn test_region_par_iter_chunks() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::minimal_region;
+    use rayon::iter::ParallelIterator;
+
+    let coords: Vec<_> = (0..32).map(|i| (i, 31 - i)).collect();
+    let region = Region::from_reader(&minimal_region(&coords).unwrap()[..]).unwrap();
+    let mut found: Vec<_> = region
+        .par_iter_chunks()
+        .map(|(x, z, chunk)| {
+            assert!(chunk.is_ok());
+            (x, z)
+        })
+        .collect();
+    found.sort_by_key(|&(x, z)| (z, x));
+    assert_eq!(found, region.present_chunks());
+}
+
+#[test]
+fn test_region_validate_layout() {
+    use anvil_nbt::anvil::SECTOR_SIZE;
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::minimal_region;
+    use anvil_nbt::anvil::layout::LayoutIssue;
+
+    let mut bytes = minimal_region(&[(0, 0), (1, 0), (2, 0), (3, 0)]).unwrap();
+    assert!(
+        Region::from_reader(&bytes[..])
+            .unwrap()
+            .validate()
+            .is_valid()
+    );
+
+    let mut set_location = |index: usize, offset: u8, count: u8| {
+        bytes[index * 4..index * 4 + 4].copy_from_slice(&[0, 0, offset, count]);
+    };
+    set_location(0, 1, 1);
+    set_location(2, 5, 1);
+    set_location(4, 9, 1);
+    bytes[3 * SECTOR_SIZE..3 * SECTOR_SIZE + 4].copy_from_slice(&5000u32.to_be_bytes());
+    let region = Region::from_reader(&bytes[..]).unwrap();
+    let report = region.validate();
+    let err = region.get_chunk_data(4, 0).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    assert_eq!(
+        report.issues,
+        vec![
+            LayoutIssue::OverlapsHeader {
+                x: 0,
+                z: 0,
+                offset: 1
+            },
+            LayoutIssue::LengthExceedsSectors {
+                x: 1,
+                z: 0,
+                length: 5000,
+                allocated: SECTOR_SIZE - 4
+            },
+            LayoutIssue::Overlap {
+                first: (2, 0),
+                second: (3, 0)
+            },
+            LayoutIssue::PastEndOfFile {
+                x: 4,
+                z: 0,
+                end: 10 * SECTOR_SIZE,
+                file_len: 6 * SECTOR_SIZE
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_region_salvage() {
+    use anvil_nbt::anvil::HEADER_SIZE;
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use std::io::Cursor;
+
+    let chunks = vec![
+        (
+            3,
+            4,
+            "".to_string(),
+            anvil_nbt::nbt!({ xPos: 35, zPos: -28 }),
+        ),
+        (
+            0,
+            0,
+            "".to_string(),
+            anvil_nbt::nbt!({ Status: "minecraft:full" }),
+        ),
+        (
+            9,
+            1,
+            "".to_string(),
+            anvil_nbt::nbt!({ Level: { xPos: 9, zPos: 1 } }),
+        ),
+    ];
+    let mut writer = RegionWriter::new(Cursor::new(Vec::new()));
+    writer.write_all_chunks(&chunks).unwrap();
+    let mut bytes = writer.into_inner().into_inner();
+
+    let intact = Region::from_reader(&bytes[..]).unwrap().salvage();
+    assert_eq!(intact[1].position, Some((0, 0)));
+
+    bytes[..HEADER_SIZE].fill(0xAB);
+    let salvaged = Region::from_reader(&bytes[..]).unwrap().salvage();
+    let found: Vec<_> = salvaged.iter().map(|c| (c.sector, c.position)).collect();
+    assert_eq!(found, vec![(2, Some((3, 4))), (3, None), (4, Some((9, 1)))]);
+    assert_eq!(salvaged[1].tag, chunks[1].3);
+}
+
+#[test]
+#[cfg(feature = "compression")]
+fn test_region_max_chunk_size() {
+    use anvil_nbt::anvil::access::{Region, RegionOptions};
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use std::io::Cursor;
+
+    // Compresses to a few kilobytes but decodes to about 1 MiB
+    let tag = anvil_nbt::nbt!({ data: (NbtTag::ByteArray(vec![0; 1 << 20])) });
+    let mut writer = RegionWriter::new(Cursor::new(Vec::new()));
+    writer.write_chunk(0, 0, "", &tag).unwrap();
+    writer.flush_header().unwrap();
+    let mut region = Region::from_reader(Cursor::new(writer.into_inner().into_inner())).unwrap();
+    assert!(region.get_chunk_nbt(0, 0).unwrap().is_some());
+
+    region.set_options(RegionOptions {
+        max_chunk_size: Some(64 * 1024),
+    });
+    let err = region.get_chunk_data(0, 0).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(region.chunk_metadata().is_err());
+
+    region.set_options(RegionOptions {
+        max_chunk_size: Some(2 << 20),
+    });
+    assert_eq!(region.get_chunk_nbt(0, 0).unwrap().unwrap().1, tag);
+}
+
+#[test]
+fn test_region_chunk_metadata() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use anvil_nbt::anvil::metadata::ChunkMetadata;
+    use std::io::Cursor;
+
+    let mut current = IndexMap::new();
+    current.insert("InhabitedTime".to_string(), NbtTag::Long(1200));
+    current.insert("Sections".to_string(), NbtTag::List(vec![NbtTag::Int(1)]));
+    current.insert("LastUpdate".to_string(), NbtTag::Long(99));
+    current.insert(
+        "Status".to_string(),
+        NbtTag::String("minecraft:full".to_string()),
+    );
+
+    let mut level = IndexMap::new();
+    level.insert("InhabitedTime".to_string(), NbtTag::Long(5));
+    let mut legacy = IndexMap::new();
+    legacy.insert("Level".to_string(), NbtTag::Compound(level));
+
+    let chunks = vec![
+        (1, 2, "".to_string(), NbtTag::Compound(current)),
+        (31, 0, "".to_string(), NbtTag::Compound(legacy)),
+    ];
+    let mut cursor = Cursor::new(Vec::new());
+    RegionWriter::new(&mut cursor)
+        .write_all_chunks(&chunks)
+        .unwrap();
+    cursor.set_position(0);
+
+    let metadata = Region::from_reader(cursor)
+        .unwrap()
+        .chunk_metadata()
+        .unwrap();
+    assert_eq!(metadata.chunk_count(), 2);
+
+    let i = ChunkMetadata::index(1, 2);
+    assert!(metadata.present[i]);
+    assert_eq!(metadata.inhabited_time[i], 1200);
+    assert_eq!(metadata.last_update[i], 99);
+    assert_eq!(metadata.status[i].as_deref(), Some("minecraft:full"));
+
+    let i = ChunkMetadata::index(31, 0);
+    assert_eq!(metadata.inhabited_time[i], 5);
+    assert_eq!(metadata.status[i], None);
+    assert!(!metadata.present[ChunkMetadata::index(0, 0)]);
+}
+
+#[test]
+fn test_region_chunk_data_version_and_status() {
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use std::io::Cursor;
+
+    let current = anvil_nbt::nbt!({
+        DataVersion: 3953,
+        Status: "minecraft:full",
+        sections: (NbtTag::List(vec![NbtTag::Int(1)]))
+    });
+    let legacy = anvil_nbt::nbt!({
+        DataVersion: 1343,
+        Level: { Status: "postprocessed" }
+    });
+    let chunks = vec![
+        (0, 0, "".to_string(), current),
+        (1, 0, "".to_string(), legacy),
+        (2, 0, "".to_string(), anvil_nbt::nbt!({ Level: {} })),
+    ];
+    let mut cursor = Cursor::new(Vec::new());
+    RegionWriter::new(&mut cursor)
+        .write_all_chunks(&chunks)
+        .unwrap();
+    let region = Region::from_reader(Cursor::new(cursor.into_inner())).unwrap();
+
+    assert_eq!(region.chunk_data_version(0, 0).unwrap(), Some(3953));
+    assert_eq!(
+        region.chunk_status(0, 0).unwrap().as_deref(),
+        Some("minecraft:full")
+    );
+    assert_eq!(region.chunk_data_version(1, 0).unwrap(), Some(1343));
+    assert_eq!(
+        region.chunk_status(1, 0).unwrap().as_deref(),
+        Some("postprocessed")
+    );
+    assert_eq!(region.chunk_data_version(2, 0).unwrap(), None);
+    assert_eq!(region.chunk_status(2, 0).unwrap(), None);
+    assert_eq!(region.chunk_data_version(5, 5).unwrap(), None);
+}
+
+#[test]
+#[cfg(feature = "compression")]
+fn test_sector_buffer() {
+    use anvil_nbt::anvil::SECTOR_SIZE;
+    use anvil_nbt::anvil::encode::SectorBuffer;
+    use flate2::read::ZlibDecoder;
+
+    let data: Vec<i64> = (0..600).map(|i| i * 7919).collect();
+    let chunk = anvil_nbt::nbt!({ DataVersion: 3953, data: data });
+    let mut buffer = SectorBuffer::new();
+    for _ in 0..2 {
+        let sectors = buffer.encode_chunk("", &chunk).unwrap();
+        let bytes = buffer.as_bytes();
+        assert_eq!(bytes.len(), sectors * SECTOR_SIZE);
+        assert_eq!(buffer.sector_count(), sectors);
+
+        let length = u32::from_be_bytes(bytes[..4].try_into().unwrap()) as usize;
+        assert!(length + 4 <= bytes.len() && length + 4 > bytes.len() - SECTOR_SIZE);
+        assert_eq!(bytes[4], 2);
+        let mut raw = Vec::new();
+        ZlibDecoder::new(&bytes[5..4 + length])
+            .read_to_end(&mut raw)
+            .unwrap();
+        assert_eq!(parse_named_tag(&mut &raw[..]).unwrap().1, chunk);
+    }
+}
+
+#[test]
+fn test_structure_paste_into_region() {
+    use anvil_nbt::anvil::RegionPos;
+    use anvil_nbt::anvil::access::Region;
+    use anvil_nbt::anvil::chunk::BlockState;
+    use anvil_nbt::anvil::editor::RegionEditor;
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use anvil_nbt::anvil::structure::{Structure, StructureFormat};
+    use anvil_nbt::nbt::snbt::parse_snbt;
+
+    let mut structure = Structure::new([3, 1, 1]).unwrap();
+    for x in 0..3 {
+        structure.set(
+            [x, 0, 0],
+            BlockState::parse("minecraft:gold_block").unwrap(),
+        );
+    }
+    let structure_path = std::env::temp_dir().join("test_paste.mcstructure");
+    structure
+        .write(&structure_path, StructureFormat::Bedrock)
+        .unwrap();
+    let structure = Structure::read(&structure_path).unwrap();
+
+    // Only chunk (-1, 0) exists; the structure also reaches into chunk (-2, 0)
+    let chunk = parse_snbt(
+        r#"{DataVersion: 3953, xPos: -1, zPos: 0, sections: [
+            {Y: 4b, block_states: {palette: [{Name: "minecraft:stone"}]}}
+        ]}"#,
+    )
+    .unwrap();
+    let mca_path = std::env::temp_dir().join("r.-1.0.mca");
+    RegionWriter::new(std::fs::File::create(&mca_path).unwrap())
+        .write_all_chunks(&[(31, 0, String::new(), chunk)])
+        .unwrap();
+
+    let mut editor = RegionEditor::open(&mca_path).unwrap();
+    let placed = structure
+        .paste_into_region(&mut editor, RegionPos::new(-1, 0), [-17, 64, 3])
+        .unwrap();
+    editor.flush().unwrap();
+    assert_eq!(placed, 2);
+
+    let chunk = Region::open(&mca_path)
+        .unwrap()
+        .get_chunk(-1, 0)
+        .unwrap()
+        .unwrap();
+    let counts = chunk.block_counts().unwrap();
+    let gold = BlockState::parse("minecraft:gold_block").unwrap();
+    let stone = BlockState::parse("minecraft:stone").unwrap();
+    assert_eq!(counts[&gold], 2);
+    assert_eq!(counts[&stone], 4094);
+
+    std::fs::remove_file(structure_path).unwrap();
+    std::fs::remove_file(mca_path).unwrap();
+}
@@ -4,7 +4,7 @@
 #[cfg(feature = "serde")]
 mod tests {
     use anvil_nbt::nbt::NbtTag;
-    use anvil_nbt::nbt::serde_impl::{from_nbt, to_nbt};
+    use anvil_nbt::nbt::serde_impl::{RawNbt, from_nbt, to_nbt};
     use serde::{Deserialize, Serialize};
 
     #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -87,4 +87,166 @@ mod tests {
 
         assert_eq!(original, decoded);
     }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Modded {
+        name: String,
+        extra: RawNbt,
+        missing: Option<RawNbt>,
+    }
+
+    #[test]
+    fn test_raw_nbt_passthrough() {
+        let tag = anvil_nbt::nbt!({
+            name: "Steve",
+            extra: { ids: [I; 1, 2], bits: [L; 3], flags: [B; 1], level: 2i16, items: [] },
+        });
+        let model: Modded = from_nbt(tag.clone()).unwrap();
+        assert_eq!(model.missing, None);
+        assert_eq!(Some(&model.extra.0), tag.get_path("extra"));
+
+        let mut written = to_nbt(&model).unwrap();
+        if let NbtTag::Compound(map) = &mut written {
+            map.shift_remove("missing");
+        }
+        assert_eq!(written, tag);
+
+        let json = serde_json::to_string(&model).unwrap();
+        let decoded: Modded = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, model);
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct ChunkHeader<'a> {
+        #[serde(rename = "DataVersion")]
+        data_version: i32,
+        #[serde(rename = "Status", borrow)]
+        status: &'a str,
+        #[serde(rename = "xPos")]
+        x_pos: Option<i32>,
+        heights: Vec<i64>,
+        structures: RawNbt,
+    }
+
+    #[test]
+    fn test_from_bytes_skips_unknown_fields() {
+        use anvil_nbt::nbt::encode::write_named_tag;
+        use anvil_nbt::nbt::serde_impl::from_bytes;
+
+        let tag = anvil_nbt::nbt!({
+            sections: [{ Y: 0i8, block_states: { data: [L; 1, 2, 3] } }],
+            DataVersion: 3953,
+            Status: "minecraft:full",
+            heights: [L; 7, 8],
+            structures: { References: {}, starts: [I; 1] },
+            block_entities: [],
+        });
+        let mut bytes = Vec::new();
+        write_named_tag(&mut bytes, "", &tag).unwrap();
+
+        let header: ChunkHeader = from_bytes(&bytes).unwrap();
+        assert_eq!(
+            header,
+            ChunkHeader {
+                data_version: 3953,
+                status: "minecraft:full",
+                x_pos: None,
+                heights: vec![7, 8],
+                structures: RawNbt(tag.get_path("structures").unwrap().clone()),
+            }
+        );
+
+        let original = TestStruct {
+            name: "Alex".to_owned(),
+            age: 30,
+            active: true,
+            scores: vec![1, 2, 3],
+            metadata: Meta {
+                version: "2.0".to_owned(),
+                tags: vec!["vip".to_owned()],
+            },
+        };
+        let mut bytes = Vec::new();
+        write_named_tag(&mut bytes, "", &to_nbt(&original).unwrap()).unwrap();
+        assert_eq!(from_bytes::<TestStruct>(&bytes).unwrap(), original);
+        assert!(from_bytes::<TestStruct>(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_from_bytes_depth_limit() {
+        use anvil_nbt::nbt::parse::ParseErrorKind;
+        use anvil_nbt::nbt::serde_impl::{SerdeError, from_bytes};
+
+        #[derive(Debug, Deserialize)]
+        struct Nested(#[allow(dead_code)] Vec<Nested>);
+
+        // 100,000 lists, each holding the next
+        let mut bytes = vec![9, 0, 0];
+        for _ in 0..100_000 {
+            bytes.extend_from_slice(&[9, 0, 0, 0, 1]);
+        }
+        bytes.extend_from_slice(&[0, 0, 0, 0, 0]);
+
+        // Unoptimized serde frames are large, so 512 levels need more than the test
+        // thread's default stack
+        let errors = std::thread::Builder::new()
+            .stack_size(64 << 20)
+            .spawn(move || {
+                [
+                    from_bytes::<Nested>(&bytes).unwrap_err(),
+                    from_bytes::<serde::de::IgnoredAny>(&bytes).unwrap_err(),
+                ]
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        for err in errors {
+            assert!(
+                matches!(&err, SerdeError::Parse(e) if e.kind == ParseErrorKind::DepthLimitExceeded),
+                "{err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_named_bytes_root_name() {
+        use anvil_nbt::nbt::serde_impl::{
+            RootName, SerdeError, from_bytes, from_named_bytes, to_named_bytes,
+        };
+
+        let meta = Meta {
+            version: "1.0".to_owned(),
+            tags: vec![],
+        };
+        let bytes = to_named_bytes("servers", &meta).unwrap();
+        assert_eq!(&bytes[1..10], b"\0\x07servers");
+        assert_eq!(from_bytes::<Meta>(&bytes).unwrap(), meta);
+        assert_eq!(
+            from_named_bytes::<Meta>(&bytes, RootName::Exact("servers")).unwrap(),
+            meta
+        );
+        assert!(matches!(
+            from_named_bytes::<Meta>(&bytes, RootName::Exact("")),
+            Err(SerdeError::UnexpectedRootName { found, .. }) if found == "servers"
+        ));
+    }
+
+    #[test]
+    fn test_raw_strings_are_not_lossy() {
+        let raw = NbtTag::RawString(vec![0xff, b'x']);
+        assert!(serde_json::to_string(&raw).is_err());
+        assert!(from_nbt::<String>(raw.clone()).is_err());
+
+        // RawNbt carries the bytes through unchanged
+        let tag = anvil_nbt::nbt!({ name: "Steve" });
+        let NbtTag::Compound(mut map) = tag else {
+            unreachable!()
+        };
+        map.insert("name".to_string(), raw);
+        let tag = NbtTag::Compound(map);
+        let passed: RawNbt = from_nbt(tag.clone()).unwrap();
+        assert_eq!(passed.0, tag);
+        let json = serde_json::to_string(&passed).unwrap();
+        assert_eq!(serde_json::from_str::<RawNbt>(&json).unwrap().0, tag);
+    }
 }
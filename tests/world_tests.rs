@@ -0,0 +1,780 @@
+// Copyright 2026 driedpampas@proton.me
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anvil_nbt::nbt::NbtTag;
+use anvil_nbt::world::level::{LEVEL_DAT, LEVEL_DAT_OLD, LevelDat};
+use indexmap::IndexMap;
+use std::path::PathBuf;
+
+fn temp_world(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("anvil-nbt-{}", name));
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Overwrites the start of the compressed data of chunk `(x, z)` in a region file.
+fn corrupt_chunk(path: &std::path::Path, x: usize, z: usize) {
+    let mut bytes = std::fs::read(path).unwrap();
+    let entry = (x % 32 + z % 32 * 32) * 4;
+    let offset = u32::from_be_bytes([0, bytes[entry], bytes[entry + 1], bytes[entry + 2]]);
+    let start = offset as usize * 4096 + 5;
+    bytes[start..start + 16].fill(0xff);
+    std::fs::write(path, bytes).unwrap();
+}
+
+fn level_with_seed(seed: i64) -> LevelDat {
+    let mut data = IndexMap::new();
+    data.insert("RandomSeed".to_string(), NbtTag::Long(seed));
+    let mut root = IndexMap::new();
+    root.insert("Data".to_string(), NbtTag::Compound(data));
+    LevelDat {
+        name: String::new(),
+        root: NbtTag::Compound(root),
+        from_backup: false,
+    }
+}
+
+#[test]
+fn test_level_dat_write_rotates_backup() {
+    let dir = temp_world("level-rotate");
+
+    level_with_seed(1).write(&dir).unwrap();
+    assert!(dir.join(LEVEL_DAT_OLD).exists());
+
+    level_with_seed(2).write(&dir).unwrap();
+    let current = LevelDat::read(&dir).unwrap();
+    assert_eq!(current, level_with_seed(2));
+
+    // Previous save should now live in level.dat_old
+    std::fs::remove_file(dir.join(LEVEL_DAT)).unwrap();
+    let backup = LevelDat::read(&dir).unwrap();
+    assert!(backup.from_backup);
+    assert_eq!(backup.root, level_with_seed(1).root);
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_level_dat_falls_back_on_truncated_file() {
+    let dir = temp_world("level-truncated");
+
+    level_with_seed(7).write(&dir).unwrap();
+    let full = std::fs::read(dir.join(LEVEL_DAT)).unwrap();
+    std::fs::write(dir.join(LEVEL_DAT), &full[..full.len() / 2]).unwrap();
+
+    let level = LevelDat::read(&dir).unwrap();
+    assert!(level.from_backup);
+    assert_eq!(level.root, level_with_seed(7).root);
+
+    // Saving over a broken level.dat must not clobber the good backup with garbage
+    level_with_seed(8).write(&dir).unwrap();
+    let level = LevelDat::read(&dir).unwrap();
+    assert!(!level.from_backup);
+    assert_eq!(level.root, level_with_seed(8).root);
+
+    // The backup still holds the last good save, not a copy of the new one
+    std::fs::remove_file(dir.join(LEVEL_DAT)).unwrap();
+    let backup = LevelDat::read(&dir).unwrap();
+    assert!(backup.from_backup);
+    assert_eq!(backup.root, level_with_seed(7).root);
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_player_data_backup_and_refusal() {
+    use anvil_nbt::world::player::{
+        PlayerWriteOptions, player_data_path, read_player_data, write_player_data,
+    };
+
+    let dir = temp_world("playerdata");
+    let uuid = "069a79f4-44e9-4726-a5be-fca90e38aaf5";
+    let options = PlayerWriteOptions::default();
+
+    write_player_data(&dir, uuid, "", &NbtTag::Int(1), &options).unwrap();
+    write_player_data(&dir, uuid, "", &NbtTag::Int(2), &options).unwrap();
+    assert_eq!(read_player_data(&dir, uuid).unwrap().1, NbtTag::Int(2));
+
+    let path = player_data_path(&dir, uuid).unwrap();
+    let backup = std::fs::read(path.with_extension("dat_old")).unwrap();
+    std::fs::write(&path, &backup[..4]).unwrap();
+
+    // A corrupt existing file is only replaced when forced
+    assert!(write_player_data(&dir, uuid, "", &NbtTag::Int(3), &options).is_err());
+    let forced = PlayerWriteOptions {
+        force: true,
+        ..options
+    };
+    write_player_data(&dir, uuid, "", &NbtTag::Int(3), &forced).unwrap();
+    assert_eq!(read_player_data(&dir, uuid).unwrap().1, NbtTag::Int(3));
+
+    // The backup still holds the last good save, not the corrupt file
+    let old = std::fs::read(path.with_extension("dat_old")).unwrap();
+    assert_eq!(old, backup);
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_player_data_backup_seeded_and_uuid_checked() {
+    use anvil_nbt::world::player::{
+        PlayerWriteOptions, player_data_path, read_player_data, write_player_data,
+    };
+    use std::io::ErrorKind;
+
+    let dir = temp_world("playerdata-seed");
+    let uuid = "069a79f4-44e9-4726-a5be-fca90e38aaf5";
+    let options = PlayerWriteOptions::default();
+
+    // A first save seeds the missing backup with the new data
+    write_player_data(&dir, uuid, "", &NbtTag::Int(1), &options).unwrap();
+    let path = player_data_path(&dir, uuid).unwrap();
+    assert_eq!(
+        std::fs::read(path.with_extension("dat_old")).unwrap(),
+        std::fs::read(&path).unwrap()
+    );
+
+    for bad in [
+        "../level",
+        "..",
+        "a/b",
+        "069a79f4-44e9-4726-a5be-fca90e38aaf5/..",
+        "",
+    ] {
+        let err = write_player_data(&dir, bad, "", &NbtTag::Int(2), &options).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            read_player_data(&dir, bad).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+    assert!(!dir.join("level.dat").exists());
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_world_dimension_paths() {
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use anvil_nbt::world::World;
+    use anvil_nbt::world::dimension::{Dimension, RegionKind};
+
+    let dir = temp_world("dimensions");
+    for sub in [
+        "region",
+        "DIM-1/region",
+        "dimensions/mypack/sky/islands/region",
+    ] {
+        std::fs::create_dir_all(dir.join(sub)).unwrap();
+    }
+    let world = World::open(&dir).unwrap();
+
+    assert_eq!(
+        world.dimensions().unwrap(),
+        vec![
+            Dimension::Overworld,
+            Dimension::Nether,
+            Dimension::from_id("mypack:sky/islands"),
+        ]
+    );
+    assert_eq!(
+        world.region_path(&Dimension::End, RegionKind::Entities, -1, 2),
+        dir.join("DIM1").join("entities").join("r.-1.2.mca")
+    );
+
+    // Chunk (-3, 40) lives in region (-1, 1) of the Nether
+    let chunks = vec![(-3, 40, "".to_string(), NbtTag::Int(9))];
+    let file = std::fs::File::create(dir.join("DIM-1/region/r.-1.1.mca")).unwrap();
+    RegionWriter::new(file).write_all_chunks(&chunks).unwrap();
+    let chunk = world
+        .get_chunk_nbt(&Dimension::Nether, RegionKind::Terrain, -3, 40)
+        .unwrap();
+    assert_eq!(chunk.unwrap().1, NbtTag::Int(9));
+    assert!(
+        world
+            .get_chunk_nbt(&Dimension::Overworld, RegionKind::Terrain, -3, 40)
+            .unwrap()
+            .is_none()
+    );
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_forced_chunks_round_trip() {
+    use anvil_nbt::world::World;
+    use anvil_nbt::world::dimension::Dimension;
+    use anvil_nbt::world::forced::{ForcedChunks, pack_chunk_pos, unpack_chunk_pos};
+
+    assert_eq!(unpack_chunk_pos(pack_chunk_pos(-5, 7)), (-5, 7));
+    assert_eq!(pack_chunk_pos(1, 2), 0x0000_0002_0000_0001);
+
+    let dir = temp_world("forced");
+    let world = World::open(&dir).unwrap();
+    assert!(
+        world
+            .forced_chunks(&Dimension::End)
+            .unwrap()
+            .chunks
+            .is_empty()
+    );
+
+    let mut forced = ForcedChunks::default();
+    forced.chunks.insert((-5, 7));
+    forced.chunks.insert((0, 0));
+    world.write_forced_chunks(&Dimension::End, &forced).unwrap();
+
+    assert!(dir.join("DIM1/data/chunks.dat").exists());
+    let read = world.forced_chunks(&Dimension::End).unwrap();
+    assert_eq!(read, forced);
+    assert!(read.contains(-5, 7));
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_block_census() {
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use anvil_nbt::world::World;
+    use anvil_nbt::world::census::CensusFilter;
+    use anvil_nbt::world::dimension::Dimension;
+    use anvil_nbt::world::scan::ChunkArea;
+
+    fn block(name: &str) -> NbtTag {
+        let mut map = IndexMap::new();
+        map.insert("Name".to_string(), NbtTag::String(name.to_string()));
+        NbtTag::Compound(map)
+    }
+
+    fn chunk(x: i32, z: i32) -> NbtTag {
+        // One all-stone section and one with a single spawner among air
+        let mut data = vec![0i64; 256];
+        data[0] = 1;
+        let mut mixed = IndexMap::new();
+        mixed.insert(
+            "palette".to_string(),
+            NbtTag::List(vec![block("minecraft:air"), block("minecraft:spawner")]),
+        );
+        mixed.insert("data".to_string(), NbtTag::LongArray(data));
+        let mut solid = IndexMap::new();
+        solid.insert(
+            "palette".to_string(),
+            NbtTag::List(vec![block("minecraft:stone")]),
+        );
+
+        let sections = [solid, mixed]
+            .into_iter()
+            .map(|states| {
+                let mut section = IndexMap::new();
+                section.insert("block_states".to_string(), NbtTag::Compound(states));
+                NbtTag::Compound(section)
+            })
+            .collect();
+        let mut root = IndexMap::new();
+        root.insert("DataVersion".to_string(), NbtTag::Int(3953));
+        root.insert("xPos".to_string(), NbtTag::Int(x));
+        root.insert("zPos".to_string(), NbtTag::Int(z));
+        root.insert("sections".to_string(), NbtTag::List(sections));
+        NbtTag::Compound(root)
+    }
+
+    let dir = temp_world("census");
+    std::fs::create_dir_all(dir.join("region")).unwrap();
+    let file = std::fs::File::create(dir.join("region/r.0.0.mca")).unwrap();
+    let chunks = vec![
+        (0, 0, String::new(), chunk(0, 0)),
+        (5, 5, String::new(), chunk(5, 5)),
+        (7, 7, String::new(), chunk(7, 7)),
+    ];
+    RegionWriter::new(file).write_all_chunks(&chunks).unwrap();
+    let path = dir.join("region/r.0.0.mca");
+    corrupt_chunk(&path, 7, 7);
+    // Empty and truncated regions, as left by a crash, are treated as empty
+    std::fs::write(dir.join("region/r.1.0.mca"), b"").unwrap();
+    std::fs::write(dir.join("region/r.2.0.mca"), [0; 100]).unwrap();
+
+    let world = World::open(&dir).unwrap();
+    let mut filter = CensusFilter::new(Dimension::Overworld);
+    let all = world.block_census(&filter).unwrap();
+    assert_eq!(all.skipped.len(), 1);
+    assert_eq!(all.skipped[0].chunk, Some((7, 7)));
+    assert_eq!(all.skipped[0].path, path);
+    let counts: Vec<(String, u64)> = all.found.iter().map(|(s, c)| (s.to_string(), *c)).collect();
+    assert_eq!(
+        counts,
+        vec![
+            ("minecraft:air".to_string(), 2 * 4095),
+            ("minecraft:spawner".to_string(), 2),
+            ("minecraft:stone".to_string(), 2 * 4096),
+        ]
+    );
+
+    filter.blocks = vec!["minecraft:spawner".to_string()];
+    filter.area = Some(ChunkArea::new(0, 0, 3, 3));
+    let spawners = world.block_census(&filter).unwrap();
+    assert_eq!(
+        spawners.found.values().copied().collect::<Vec<_>>(),
+        vec![1]
+    );
+    assert!(spawners.skipped.is_empty());
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_find_block_entities() {
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use anvil_nbt::world::World;
+    use anvil_nbt::world::dimension::Dimension;
+    use anvil_nbt::world::scan::ChunkArea;
+
+    fn block_entity(id: &str, x: i32, y: i32, z: i32) -> NbtTag {
+        let mut map = IndexMap::new();
+        map.insert("id".to_string(), NbtTag::String(id.to_string()));
+        map.insert("x".to_string(), NbtTag::Int(x));
+        map.insert("y".to_string(), NbtTag::Int(y));
+        map.insert("z".to_string(), NbtTag::Int(z));
+        NbtTag::Compound(map)
+    }
+
+    let mut current = IndexMap::new();
+    current.insert(
+        "block_entities".to_string(),
+        NbtTag::List(vec![
+            block_entity("minecraft:chest", -20, 64, 5),
+            block_entity("minecraft:furnace", -19, 64, 5),
+        ]),
+    );
+    let mut level = IndexMap::new();
+    level.insert(
+        "TileEntities".to_string(),
+        NbtTag::List(vec![block_entity("minecraft:chest", -500, 12, 40)]),
+    );
+    let mut legacy = IndexMap::new();
+    legacy.insert("Level".to_string(), NbtTag::Compound(level));
+
+    let dir = temp_world("block-entities");
+    std::fs::create_dir_all(dir.join("DIM-1/region")).unwrap();
+    let file = std::fs::File::create(dir.join("DIM-1/region/r.-1.0.mca")).unwrap();
+    let chunks = vec![
+        (-2, 0, String::new(), NbtTag::Compound(current)),
+        (-32, 2, String::new(), NbtTag::Compound(legacy)),
+        (-5, 5, String::new(), NbtTag::Compound(IndexMap::new())),
+    ];
+    RegionWriter::new(file).write_all_chunks(&chunks).unwrap();
+    corrupt_chunk(&dir.join("DIM-1/region/r.-1.0.mca"), 27, 5);
+
+    let world = World::open(&dir).unwrap();
+    let report = world
+        .find_block_entities(&Dimension::Nether, "chest", None)
+        .unwrap();
+    // The corrupt chunk is reported and the rest still searched
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].chunk, Some((-5, 5)));
+    let mut chests = report.found;
+    chests.sort_by_key(|m| m.chunk);
+    let positions: Vec<_> = chests.iter().map(|m| (m.chunk, m.position)).collect();
+    assert_eq!(
+        positions,
+        vec![((-32, 2), (-500, 12, 40)), ((-2, 0), (-20, 64, 5))]
+    );
+
+    let area = ChunkArea::from_blocks(-32, 0, -1, 15);
+    let nearby = world
+        .find_block_entities(&Dimension::Nether, "minecraft:chest", Some(area))
+        .unwrap()
+        .found;
+    assert_eq!(nearby.len(), 1);
+    assert_eq!(nearby[0].entry, block_entity("minecraft:chest", -20, 64, 5));
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_find_items() {
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use anvil_nbt::nbt::snbt::parse_snbt;
+    use anvil_nbt::world::World;
+    use anvil_nbt::world::dimension::Dimension;
+    use anvil_nbt::world::player::{PlayerWriteOptions, write_player_data};
+    use anvil_nbt::world::search::ItemOwner;
+
+    let dir = temp_world("find-items");
+    let uuid = "00000000-0000-0000-0000-000000000001";
+    let player = parse_snbt(
+        r#"{Inventory: [
+            {Slot: 0b, id: "minecraft:dirt", count: 3},
+            {Slot: 1b, id: "minecraft:shulker_box", count: 1, components: {
+                "minecraft:container": [{slot: 0, item: {id: "minecraft:beacon", count: 1}}]
+            }}
+        ]}"#,
+    )
+    .unwrap();
+    write_player_data(&dir, uuid, "", &player, &PlayerWriteOptions::default()).unwrap();
+
+    let terrain = parse_snbt(
+        r#"{block_entities: [{id: "minecraft:chest", x: 1, y: 70, z: 2, Items: [
+            {Slot: 0b, id: "minecraft:shulker_box", Count: 1b, tag: {BlockEntityTag: {Items: [
+                {Slot: 5b, id: "minecraft:beacon", Count: 2b}
+            ]}}}
+        ]}]}"#,
+    )
+    .unwrap();
+    let entities = parse_snbt(
+        r#"{Entities: [{id: "minecraft:item_frame", Item: {id: "minecraft:beacon", count: 1}}]}"#,
+    )
+    .unwrap();
+    for (folder, chunk) in [("region", terrain), ("entities", entities)] {
+        std::fs::create_dir_all(dir.join(folder)).unwrap();
+        let file = std::fs::File::create(dir.join(folder).join("r.0.0.mca")).unwrap();
+        let empty = NbtTag::Compound(IndexMap::new());
+        RegionWriter::new(file)
+            .write_all_chunks(&[(0, 0, String::new(), chunk), (1, 0, String::new(), empty)])
+            .unwrap();
+    }
+    // One unreadable player and one corrupt chunk do not end the search
+    let broken_player = dir.join("playerdata/00000000-0000-0000-0000-000000000002.dat");
+    std::fs::write(&broken_player, b"not nbt").unwrap();
+    corrupt_chunk(&dir.join("region/r.0.0.mca"), 1, 0);
+
+    let world = World::open(&dir).unwrap();
+    let beacon = NbtTag::String("minecraft:beacon".to_string());
+    let report = world
+        .find_items(|item| matches!(item, NbtTag::Compound(map) if map.get("id") == Some(&beacon)))
+        .unwrap();
+    let skipped: Vec<_> = report
+        .skipped
+        .iter()
+        .map(|s| (s.path.clone(), s.chunk))
+        .collect();
+    assert_eq!(
+        skipped,
+        vec![
+            (broken_player, None),
+            (dir.join("region/r.0.0.mca"), Some((1, 0)))
+        ]
+    );
+    let summary: Vec<(ItemOwner, &str)> = report
+        .found
+        .iter()
+        .map(|m| (m.owner.clone(), m.path.as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                ItemOwner::Player {
+                    uuid: uuid.to_string()
+                },
+                "Inventory[1].components.minecraft:container[0].item"
+            ),
+            (
+                ItemOwner::Entity {
+                    dimension: Dimension::Overworld,
+                    chunk: (0, 0),
+                    id: "minecraft:item_frame".to_string(),
+                },
+                "Item"
+            ),
+            (
+                ItemOwner::BlockEntity {
+                    dimension: Dimension::Overworld,
+                    position: (1, 70, 2),
+                    id: "minecraft:chest".to_string(),
+                },
+                "Items[0].tag.BlockEntityTag.Items[0]"
+            ),
+        ]
+    );
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_scoreboard_editing() {
+    use anvil_nbt::nbt::snbt::parse_snbt;
+    use anvil_nbt::world::World;
+    use anvil_nbt::world::scoreboard::{Scoreboard, ScoreboardError};
+
+    let dir = temp_world("scoreboard");
+    let world = World::open(&dir).unwrap();
+    assert_eq!(world.scoreboard().unwrap(), Scoreboard::default());
+
+    let mut scoreboard = Scoreboard::from_nbt(
+        parse_snbt(
+            r#"{DataVersion: 3953, data: {
+                Objectives: [{Name: kills, CriteriaName: playerKillCount, DisplayName: '"Kills"', RenderType: integer}],
+                PlayerScores: [{Score: 4, Name: Steve, Objective: kills, Locked: 0b}],
+                Teams: [{Name: red}],
+                DisplaySlots: {slot_1: kills}
+            }}"#,
+        )
+        .unwrap(),
+    );
+    assert_eq!(scoreboard.score("Steve", "kills"), Some(4));
+
+    scoreboard
+        .create_objective("deaths", "deathCount", "Deaths \"total\"")
+        .unwrap();
+    assert_eq!(
+        scoreboard
+            .create_objective("deaths", "dummy", "x")
+            .unwrap_err(),
+        ScoreboardError::DuplicateObjective("deaths".to_string())
+    );
+    assert!(
+        scoreboard
+            .create_objective("bad name", "dummy", "x")
+            .is_err()
+    );
+    scoreboard.set_score("Steve", "deaths", 2).unwrap();
+    scoreboard.set_score("Alex", "deaths", 1).unwrap();
+    scoreboard.set_score("Steve", "kills", 5).unwrap();
+    assert!(scoreboard.set_score("Steve", "missing", 1).is_err());
+    assert_eq!(scoreboard.remove_player("Alex"), 1);
+    assert!(scoreboard.reset_score("Steve", "deaths"));
+
+    world.write_scoreboard(&scoreboard).unwrap();
+    let mut reread = world.scoreboard().unwrap();
+    assert_eq!(reread, scoreboard);
+    assert_eq!(reread.score("Steve", "kills"), Some(5));
+    assert_eq!(
+        reread.objective("deaths").unwrap().display_name,
+        NbtTag::String(r#""Deaths \"total\"""#.to_string())
+    );
+    assert!(reread.extra.contains_key("Teams"));
+
+    reread.remove_objective("kills").unwrap();
+    assert!(reread.scores.is_empty());
+    assert!(reread.display_slots.is_empty());
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_game_rules_validation() {
+    use anvil_nbt::world::gamerules::{GameRuleError, GameRuleKind, GameRuleValue, GameRules};
+
+    let mut level = level_with_seed(5);
+    assert_eq!(level.game_rules().iter().count(), 0);
+
+    let mut rules = level.game_rules();
+    rules
+        .set("keepInventory", GameRuleValue::Boolean(true))
+        .unwrap();
+    rules
+        .set("randomTickSpeed", GameRuleValue::Integer(10))
+        .unwrap();
+    assert_eq!(
+        rules.set("keepInventory", GameRuleValue::Integer(1)),
+        Err(GameRuleError::WrongType {
+            rule: "keepInventory".to_string(),
+            expected: GameRuleKind::Boolean,
+        })
+    );
+    assert!(rules.set_raw("doFireTick", "1").is_err());
+    rules.set_raw("myModRule", "anything").unwrap();
+    level.set_game_rules(&rules);
+
+    let dir = temp_world("gamerules");
+    level.write(&dir).unwrap();
+    let reread = LevelDat::read(&dir).unwrap().game_rules();
+    assert_eq!(
+        reread.get("keepInventory"),
+        Some(GameRuleValue::Boolean(true))
+    );
+    assert_eq!(reread.get_raw("randomTickSpeed"), Some("10"));
+    assert!(reread.validate().is_empty());
+
+    let mut raw = IndexMap::new();
+    raw.insert("mobGriefing".to_string(), NbtTag::String("0".to_string()));
+    let broken = GameRules::from_nbt(&NbtTag::Compound(raw));
+    assert_eq!(broken.validate().len(), 1);
+    assert_eq!(broken.get("mobGriefing"), None);
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_world_transaction_spans_region_kinds() {
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use anvil_nbt::world::World;
+    use anvil_nbt::world::dimension::{Dimension, RegionKind};
+    use anvil_nbt::world::transaction::TRANSACTION_MANIFEST;
+
+    let dir = temp_world("transaction");
+    for kind in [RegionKind::Terrain, RegionKind::Entities] {
+        std::fs::create_dir_all(dir.join(kind.dir_name())).unwrap();
+        let chunks = vec![
+            (0, 0, "".to_string(), NbtTag::Int(1)),
+            (1, 0, "".to_string(), NbtTag::Int(2)),
+        ];
+        let file = std::fs::File::create(dir.join(kind.dir_name()).join("r.0.0.mca")).unwrap();
+        RegionWriter::new(file).write_all_chunks(&chunks).unwrap();
+    }
+    let world = World::open(&dir).unwrap();
+    let overworld = Dimension::Overworld;
+    let chunk = |kind, x, z| {
+        world
+            .get_chunk_nbt(&overworld, kind, x, z)
+            .unwrap()
+            .map(|(_, tag)| tag)
+    };
+
+    let mut txn = world.transaction();
+    txn.delete_chunk(&overworld, 0, 0).unwrap();
+    txn.set_chunk(&overworld, RegionKind::Poi, 1, 0, "", &NbtTag::Int(3))
+        .unwrap();
+    assert!(txn.is_modified());
+    assert!(
+        txn.get_chunk_nbt(&overworld, RegionKind::Terrain, 0, 0)
+            .unwrap()
+            .is_none()
+    );
+    // Nothing reaches the disk before the commit
+    assert_eq!(chunk(RegionKind::Terrain, 0, 0), Some(NbtTag::Int(1)));
+    assert!(!dir.join("poi").exists());
+    txn.commit().unwrap();
+
+    assert_eq!(chunk(RegionKind::Terrain, 0, 0), None);
+    assert_eq!(chunk(RegionKind::Entities, 0, 0), None);
+    assert_eq!(chunk(RegionKind::Terrain, 1, 0), Some(NbtTag::Int(2)));
+    assert_eq!(chunk(RegionKind::Poi, 1, 0), Some(NbtTag::Int(3)));
+    assert!(!dir.join(TRANSACTION_MANIFEST).exists());
+    assert!(!dir.join("region/r.0.0.mca.tmp").exists());
+
+    // A commit interrupted after the manifest was written is rolled forward
+    let mut txn = world.transaction();
+    txn.set_chunk(&overworld, RegionKind::Terrain, 0, 0, "", &NbtTag::Int(4))
+        .unwrap();
+    txn.commit().unwrap();
+    let committed = std::fs::read(dir.join("region/r.0.0.mca")).unwrap();
+    let mut txn = world.transaction();
+    txn.remove_chunk(&overworld, RegionKind::Terrain, 0, 0)
+        .unwrap();
+    txn.commit().unwrap();
+    std::fs::write(dir.join("region/r.0.0.mca.tmp"), committed).unwrap();
+    std::fs::write(dir.join(TRANSACTION_MANIFEST), "region/r.0.0.mca\n").unwrap();
+    assert_eq!(chunk(RegionKind::Terrain, 0, 0), None);
+    assert!(world.recover_transaction().unwrap());
+    assert_eq!(chunk(RegionKind::Terrain, 0, 0), Some(NbtTag::Int(4)));
+    assert!(!world.recover_transaction().unwrap());
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_cancellation_stops_scans_and_writes() {
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use anvil_nbt::cancel::CancellationToken;
+    use anvil_nbt::world::World;
+    use anvil_nbt::world::dimension::Dimension;
+    use std::io::{Cursor, ErrorKind};
+
+    let dir = temp_world("cancellation");
+    std::fs::create_dir_all(dir.join("region")).unwrap();
+    let file = std::fs::File::create(dir.join("region/r.0.0.mca")).unwrap();
+    let chunks = vec![(0, 0, String::new(), NbtTag::Compound(IndexMap::new()))];
+    RegionWriter::new(file).write_all_chunks(&chunks).unwrap();
+
+    let token = CancellationToken::new();
+    let mut world = World::open(&dir).unwrap();
+    world.set_cancellation(Some(token.clone()));
+    assert!(
+        world
+            .find_block_entities(&Dimension::Overworld, "chest", None)
+            .is_ok()
+    );
+
+    token.cancel();
+    let err = world
+        .find_block_entities(&Dimension::Overworld, "chest", None)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Interrupted);
+
+    let mut writer = RegionWriter::new(Cursor::new(Vec::new()));
+    writer.set_cancellation(Some(token));
+    let err = writer.write_all_chunks(&chunks).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Interrupted);
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_world_tickets() {
+    use anvil_nbt::anvil::encode::RegionWriter;
+    use anvil_nbt::world::World;
+    use anvil_nbt::world::dimension::Dimension;
+    use anvil_nbt::world::forced::ForcedChunks;
+    use anvil_nbt::world::tickets::{ChunkTicket, PoiRecord};
+
+    fn record(kind: &str, pos: [i32; 3], free_tickets: i32) -> NbtTag {
+        let mut map = IndexMap::new();
+        map.insert("type".to_string(), NbtTag::String(kind.to_string()));
+        map.insert("pos".to_string(), NbtTag::IntArray(pos.to_vec()));
+        map.insert("free_tickets".to_string(), NbtTag::Int(free_tickets));
+        NbtTag::Compound(map)
+    }
+    fn section(valid: i8, records: Vec<NbtTag>) -> NbtTag {
+        let mut map = IndexMap::new();
+        map.insert("Valid".to_string(), NbtTag::Byte(valid));
+        map.insert("Records".to_string(), NbtTag::List(records));
+        NbtTag::Compound(map)
+    }
+
+    let dir = temp_world("tickets");
+    let world = World::open(&dir).unwrap();
+    let mut forced = ForcedChunks::default();
+    forced.chunks.insert((1, 2));
+    world
+        .write_forced_chunks(&Dimension::Overworld, &forced)
+        .unwrap();
+
+    let mut sections = IndexMap::new();
+    sections.insert(
+        "4".to_string(),
+        section(
+            1,
+            vec![
+                record("minecraft:home", [20, 70, 35], 0),
+                record("minecraft:bell", [22, 70, 36], 32),
+            ],
+        ),
+    );
+    sections.insert(
+        "5".to_string(),
+        section(0, vec![record("minecraft:home", [20, 80, 35], 1)]),
+    );
+    let mut root = IndexMap::new();
+    root.insert("Sections".to_string(), NbtTag::Compound(sections));
+    std::fs::create_dir_all(dir.join("poi")).unwrap();
+    let file = std::fs::File::create(dir.join("poi/r.0.0.mca")).unwrap();
+    let chunks = [
+        (1, 2, String::new(), NbtTag::Compound(root)),
+        (3, 3, String::new(), NbtTag::Compound(IndexMap::new())),
+    ];
+    RegionWriter::new(file).write_all_chunks(&chunks).unwrap();
+    corrupt_chunk(&dir.join("poi/r.0.0.mca"), 3, 3);
+
+    let report = world.tickets(&Dimension::Overworld).unwrap();
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].chunk, Some((3, 3)));
+    let tickets = report.found;
+    assert_eq!(tickets.chunks.len(), 1);
+    assert!(tickets.is_forced(1, 2));
+    assert_eq!(tickets.get(1, 2).len(), 3);
+    assert_eq!(tickets.get(1, 2)[0], ChunkTicket::Forced);
+    let claimed: Vec<_> = tickets.claimed_pois(1, 2).collect();
+    assert_eq!(
+        claimed,
+        vec![&PoiRecord {
+            kind: "minecraft:home".to_string(),
+            position: (20, 70, 35),
+            free_tickets: 0,
+        }]
+    );
+    assert!(tickets.get(0, 0).is_empty());
+
+    std::fs::remove_dir_all(dir).ok();
+}